@@ -0,0 +1,221 @@
+-- The schema as of the switch to versioned migrations. Databases created before then already
+-- have these tables; `engine::migrations` adds the columns older builds added late.
+
+-- Fish Table: Stores discovered fish
+CREATE TABLE IF NOT EXISTS fish (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT UNIQUE NOT NULL,
+    rarity TEXT,
+    base_value REAL,
+    biome TEXT,
+    sell_value REAL
+);
+
+-- Catch History: Logs every fishing result
+CREATE TABLE IF NOT EXISTS catch_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    fish_name TEXT,
+    quantity INTEGER,
+    xp REAL,
+    biome TEXT,
+    money_gained REAL
+);
+
+-- Player Snapshots: Logs player stats over time
+CREATE TABLE IF NOT EXISTS player_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    level INTEGER,
+    xp REAL,
+    balance REAL,
+    current_biome TEXT
+);
+
+-- Cooldown Events: Track when we hit a cooldown
+CREATE TABLE IF NOT EXISTS cooldown_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    wait_time REAL,
+    total_cooldown REAL,
+    biome TEXT -- the account's biome when the cooldown hit
+);
+
+CREATE TABLE IF NOT EXISTS net_worth_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    balance INTEGER,
+    inventory INTEGER,
+    assets INTEGER
+);
+
+-- Shop Items: Catalogs items found in shops
+CREATE TABLE IF NOT EXISTS shop_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    shop_type TEXT NOT NULL,
+    price REAL,
+    currency TEXT,
+    description TEXT,
+    stock INTEGER,
+    stats TEXT,
+    last_seen DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(name, shop_type)
+);
+
+-- Game Entities: Generic storage for anything else (Buffs, Quests, etc found in lists)
+CREATE TABLE IF NOT EXISTS game_entities (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity_type TEXT NOT NULL, -- "Buff", "Quest", "Badge"
+    name TEXT NOT NULL,
+    details TEXT, -- JSON or raw text
+    last_seen DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(entity_type, name)
+);
+
+-- Command Registry: Tracks commands we've found and executed
+CREATE TABLE IF NOT EXISTS command_registry (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    full_command_name TEXT UNIQUE NOT NULL, -- e.g. "shop buy"
+    description TEXT,
+    params TEXT,
+    command_structure TEXT, -- JSON of full command definition
+    last_executed DATETIME
+);
+
+-- Biome Stats: Persistent learning for Optimizer
+CREATE TABLE IF NOT EXISTS biome_stats (
+    biome_name TEXT PRIMARY KEY,
+    total_gold INTEGER,
+    total_xp INTEGER,
+    total_catches INTEGER
+);
+
+-- Captcha Events: one row per captcha the bot ran into
+CREATE TABLE IF NOT EXISTS captcha_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Clan Events: boss/raid sightings and our contributions to them
+CREATE TABLE IF NOT EXISTS clan_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    event_type TEXT NOT NULL, -- "sighting", "attack"
+    name TEXT NOT NULL,
+    damage INTEGER,
+    details TEXT
+);
+
+-- Quest Claims: rewards collected for completed quests; reward is NULL when no confirmation came
+CREATE TABLE IF NOT EXISTS quest_claims (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    objective TEXT NOT NULL,
+    reward TEXT
+);
+
+CREATE TABLE IF NOT EXISTS clan_deposits (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    amount INTEGER NOT NULL,
+    balance_before INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS daily_claims (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    reward INTEGER, -- money from the reply embed, once it arrives
+    streak INTEGER -- streak the game reported
+);
+
+CREATE TABLE IF NOT EXISTS vote_windows (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    available_at INTEGER NOT NULL, -- unix seconds when the next vote opens
+    source TEXT NOT NULL -- "embed", "claim"
+);
+
+CREATE TABLE IF NOT EXISTS treasure_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    source TEXT NOT NULL, -- what was opened: "Super Crate", "Present"...
+    item TEXT NOT NULL, -- "Gold Fish", "Money"...
+    amount INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS interactions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    sent_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    nonce TEXT NOT NULL UNIQUE,
+    command TEXT NOT NULL,
+    options TEXT, -- JSON
+    interaction_id TEXT,
+    status TEXT NOT NULL DEFAULT 'sent', -- "sent", "acknowledged", "replied", "failed", "retried", "abandoned", "rejected"
+    reply_message_id TEXT,
+    outcome TEXT, -- reply's embed title or first line
+    replied_at DATETIME,
+    retry_of TEXT -- nonce of the send this one retries
+);
+
+-- Owned Boats: every boat seen as owned, so the optimizer knows the fleet after a restart
+CREATE TABLE IF NOT EXISTS owned_boats (
+    name TEXT PRIMARY KEY,
+    first_seen DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Event Earnings: event currency found while fishing ("You found 3 Presents")
+CREATE TABLE IF NOT EXISTS event_earnings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Coinflips: every flip's result, for the bankroll's odds and loss limits
+CREATE TABLE IF NOT EXISTS coinflips (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    won INTEGER NOT NULL,
+    amount INTEGER NOT NULL,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Bot Snapshot: single row with the latest `engine::state::Snapshot` as JSON
+CREATE TABLE IF NOT EXISTS bot_snapshot (
+    id INTEGER PRIMARY KEY,
+    snapshot TEXT NOT NULL,
+    saved_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Health Probe: single row rewritten by health checks to prove the file is writable
+CREATE TABLE IF NOT EXISTS health_probe (
+    id INTEGER PRIMARY KEY,
+    checked_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Leaderboard Ranks: this account's place on each `/leaderboards` board seen, with
+-- the gaps to the players around it
+CREATE TABLE IF NOT EXISTS leaderboard_ranks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    category TEXT NOT NULL,
+    rank INTEGER NOT NULL,
+    value INTEGER,
+    gap_above INTEGER,
+    gap_below INTEGER,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Fishdex: every species a `/fishdex` page listed, and whether it was caught
+CREATE TABLE IF NOT EXISTS fishdex (
+    name TEXT PRIMARY KEY,
+    biome TEXT,
+    caught INTEGER NOT NULL,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Level Ups: when each level was reached, for the XP/hour between them
+CREATE TABLE IF NOT EXISTS level_ups (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    level INTEGER NOT NULL,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
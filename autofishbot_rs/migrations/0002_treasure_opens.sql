@@ -0,0 +1,15 @@
+-- One row per opened crate or chest, so drops are counted per open rather than per second
+CREATE TABLE IF NOT EXISTS treasure_opens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    source TEXT NOT NULL -- what was opened: "Super Crate", "Rare Treasure"...
+);
+
+ALTER TABLE treasure_history ADD COLUMN open_id INTEGER REFERENCES treasure_opens(id);
+
+-- Older drops have no open id; the best guess left is one open per source and second
+INSERT INTO treasure_opens (timestamp, source)
+    SELECT DISTINCT timestamp, source FROM treasure_history;
+UPDATE treasure_history SET open_id = (
+    SELECT o.id FROM treasure_opens o WHERE o.timestamp = treasure_history.timestamp AND o.source = treasure_history.source
+);
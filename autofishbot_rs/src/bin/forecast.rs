@@ -0,0 +1,176 @@
+use anyhow::{Result, anyhow};
+
+use autofishbot_rs::engine::bankroll::Bankroll;
+use autofishbot_rs::engine::database::Database;
+use autofishbot_rs::engine::game_data::{Biome, Boat, BoatType, Rod, RodType, BIOME_DATA, BOAT_DATA, ROD_DATA};
+use autofishbot_rs::engine::optimizer::{ActionType, Optimizer};
+use autofishbot_rs::engine::profile::Profile;
+use autofishbot_rs::util::money;
+
+/// How far back to look when estimating levels/hour.
+const LEVEL_RATE_WINDOW_HOURS: i64 = 72;
+/// Upper bound on simulated purchases so a stalled strategy can't loop forever.
+const MAX_STRATEGY_STEPS: usize = 8;
+
+fn arg_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter().position(|a| a == flag).and_then(|i| args.get(i + 1)).cloned()
+}
+
+fn format_duration(seconds: f64) -> String {
+    if !seconds.is_finite() || seconds < 0.0 {
+        return "never".to_string();
+    }
+    if seconds < 1.0 {
+        return "now".to_string();
+    }
+    let total = seconds as u64;
+    let (days, hours, minutes) = (total / 86_400, (total % 86_400) / 3600, (total % 3600) / 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m {}s", minutes, total % 60)
+    }
+}
+
+fn find_rod(name: &str) -> Option<&'static Rod> {
+    ROD_DATA.values().find(|r| r.name.eq_ignore_ascii_case(name.trim()))
+}
+
+fn find_boat(name: &str) -> Option<&'static Boat> {
+    BOAT_DATA.values().find(|b| b.name.eq_ignore_ascii_case(name.trim()))
+}
+
+struct Row {
+    target: String,
+    cost: u64,
+    eta_seconds: f64,
+    bridge_bet: Option<u64>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("Usage: forecast [--db autofishbot.db] [--rod \"Steel Rod\"] [--boat \"Rowboat\"] [--biome River] [--balance N] [--level-target N]");
+        return Ok(());
+    }
+
+    let db_path = arg_value(&args, "--db").unwrap_or_else(|| "autofishbot.db".to_string());
+    if !std::path::Path::new(&db_path).exists() {
+        return Err(anyhow!("Database {} not found. Run the bot first or pass --db.", db_path));
+    }
+    let db = Database::new(&db_path).await?;
+
+    let mut optimizer = Optimizer::new();
+    optimizer.biome_knowledge = db.load_biome_stats().await?;
+    optimizer.bankroll = Bankroll::default().with_stats(db.gambling_stats().await?);
+
+    let snapshot = db.latest_snapshot().await?;
+
+    // CLI flags override whatever the last snapshot knows
+    let balance = arg_value(&args, "--balance").and_then(|s| money::parse_amount(&s))
+        .or_else(|| snapshot.as_ref().map(|s| s.balance as u64))
+        .unwrap_or(0);
+    let level = snapshot.as_ref().map(|s| s.level).unwrap_or(0);
+    let biome = arg_value(&args, "--biome").and_then(|b| Biome::from_display_name(&b))
+        .or_else(|| snapshot.as_ref().and_then(|s| Biome::from_display_name(&s.biome)))
+        .unwrap_or(Biome::River);
+    let rod = arg_value(&args, "--rod").and_then(|r| find_rod(&r)).unwrap_or(&ROD_DATA[&RodType::Plastic]);
+    let boat = arg_value(&args, "--boat").and_then(|b| find_boat(&b)).unwrap_or(&BOAT_DATA[&BoatType::Rowboat]);
+    let level_target = arg_value(&args, "--level-target").and_then(|s| s.parse::<i32>().ok());
+
+    let profile = Profile::default();
+    let gps = optimizer.calculate_metrics(rod, boat, biome, &profile);
+
+    println!("Current: {} / {} in {:?} | Level {} | Balance ${} | {:.2} gold/s", rod.name, boat.name, biome, level, balance, gps);
+    println!();
+
+    let eta = |cost: u64| -> f64 {
+        if cost <= balance { 0.0 } else if gps > 0.0 { (cost - balance) as f64 / gps } else { f64::INFINITY }
+    };
+
+    let mut rows = Vec::new();
+
+    if let Some(next_rod) = ROD_DATA.values().filter(|r| r.price > rod.price).min_by_key(|r| r.price) {
+        rows.push(Row {
+            target: next_rod.name.to_string(),
+            cost: next_rod.price,
+            eta_seconds: eta(next_rod.price),
+            bridge_bet: optimizer.bankroll.bridge_bet(balance, next_rod.price, gps),
+        });
+    }
+
+    if let Some(next_boat) = BOAT_DATA.values().filter(|b| b.price > boat.price).min_by_key(|b| b.price) {
+        rows.push(Row {
+            target: next_boat.name.to_string(),
+            cost: next_boat.price,
+            eta_seconds: eta(next_boat.price),
+            bridge_bet: optimizer.bankroll.bridge_bet(balance, next_boat.price, gps),
+        });
+    }
+
+    if let Some(next_biome) = biome.next() {
+        let unlock = BIOME_DATA[&next_biome].unlock_cost;
+        rows.push(Row {
+            target: format!("{} biome", BIOME_DATA[&next_biome].name),
+            cost: unlock,
+            eta_seconds: eta(unlock),
+            bridge_bet: optimizer.bankroll.bridge_bet(balance, unlock, gps),
+        });
+    }
+
+    if let Some(target) = level_target {
+        // Levels/hour from snapshot history; XP curve data isn't modeled so this is purely empirical
+        let baseline = db.earliest_snapshot_since(LEVEL_RATE_WINDOW_HOURS).await?;
+        let eta_seconds = match (&baseline, &snapshot) {
+            _ if target <= level => 0.0,
+            (Some(first), Some(last)) if last.level > first.level && last.timestamp > first.timestamp => {
+                let rate = (last.level - first.level) as f64 / (last.timestamp - first.timestamp) as f64;
+                (target - level) as f64 / rate
+            },
+            _ => f64::INFINITY,
+        };
+        rows.push(Row { target: format!("Level {}", target), cost: 0, eta_seconds, bridge_bet: None });
+    }
+
+    println!("{:<24} {:>18} {:>14} {:>16}", "Target", "Cost", "ETA", "Coinflip bridge");
+    println!("{}", "-".repeat(75));
+    for row in &rows {
+        let bet = row.bridge_bet.map(|b| format!("${}", b)).unwrap_or_else(|| "-".to_string());
+        let cost = if row.cost > 0 { format!("${}", row.cost) } else { "-".to_string() };
+        println!("{:<24} {:>18} {:>14} {:>16}", row.target, cost, format_duration(row.eta_seconds), bet);
+    }
+
+    // Simulate the optimizer's own purchase path: buy each recommended rod/boat as soon as it is affordable
+    println!();
+    println!("Strategy path (optimizer purchases, no coinflips):");
+    let (mut sim_rod, mut sim_boat, mut sim_balance, mut elapsed) = (rod, boat, balance, 0.0);
+    for step in 1..=MAX_STRATEGY_STEPS {
+        let sim_gps = optimizer.calculate_metrics(sim_rod, sim_boat, biome, &profile);
+        let recs = optimizer.solve_next_move(sim_rod, sim_boat, biome, &profile, sim_balance);
+        let Some(next) = recs.iter().find(|r| matches!(r.action, ActionType::BuyRod | ActionType::BuyBoat)) else {
+            println!("  (no further rod/boat upgrades recommended)");
+            break;
+        };
+
+        let wait = if next.cost <= sim_balance { 0.0 } else if sim_gps > 0.0 { (next.cost - sim_balance) as f64 / sim_gps } else { f64::INFINITY };
+        if !wait.is_finite() {
+            println!("  (cannot afford {} at current income)", next.target_name);
+            break;
+        }
+        elapsed += wait;
+        // We waited exactly until the balance covered the cost, then spent it
+        sim_balance = sim_balance.max(next.cost) - next.cost;
+
+        match next.action {
+            ActionType::BuyRod => if let Some(r) = find_rod(&next.target_name) { sim_rod = r },
+            ActionType::BuyBoat => if let Some(b) = find_boat(&next.target_name) { sim_boat = b },
+            _ => {},
+        }
+        println!("  {}. +{:<10} buy {} (${})", step, format_duration(elapsed), next.target_name, next.cost);
+    }
+
+    Ok(())
+}
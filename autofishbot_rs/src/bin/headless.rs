@@ -1,19 +1,202 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow, bail};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use std::time::Duration;
+use std::io::Write;
+use log::{info, error};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, BufReader};
 
-use autofishbot_rs::config::Config;
+use autofishbot_rs::config::{AccountConfig, AccountMode, Config, WebConfig};
 use autofishbot_rs::tui::app::App;
+use autofishbot_rs::scripting;
 use autofishbot_rs::discord::gateway::Gateway;
+use autofishbot_rs::discord::replay::Replay;
 use autofishbot_rs::discord::types::GatewayPayload;
 use autofishbot_rs::discord::client::DiscordClient;
+use autofishbot_rs::discord::command::CommandBuilder;
+use autofishbot_rs::discord::session::SessionState;
+use autofishbot_rs::engine::accounts::supervisor::AccountSupervisor;
 use autofishbot_rs::engine::bot::Bot;
 use autofishbot_rs::engine::database::Database;
-use autofishbot_rs::engine::parser;
+use autofishbot_rs::engine::config_watcher::ConfigWatcher;
+use autofishbot_rs::engine::correlation::CorrelationStore;
+use autofishbot_rs::engine::events::EventProcessor;
+use autofishbot_rs::engine::health::HealthMonitor;
+use autofishbot_rs::engine::captcha::Captcha;
+use autofishbot_rs::engine::update_check;
+use autofishbot_rs::engine::session_replay;
+use autofishbot_rs::engine::watchdog;
+use autofishbot_rs::notifications::hub;
+use autofishbot_rs::telemetry;
+use autofishbot_rs::web;
+
+fn arg_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter().position(|a| a == flag).and_then(|i| args.get(i + 1)).cloned()
+}
+
+/// Runs every `[[accounts]]` entry in this process until Ctrl-C.
+async fn run_all_accounts(config: Config, config_path: &str, web_config: WebConfig) -> Result<()> {
+    let mut supervisor = AccountSupervisor::start(config, config_path).await?;
+    info!("Running {} accounts. Press Ctrl-C to stop.", supervisor.accounts().len());
+
+    // The dashboard shows the first account; /api/accounts lists all of them
+    if web_config.enabled {
+        let first = &supervisor.accounts()[0];
+        let (app, health, db, accounts) = (first.app.clone(), first.health.clone(), first.db.clone(), supervisor.handle());
+        tokio::spawn(async move {
+            if let Err(e) = web::server::serve(&web_config, app, health, db, Some(accounts)).await {
+                error!("Web dashboard error: {}", e);
+            }
+        });
+    }
+
+    tokio::select! {
+        _ = supervisor.supervise() => {},
+        _ = tokio::signal::ctrl_c() => info!("Shutting down."),
+    }
+    Ok(())
+}
+
+/// `headless healthcheck [url]`: exit 0 if `/healthz` reports healthy, 1 otherwise.
+/// Meant for Docker HEALTHCHECK / exec probes; defaults to the `[web] bind` address from config.toml.
+async fn run_healthcheck(url: Option<String>) -> i32 {
+    let url = url.unwrap_or_else(|| {
+        let bind = Config::load("config.toml").map(|c| c.web.bind).unwrap_or_else(|_| WebConfig::default().bind);
+        format!("http://{}/healthz", bind.replace("0.0.0.0", "127.0.0.1"))
+    });
+
+    let response = reqwest::Client::new().get(&url).timeout(Duration::from_secs(5)).send().await;
+    match response {
+        Ok(res) => {
+            let healthy = res.status().is_success();
+            println!("{} {}", res.status(), res.text().await.unwrap_or_default());
+            if healthy { 0 } else { 1 }
+        },
+        Err(e) => {
+            eprintln!("Health check failed: {}", e);
+            1
+        }
+    }
+}
+
+/// Collects a log record's key-values into a JSON object.
+struct JsonFields<'a>(&'a mut serde_json::Map<String, Value>);
+
+impl<'kvs> log::kv::VisitSource<'kvs> for JsonFields<'_> {
+    fn visit_pair(&mut self, key: log::kv::Key<'kvs>, value: log::kv::Value<'kvs>) -> Result<(), log::kv::Error> {
+        let value = serde_json::to_value(&value).unwrap_or_else(|_| Value::String(value.to_string()));
+        self.0.insert(key.to_string(), value);
+        Ok(())
+    }
+}
+
+/// `--log-format json` writes one JSON object per line to stdout for Loki/ELK; otherwise the usual
+/// env_logger text on stderr. Defaults to this binary's info logs plus warnings from everything
+/// else (reqwest, sqlx, ...); `RUST_LOG` overrides.
+fn init_logging(json: bool) {
+    let mut builder = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn,headless=info,autofishbot_rs::engine::events=info,autofishbot_rs::engine::accounts=info"));
+    if json {
+        builder.target(env_logger::Target::Stdout).format(|buf, record| {
+            let mut fields = serde_json::Map::new();
+            let _ = record.key_values().visit(&mut JsonFields(&mut fields));
+            let line = json!({
+                "timestamp": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                "level": record.level().as_str(),
+                "module": record.target(),
+                "message": record.args().to_string(),
+                "fields": fields,
+            });
+            writeln!(buf, "{}", line)
+        });
+    }
+    builder.init();
+}
+
+const REPL_HELP: &str = "Commands: status | start | stop | sell | daily | captcha <answer> | quit";
+
+/// Sends a slash command by name, filling only the given top-level options.
+async fn send_named_command(client: &DiscordClient, config: &Config, name: &str, args: &[&str]) -> Result<()> {
+    let guild_id = config.system.guild_id.to_string();
+    let cmd = client.get_command(&guild_id, name).await?
+        .ok_or_else(|| anyhow!("/{} not found in the guild command index", name))?;
+    let options = CommandBuilder::new(&cmd).text(args).build()?;
+    client.send_command(&guild_id, &config.system.channel_id.to_string(), &cmd, options).await
+}
+
+/// Minimal stdin control for tmux/nohup sessions. Returns on `quit`/`exit` (true) or EOF (false).
+/// Replies go to stderr, so stdout stays valid JSON lines with `--log-format json`.
+async fn run_repl(app: Arc<Mutex<App>>, client: Arc<DiscordClient>, config: Config, captcha: Arc<Mutex<Captcha>>, health: Arc<HealthMonitor>) -> bool {
+    eprintln!("{}", REPL_HELP);
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        let mut parts = line.split_whitespace();
+        let Some(command) = parts.next() else { continue };
+
+        match command {
+            "status" => {
+                let app = app.lock().await;
+                let report = health.report(true, app.is_running);
+                let gateway = match &app.gateway {
+                    Some(gateway) => gateway.snapshot().summary(),
+                    None if report.gateway_connected => "connected".to_string(),
+                    None => "disconnected".to_string(),
+                };
+                eprintln!("Status: {} | Fish caught: {} | Gateway: {}", app.status, app.stats.fish_caught, gateway);
+                eprintln!("Balance: {} | Level: {} | Biome: {}", app.profile.balance_text(), app.profile.level_text(), app.profile.biome);
+                eprintln!("Strategy: {} ({})", app.strategy.current_goal, app.strategy.progress);
+            },
+            "start" | "stop" => {
+                let mut app = app.lock().await;
+                if app.is_running != (command == "start") {
+                    app.toggle_bot();
+                }
+                eprintln!("Bot {}", app.status);
+            },
+            "sell" | "daily" => {
+                match send_named_command(&client, &config, command, &[]).await {
+                    Ok(()) => eprintln!("Sent /{}", command),
+                    Err(e) => eprintln!("/{} failed: {}", command, e),
+                }
+            },
+            "captcha" => {
+                let answer: String = parts.collect::<Vec<_>>().join("");
+                if answer.is_empty() {
+                    eprintln!("Usage: captcha <answer>");
+                    continue;
+                }
+                match send_named_command(&client, &config, "verify", &[&answer]).await {
+                    Ok(()) => {
+                        captcha.lock().await.reset();
+                        eprintln!("Sent /verify {}", answer);
+                    },
+                    Err(e) => eprintln!("/verify failed: {}", e),
+                }
+            },
+            "quit" | "exit" => return true,
+            "help" => eprintln!("{}", REPL_HELP),
+            other => eprintln!("Unknown command '{}'. {}", other, REPL_HELP),
+        }
+    }
+    false
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(|a| a.as_str()) == Some("healthcheck") {
+        std::process::exit(run_healthcheck(args.get(2).cloned()).await);
+    }
+    // --replay <file.jsonl> [--speed <multiplier>] feeds a capture instead of the live gateway
+    let replay_path = arg_value(&args, "--replay");
+    let replay_speed = arg_value(&args, "--speed").and_then(|s| s.parse::<f64>().ok()).unwrap_or(1.0);
+    // --replay <file.jsonl> --summary processes the capture at once and prints what it left behind,
+    // the same summary the fixtures/gateway regression cases pin
+    let replay_summary = args.iter().any(|a| a == "--summary");
+    // --account <name> runs one [[accounts]] entry, --account all runs every account in this process
+    let account = arg_value(&args, "--account");
+
     // Load config
     let config_path = "config.toml";
     let config = if std::path::Path::new(config_path).exists() {
@@ -21,187 +204,199 @@ async fn main() -> Result<()> {
     } else {
         let cfg = Config::default();
         cfg.save(config_path)?;
+        eprintln!("Created {} with default settings; fill in the [system] section.", config_path);
         cfg
     };
 
-     env_logger::init();
+    let log_json = arg_value(&args, "--log-format").as_deref() == Some("json");
+    init_logging(log_json);
+    let _telemetry = telemetry::init(&config.telemetry)?;
+
+    // [web] enabled = true, or --web <addr> to enable it for this run
+    let mut web_config = config.web.clone();
+    if let Some(bind) = arg_value(&args, "--web") {
+        web_config.enabled = true;
+        web_config.bind = bind;
+    }
+    if web_config.enabled && web_config.auth_token.is_empty() && !web_config.is_loopback() {
+        bail!("--web {} is reachable from other machines: set [web] auth_token first, or bind to 127.0.0.1", web_config.bind);
+    }
+
+    if account.as_deref() == Some("all") {
+        return run_all_accounts(config, config_path, web_config).await;
+    }
+    let config = match &account {
+        Some(name) => config.for_account(name)?,
+        None => config,
+    };
+    // Replays never talk to Discord, so credentials may legitimately be missing
+    if replay_path.is_none() {
+        config.validate()?;
+    }
+    if let (Some(path), true) = (&replay_path, replay_summary) {
+        // In memory, so the catch count is this capture's alone and nothing is left behind
+        let db = Arc::new(Database::in_memory().await?);
+        let summary = session_replay::replay_session(&config, std::path::Path::new(path), db).await?;
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
 
-    println!("Loaded config.");
-    println!("Starting headless bot...");
+    info!("Loaded config.");
+    match &account {
+        Some(name) => info!(account = name.as_str(); "Starting headless bot for account '{}'...", name),
+        None => info!("Starting headless bot..."),
+    }
 
     // Setup App State (mocked TUI)
     let app = Arc::new(Mutex::new(App::new(config.clone())));
 
-    // Setup Database
-    let db = Arc::new(Database::new("autofishbot.db").await?);
+    // Setup Database (replays get their own file so captured sessions don't pollute real stats)
+    // Named accounts keep separate stats as well
+    let db_path = match (&replay_path, &account) {
+        (Some(_), _) => "autofishbot_replay.db".to_string(),
+        (None, Some(name)) => AccountConfig::db_path(name),
+        (None, None) => "autofishbot.db".to_string(),
+    };
+    let db = Arc::new(Database::new(&db_path).await?);
+
+    // Web dashboard + /healthz
+    let health = Arc::new(HealthMonitor::new());
+    if web_config.enabled {
+        let (app_clone, health_clone, db_clone) = (app.clone(), health.clone(), db.clone());
+        tokio::spawn(async move {
+            if let Err(e) = web::server::serve(&web_config, app_clone, health_clone, db_clone, None).await {
+                error!("Web dashboard error: {}", e);
+            }
+        });
+    }
 
-    // Enable running by default for headless
+    // Enable running by default for headless (replays only exercise the event pipeline, never send commands)
     {
         let mut app_guard = app.lock().await;
-        app_guard.is_running = true;
+        if replay_path.is_none() && !app_guard.is_observer() {
+            app_guard.toggle_bot();
+        }
     }
 
     // Discord Client
-    let client = Arc::new(DiscordClient::new(config.clone())?);
+    let correlation = Arc::new(CorrelationStore::new(db.clone()));
+    let session = Arc::new(SessionState::new());
+    let client = Arc::new(DiscordClient::new(config.clone())?.with_correlation(correlation.clone()).with_session(session.clone()));
 
     // Gateway event channel
-    let (gateway_tx, mut gateway_rx) = tokio::sync::mpsc::channel::<GatewayPayload>(100);
-
-    // Gateway
-    let mut gateway = Gateway::new(config.clone(), gateway_tx);
-    let _gateway_handle = tokio::spawn(async move {
-        println!("Starting Gateway connection...");
-        loop {
-            if let Err(e) = gateway.run().await {
-                eprintln!("Gateway error: {}", e);
-                tokio::time::sleep(Duration::from_secs(5)).await;
-            } else {
-                // Reconnect immediately on clean exit (reconnect opcode)
-                tokio::time::sleep(Duration::from_millis(500)).await;
+    let (gateway_tx, gateway_rx) = tokio::sync::mpsc::channel::<GatewayPayload>(100);
+
+    // Gateway (or Replay)
+    let replay_handle = if let Some(path) = replay_path.clone() {
+        let mut replay = Replay::new(path, replay_speed, gateway_tx);
+        Some(tokio::spawn(async move {
+            info!("Starting Replay...");
+            if let Err(e) = replay.run().await {
+                error!("Replay error: {}", e);
             }
+        }))
+    } else {
+        let gateway = Gateway::new(config.clone(), gateway_tx).with_health(health.clone()).with_session(session);
+        // No-op unless started by systemd with Type=notify
+        watchdog::spawn(health.clone(), app.clone(), db.clone());
+        let notifier = {
+            let mut app = app.lock().await;
+            app.gateway = Some(gateway.stats());
+            app.notifier.clone()
+        };
+        info!("Starting Gateway connection...");
+        tokio::spawn(gateway.run_forever(notifier));
+        None
+    };
+
+    // Apply edits to config.toml at runtime without dropping the gateway session
+    let _config_watcher = match ConfigWatcher::spawn(config_path, account.clone(), app.clone()) {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            app.lock().await.add_log(format!("Config hot reload disabled: {}", e));
+            None
         }
-    });
+    };
+
+    // Replays stay offline: no notifications, no update check
+    if replay_path.is_none() {
+        hub::start(&config, app.clone()).await;
+        let _ = scripting::host::start(&config.scripting, app.clone());
+        update_check::spawn(config.updates.clone(), app.clone());
+    }
 
     // Bot Engine
     let mut bot = Bot::new(config.clone(), client.clone(), app.clone(), db.clone()).await;
     let bot_cooldown_manager = bot.cooldown_manager.clone(); // Share cooldown manager
+    let bot_captcha = bot.captcha.clone();
 
-    let _bot_handle = tokio::spawn(async move {
-        println!("Starting Bot engine...");
-        bot.run().await;
-    });
+    if config.system.mode == AccountMode::Observer {
+        info!("Observer mode: logging game data only, never sending commands.");
+    } else {
+        tokio::spawn(async move {
+            info!("Starting Bot engine...");
+            bot.run().await;
+        });
+    }
 
     // Event Processor & Logger
-    let app_clone = app.clone();
-    let db_clone = db.clone();
-
-    let _event_processor = tokio::spawn(async move {
-        while let Some(payload) = gateway_rx.recv().await {
-             let mut app = app_clone.lock().await;
-
-             // Handle specific events like MESSAGE_CREATE
-             if let Some(t) = payload.t {
-                 // println!("Event received: {}", t);
-                 app.add_log(format!("Event: {}", t));
-                 if t == "MESSAGE_CREATE" || t == "MESSAGE_UPDATE" {
-                      if let Some(d) = payload.d {
-                           // Try to parse full message object
-                           if let Ok(msg) = serde_json::from_value::<autofishbot_rs::discord::types::Message>(d.clone()) {
-                               app.last_message_object = Some(msg);
-                           }
-
-                           // Check Author
-                           let is_vf = if let Some(author) = d.get("author") {
-                                author.get("id").and_then(|id| id.as_str()).map(|s| s == "574652751745777665").unwrap_or(false)
-                           } else { false };
-
-                           if let Some(content) = d.get("content").and_then(|v| v.as_str()) {
-                               app.last_message = content.to_string();
-                               // println!("Message Content: {}", content);
-
-                               // Update Profile Parsing
-                               let embeds = d.get("embeds").and_then(|v| v.as_array());
-                               if let Some(embeds_arr) = embeds {
-                                   if let Some(first_embed) = embeds_arr.first() {
-                                       if let Some(title) = first_embed.get("title").and_then(|v| v.as_str()) {
-                                            println!("Embed Title: {}", title);
-                                            if let Some(desc) = first_embed.get("description").and_then(|v| v.as_str()) {
-                                                 // app.profile.update_from_message(desc, Some(title));
-
-                                                 if is_vf {
-                                                     if title.contains("You caught") {
-                                                         if let Some(catch) = parser::parse_catch_embed(desc) {
-                                                             println!("Parsed Catch: {:?}", catch);
-                                                              // Report success to cooldown manager
-                                                              {
-                                                                  let mut cm = bot_cooldown_manager.lock().await;
-                                                                  cm.report_success();
-                                                              }
-
-                                                             // Default biome for now or from state
-                                                             let current_biome = app.profile.current_biome.clone().unwrap_or("Unknown".to_string());
-                                                             for (fish, count) in catch.fish {
-                                                                 if let Err(e) = db_clone.log_catch(&fish, count, catch.xp, &current_biome).await {
-                                                                     eprintln!("DB Error: {}", e);
-                                                                 }
-                                                             }
-                                                         }
-                                                     } else if title.contains("Inventory") || title.contains("Virtual Farmer") { // "Virtual Farmer" is profile?
-                                                         let stats = parser::parse_profile_embed(desc);
-                                                         println!("Parsed Stats: {:?}", stats);
-                                                         if let (Some(lvl), Some(bal), Some(bio)) = (stats.level, stats.balance, stats.biome) {
-                                                             if let Err(e) = db_clone.log_snapshot(lvl, 0.0, bal, &bio).await {
-                                                                  eprintln!("DB Error: {}", e);
-                                                             }
-                                                             // Update app state too
-                                                             app.profile.current_biome = Some(bio);
-                                                         }
-                                                     }
-                                                 }
-                                            }
-                                       } else if let Some(desc) = first_embed.get("description").and_then(|v| v.as_str()) {
-                                            // Some embeds might not have a title but have a description (e.g., Cooldown warnings)
-                                            // println!("Embed Description (No Title): {}", desc);
-
-                                            if is_vf {
-                                                if let Some(cd_event) = parser::parse_cooldown_embed(desc) {
-                                                    println!("Parsed Cooldown: {:?}", cd_event);
-
-                                                    // Log to DB
-                                                    if let Err(e) = db_clone.log_cooldown(cd_event.wait_time, cd_event.total_cooldown).await {
-                                                        eprintln!("DB Error: {}", e);
-                                                    }
-
-                                                    // Update Cooldown Manager
-                                                    {
-                                                        let mut cm = bot_cooldown_manager.lock().await;
-                                                        cm.report_cooldown_hit(cd_event.wait_time as f64, cd_event.total_cooldown as f64);
-                                                    }
-                                                }
-                                            }
-                                       }
-                                   }
-                               }
-                           }
-                      }
-                 }
-             }
-        }
-    });
+    let processor = EventProcessor::new(&config, app.clone(), db.clone(), bot_cooldown_manager, health.clone())
+        .with_correlation(correlation)
+        .with_captcha(bot_captcha.clone());
+    let event_processor = tokio::spawn(processor.run(gateway_rx));
 
-    // Keep alive for testing
-    let minutes = 30;
-    println!("Running for {} minutes...", minutes);
-    tokio::time::sleep(Duration::from_secs(60 * minutes)).await;
-    println!("Test complete.");
+    if let Some(handle) = replay_handle {
+        // Replay ends when the capture is exhausted; wait for the processor to drain the channel
+        let _ = handle.await;
+        let _ = event_processor.await;
+        info!("Replay complete.");
+    } else {
+        // Keep alive for testing, or until `quit` is typed on stdin
+        let minutes = 30;
+        info!("Running for {} minutes...", minutes);
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(60 * minutes);
+        let repl = run_repl(app.clone(), client.clone(), config.clone(), bot_captcha, health.clone());
+        tokio::select! {
+            _ = tokio::time::sleep_until(deadline) => info!("Test complete."),
+            quit = repl => {
+                if quit {
+                    info!("Quitting.");
+                } else {
+                    // stdin closed (e.g. nohup): keep running for the remaining time
+                    tokio::time::sleep_until(deadline).await;
+                    info!("Test complete.");
+                }
+            },
+        }
+    }
 
     // Verify Database
-    println!("--- Database Verification ---");
+    info!("--- Database Verification ---");
     let catches: i32 = sqlx::query_scalar("SELECT count(*) FROM catch_history")
         .fetch_one(&db.pool)
         .await?;
-    println!("Catches logged: {}", catches);
+    info!(catches; "Catches logged: {}", catches);
 
     let snapshots: i32 = sqlx::query_scalar("SELECT count(*) FROM player_snapshots")
         .fetch_one(&db.pool)
         .await?;
-    println!("Snapshots logged: {}", snapshots);
+    info!(snapshots; "Snapshots logged: {}", snapshots);
 
     let fish: i32 = sqlx::query_scalar("SELECT count(*) FROM fish")
         .fetch_one(&db.pool)
         .await?;
-    println!("Unique fish known: {}", fish);
+    info!(fish; "Unique fish known: {}", fish);
 
     // Check gathered data
     let shop_items: i32 = sqlx::query_scalar("SELECT count(*) FROM shop_items")
         .fetch_one(&db.pool)
         .await?;
-    println!("Shop items gathered: {}", shop_items);
+    info!(shop_items; "Shop items gathered: {}", shop_items);
 
     let entities: i32 = sqlx::query_scalar("SELECT count(*) FROM game_entities")
         .fetch_one(&db.pool)
         .await?;
-    println!("Game entities gathered: {}", entities);
+    info!(entities; "Game entities gathered: {}", entities);
 
     Ok(())
 }
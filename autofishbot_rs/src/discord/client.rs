@@ -1,16 +1,107 @@
 use anyhow::{Result, anyhow};
-use reqwest::{Client, Proxy};
+use reqwest::{Client, Proxy, RequestBuilder, Response};
 use serde_json::{json, Value};
-use crate::config::Config;
-use log::error;
+use crate::config::{AccountMode, Config};
+use crate::discord::command_cache::{self, CommandCache};
+use crate::discord::rate_limit::{MAX_RATE_LIMIT_WAIT, RateLimiter};
+use crate::discord::session::SessionState;
+use crate::discord::types::{Message, Modal};
+use crate::engine::correlation::CorrelationStore;
+use crate::engine::parser;
+use log::{error, warn};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 use base64::{Engine as _, engine::general_purpose};
 
+/// How long the guild's command index is used before it's fetched again.
+const COMMAND_CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+/// A command missing from an index at least this old triggers a fresh fetch.
+const MIN_COMMAND_REFRESH: Duration = Duration::from_secs(60);
+/// Attachments bigger than this aren't downloaded; captcha images are a few KB.
+const MAX_ATTACHMENT_BYTES: usize = 8 * 1024 * 1024;
+/// Discord's attachment hosts, the only ones the token is sent to.
+const CDN_HOSTS: &[&str] = &["cdn.discordapp.com", "media.discordapp.net"];
+/// REST API root every endpoint hangs off.
+const DISCORD_API: &str = "https://discord.com/api/v9";
+
+/// Interaction nonces: microsecond-ish timestamps, with a counter so two sends in the same
+/// millisecond differ.
+fn new_nonce() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed) % 1000;
+    (chrono::Utc::now().timestamp_millis() as u64 * 1000 + count).to_string()
+}
+
+/// 429s retried per request before giving up.
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Returned by REST calls when Discord answers 401, or 403 with an account-level error code:
+/// the token was reset or the account lost access. Callers can `downcast_ref` for it to stop sending until the token works again.
+#[derive(Debug)]
+pub struct Unauthorized(pub reqwest::StatusCode);
+
+impl std::fmt::Display for Unauthorized {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Discord rejected the token ({})", self.0)
+    }
+}
+
+impl std::error::Error for Unauthorized {}
+
+/// Discord error codes that come with a 403 when the account itself is locked out: invalid
+/// token, unverified account. Others, like 50001 Missing Access or 50013 Missing Permissions,
+/// only mean one channel or command is off limits.
+const ACCOUNT_ERROR_CODES: &[u64] = &[40001, 40002, 50014];
+
+impl Unauthorized {
+    /// `Some` for a 401, and for a 403 whose error `code` is in `ACCOUNT_ERROR_CODES`.
+    fn check(status: reqwest::StatusCode, body: &str) -> Option<anyhow::Error> {
+        let account_level = || serde_json::from_str::<Value>(body).ok()
+            .and_then(|v| v["code"].as_u64())
+            .is_some_and(|code| ACCOUNT_ERROR_CODES.contains(&code));
+        (status == reqwest::StatusCode::UNAUTHORIZED || (status == reqwest::StatusCode::FORBIDDEN && account_level()))
+            .then(|| Unauthorized(status).into())
+    }
+}
+
+/// Returned when Discord refuses a command because its definition changed since it was
+/// fetched (the bot was updated) or it's no longer registered. `nonce` identifies the refused send.
+#[derive(Debug)]
+pub struct OutdatedCommand {
+    pub nonce: String,
+}
+
+impl std::fmt::Display for OutdatedCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the command definition is outdated")
+    }
+}
+
+impl std::error::Error for OutdatedCommand {}
+
+impl OutdatedCommand {
+    /// `Some` for a 400 that points at the command version, or a 404 "Unknown application command".
+    fn check(status: reqwest::StatusCode, body: &str, nonce: &str) -> Option<anyhow::Error> {
+        let outdated = status == reqwest::StatusCode::BAD_REQUEST && (body.contains("outdated") || body.contains("\"version\""));
+        let unknown = status == reqwest::StatusCode::NOT_FOUND && (body.contains("10063") || body.contains("Unknown application command"));
+        (outdated || unknown).then(|| OutdatedCommand { nonce: nonce.to_string() }.into())
+    }
+}
+
 pub struct DiscordClient {
     client: Client,
     _config: Config,
+    /// Observer accounts never send interactions, whatever asks them to
+    read_only: bool,
     token: String,
     application_id: String,
+    correlation: Option<Arc<CorrelationStore>>,
+    session: Option<Arc<SessionState>>,
+    rate_limiter: RateLimiter,
+    commands: CommandCache,
+    /// `DISCORD_API` unless pointed elsewhere with `with_api_base`
+    api_base: String,
 }
 
 impl DiscordClient {
@@ -24,15 +115,9 @@ impl DiscordClient {
             client_builder = client_builder.user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/103.0.0.0 Safari/537.36");
         }
 
-        if let Some(proxy_ip) = &config.network.proxy_ip {
-             if let Some(proxy_port) = config.network.proxy_port {
-                let proxy_url = format!("http://{}:{}", proxy_ip, proxy_port);
-                let mut proxy = Proxy::all(&proxy_url)?;
-                if let (Some(user), Some(pass)) = (&config.network.proxy_auth_user, &config.network.proxy_auth_password) {
-                    proxy = proxy.basic_auth(user, pass);
-                }
-                client_builder = client_builder.proxy(proxy);
-             }
+        if let Some(proxy_url) = config.network.effective_proxy() {
+            // Credentials in the URL become the proxy's basic auth
+            client_builder = client_builder.proxy(Proxy::all(&proxy_url)?);
         }
 
         let client = client_builder.build()?;
@@ -42,27 +127,133 @@ impl DiscordClient {
         Ok(Self {
             client,
             token: config.system.user_token.clone(),
+            read_only: config.system.mode == AccountMode::Observer,
             _config: config,
             application_id,
+            correlation: None,
+            session: None,
+            rate_limiter: RateLimiter::new(),
+            commands: CommandCache::new(COMMAND_CACHE_TTL),
+            api_base: DISCORD_API.to_string(),
         })
     }
 
-    // Add get_commands_search to find commands by name
-    pub async fn get_command(&self, guild_id: &str, name: &str) -> Result<Option<Value>> {
+    /// Records every sent command's nonce so replies can be matched to it.
+    pub fn with_correlation(mut self, store: Arc<CorrelationStore>) -> Self {
+        self.correlation = Some(store);
+        self
+    }
+
+    /// Sends interactions with the gateway's session id instead of a random one.
+    pub fn with_session(mut self, session: Arc<SessionState>) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Sends REST requests to `base` ("http://127.0.0.1:1234/api/v9") instead of Discord,
+    /// e.g. the test server in `discord::mock`.
+    pub fn with_api_base(mut self, base: impl Into<String>) -> Self {
+        self.api_base = base.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// The gateway session id, or a random one while it's disconnected (or without a gateway).
+    fn session_id(&self) -> String {
+        match &self.session {
+            Some(session) => session.session_id_or_random(),
+            None => SessionState::new().session_id_or_random(),
+        }
+    }
+
+    /// Sends `request()` once `route`'s rate limit allows, queued behind earlier requests on
+    /// the same route. A 429 is waited out and retried up to `MAX_RATE_LIMIT_RETRIES` times
+    /// unless it asks for more than `MAX_RATE_LIMIT_WAIT`; then the 429 is returned.
+    async fn execute(&self, route: &str, request: impl Fn() -> RequestBuilder) -> Result<Response> {
+        let _turn = self.rate_limiter.queue(route).await;
+        let mut attempt = 0;
+        loop {
+            self.rate_limiter.wait(route).await;
+            let res = request().send().await?;
+            if res.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+                self.rate_limiter.update(route, res.headers());
+                return Ok(res);
+            }
+            let headers = res.headers().clone();
+            let body = res.text().await.unwrap_or_default();
+            let retry = self.rate_limiter.limited(route, &headers, &body);
+            attempt += 1;
+            if attempt > MAX_RATE_LIMIT_RETRIES || retry.wait > MAX_RATE_LIMIT_WAIT {
+                error!("Rate limited on {}{}: {}", route, if retry.global { " (global)" } else { "" }, body);
+                return Err(anyhow!("Rate limited on {} (retry after {:.1}s)", route, retry.wait.as_secs_f64()));
+            }
+            warn!("Rate limited on {}{}, retrying in {:.1}s", route, if retry.global { " (global)" } else { "" }, retry.wait.as_secs_f64());
+        }
+    }
+
+    pub async fn get_current_user(&self) -> Result<crate::discord::types::User> {
+        let url = format!("{}/users/@me", self.api_base);
+        let res = self.execute("GET /users/@me", || self.client.get(&url)
+            .header("Authorization", &self.token))
+            .await?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await?;
+            if let Some(e) = Unauthorized::check(status, &text) {
+                return Err(e);
+            }
+            return Err(anyhow!("Failed to get current user: {} - {}", status, text));
+        }
+        let user: crate::discord::types::User = res.json().await?;
+        Ok(user)
+    }
+
+    /// The command for `path` ("fish", "shop buy"), from the cached index. A command that's
+    /// missing gets the index fetched again, unless it was fetched within the last minute.
+    pub async fn get_command(&self, guild_id: &str, path: &str) -> Result<Option<Value>> {
         let commands = self.get_commands(guild_id).await?;
-        Ok(commands.into_iter().find(|c| c["name"] == name))
+        if let Some(command) = command_cache::find(&commands, path) {
+            return Ok(Some(command.clone()));
+        }
+        if self.commands.age(guild_id).is_some_and(|age| age < MIN_COMMAND_REFRESH) {
+            return Ok(None);
+        }
+        let commands = self.refresh_commands(guild_id).await?;
+        Ok(command_cache::find(&commands, path).cloned())
     }
 
-    pub async fn get_commands(&self, guild_id: &str) -> Result<Vec<Value>> {
-        let url = format!("https://discord.com/api/v9/guilds/{}/application-command-index", guild_id);
-        let res = self.client.get(&url)
-            .header("Authorization", &self.token)
-            .send()
+    /// The guild's Virtual Fisher commands, cached for `COMMAND_CACHE_TTL`.
+    pub async fn get_commands(&self, guild_id: &str) -> Result<Arc<Vec<Value>>> {
+        match self.commands.get(guild_id) {
+            Some(commands) => Ok(commands),
+            None => self.refresh_commands(guild_id).await,
+        }
+    }
+
+    /// Fetches the command index again, e.g. at startup or after the bot was updated.
+    pub async fn refresh_commands(&self, guild_id: &str) -> Result<Arc<Vec<Value>>> {
+        let commands = self.fetch_commands(guild_id).await?;
+        Ok(self.commands.store(guild_id, commands))
+    }
+
+    /// Drops the cached index, so the next lookup fetches it.
+    pub fn invalidate_commands(&self, guild_id: &str) {
+        self.commands.invalidate(guild_id);
+    }
+
+    async fn fetch_commands(&self, guild_id: &str) -> Result<Vec<Value>> {
+        let url = format!("{}/guilds/{}/application-command-index", self.api_base, guild_id);
+        let route = format!("GET /guilds/{}/application-command-index", guild_id);
+        let res = self.execute(&route, || self.client.get(&url)
+            .header("Authorization", &self.token))
             .await?;
 
         if !res.status().is_success() {
             let status = res.status();
             let text = res.text().await?;
+            if let Some(e) = Unauthorized::check(status, &text) {
+                return Err(e);
+            }
             error!("Failed to get commands: {} - {}", status, text);
             return Err(anyhow!("Failed to get commands: {}", status));
         }
@@ -86,10 +277,10 @@ impl DiscordClient {
     }
 
     pub async fn get_message(&self, channel_id: &str, message_id: &str) -> Result<crate::discord::types::Message> {
-        let url = format!("https://discord.com/api/v9/channels/{}/messages/{}", channel_id, message_id);
-        let res = self.client.get(&url)
-            .header("Authorization", &self.token)
-            .send()
+        let url = format!("{}/channels/{}/messages/{}", self.api_base, channel_id, message_id);
+        let route = format!("GET /channels/{}/messages", channel_id);
+        let res = self.execute(&route, || self.client.get(&url)
+            .header("Authorization", &self.token))
             .await?;
 
         if !res.status().is_success() {
@@ -101,47 +292,138 @@ impl DiscordClient {
         Ok(msg)
     }
 
+    /// The channel's newest `limit` messages (at most 100), newest first.
+    pub async fn get_messages(&self, channel_id: &str, limit: u32) -> Result<Vec<Message>> {
+        let url = format!("{}/channels/{}/messages", self.api_base, channel_id);
+        let route = format!("GET /channels/{}/messages", channel_id);
+        let limit = limit.clamp(1, 100).to_string();
+        let res = self.execute(&route, || self.client.get(&url)
+            .header("Authorization", &self.token)
+            .query(&[("limit", limit.as_str())]))
+            .await?;
+
+        if !res.status().is_success() {
+             let status = res.status();
+             let text = res.text().await?;
+             if let Some(e) = Unauthorized::check(status, &text) {
+                 return Err(e);
+             }
+             return Err(anyhow!("Failed to get messages: {} - {}", status, text));
+        }
+        Ok(res.json().await?)
+    }
+
+    /// Fetches an attachment (e.g. a captcha image) as bytes. Requests to Discord's CDN carry
+    /// the account's token, like the official client's; other hosts get a plain GET.
+    pub async fn download_attachment(&self, url: &str) -> Result<Vec<u8>> {
+        let parsed = url::Url::parse(url)?;
+        let mut request = self.client.get(parsed.clone());
+        if parsed.host_str().is_some_and(|host| CDN_HOSTS.contains(&host)) {
+            request = request.header("Authorization", &self.token);
+        }
+        let res = request.send().await?;
+        if !res.status().is_success() {
+            return Err(anyhow!("Failed to download {}: {}", url, res.status()));
+        }
+        if res.content_length().is_some_and(|len| len as usize > MAX_ATTACHMENT_BYTES) {
+            return Err(anyhow!("Attachment {} is over {} MB", url, MAX_ATTACHMENT_BYTES / 1024 / 1024));
+        }
+        let bytes = res.bytes().await?;
+        if bytes.len() > MAX_ATTACHMENT_BYTES {
+            return Err(anyhow!("Attachment {} is over {} MB", url, MAX_ATTACHMENT_BYTES / 1024 / 1024));
+        }
+        Ok(bytes.to_vec())
+    }
+
+    /// Sends a slash command. If Discord says `command` is outdated, the definition is fetched
+    /// again and the command resent once.
     pub async fn send_command(&self, guild_id: &str, channel_id: &str, command: &Value, options: Option<Vec<Value>>) -> Result<()> {
-        let url = "https://discord.com/api/v9/interactions";
+        self.send_fresh(guild_id, channel_id, command, options, |_| {}).await.map(|_| ())
+    }
+
+    /// Whether `send_command_and_wait` can be used, i.e. `with_correlation` was set.
+    pub fn matches_replies(&self) -> bool {
+        self.correlation.is_some()
+    }
 
-        let nonce = chrono::Utc::now().timestamp_millis() * 1000; // Simple nonce
+    /// Like `send_command`, but returns Virtual Fisher's reply to it (a deferred reply's final
+    /// edit, not the "thinking..." placeholder). Needs `with_correlation`.
+    pub async fn send_command_and_wait(&self, guild_id: &str, channel_id: &str, command: &Value, options: Option<Vec<Value>>, timeout: Duration) -> Result<Message> {
+        let store = self.correlation.clone()
+            .ok_or_else(|| anyhow!("Replies can't be matched without a correlation store"))?;
+        let registered = parking_lot::Mutex::new(Vec::new());
+        let sent = self.send_fresh(guild_id, channel_id, command, options, |nonce| {
+            store.expect_reply(nonce);
+            registered.lock().push(nonce.to_string());
+        }).await;
+        let nonce = match sent {
+            Ok(nonce) => nonce,
+            Err(e) => {
+                registered.lock().iter().for_each(|nonce| store.forget(nonce));
+                return Err(e);
+            }
+        };
+        store.wait_for_reply(&nonce, timeout).await
+    }
+
+    /// The outdated-command handling behind `send_command`. `before_send` sees each nonce
+    /// before it goes out; the one Discord accepted is returned.
+    async fn send_fresh(&self, guild_id: &str, channel_id: &str, command: &Value, options: Option<Vec<Value>>, before_send: impl Fn(&str)) -> Result<String> {
+        let nonce = new_nonce();
+        before_send(&nonce);
+        let err = match self.send_interaction(guild_id, channel_id, command, options.clone(), &nonce, None).await {
+            Ok(()) => return Ok(nonce),
+            Err(e) => e,
+        };
+        let Some(outdated) = err.downcast_ref::<OutdatedCommand>() else { return Err(err) };
+        let name = command["name"].as_str().unwrap_or_default();
+        warn!("/{} is outdated, fetching it again", name);
+        let commands = self.refresh_commands(guild_id).await?;
+        let fresh = command_cache::find(&commands, name).cloned()
+            .ok_or_else(|| anyhow!("/{} is outdated and no longer registered", name))?;
+        let nonce = new_nonce();
+        before_send(&nonce);
+        self.send_interaction(guild_id, channel_id, &fresh, options, &nonce, Some(&outdated.nonce)).await?;
+        Ok(nonce)
+    }
+
+    /// Sends `command` again for an earlier send (`retry_of` is its nonce) that got no reply.
+    pub async fn resend_command(&self, guild_id: &str, channel_id: &str, command: &Value, options: Vec<Value>, retry_of: &str) -> Result<()> {
+        self.send_interaction(guild_id, channel_id, command, Some(options), &new_nonce(), Some(retry_of)).await
+    }
+
+    #[tracing::instrument(skip_all, fields(command = command["name"].as_str().unwrap_or_default()))]
+    async fn send_interaction(&self, guild_id: &str, channel_id: &str, command: &Value, options: Option<Vec<Value>>, nonce: &str, retry_of: Option<&str>) -> Result<()> {
+        if self.read_only {
+            return Err(anyhow!("Observer mode: not sending /{}", command["name"].as_str().unwrap_or_default()));
+        }
+        crate::telemetry::record_command(&tracing::Span::current());
+        let url = format!("{}/interactions", self.api_base);
+
+        let options = options.unwrap_or_default();
+        // Before sending: the gateway's INTERACTION_CREATE can beat the HTTP response
+        if let Some(store) = &self.correlation {
+            store.record_sent(nonce, command["name"].as_str().unwrap_or_default(), &options, retry_of).await;
+        }
 
         let payload = json!({
             "type": 2,
             "application_id": self.application_id,
             "guild_id": guild_id,
             "channel_id": channel_id,
-            "session_id": "random_session_id_placeholder", // In real client we might need the session id from gateway
+            "session_id": self.session_id(),
             "data": {
                 "version": command["version"],
                 "id": command["id"],
                 "name": command["name"],
                 "type": command["type"],
-                "options": options.unwrap_or_default(),
+                "options": options,
                 "application_command": command,
                 "attachments": []
             },
-            "nonce": nonce.to_string()
+            "nonce": nonce
         });
 
-        // Note: The original code uses a random session_id generated locally.
-        // "session.join(choice(ascii_letters + digits) for _ in range(32))"
-        // So we can generate one here if needed or pass it in.
-
-        // Generate random session ID (32 chars)
-        use rand::distributions::Alphanumeric;
-        use rand::{thread_rng, Rng};
-        let session_id: String = thread_rng()
-            .sample_iter(&Alphanumeric)
-            .take(32)
-            .map(char::from)
-            .collect();
-
-        let mut payload_value = payload;
-        if let Some(obj) = payload_value.as_object_mut() {
-            obj.insert("session_id".to_string(), json!(session_id));
-        }
-
         let super_properties = json!({
             "os": "Windows",
             "browser": "Chrome",
@@ -162,23 +444,35 @@ impl DiscordClient {
         let super_properties_str = super_properties.to_string();
         let super_properties_base64 = general_purpose::STANDARD.encode(super_properties_str);
 
-        let res = self.client.post(url)
+        let sent = self.execute("POST /interactions", || self.client.post(&url)
             .header("Authorization", &self.token)
-            .header("x-super-properties", super_properties_base64)
+            .header("x-super-properties", &super_properties_base64)
             .header("origin", "https://discord.com")
             .header("referer", "https://discord.com/channels/@me") // Or specific channel
-            .json(&payload_value)
-            .send()
-            .await?;
+            .json(&payload))
+            .await;
+        let res = match sent {
+            Ok(res) => res,
+            Err(e) => {
+                // Still rate limited after the retries, or the request itself failed
+                if let Some(store) = &self.correlation {
+                    store.mark_rejected(nonce).await;
+                }
+                return Err(e);
+            }
+        };
 
         if !res.status().is_success() {
              let status = res.status();
              let text = res.text().await?;
-             // Handle 429
-             if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
-                 // Parse retry_after
-                 // For now just log
-                 error!("Rate limited: {}", text);
+             if let Some(e) = Unauthorized::check(status, &text) {
+                 return Err(e);
+             }
+             if let Some(store) = &self.correlation {
+                 store.mark_rejected(nonce).await;
+             }
+             if let Some(e) = OutdatedCommand::check(status, &text, nonce) {
+                 return Err(e);
              }
              return Err(anyhow!("Failed to send command: {} - {}", status, text));
         }
@@ -187,8 +481,10 @@ impl DiscordClient {
     }
 
     pub async fn interact_component(&self, guild_id: &str, channel_id: &str, message_id: &str, custom_id: &str, component_type: Option<u8>, values: Option<Vec<String>>) -> Result<()> {
-        let url = "https://discord.com/api/v9/interactions";
-        let nonce = chrono::Utc::now().timestamp_millis() * 1000;
+        if self.read_only {
+            return Err(anyhow!("Observer mode: not clicking {}", custom_id));
+        }
+        let nonce = new_nonce();
 
         let c_type = component_type.unwrap_or(2);
         let mut data = json!({
@@ -204,27 +500,97 @@ impl DiscordClient {
 
          let payload = json!({
             "type": 3,
-            "nonce": nonce.to_string(),
+            "nonce": nonce,
             "guild_id": guild_id,
             "channel_id": channel_id,
             "message_flags": 0,
             "message_id": message_id,
             "application_id": self.application_id,
             "data": data,
-            "session_id": "random_session_id_placeholder"
+            "session_id": self.session_id()
+        });
+        self.post_interaction(&payload, "interact component").await
+    }
+
+    /// Clicks the button on `msg` whose label contains `label` (case-insensitive), e.g. "Confirm purchase".
+    pub async fn click_button(&self, guild_id: &str, channel_id: &str, msg: &Message, label: &str) -> Result<()> {
+        let custom_id = parser::find_button(msg, label).ok_or_else(|| anyhow!("No '{}' button on message {}", label, msg.id))?;
+        self.interact_component(guild_id, channel_id, &msg.id, &custom_id, Some(2), None).await
+    }
+
+    /// Picks `value` in the select menu on `msg` whose placeholder contains `placeholder`; an
+    /// empty placeholder takes the first menu.
+    pub async fn select_menu(&self, guild_id: &str, channel_id: &str, msg: &Message, placeholder: &str, value: &str) -> Result<()> {
+        let (custom_id, component_type) = parser::find_select(msg, placeholder)
+            .ok_or_else(|| anyhow!("No '{}' select menu on message {}", placeholder, msg.id))?;
+        self.interact_component(guild_id, channel_id, &msg.id, &custom_id, Some(component_type), Some(vec![value.to_string()])).await
+    }
+
+    /// Answers `modal` (interaction type 5) with `(text input custom_id, value)` pairs. Inputs
+    /// left out are sent with their prefilled value.
+    pub async fn submit_modal(&self, guild_id: &str, channel_id: &str, modal: &Modal, values: &[(&str, &str)]) -> Result<()> {
+        if self.read_only {
+            return Err(anyhow!("Observer mode: not submitting {}", modal.custom_id));
+        }
+        let rows: Vec<Value> = modal.components.iter().map(|row| {
+            let inputs: Vec<Value> = row.components.iter().flatten().filter_map(|input| {
+                let custom_id = input.custom_id.as_deref()?;
+                let value = values.iter().find(|(id, _)| *id == custom_id).map(|(_, v)| *v)
+                    .or(input.value.as_deref())
+                    .unwrap_or_default();
+                Some(json!({ "type": input.r#type, "custom_id": custom_id, "value": value }))
+            }).collect();
+            json!({ "type": 1, "components": inputs })
+        }).collect();
+
+        let payload = json!({
+            "type": 5,
+            "nonce": new_nonce(),
+            "guild_id": guild_id,
+            "channel_id": channel_id,
+            "application_id": self.application_id,
+            "data": {
+                "id": modal.id,
+                "custom_id": modal.custom_id,
+                "components": rows
+            },
+            "session_id": self.session_id()
         });
+        self.post_interaction(&payload, "submit modal").await
+    }
 
-        let res = self.client.post(url)
+    /// POSTs a component or modal interaction; `what` names it in the error.
+    async fn post_interaction(&self, payload: &Value, what: &str) -> Result<()> {
+        let url = format!("{}/interactions", self.api_base);
+        let res = self.execute("POST /interactions", || self.client.post(&url)
             .header("Authorization", &self.token)
-            .json(&payload)
-            .send()
+            .json(payload))
             .await?;
 
         if !res.status().is_success() {
              let status = res.status();
              let text = res.text().await?;
-             return Err(anyhow!("Failed to interact component: {} - {}", status, text));
+             if let Some(e) = Unauthorized::check(status, &text) {
+                 return Err(e);
+             }
+             return Err(anyhow!("Failed to {}: {} - {}", what, status, text));
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn test_unauthorized_check() {
+        assert!(Unauthorized::check(StatusCode::UNAUTHORIZED, "").is_some());
+        assert!(Unauthorized::check(StatusCode::FORBIDDEN, r#"{"message": "You need to verify your account", "code": 40002}"#).is_some());
+        // A channel the account can't post in isn't a logout
+        assert!(Unauthorized::check(StatusCode::FORBIDDEN, r#"{"message": "Missing Permissions", "code": 50013}"#).is_none());
+        assert!(Unauthorized::check(StatusCode::FORBIDDEN, "<html>").is_none());
+        assert!(Unauthorized::check(StatusCode::BAD_REQUEST, r#"{"code": 40001}"#).is_none());
+    }
+}
@@ -0,0 +1,270 @@
+use anyhow::{Result, anyhow, bail};
+use serde_json::{json, Value};
+use std::fmt;
+
+const SUB_COMMAND: u64 = 1;
+const SUB_COMMAND_GROUP: u64 = 2;
+const STRING: u64 = 3;
+const INTEGER: u64 = 4;
+const BOOLEAN: u64 = 5;
+const NUMBER: u64 = 10;
+const ATTACHMENT: u64 = 11;
+
+/// A parameter value for `CommandBuilder::option`, converted to the type the schema declares.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OptionValue {
+    String(String),
+    Integer(i64),
+    Number(f64),
+    Boolean(bool),
+}
+
+impl fmt::Display for OptionValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptionValue::String(s) => write!(f, "{}", s),
+            OptionValue::Integer(n) => write!(f, "{}", n),
+            OptionValue::Number(x) => write!(f, "{}", x),
+            OptionValue::Boolean(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+impl From<&str> for OptionValue {
+    fn from(s: &str) -> Self {
+        OptionValue::String(s.to_string())
+    }
+}
+
+impl From<String> for OptionValue {
+    fn from(s: String) -> Self {
+        OptionValue::String(s)
+    }
+}
+
+impl From<i64> for OptionValue {
+    fn from(n: i64) -> Self {
+        OptionValue::Integer(n)
+    }
+}
+
+impl From<u64> for OptionValue {
+    fn from(n: u64) -> Self {
+        OptionValue::Integer(n as i64)
+    }
+}
+
+impl From<f64> for OptionValue {
+    fn from(x: f64) -> Self {
+        OptionValue::Number(x)
+    }
+}
+
+impl From<bool> for OptionValue {
+    fn from(b: bool) -> Self {
+        OptionValue::Boolean(b)
+    }
+}
+
+/// Builds a slash command's `options` from its schema (as `DiscordClient::get_command` returns
+/// it), so a misspelled subcommand or option, or a value of the wrong type, fails here instead
+/// of being sent. The first mistake is kept and returned by `build`.
+pub struct CommandBuilder<'a> {
+    command: &'a Value,
+    /// Subcommand group and subcommand picked so far, outermost first
+    path: Vec<&'a Value>,
+    params: Vec<Value>,
+    error: Option<anyhow::Error>,
+}
+
+impl<'a> CommandBuilder<'a> {
+    pub fn new(command: &'a Value) -> Self {
+        Self { command, path: Vec::new(), params: Vec::new(), error: None }
+    }
+
+    /// "/shop buy", for errors.
+    fn name(&self) -> String {
+        std::iter::once(self.command).chain(self.path.iter().copied())
+            .map(|def| def["name"].as_str().unwrap_or("?"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Option definitions at the current level.
+    fn schema(&self) -> &'a [Value] {
+        let level = self.path.last().copied().unwrap_or(self.command);
+        level["options"].as_array().map(Vec::as_slice).unwrap_or_default()
+    }
+
+    fn fail(&mut self, error: anyhow::Error) {
+        self.error.get_or_insert(error);
+    }
+
+    /// Picks subcommand group or subcommand `name` at the current level.
+    pub fn subcommand(mut self, name: &str) -> Self {
+        if self.error.is_some() {
+            return self;
+        }
+        match self.schema().iter().find(|o| is_subcommand(o) && o["name"] == name) {
+            Some(def) if self.params.is_empty() => self.path.push(def),
+            Some(_) => self.fail(anyhow!("/{}: subcommand '{}' after parameters", self.name(), name)),
+            None => self.fail(anyhow!("/{} has no subcommand '{}'", self.name(), name)),
+        }
+        self
+    }
+
+    /// Follows the first subcommand group/subcommand down to a leaf, for when only the
+    /// command was named.
+    pub fn first_subcommand(mut self) -> Self {
+        while self.error.is_none() && self.params.is_empty() {
+            let Some(def) = self.schema().iter().find(|o| is_subcommand(o)) else { break };
+            self.path.push(def);
+        }
+        self
+    }
+
+    /// Sets parameter `name` of the chosen subcommand. Strings are parsed for numeric and
+    /// boolean parameters, and matched against the choices' names when there are choices.
+    pub fn option(mut self, name: &str, value: impl Into<OptionValue>) -> Self {
+        if self.error.is_some() {
+            return self;
+        }
+        let Some(def) = self.schema().iter().find(|o| !is_subcommand(o) && o["name"] == name) else {
+            self.fail(anyhow!("/{} has no option '{}'", self.name(), name));
+            return self;
+        };
+        if self.params.iter().any(|p| p["name"] == name) {
+            self.fail(anyhow!("/{}: option '{}' set twice", self.name(), name));
+            return self;
+        }
+        match convert(def, value.into()) {
+            Ok(value) => self.params.push(json!({ "name": name, "type": def["type"], "value": value })),
+            Err(e) => self.fail(anyhow!("/{} {}: {}", self.name(), name, e)),
+        }
+        self
+    }
+
+    /// The words after the command name, as typed ("deposit 5000"): leading subcommand names,
+    /// then the rest as the first parameter's value (all of it, for a string).
+    pub fn text(mut self, parts: &[&str]) -> Self {
+        let mut parts = parts;
+        while let Some((first, rest)) = parts.split_first() {
+            if self.error.is_some() || !self.schema().iter().any(|o| is_subcommand(o) && o["name"] == *first) {
+                break;
+            }
+            self = self.subcommand(first);
+            parts = rest;
+        }
+        if parts.is_empty() || self.error.is_some() {
+            return self;
+        }
+        let Some(def) = self.schema().iter().find(|o| !is_subcommand(o)) else {
+            self.fail(anyhow!("/{} has no subcommand or option for '{}'", self.name(), parts.join(" ")));
+            return self;
+        };
+        let name = def["name"].as_str().unwrap_or_default();
+        if def["type"].as_u64().unwrap_or(STRING) == STRING {
+            self.option(name, parts.join(" "))
+        } else if let [value] = parts {
+            self.option(name, *value)
+        } else {
+            self.fail(anyhow!("/{} {}: expected one value, got '{}'", self.name(), name, parts.join(" ")));
+            self
+        }
+    }
+
+    /// The interaction's `options`: the parameters, nested inside the chosen subcommands.
+    /// `None` for a command used without any.
+    pub fn build(self) -> Result<Option<Vec<Value>>> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        let schema = self.schema();
+        if schema.iter().any(is_subcommand) {
+            let names: Vec<&str> = schema.iter().filter(|o| is_subcommand(o)).filter_map(|o| o["name"].as_str()).collect();
+            bail!("/{} needs a subcommand: {}", self.name(), names.join(", "));
+        }
+        if let Some(missing) = schema.iter().find(|o| o["required"] == true && !self.params.iter().any(|p| p["name"] == o["name"])) {
+            bail!("/{} needs option '{}'", self.name(), missing["name"].as_str().unwrap_or_default());
+        }
+        let mut options = self.params;
+        for def in self.path.iter().rev() {
+            options = vec![json!({ "name": def["name"], "type": def["type"], "options": options })];
+        }
+        Ok((!options.is_empty()).then_some(options))
+    }
+}
+
+fn is_subcommand(def: &Value) -> bool {
+    matches!(def["type"].as_u64(), Some(SUB_COMMAND | SUB_COMMAND_GROUP))
+}
+
+/// `value` as the JSON the option's type takes, mapped to a choice's value when it has choices.
+fn convert(def: &Value, value: OptionValue) -> Result<Value> {
+    let value = match (def["type"].as_u64().unwrap_or(STRING), value) {
+        (INTEGER, OptionValue::Integer(n)) => json!(n),
+        (INTEGER, OptionValue::String(s)) => json!(s.replace(',', "").parse::<i64>().map_err(|_| anyhow!("'{}' is not an integer", s))?),
+        (NUMBER, OptionValue::Integer(n)) => json!(n),
+        (NUMBER, OptionValue::Number(x)) => json!(x),
+        (NUMBER, OptionValue::String(s)) => json!(s.replace(',', "").parse::<f64>().map_err(|_| anyhow!("'{}' is not a number", s))?),
+        (BOOLEAN, OptionValue::Boolean(b)) => json!(b),
+        (BOOLEAN, OptionValue::String(s)) => json!(s.parse::<bool>().map_err(|_| anyhow!("'{}' is not true or false", s))?),
+        (ATTACHMENT, _) => bail!("attachment options aren't supported"),
+        // Strings, and users/channels/roles/mentionables by id
+        (STRING | 6..=9, value) => json!(value.to_string()),
+        (kind, value) => bail!("{} doesn't fit option type {}", value, kind),
+    };
+    let Some(choices) = def["choices"].as_array().filter(|c| !c.is_empty()) else { return Ok(value) };
+    if choices.iter().any(|c| c["value"] == value) {
+        return Ok(value);
+    }
+    let by_name = value.as_str().and_then(|s| choices.iter().find(|c| c["name"].as_str().is_some_and(|n| n.eq_ignore_ascii_case(s))));
+    match by_name {
+        Some(choice) => Ok(choice["value"].clone()),
+        None => bail!("{} is not one of its choices", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shop() -> Value {
+        json!({ "name": "shop", "options": [
+            { "type": 1, "name": "view" },
+            { "type": 1, "name": "buy", "options": [
+                { "type": 3, "name": "rod", "choices": [{ "name": "Fiberglass Rod", "value": "fiberglass" }] },
+                { "type": 4, "name": "amount" }
+            ]},
+            { "type": 2, "name": "clan", "options": [
+                { "type": 1, "name": "deposit", "options": [{ "type": 4, "name": "amount", "required": true }] }
+            ]}
+        ]})
+    }
+
+    #[test]
+    fn test_command_builder() {
+        let shop = shop();
+        let options = CommandBuilder::new(&shop).subcommand("buy").option("rod", "fiberglass rod").option("amount", "2").build().unwrap();
+        assert_eq!(options, Some(vec![json!({ "name": "buy", "type": 1, "options": [
+            { "name": "rod", "type": 3, "value": "fiberglass" },
+            { "name": "amount", "type": 4, "value": 2 }
+        ]})]));
+
+        // Subcommand group, from typed text
+        let options = CommandBuilder::new(&shop).text(&["clan", "deposit", "5,000"]).build().unwrap();
+        assert_eq!(options, Some(vec![json!({ "name": "clan", "type": 2, "options": [
+            { "name": "deposit", "type": 1, "options": [{ "name": "amount", "type": 4, "value": 5000 }] }
+        ]})]));
+        assert_eq!(CommandBuilder::new(&shop).first_subcommand().build().unwrap().unwrap()[0]["name"], "view");
+        assert_eq!(CommandBuilder::new(&json!({ "name": "fish" })).build().unwrap(), None);
+
+        let error = |builder: CommandBuilder| builder.build().unwrap_err().to_string();
+        assert_eq!(error(CommandBuilder::new(&shop)), "/shop needs a subcommand: view, buy, clan");
+        assert_eq!(error(CommandBuilder::new(&shop).subcommand("sell")), "/shop has no subcommand 'sell'");
+        assert_eq!(error(CommandBuilder::new(&shop).subcommand("buy").option("boat", "x")), "/shop buy has no option 'boat'");
+        assert_eq!(error(CommandBuilder::new(&shop).subcommand("buy").option("amount", "lots")), "/shop buy amount: 'lots' is not an integer");
+        assert_eq!(error(CommandBuilder::new(&shop).subcommand("buy").option("rod", "Golden Rod")), "/shop buy rod: \"Golden Rod\" is not one of its choices");
+        assert_eq!(error(CommandBuilder::new(&shop).subcommand("clan").subcommand("deposit")), "/shop clan deposit needs option 'amount'");
+    }
+}
@@ -0,0 +1,90 @@
+use parking_lot::RwLock;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Each guild's application-command-index, fetched once and shared by everything that sends
+/// commands through `DiscordClient`. Entries older than the TTL are fetched again.
+pub struct CommandCache {
+    ttl: Duration,
+    guilds: RwLock<HashMap<String, Cached>>,
+}
+
+struct Cached {
+    fetched_at: Instant,
+    commands: Arc<Vec<Value>>,
+}
+
+impl CommandCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, guilds: RwLock::new(HashMap::new()) }
+    }
+
+    /// `None` when the guild's index was never fetched or has expired.
+    pub fn get(&self, guild_id: &str) -> Option<Arc<Vec<Value>>> {
+        self.guilds.read().get(guild_id)
+            .filter(|c| c.fetched_at.elapsed() < self.ttl)
+            .map(|c| c.commands.clone())
+    }
+
+    pub fn store(&self, guild_id: &str, commands: Vec<Value>) -> Arc<Vec<Value>> {
+        let commands = Arc::new(commands);
+        self.guilds.write().insert(guild_id.to_string(), Cached { fetched_at: Instant::now(), commands: commands.clone() });
+        commands
+    }
+
+    /// How long ago the guild's index was fetched.
+    pub fn age(&self, guild_id: &str) -> Option<Duration> {
+        self.guilds.read().get(guild_id).map(|c| c.fetched_at.elapsed())
+    }
+
+    pub fn invalidate(&self, guild_id: &str) {
+        self.guilds.write().remove(guild_id);
+    }
+}
+
+/// The top-level command for `path` ("shop buy"): its first word names the command, and the
+/// rest must be subcommand groups/subcommands it has.
+pub fn find<'a>(commands: &'a [Value], path: &str) -> Option<&'a Value> {
+    let mut words = path.split_whitespace();
+    let name = words.next()?;
+    let command = commands.iter().find(|c| c["name"] == name)?;
+    let mut level = command;
+    for word in words {
+        level = level["options"].as_array()?.iter()
+            .find(|o| o["name"] == word && matches!(o["type"].as_u64(), Some(1 | 2)))?;
+    }
+    Some(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_command_cache() {
+        let commands = vec![
+            json!({ "name": "fish" }),
+            json!({ "name": "shop", "options": [{ "type": 1, "name": "buy" }, { "type": 3, "name": "item" }] }),
+        ];
+        assert_eq!(find(&commands, "shop buy").unwrap()["name"], "shop");
+        assert_eq!(find(&commands, "fish").unwrap()["name"], "fish");
+        // Parameters aren't part of a path
+        assert!(find(&commands, "shop item").is_none());
+        assert!(find(&commands, "sell").is_none());
+
+        let cache = CommandCache::new(Duration::from_secs(60));
+        assert!(cache.get("1").is_none());
+        cache.store("1", commands);
+        assert_eq!(cache.get("1").unwrap().len(), 2);
+        cache.invalidate("1");
+        assert!(cache.get("1").is_none() && cache.age("1").is_none());
+
+        let expired = CommandCache::new(Duration::ZERO);
+        expired.store("1", Vec::new());
+        assert!(expired.get("1").is_none());
+        assert!(expired.age("1").is_some());
+    }
+}
@@ -1,20 +1,42 @@
 use crate::config::Config;
+use crate::discord::gateway_health::GatewayHealth;
+use crate::discord::proxy;
+use crate::discord::session::SessionState;
 use crate::discord::types::{GatewayPayload, HelloPayload};
+use crate::engine::health::HealthMonitor;
+use crate::notifications::event::{EventKind, Notification, Severity};
+use crate::notifications::hub::Notifier;
 use anyhow::{Result, anyhow};
 use futures_util::{SinkExt, StreamExt};
 use log::{info, error, debug, warn};
 use serde_json::json;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 use tokio::net::TcpStream;
 use tokio::sync::Mutex;
 use tokio::time::interval;
-use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::{client_async_tls, connect_async, MaybeTlsStream, WebSocketStream};
 use tokio_tungstenite::tungstenite::Message;
 use url::Url;
 
 // Gateway constants
 const GATEWAY_URL: &str = "wss://gateway.discord.gg/?v=9&encoding=json";
+/// Close code Discord uses when the token in IDENTIFY is rejected
+const AUTHENTICATION_FAILED: u16 = 4004;
+
+/// Returned by `Gateway::run` when Discord rejects the token. Reconnecting won't help;
+/// callers can `downcast_ref` for it to alert the owner.
+#[derive(Debug)]
+pub struct AuthenticationFailed;
+
+impl std::fmt::Display for AuthenticationFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Gateway authentication failed ({}): the token is invalid or was reset", AUTHENTICATION_FAILED)
+    }
+}
+
+impl std::error::Error for AuthenticationFailed {}
 
 pub struct Gateway {
     config: Config,
@@ -24,6 +46,12 @@ pub struct Gateway {
     session_id: Option<String>,
     event_sender: tokio::sync::mpsc::Sender<GatewayPayload>,
     running: bool,
+    health: Option<Arc<HealthMonitor>>,
+    stats: Arc<GatewayHealth>,
+    /// Published copy of `session_id` while the session is live
+    session: Option<Arc<SessionState>>,
+    /// `GATEWAY_URL` unless pointed elsewhere with `with_url`
+    url: String,
 }
 
 impl Gateway {
@@ -36,19 +64,87 @@ impl Gateway {
             session_id: None,
             event_sender,
             running: false,
+            health: None,
+            stats: Arc::new(GatewayHealth::new()),
+            session: None,
+            url: GATEWAY_URL.to_string(),
         }
     }
 
+    /// Reports connection state and activity to `health`.
+    pub fn with_health(mut self, health: Arc<HealthMonitor>) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Publishes the session id from READY for `DiscordClient::with_session`.
+    pub fn with_session(mut self, session: Arc<SessionState>) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Connects to `url` instead of Discord's gateway, e.g. the test server in `discord::mock`.
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Heartbeat RTT, missed ACKs, reconnects and the last event, for the TUI.
+    pub fn stats(&self) -> Arc<GatewayHealth> {
+        self.stats.clone()
+    }
+
     pub async fn connect(&mut self) -> Result<()> {
         info!("Connecting to gateway...");
-        let url = Url::parse(GATEWAY_URL)?;
-        let (ws_stream, _) = connect_async(url.as_str()).await?;
+        let url = Url::parse(&self.url)?;
+        let (ws_stream, _) = match self.config.network.effective_proxy() {
+            Some(proxy_url) => {
+                let host = url.host_str().unwrap_or_default();
+                let tunnel = proxy::connect(&proxy_url, host, url.port_or_known_default().unwrap_or(443)).await?;
+                client_async_tls(url.as_str(), tunnel).await?
+            },
+            None => connect_async(url.as_str()).await?,
+        };
         info!("Connected to gateway!");
         self.ws_stream = Some(ws_stream);
         self.running = true;
         Ok(())
     }
 
+    /// Runs and reconnects until the event receiver is dropped, notifying the owner about failure streaks.
+    pub async fn run_forever(mut self, notifier: Notifier) {
+        let mut failures: u32 = 0;
+        let mut first = true;
+        loop {
+            if !std::mem::take(&mut first) {
+                self.stats.reconnecting();
+            }
+            if let Err(e) = self.run().await {
+                error!("Gateway error: {}", e);
+                failures += 1;
+                // First failure of a streak, then about every 5 minutes while it lasts
+                if failures == 1 || failures.is_multiple_of(60) {
+                    // A rejected token won't fix itself, unlike a dropped connection
+                    let (kind, severity) = if e.downcast_ref::<AuthenticationFailed>().is_some() {
+                        (EventKind::TokenInvalid, Severity::Critical)
+                    } else {
+                        (EventKind::Error, Severity::Warning)
+                    };
+                    notifier.send(Notification::new(kind, severity,
+                        format!("Gateway error ({} in a row)", failures), e.to_string()));
+                }
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            } else {
+                failures = 0;
+                if self.event_sender.is_closed() {
+                    break;
+                }
+                // Clean exit means a reconnect opcode; reconnect right away
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+        }
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         if self.ws_stream.is_none() {
             self.connect().await?;
@@ -82,6 +178,9 @@ impl Gateway {
 
         let (incoming_tx, mut incoming_rx) = tokio::sync::mpsc::channel::<GatewayPayload>(100);
 
+        let auth_failed = Arc::new(AtomicBool::new(false));
+        let auth_failed_reader = auth_failed.clone();
+
         // Reader task
         let _reader_handle = tokio::spawn(async move {
             while let Some(message) = read.next().await {
@@ -89,15 +188,18 @@ impl Gateway {
                     Ok(Message::Text(text)) => {
                         match serde_json::from_str::<GatewayPayload>(&text) {
                             Ok(payload) => {
-                                if let Err(_) = incoming_tx.send(payload).await {
+                                if incoming_tx.send(payload).await.is_err() {
                                     break;
                                 }
                             },
                             Err(e) => error!("Failed to parse payload: {}", e),
                         }
                     },
-                    Ok(Message::Close(_)) => {
-                        info!("Gateway closed connection");
+                    Ok(Message::Close(frame)) => {
+                        info!("Gateway closed connection: {:?}", frame);
+                        if frame.is_some_and(|f| u16::from(f.code) == AUTHENTICATION_FAILED) {
+                            auth_failed_reader.store(true, Ordering::Relaxed);
+                        }
                         break;
                     },
                     Err(e) => {
@@ -124,8 +226,14 @@ impl Gateway {
                         error!("Failed to send heartbeat: {}", e);
                         break;
                     }
+                    self.stats.heartbeat_sent();
                 }
                 Some(payload) = incoming_rx.recv() => {
+                    if let Some(health) = &self.health {
+                        health.record_gateway_activity();
+                    }
+                    self.stats.event_received();
+
                     // Update sequence
                     if let Some(s) = payload.s {
                         let mut seq = sequence.lock().await;
@@ -146,21 +254,32 @@ impl Gateway {
                         },
                         11 => { // Heartbeat ACK
                             debug!("Heartbeat ACK");
+                            self.stats.ack_received();
                         },
                         0 => { // Dispatch
                             // Intercept READY to capture session_id
                             if let Some(ref t) = payload.t {
+                                if t == "READY" || t == "RESUMED" {
+                                    if let Some(health) = &self.health {
+                                        health.set_gateway_connected(true);
+                                    }
+                                    self.stats.set_connected(true);
+                                }
+                                if t == "RESUMED" {
+                                    self.publish_session(self.session_id.clone());
+                                }
                                 if t == "READY" {
                                     if let Some(d) = &payload.d {
                                         if let Some(sid) = d.get("session_id").and_then(|v| v.as_str()) {
                                             self.session_id = Some(sid.to_string());
+                                            self.publish_session(Some(sid.to_string()));
                                             info!("Session ID acquired: {}", sid);
                                         }
                                     }
                                 }
                             }
 
-                            if let Err(_) = self.event_sender.send(payload).await {
+                            if self.event_sender.send(payload).await.is_err() {
                                 break;
                             }
                         },
@@ -190,9 +309,24 @@ impl Gateway {
 
         // Cleanup
         self.running = false;
+        if let Some(health) = &self.health {
+            health.set_gateway_connected(false);
+        }
+        self.stats.set_connected(false);
+        // Kept for RESUME, but not live until RESUMED
+        self.publish_session(None);
+        if auth_failed.load(Ordering::Relaxed) {
+            return Err(AuthenticationFailed.into());
+        }
         Ok(())
     }
 
+    fn publish_session(&self, session_id: Option<String>) {
+        if let Some(session) = &self.session {
+            session.set(session_id);
+        }
+    }
+
     async fn identify(&mut self) -> Result<()> {
         let msg = if self.session_id.is_some() && self.sequence.is_some() {
             info!("Resuming session...");
@@ -0,0 +1,113 @@
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+
+/// Websocket liveness recorded by `Gateway`, shown in the TUI status bar.
+#[derive(Debug, Default)]
+pub struct GatewayHealth {
+    stats: Mutex<GatewayStats>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GatewayStats {
+    /// Between READY/RESUMED and the connection dropping
+    pub connected: bool,
+    /// Heartbeat to ACK time of the last acknowledged heartbeat
+    pub heartbeat_rtt: Option<Duration>,
+    /// Heartbeats sent while the previous one was still unacknowledged
+    pub missed_acks: u32,
+    pub reconnects: u32,
+    pub last_event: Option<Instant>,
+    heartbeat_sent: Option<Instant>,
+}
+
+impl GatewayHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_connected(&self, connected: bool) {
+        self.stats.lock().connected = connected;
+    }
+
+    pub fn heartbeat_sent(&self) {
+        let mut stats = self.stats.lock();
+        if stats.heartbeat_sent.is_some() {
+            stats.missed_acks += 1;
+        }
+        stats.heartbeat_sent = Some(Instant::now());
+    }
+
+    pub fn ack_received(&self) {
+        let mut stats = self.stats.lock();
+        if let Some(sent) = stats.heartbeat_sent.take() {
+            stats.heartbeat_rtt = Some(sent.elapsed());
+        }
+    }
+
+    pub fn event_received(&self) {
+        self.stats.lock().last_event = Some(Instant::now());
+    }
+
+    /// A new connection starts with no heartbeat in flight.
+    pub fn reconnecting(&self) {
+        let mut stats = self.stats.lock();
+        stats.reconnects += 1;
+        stats.connected = false;
+        stats.heartbeat_sent = None;
+    }
+
+    pub fn snapshot(&self) -> GatewayStats {
+        self.stats.lock().clone()
+    }
+}
+
+impl GatewayStats {
+    /// e.g. "WS 84ms, event 3s ago, 2 reconnects"; counters only show once non-zero.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![match (self.connected, self.heartbeat_rtt) {
+            (false, _) => "WS down".to_string(),
+            (true, Some(rtt)) => format!("WS {}ms", rtt.as_millis()),
+            (true, None) => "WS up".to_string(),
+        }];
+        if let Some(at) = self.last_event {
+            parts.push(format!("event {}s ago", at.elapsed().as_secs()));
+        }
+        if self.reconnects > 0 {
+            parts.push(format!("{} reconnect{}", self.reconnects, if self.reconnects == 1 { "" } else { "s" }));
+        }
+        if self.missed_acks > 0 {
+            parts.push(format!("{} missed ACK{}", self.missed_acks, if self.missed_acks == 1 { "" } else { "s" }));
+        }
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gateway_health() {
+        let health = GatewayHealth::new();
+        assert_eq!(health.snapshot().summary(), "WS down");
+
+        health.set_connected(true);
+        health.heartbeat_sent();
+        health.ack_received();
+        let stats = health.snapshot();
+        assert!(stats.heartbeat_rtt.is_some());
+        assert_eq!(stats.missed_acks, 0);
+
+        // The second heartbeat goes out with the first still unacknowledged
+        health.heartbeat_sent();
+        health.heartbeat_sent();
+        assert_eq!(health.snapshot().missed_acks, 1);
+
+        health.reconnecting();
+        health.heartbeat_sent();
+        health.event_received();
+        let stats = health.snapshot();
+        assert_eq!((stats.connected, stats.missed_acks, stats.reconnects), (false, 1, 1));
+        assert_eq!(stats.summary(), "WS down, event 0s ago, 1 reconnect, 1 missed ACK");
+    }
+}
@@ -0,0 +1,381 @@
+use crate::config::Config;
+use anyhow::Result;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures_util::{SinkExt, StreamExt};
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+/// The account the mock logs in as.
+pub const MOCK_USER_ID: &str = "100000000000000001";
+pub const MOCK_SESSION_ID: &str = "mock-session";
+
+/// A local stand-in for the parts of Discord the bot talks to: the command index,
+/// `interactions`, channel messages and `users/@me` over HTTP, and a gateway that says
+/// hello, answers heartbeats, sends READY and forwards dispatches. Interactions for a
+/// command with a `reply_to` reply are acknowledged and answered over the gateway, the way
+/// Virtual Fisher's replies arrive. Point `DiscordClient::with_api_base` at `api_base()` and
+/// `Gateway::with_url` at `gateway_url()`.
+pub struct MockDiscord {
+    api_base: String,
+    gateway_url: String,
+    state: Arc<MockState>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+struct MockState {
+    application_id: String,
+    commands: Vec<Value>,
+    /// Per channel, oldest first
+    messages: Mutex<HashMap<String, Vec<Value>>>,
+    interactions: Mutex<Vec<Value>>,
+    /// Command name to the message sent back for it
+    replies: Mutex<HashMap<String, Value>>,
+    dispatches: broadcast::Sender<(String, Value)>,
+    next_id: AtomicU64,
+    sessions: AtomicUsize,
+}
+
+impl MockState {
+    fn next_id(&self) -> String {
+        (900_000_000_000_000_000 + self.next_id.fetch_add(1, Ordering::Relaxed)).to_string()
+    }
+
+    fn dispatch(&self, t: &str, d: Value) {
+        // Nobody connected yet is fine: the dispatch is simply missed, like on Discord
+        let _ = self.dispatches.send((t.to_string(), d));
+    }
+}
+
+impl MockDiscord {
+    /// Serves `commands` ("fish", "sell", ...) as `[system] application_id`'s, on free local ports.
+    pub async fn start(config: &Config, commands: &[&str]) -> Result<Self> {
+        let application_id = config.system.application_id.to_string();
+        let commands = commands.iter().enumerate().map(|(i, name)| json!({
+            "id": format!("{}", 800_000 + i),
+            "application_id": application_id,
+            "version": "1",
+            "type": 1,
+            "name": name,
+            "description": format!("/{}", name),
+            "options": [],
+        })).collect();
+        let state = Arc::new(MockState {
+            application_id,
+            commands,
+            messages: Mutex::new(HashMap::new()),
+            interactions: Mutex::new(Vec::new()),
+            replies: Mutex::new(HashMap::new()),
+            dispatches: broadcast::channel(256).0,
+            next_id: AtomicU64::new(1),
+            sessions: AtomicUsize::new(0),
+        });
+
+        let router = Router::new()
+            .route("/api/v9/users/@me", get(current_user))
+            .route("/api/v9/guilds/:guild_id/application-command-index", get(command_index))
+            .route("/api/v9/channels/:channel_id/messages", get(channel_messages))
+            .route("/api/v9/channels/:channel_id/messages/:message_id", get(channel_message))
+            .route("/api/v9/interactions", post(interaction))
+            .with_state(state.clone());
+        let http = TcpListener::bind("127.0.0.1:0").await?;
+        let api_base = format!("http://{}/api/v9", http.local_addr()?);
+        let ws = TcpListener::bind("127.0.0.1:0").await?;
+        let gateway_url = format!("ws://{}/?v=9&encoding=json", ws.local_addr()?);
+
+        let gateway_state = state.clone();
+        let tasks = vec![
+            tokio::spawn(async move {
+                let _ = axum::serve(http, router).await;
+            }),
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = ws.accept().await {
+                    tokio::spawn(serve_gateway(stream, gateway_state.clone()));
+                }
+            }),
+        ];
+        Ok(Self { api_base, gateway_url, state, tasks })
+    }
+
+    pub fn api_base(&self) -> &str {
+        &self.api_base
+    }
+
+    pub fn gateway_url(&self) -> &str {
+        &self.gateway_url
+    }
+
+    /// Answers every `/command` interaction with `reply` (`content`, `embeds`, ...), sent as
+    /// Virtual Fisher's MESSAGE_CREATE after the INTERACTION_CREATE.
+    pub fn reply_to(&self, command: &str, reply: Value) {
+        self.state.replies.lock().insert(command.to_string(), reply);
+    }
+
+    /// Sends a gateway dispatch to every connected session.
+    pub fn dispatch(&self, t: &str, d: Value) {
+        self.state.dispatch(t, d);
+    }
+
+    /// Adds a message to `channel_id`'s history, as returned by the messages endpoints.
+    pub fn add_message(&self, channel_id: &str, message: Value) {
+        self.state.messages.lock().entry(channel_id.to_string()).or_default().push(message);
+    }
+
+    /// Every interaction POSTed so far, oldest first.
+    pub fn interactions(&self) -> Vec<Value> {
+        self.state.interactions.lock().clone()
+    }
+
+    /// The first `/command` interaction, waiting up to `timeout` for it.
+    pub async fn wait_for_interaction(&self, command: &str, timeout: Duration) -> Option<Value> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let found = self.state.interactions.lock().iter().find(|i| i["data"]["name"] == command).cloned();
+            if found.is_some() || Instant::now() >= deadline {
+                return found;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    /// Waits up to `timeout` for a gateway client to identify, so dispatches reach it.
+    pub async fn wait_for_session(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        while self.state.sessions.load(Ordering::Relaxed) == 0 {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        true
+    }
+}
+
+impl Drop for MockDiscord {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+async fn current_user() -> Json<Value> {
+    Json(mock_user())
+}
+
+async fn command_index(State(state): State<Arc<MockState>>, Path(_guild_id): Path<String>) -> Json<Value> {
+    Json(json!({ "application_commands": state.commands }))
+}
+
+async fn channel_messages(State(state): State<Arc<MockState>>, Path(channel_id): Path<String>,
+                          Query(query): Query<HashMap<String, String>>) -> Json<Value> {
+    let limit = query.get("limit").and_then(|l| l.parse().ok()).unwrap_or(50);
+    let messages = state.messages.lock().get(&channel_id).cloned().unwrap_or_default();
+    Json(Value::Array(messages.into_iter().rev().take(limit).collect()))
+}
+
+async fn channel_message(State(state): State<Arc<MockState>>,
+                         Path((channel_id, message_id)): Path<(String, String)>) -> Result<Json<Value>, StatusCode> {
+    let messages = state.messages.lock();
+    messages.get(&channel_id)
+        .and_then(|m| m.iter().find(|m| m["id"] == message_id.as_str()))
+        .map(|m| Json(m.clone()))
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Records the interaction; slash commands are acknowledged and, with a `reply_to`, answered.
+async fn interaction(State(state): State<Arc<MockState>>, Json(payload): Json<Value>) -> StatusCode {
+    state.interactions.lock().push(payload.clone());
+    if payload["type"] != 2 {
+        return StatusCode::NO_CONTENT;
+    }
+    let interaction_id = state.next_id();
+    let name = payload["data"]["name"].as_str().unwrap_or_default().to_string();
+    state.dispatch("INTERACTION_CREATE", json!({ "id": interaction_id, "nonce": payload["nonce"] }));
+
+    let Some(reply) = state.replies.lock().get(&name).cloned() else { return StatusCode::NO_CONTENT };
+    let channel_id = payload["channel_id"].as_str().unwrap_or_default().to_string();
+    let mut message = json!({
+        "id": state.next_id(),
+        "channel_id": channel_id,
+        "author": { "id": state.application_id, "username": "Virtual Fisher", "discriminator": "0", "bot": true },
+        "content": "",
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "embeds": [],
+        "components": [],
+        "attachments": [],
+        "interaction_metadata": { "id": interaction_id, "user": mock_user() },
+        "interaction": { "id": interaction_id, "name": name, "user": mock_user() },
+    });
+    if let (Some(message), Some(reply)) = (message.as_object_mut(), reply.as_object()) {
+        message.extend(reply.clone());
+    }
+    state.messages.lock().entry(channel_id).or_default().push(message.clone());
+    state.dispatch("MESSAGE_CREATE", message);
+    StatusCode::NO_CONTENT
+}
+
+fn mock_user() -> Value {
+    json!({ "id": MOCK_USER_ID, "username": "angler", "discriminator": "0", "bot": false })
+}
+
+/// One gateway connection: HELLO, then READY (or RESUMED) once identified, heartbeat ACKs,
+/// and every dispatch with a sequence number.
+async fn serve_gateway(stream: TcpStream, state: Arc<MockState>) {
+    let Ok(ws) = tokio_tungstenite::accept_async(stream).await else { return };
+    let (mut write, mut read) = ws.split();
+    let mut dispatches = state.dispatches.subscribe();
+    let mut sequence = 0u64;
+    let hello = json!({ "op": 10, "d": { "heartbeat_interval": 45000 }, "s": null, "t": null });
+    if write.send(WsMessage::Text(hello.to_string())).await.is_err() {
+        return;
+    }
+    let mut identified = false;
+    loop {
+        let outgoing = tokio::select! {
+            incoming = read.next() => {
+                let Some(Ok(WsMessage::Text(text))) = incoming else { break };
+                let Ok(payload) = serde_json::from_str::<Value>(&text) else { continue };
+                match payload["op"].as_u64() {
+                    Some(1) => json!({ "op": 11, "d": null, "s": null, "t": null }),
+                    Some(op @ (2 | 6)) => {
+                        if !identified {
+                            identified = true;
+                            state.sessions.fetch_add(1, Ordering::Relaxed);
+                        }
+                        sequence += 1;
+                        let (t, d) = if op == 2 {
+                            ("READY", json!({ "session_id": MOCK_SESSION_ID, "user": mock_user() }))
+                        } else {
+                            ("RESUMED", json!({}))
+                        };
+                        json!({ "op": 0, "d": d, "s": sequence, "t": t })
+                    },
+                    _ => continue,
+                }
+            },
+            dispatch = dispatches.recv() => match dispatch {
+                Ok((t, d)) => {
+                    sequence += 1;
+                    json!({ "op": 0, "d": d, "s": sequence, "t": t })
+                },
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        };
+        if write.send(WsMessage::Text(outgoing.to_string())).await.is_err() {
+            break;
+        }
+    }
+    if identified {
+        state.sessions.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discord::client::DiscordClient;
+    use crate::discord::gateway::Gateway;
+    use crate::discord::session::SessionState;
+    use crate::discord::types::GatewayPayload;
+    use crate::engine::bot::Bot;
+    use crate::engine::cooldown::CooldownManager;
+    use crate::engine::correlation::CorrelationStore;
+    use crate::engine::database::Database;
+    use crate::engine::capture::EmbedCapture;
+    use crate::engine::events::EventProcessor;
+    use crate::engine::explorer::Explorer;
+    use crate::engine::health::HealthMonitor;
+    use crate::engine::scheduler::Scheduler;
+    use crate::tui::app::App;
+    use tokio::sync::Mutex as AsyncMutex;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn mock_config() -> Config {
+        let mut config = Config::default();
+        config.system.user_token = "mock-token".to_string();
+        config.system.guild_id = 11;
+        config.system.channel_id = 22;
+        config.system.backfill_messages = 0;
+        config
+    }
+
+    /// Scheduler and explorer sends, through the client, the gateway and the event processor
+    /// back to their replies.
+    #[tokio::test]
+    async fn test_scheduler_and_explorer_against_mock() {
+        let config = mock_config();
+        let mock = MockDiscord::start(&config, &["fish", "shop"]).await.unwrap();
+        mock.reply_to("fish", json!({ "content": "You caught a **Common Fish**!" }));
+        mock.reply_to("shop", json!({ "embeds": [{ "title": "Shop", "description": "Rods and boats" }] }));
+
+        let db = Arc::new(Database::in_memory().await.unwrap());
+        let correlation = Arc::new(CorrelationStore::new(db.clone()));
+        let session = Arc::new(SessionState::new());
+        let client = Arc::new(DiscordClient::new(config.clone()).unwrap()
+            .with_api_base(mock.api_base())
+            .with_correlation(correlation.clone())
+            .with_session(session.clone()));
+
+        let (gateway_tx, gateway_rx) = tokio::sync::mpsc::channel::<GatewayPayload>(100);
+        let mut gateway = Gateway::new(config.clone(), gateway_tx).with_url(mock.gateway_url()).with_session(session.clone());
+        let gateway_task = tokio::spawn(async move { gateway.run().await });
+        let app = Arc::new(AsyncMutex::new(App::new(config.clone())));
+        let cooldown = Arc::new(AsyncMutex::new(CooldownManager::new(config.system.user_cooldown)));
+        let processor = EventProcessor::new(&config, app.clone(), db.clone(), cooldown, Arc::new(HealthMonitor::new()))
+            .with_correlation(correlation);
+        let processor_task = tokio::spawn(processor.run(gateway_rx));
+        assert!(mock.wait_for_session(TIMEOUT).await);
+
+        let scheduler = Scheduler::new(config.clone());
+        let reply = scheduler.run_command_and_wait(&client, "22", "fish", TIMEOUT).await.unwrap().expect("no reply to /fish");
+        assert!(reply.content.contains("Common Fish"));
+        let sent = &mock.interactions()[0];
+        assert_eq!((sent["guild_id"].as_str(), sent["channel_id"].as_str()), (Some("11"), Some("22")));
+        assert_eq!(sent["session_id"], MOCK_SESSION_ID);
+
+        let mut explorer = Explorer::new(client.clone(), db.clone(), EmbedCapture::new(&config), "11".to_string(), "22".to_string());
+        explorer.start().await;
+        explorer.tick(None).await;
+        assert_eq!(explorer.status().known_commands, 2);
+        explorer.tick(None).await;
+        assert!(mock.wait_for_interaction("shop", Duration::ZERO).await.is_some());
+        assert_eq!(explorer.status().state, "WaitingForResponse");
+
+        gateway_task.abort();
+        processor_task.abort();
+    }
+
+    /// A started bot fetches the command index and casts in the configured channel.
+    #[tokio::test]
+    async fn test_bot_casts_against_mock() {
+        let config = mock_config();
+        let mock = MockDiscord::start(&config, &["fish"]).await.unwrap();
+        let db = Arc::new(Database::in_memory().await.unwrap());
+        let client = Arc::new(DiscordClient::new(config.clone()).unwrap().with_api_base(mock.api_base()));
+        let mut app = App::new(config.clone());
+        app.toggle_bot();
+        let app = Arc::new(AsyncMutex::new(app));
+
+        let mut bot = Bot::new(config.clone(), client, app, db).await;
+        let bot_task = tokio::spawn(async move { bot.run().await });
+        // After the bot's 5 second warmup
+        let cast = mock.wait_for_interaction("fish", Duration::from_secs(30)).await;
+        bot_task.abort();
+        let cast = cast.expect("the bot never cast");
+        assert_eq!(cast["channel_id"], "22");
+        assert_eq!(cast["data"]["application_command"]["application_id"], config.system.application_id.to_string());
+    }
+}
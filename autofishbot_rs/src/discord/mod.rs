@@ -1,3 +1,13 @@
 pub mod types;
 pub mod client;
+pub mod command;
+pub mod command_cache;
 pub mod gateway;
+pub mod gateway_health;
+#[cfg(test)]
+pub mod mock;
+pub mod proxy;
+pub mod rate_limit;
+pub mod recorder;
+pub mod replay;
+pub mod session;
@@ -0,0 +1,89 @@
+use anyhow::{Result, anyhow};
+use base64::{Engine as _, engine::general_purpose};
+use percent_encoding::percent_decode_str;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use url::Url;
+
+/// Proxy responses larger than this are not a CONNECT reply.
+const MAX_RESPONSE_HEADER: usize = 8 * 1024;
+
+/// Opens a TCP tunnel to `host:port` through an HTTP proxy with `CONNECT`, so the gateway
+/// websocket egresses from the same IP as the REST client.
+pub async fn connect(proxy_url: &str, host: &str, port: u16) -> Result<TcpStream> {
+    let proxy = Url::parse(proxy_url)?;
+    let proxy_host = proxy.host_str().ok_or_else(|| anyhow!("Proxy URL has no host: {}", proxy_url))?;
+    let proxy_port = proxy.port_or_known_default().unwrap_or(8080);
+
+    let mut stream = TcpStream::connect((proxy_host, proxy_port)).await?;
+
+    let mut request = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n");
+    if !proxy.username().is_empty() {
+        // `Url` keeps userinfo percent-encoded
+        let user = percent_decode_str(proxy.username()).decode_utf8_lossy();
+        let pass = percent_decode_str(proxy.password().unwrap_or_default()).decode_utf8_lossy();
+        let credentials = general_purpose::STANDARD.encode(format!("{}:{}", user, pass));
+        request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", credentials));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // Read byte by byte up to the blank line so no tunneled data is consumed
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() > MAX_RESPONSE_HEADER {
+            return Err(anyhow!("Proxy response header too long"));
+        }
+        let byte = stream.read_u8().await?;
+        response.push(byte);
+    }
+
+    let status_line = String::from_utf8_lossy(&response);
+    let status_line = status_line.lines().next().unwrap_or_default();
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default();
+    if status != "200" {
+        return Err(anyhow!("Proxy refused CONNECT to {}:{}: {}", host, port, status_line));
+    }
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_connect_tunnel() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // Fake proxy: check the CONNECT request, accept it, then echo a greeting through the tunnel
+        let proxy = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut reader = BufReader::new(socket);
+            let mut head = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).await.unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                head.push_str(&line);
+            }
+            let mut socket = reader.into_inner();
+            socket.write_all(b"HTTP/1.1 200 Connection established\r\n\r\nhello").await.unwrap();
+            head
+        });
+
+        let mut stream = connect(&format!("http://user:p%40ss@{}", addr), "gateway.discord.gg", 443).await.unwrap();
+        let mut greeting = [0u8; 5];
+        stream.read_exact(&mut greeting).await.unwrap();
+        assert_eq!(&greeting, b"hello");
+
+        let head = proxy.await.unwrap();
+        assert!(head.starts_with("CONNECT gateway.discord.gg:443 HTTP/1.1\r\n"));
+        let expected = general_purpose::STANDARD.encode("user:p@ss");
+        assert!(head.contains(&format!("Proxy-Authorization: Basic {}", expected)));
+    }
+}
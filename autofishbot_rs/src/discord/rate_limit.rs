@@ -0,0 +1,179 @@
+use parking_lot::Mutex;
+use reqwest::header::HeaderMap;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::OwnedMutexGuard;
+
+/// Longer waits than this (Cloudflare bans, hour-long limits) aren't waited out: `wait` sleeps
+/// at most this long, and `DiscordClient` gives up on a 429 asking for more.
+pub const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// Discord's REST rate limits as its response headers describe them: per-route buckets
+/// (routes can share a bucket, named by `X-RateLimit-Bucket`) and the account-wide global limit.
+#[derive(Default)]
+pub struct RateLimiter {
+    state: Mutex<State>,
+    /// Requests on the same route wait their turn here, so a burst is sent in order
+    queues: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+#[derive(Default)]
+struct State {
+    /// Route -> bucket id, learned from responses
+    routes: HashMap<String, String>,
+    buckets: HashMap<String, Bucket>,
+    global_until: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    remaining: u32,
+    reset_at: Instant,
+}
+
+/// A 429's wait, from the body's `retry_after` or the `Retry-After` header.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryAfter {
+    pub wait: Duration,
+    pub global: bool,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Holds the route's place in line until the returned guard is dropped.
+    pub async fn queue(&self, route: &str) -> OwnedMutexGuard<()> {
+        let queue = self.queues.lock().entry(route.to_string()).or_default().clone();
+        queue.lock_owned().await
+    }
+
+    /// Sleeps until a request on `route` is allowed, then counts it against the bucket. A bad
+    /// `X-RateLimit-Reset-After` can't park the caller: each sleep is capped at `MAX_RATE_LIMIT_WAIT`,
+    /// after which the request goes out and a real limit comes back as a 429.
+    pub async fn wait(&self, route: &str) {
+        let delay = self.delay(route);
+        if !delay.is_zero() {
+            log::debug!("Rate limit: waiting {:.1}s before {}", delay.as_secs_f64(), route);
+            tokio::time::sleep(delay).await;
+        }
+        let mut state = self.state.lock();
+        if let Some(bucket) = state.routes.get(route).cloned().and_then(|id| state.buckets.get_mut(&id)) {
+            bucket.remaining = bucket.remaining.saturating_sub(1);
+        }
+    }
+
+    /// How long `route` has to wait right now: the global limit, or its bucket being empty, up to
+    /// `MAX_RATE_LIMIT_WAIT`.
+    fn delay(&self, route: &str) -> Duration {
+        let state = self.state.lock();
+        let now = Instant::now();
+        let global = state.global_until.map_or(Duration::ZERO, |until| until.saturating_duration_since(now));
+        let bucket = state.routes.get(route)
+            .and_then(|id| state.buckets.get(id))
+            .filter(|b| b.remaining == 0)
+            .map_or(Duration::ZERO, |b| b.reset_at.saturating_duration_since(now));
+        global.max(bucket).min(MAX_RATE_LIMIT_WAIT)
+    }
+
+    /// Learns the route's bucket from `X-RateLimit-*` headers.
+    pub fn update(&self, route: &str, headers: &HeaderMap) {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        let (Some(id), Some(remaining), Some(reset_after)) = (
+            header("x-ratelimit-bucket"),
+            header("x-ratelimit-remaining").and_then(|v| v.parse::<u32>().ok()),
+            header("x-ratelimit-reset-after").and_then(|v| v.parse::<f64>().ok()).filter(|v| v.is_finite()),
+        ) else { return };
+        let mut state = self.state.lock();
+        state.routes.insert(route.to_string(), id.to_string());
+        let reset_at = Instant::now() + Duration::from_secs_f64(reset_after.clamp(0.0, MAX_RATE_LIMIT_WAIT.as_secs_f64()));
+        state.buckets.insert(id.to_string(), Bucket { remaining, reset_at });
+    }
+
+    /// Records a 429 so the route (or, for a global limit, every route) waits it out.
+    pub fn limited(&self, route: &str, headers: &HeaderMap, body: &str) -> RetryAfter {
+        self.update(route, headers);
+        let retry = RetryAfter::parse(headers, body);
+        let until = Instant::now() + retry.wait;
+        let mut state = self.state.lock();
+        if retry.global {
+            state.global_until = Some(state.global_until.map_or(until, |at| at.max(until)));
+        } else {
+            let id = state.routes.get(route).cloned().unwrap_or_else(|| route.to_string());
+            state.routes.insert(route.to_string(), id.clone());
+            state.buckets.insert(id, Bucket { remaining: 0, reset_at: until });
+        }
+        retry
+    }
+}
+
+impl RetryAfter {
+    /// Defaults to one second when neither the body nor the headers say.
+    pub fn parse(headers: &HeaderMap, body: &str) -> Self {
+        let body: Value = serde_json::from_str(body).unwrap_or(Value::Null);
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        let secs = body["retry_after"].as_f64()
+            .or_else(|| header("retry-after").and_then(|v| v.parse::<f64>().ok()))
+            .filter(|secs| secs.is_finite())
+            .unwrap_or(1.0);
+        let global = body["global"].as_bool().unwrap_or(false)
+            || header("x-ratelimit-global").is_some_and(|v| v.eq_ignore_ascii_case("true"))
+            || header("x-ratelimit-scope") == Some("global");
+        Self { wait: Duration::from_secs_f64(secs.max(0.0)), global }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, value.parse().unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_rate_limit_buckets() {
+        let limiter = RateLimiter::new();
+        assert!(limiter.delay("POST /interactions").is_zero());
+
+        // Two routes in one bucket, which just ran out
+        let empty = headers(&[("x-ratelimit-bucket", "abc"), ("x-ratelimit-remaining", "0"), ("x-ratelimit-reset-after", "30")]);
+        limiter.update("POST /interactions", &empty);
+        limiter.update("GET /channels/1/messages", &empty);
+        assert!(limiter.delay("POST /interactions") > Duration::from_secs(29));
+        assert!(limiter.delay("GET /channels/1/messages") > Duration::from_secs(29));
+        assert!(limiter.delay("GET /users/@me").is_zero());
+
+        let retry = limiter.limited("GET /users/@me", &headers(&[("x-ratelimit-scope", "global")]), r#"{"retry_after": 2.5, "global": true}"#);
+        assert_eq!(retry, RetryAfter { wait: Duration::from_millis(2500), global: true });
+        assert!(limiter.delay("GET /guilds/1/application-command-index") > Duration::from_secs(2));
+
+        let retry = RetryAfter::parse(&headers(&[("retry-after", "3")]), "");
+        assert_eq!(retry, RetryAfter { wait: Duration::from_secs(3), global: false });
+    }
+
+    #[test]
+    fn test_wait_is_capped() {
+        let limiter = RateLimiter::new();
+        let bogus = headers(&[("x-ratelimit-bucket", "abc"), ("x-ratelimit-remaining", "0"), ("x-ratelimit-reset-after", "1e12")]);
+        limiter.update("POST /interactions", &bogus);
+        let capped = |delay: Duration| delay <= MAX_RATE_LIMIT_WAIT && delay > MAX_RATE_LIMIT_WAIT - Duration::from_secs(1);
+        assert!(capped(limiter.delay("POST /interactions")));
+
+        // A long 429 is remembered as asked, so the client can refuse it, but never slept on in full
+        let retry = limiter.limited("GET /users/@me", &HeaderMap::new(), r#"{"retry_after": 3600, "global": true}"#);
+        assert_eq!(retry.wait, Duration::from_secs(3600));
+        assert!(capped(limiter.delay("GET /users/@me")));
+
+        let nan = headers(&[("x-ratelimit-bucket", "def"), ("x-ratelimit-remaining", "0"), ("x-ratelimit-reset-after", "NaN")]);
+        limiter.update("GET /channels/1/messages", &nan);
+        assert_eq!(RetryAfter::parse(&headers(&[("retry-after", "inf")]), "").wait, Duration::from_secs(1));
+    }
+}
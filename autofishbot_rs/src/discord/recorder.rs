@@ -0,0 +1,119 @@
+use crate::discord::types::{GatewayPayload, RecordedPayload};
+use anyhow::Result;
+use log::{info, warn};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Capture files kept per channel; older ones are deleted on rotation.
+const MAX_FILES: usize = 10;
+
+/// Writes raw gateway payloads for the fishing channels to `gateway_<channel>_<time>_<n>.jsonl`
+/// files (named after the first channel), one `RecordedPayload` per line, so a session can be
+/// fed back with `headless --replay`.
+/// A file is closed and a new one started once it reaches `max_bytes`.
+pub struct GatewayRecorder {
+    dir: PathBuf,
+    channels: Vec<String>,
+    max_bytes: u64,
+    current: Option<(PathBuf, File, u64)>,
+    /// Files started so far, in the name so rotations within one second don't collide
+    files_opened: u32,
+}
+
+impl GatewayRecorder {
+    pub fn new(dir: impl Into<PathBuf>, channels: Vec<String>, max_bytes: u64) -> Self {
+        Self { dir: dir.into(), channels, max_bytes: max_bytes.max(1), current: None, files_opened: 0 }
+    }
+
+    /// Payloads about these channels, plus `INTERACTION_*` events (they carry no channel id but
+    /// replies are matched against them). READY, presence and other guilds' traffic are skipped.
+    pub fn wants(&self, payload: &GatewayPayload) -> bool {
+        let channel = payload.d.as_ref().and_then(|d| d.get("channel_id")).and_then(|v| v.as_str());
+        channel.is_some_and(|c| self.channels.iter().any(|id| id == c)) || payload.t.as_deref().is_some_and(|t| t.starts_with("INTERACTION_"))
+    }
+
+    pub fn record(&mut self, payload: &GatewayPayload) -> Result<()> {
+        if !self.wants(payload) {
+            return Ok(());
+        }
+        let recorded = RecordedPayload { timestamp: chrono::Utc::now().timestamp_millis(), payload: payload.clone() };
+        let mut line = serde_json::to_string(&recorded)?;
+        line.push('\n');
+
+        if self.current.as_ref().is_none_or(|(_, _, written)| *written >= self.max_bytes) {
+            self.rotate()?;
+        }
+        let (_, file, written) = self.current.as_mut().expect("rotate opened a file");
+        file.write_all(line.as_bytes())?;
+        *written += line.len() as u64;
+        Ok(())
+    }
+
+    /// Starts a new file and deletes the oldest ones beyond `MAX_FILES`.
+    fn rotate(&mut self) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let prefix = format!("gateway_{}_", self.channels.first().map_or("all", String::as_str));
+        let name = format!("{}{}_{:04}.jsonl", prefix, chrono::Utc::now().format("%Y%m%d_%H%M%S"), self.files_opened);
+        self.files_opened += 1;
+        let path = self.dir.join(name);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        info!("Capturing gateway payloads to {}", path.display());
+        self.current = Some((path, file, 0));
+        prune(&self.dir, &prefix);
+        Ok(())
+    }
+
+    pub fn current_path(&self) -> Option<&Path> {
+        self.current.as_ref().map(|(path, _, _)| path.as_path())
+    }
+}
+
+fn prune(dir: &Path, prefix: &str) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    let mut files: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.file_name().is_some_and(|f| f.to_string_lossy().starts_with(prefix)))
+        .collect();
+    // The timestamp in the name sorts oldest first
+    files.sort();
+    let excess = files.len().saturating_sub(MAX_FILES);
+    for old in &files[..excess] {
+        if let Err(e) = std::fs::remove_file(old) {
+            warn!("Failed to delete old capture {}: {}", old.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discord::replay;
+
+    #[test]
+    fn test_gateway_recorder() {
+        let dir = std::env::temp_dir().join(format!("autofishbot_capture_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let payload = |t: &str, d: serde_json::Value| GatewayPayload { op: 0, d: Some(d), s: Some(1), t: Some(t.to_string()) };
+
+        let mut recorder = GatewayRecorder::new(&dir, vec!["42".to_string(), "43".to_string()], 200);
+        recorder.record(&payload("MESSAGE_CREATE", serde_json::json!({"channel_id": "42", "content": "caught"}))).unwrap();
+        recorder.record(&payload("MESSAGE_CREATE", serde_json::json!({"channel_id": "7", "content": "elsewhere"}))).unwrap();
+        recorder.record(&payload("INTERACTION_SUCCESS", serde_json::json!({"id": "1", "nonce": "2"}))).unwrap();
+
+        let first = recorder.current_path().unwrap().to_path_buf();
+        let text = std::fs::read_to_string(&first).unwrap();
+        let lines: Vec<_> = text.lines().map(|l| replay::parse_line(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|(ts, _)| ts.is_some()));
+        assert_eq!(lines[1].1.t.as_deref(), Some("INTERACTION_SUCCESS"));
+        assert!(recorder.wants(&payload("MESSAGE_CREATE", serde_json::json!({"channel_id": "43", "content": "rotated"}))));
+
+        // Over 200 bytes now, so the next payload starts a new file
+        for _ in 0..MAX_FILES + 2 {
+            recorder.record(&payload("MESSAGE_UPDATE", serde_json::json!({"channel_id": "42", "content": "x".repeat(250)}))).unwrap();
+        }
+        assert_ne!(recorder.current_path().unwrap(), first);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), MAX_FILES);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
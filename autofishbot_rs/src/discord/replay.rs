@@ -0,0 +1,96 @@
+use crate::discord::types::{GatewayPayload, RecordedPayload};
+use anyhow::Result;
+use log::{info, warn};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// Feeds recorded gateway payloads into the event pipeline instead of a live websocket.
+/// Drop-in replacement for `Gateway` when debugging parsers and bot logic offline.
+pub struct Replay {
+    path: PathBuf,
+    /// Playback speed multiplier. 1.0 = original timing, 0.0 = as fast as possible.
+    speed: f64,
+    event_sender: tokio::sync::mpsc::Sender<GatewayPayload>,
+}
+
+impl Replay {
+    pub fn new<P: Into<PathBuf>>(path: P, speed: f64, event_sender: tokio::sync::mpsc::Sender<GatewayPayload>) -> Self {
+        Self {
+            path: path.into(),
+            speed,
+            event_sender,
+        }
+    }
+
+    pub async fn run(&mut self) -> Result<()> {
+        info!("Replaying gateway events from {} (speed {}x)", self.path.display(), self.speed);
+        let file = tokio::fs::File::open(&self.path).await?;
+        let mut lines = BufReader::new(file).lines();
+
+        let mut last_timestamp: Option<i64> = None;
+        let mut sent = 0;
+        let mut line_no = 0;
+
+        while let Some(line) = lines.next_line().await? {
+            line_no += 1;
+            if line.trim().is_empty() { continue; }
+
+            let (timestamp, payload) = match parse_line(&line) {
+                Some(entry) => entry,
+                None => {
+                    warn!("Skipping malformed replay line {}", line_no);
+                    continue;
+                }
+            };
+
+            // Respect the original gap between events, scaled by speed
+            if let (Some(prev), Some(ts)) = (last_timestamp, timestamp) {
+                if self.speed > 0.0 && ts > prev {
+                    let delay = (ts - prev) as f64 / 1000.0 / self.speed;
+                    tokio::time::sleep(Duration::from_secs_f64(delay)).await;
+                }
+            }
+            if timestamp.is_some() {
+                last_timestamp = timestamp;
+            }
+
+            if self.event_sender.send(payload).await.is_err() {
+                break;
+            }
+            sent += 1;
+        }
+
+        info!("Replay finished: {} events sent.", sent);
+        Ok(())
+    }
+}
+
+/// Accepts both timestamped `RecordedPayload` lines and bare `GatewayPayload` lines (replayed without delay).
+pub fn parse_line(line: &str) -> Option<(Option<i64>, GatewayPayload)> {
+    if let Ok(recorded) = serde_json::from_str::<RecordedPayload>(line) {
+        return Some((Some(recorded.timestamp), recorded.payload));
+    }
+    serde_json::from_str::<GatewayPayload>(line).ok().map(|p| (None, p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line_formats() {
+        let recorded = r#"{"timestamp": 1700000000123, "payload": {"op": 0, "d": {"content": ""}, "s": 4, "t": "MESSAGE_CREATE"}}"#;
+        let (ts, payload) = parse_line(recorded).expect("recorded line");
+        assert_eq!(ts, Some(1700000000123));
+        assert_eq!(payload.t.as_deref(), Some("MESSAGE_CREATE"));
+        assert_eq!(payload.s, Some(4));
+
+        let bare = r#"{"op": 11, "d": null, "s": null, "t": null}"#;
+        let (ts, payload) = parse_line(bare).expect("bare line");
+        assert_eq!(ts, None);
+        assert_eq!(payload.op, 11);
+
+        assert!(parse_line("not json").is_none());
+    }
+}
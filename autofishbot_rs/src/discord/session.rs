@@ -0,0 +1,48 @@
+use parking_lot::RwLock;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+
+/// The gateway session id from READY, shared with `DiscordClient` so interactions carry the
+/// session they really come from, as the official client's do.
+#[derive(Debug, Default)]
+pub struct SessionState {
+    session_id: RwLock<Option<String>>,
+}
+
+impl SessionState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `None` while the gateway is disconnected or the session was invalidated.
+    pub fn set(&self, session_id: Option<String>) {
+        *self.session_id.write() = session_id;
+    }
+
+    pub fn get(&self) -> Option<String> {
+        self.session_id.read().clone()
+    }
+
+    /// The live session id, or a random 32-character one while disconnected.
+    pub fn session_id_or_random(&self) -> String {
+        self.get().unwrap_or_else(|| thread_rng().sample_iter(&Alphanumeric).take(32).map(char::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_fallback() {
+        let session = SessionState::new();
+        let random = session.session_id_or_random();
+        assert_eq!(random.len(), 32);
+        assert!(random.chars().all(|c| c.is_ascii_alphanumeric()));
+
+        session.set(Some("abc123".to_string()));
+        assert_eq!(session.session_id_or_random(), "abc123");
+        session.set(None);
+        assert_ne!(session.session_id_or_random(), "abc123");
+    }
+}
@@ -23,6 +23,22 @@ pub struct Message {
     pub timestamp: String,
     pub embeds: Vec<Embed>,
     pub components: Option<Vec<Component>>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    /// Set on replies to a slash command or component: who used it
+    pub interaction_metadata: Option<InteractionMetadata>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InteractionMetadata {
+    pub id: String,
+    pub user: Option<User>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Attachment {
+    pub url: String,
+    pub filename: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -31,6 +47,12 @@ pub struct Embed {
     pub description: Option<String>,
     pub fields: Option<Vec<EmbedField>>,
     pub footer: Option<EmbedFooter>,
+    pub image: Option<EmbedImage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmbedImage {
+    pub url: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -56,6 +78,19 @@ pub struct Component {
     pub emoji: Option<Emoji>,
     pub options: Option<Vec<SelectOption>>,
     pub placeholder: Option<String>,
+    /// A text input's prefilled value, in modals
+    pub value: Option<String>,
+}
+
+/// A form opened by a component click or command (`INTERACTION_MODAL_CREATE`), answered with
+/// `DiscordClient::submit_modal`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Modal {
+    pub id: String,
+    pub custom_id: String,
+    pub title: String,
+    /// Action rows of text inputs (type 4)
+    pub components: Vec<Component>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -73,7 +108,7 @@ pub struct Emoji {
     pub id: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GatewayPayload {
     pub op: u8,
     pub d: Option<serde_json::Value>,
@@ -81,6 +116,14 @@ pub struct GatewayPayload {
     pub t: Option<String>,
 }
 
+/// A gateway payload as stored in a capture file (one JSON object per line).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecordedPayload {
+    /// Unix timestamp in milliseconds when the payload was received
+    pub timestamp: i64,
+    pub payload: GatewayPayload,
+}
+
 #[derive(Debug, Serialize)]
 pub struct IdentifyPayload {
     pub token: String,
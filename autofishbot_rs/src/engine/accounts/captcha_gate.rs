@@ -0,0 +1,79 @@
+use crate::config::CaptchaPolicy;
+use parking_lot::Mutex;
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+/// What an account should do before casting while another account has a captcha.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hold {
+    None,
+    /// Don't cast; `blocked_by` names the accounts with a pending captcha
+    Pause { blocked_by: Vec<String> },
+    /// Cast no more often than this
+    Slow { min_interval: Duration, blocked_by: Vec<String> },
+}
+
+/// Tracks which accounts have an unsolved captcha so the rest can back off
+/// (`[multi_account] on_captcha`). A ban review of one account tends to look at
+/// whatever else was active alongside it.
+pub struct CaptchaGate {
+    policy: CaptchaPolicy,
+    slow_interval: Duration,
+    pending: Mutex<BTreeSet<String>>,
+}
+
+impl CaptchaGate {
+    pub fn new(policy: CaptchaPolicy, slow_interval: Duration) -> Self {
+        Self { policy, slow_interval, pending: Mutex::new(BTreeSet::new()) }
+    }
+
+    /// Records whether `account` currently has an unsolved captcha.
+    pub fn set_pending(&self, account: &str, pending: bool) {
+        let mut accounts = self.pending.lock();
+        if pending {
+            accounts.insert(account.to_string());
+        } else {
+            accounts.remove(account);
+        }
+    }
+
+    /// The hold for `account` caused by the other accounts' captchas; its own captcha is the bot's business.
+    pub fn hold(&self, account: &str) -> Hold {
+        let blocked_by: Vec<String> = self.pending.lock().iter().filter(|a| *a != account).cloned().collect();
+        if blocked_by.is_empty() {
+            return Hold::None;
+        }
+        match self.policy {
+            CaptchaPolicy::Continue => Hold::None,
+            CaptchaPolicy::Pause => Hold::Pause { blocked_by },
+            CaptchaPolicy::Slow => Hold::Slow { min_interval: self.slow_interval, blocked_by },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hold_follows_other_accounts_captchas() {
+        let gate = CaptchaGate::new(CaptchaPolicy::Pause, Duration::from_secs(300));
+        assert_eq!(gate.hold("a"), Hold::None);
+
+        gate.set_pending("a", true);
+        // The account with the captcha isn't held by the gate, everyone else is
+        assert_eq!(gate.hold("a"), Hold::None);
+        assert_eq!(gate.hold("b"), Hold::Pause { blocked_by: vec!["a".to_string()] });
+
+        gate.set_pending("a", false);
+        assert_eq!(gate.hold("b"), Hold::None);
+
+        let slow = CaptchaGate::new(CaptchaPolicy::Slow, Duration::from_secs(300));
+        slow.set_pending("a", true);
+        assert_eq!(slow.hold("b"), Hold::Slow { min_interval: Duration::from_secs(300), blocked_by: vec!["a".to_string()] });
+
+        let independent = CaptchaGate::new(CaptchaPolicy::Continue, Duration::from_secs(300));
+        independent.set_pending("a", true);
+        assert_eq!(independent.hold("b"), Hold::None);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::config::AccountConfig;
+use crate::engine::accounts::overview::AccountsOverview;
+use crate::tui::app::App;
+use anyhow::{Result, anyhow};
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+/// A change to the set of running accounts, applied by `AccountSupervisor::supervise`.
+pub enum AccountCommand {
+    Add { account: AccountConfig, reply: Option<oneshot::Sender<Result<()>>> },
+    Remove { name: String, reply: Option<oneshot::Sender<Result<()>>> },
+}
+
+/// Cloneable handle to a running supervisor for the TUI and web API: reads the overview
+/// and asks for accounts to be added or removed without restarting the process.
+#[derive(Clone)]
+pub struct AccountsHandle {
+    overview: AccountsOverview,
+    commands: mpsc::Sender<AccountCommand>,
+    /// Each running account's app, for the TUI's account switcher
+    apps: Arc<RwLock<HashMap<String, Arc<Mutex<App>>>>>,
+}
+
+impl AccountsHandle {
+    pub fn new(overview: AccountsOverview, commands: mpsc::Sender<AccountCommand>) -> Self {
+        Self { overview, commands, apps: Arc::default() }
+    }
+
+    pub fn overview(&self) -> &AccountsOverview {
+        &self.overview
+    }
+
+    /// The app of a running account, to show it in the TUI.
+    pub fn app(&self, name: &str) -> Option<Arc<Mutex<App>>> {
+        self.apps.read().get(name).cloned()
+    }
+
+    pub(crate) fn set_apps(&self, apps: HashMap<String, Arc<Mutex<App>>>) {
+        *self.apps.write() = apps;
+    }
+
+    /// Starts a new account and waits until its pipeline is up (or refused).
+    pub async fn add(&self, account: AccountConfig) -> Result<()> {
+        let (reply, result) = oneshot::channel();
+        self.send(AccountCommand::Add { account, reply: Some(reply) }).await?;
+        result.await.map_err(|_| anyhow!("Account supervisor stopped"))?
+    }
+
+    /// Stops an account's tasks and drops it from the overview.
+    pub async fn remove(&self, name: &str) -> Result<()> {
+        let (reply, result) = oneshot::channel();
+        self.send(AccountCommand::Remove { name: name.to_string(), reply: Some(reply) }).await?;
+        result.await.map_err(|_| anyhow!("Account supervisor stopped"))?
+    }
+
+    /// Fire-and-forget variants for callers that can't await, like the TUI key handler.
+    /// The outcome shows up in the logs and the overview.
+    pub fn request_add(&self, account: AccountConfig) -> Result<()> {
+        self.commands.try_send(AccountCommand::Add { account, reply: None })
+            .map_err(|_| anyhow!("Account supervisor is busy or stopped"))
+    }
+
+    pub fn request_remove(&self, name: &str) -> Result<()> {
+        self.commands.try_send(AccountCommand::Remove { name: name.to_string(), reply: None })
+            .map_err(|_| anyhow!("Account supervisor is busy or stopped"))
+    }
+
+    async fn send(&self, command: AccountCommand) -> Result<()> {
+        self.commands.send(command).await.map_err(|_| anyhow!("Account supervisor stopped"))
+    }
+}
+
+/// Parses the TUI's one-line form, `name token channel_id [guild_id]`, into an account entry.
+pub fn parse_account_line(line: &str) -> Result<AccountConfig> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    let [name, token, channel_id, rest @ ..] = parts.as_slice() else {
+        return Err(anyhow!("Expected: name token channel_id [guild_id]"));
+    };
+    if rest.len() > 1 {
+        return Err(anyhow!("Expected: name token channel_id [guild_id]"));
+    }
+    let channel_id = channel_id.parse::<u64>().map_err(|_| anyhow!("channel_id '{}' is not a number", channel_id))?;
+    let guild_id = match rest.first() {
+        Some(id) => Some(id.parse::<u64>().map_err(|_| anyhow!("guild_id '{}' is not a number", id))?),
+        None => None,
+    };
+    Ok(AccountConfig {
+        name: name.to_string(),
+        user_token: token.to_string(),
+        user_token_file: None,
+        channel_id,
+        guild_id,
+        proxy: None,
+        mode: None,
+        automation: Default::default(),
+    })
+}
+
+/// `line` as the add-account prompt shows it: the token (second word) replaced by `*`s.
+pub fn mask_account_line(line: &str) -> String {
+    let mut word = 0;
+    let mut in_word = false;
+    line.chars().map(|c| {
+        if c.is_whitespace() {
+            in_word = false;
+            return c;
+        }
+        if !in_word {
+            in_word = true;
+            word += 1;
+        }
+        if word == 2 { '*' } else { c }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_account_line() {
+        let account = parse_account_line("alt3 abc.def 123 456").unwrap();
+        assert_eq!(account.name, "alt3");
+        assert_eq!(account.user_token, "abc.def");
+        assert_eq!(account.channel_id, 123);
+        assert_eq!(account.guild_id, Some(456));
+
+        assert_eq!(parse_account_line("  alt3   abc   123 ").unwrap().guild_id, None);
+        assert!(parse_account_line("alt3 abc").is_err());
+        assert!(parse_account_line("alt3 abc channel").is_err());
+        assert!(parse_account_line("alt3 abc 1 2 3").is_err());
+    }
+
+    #[test]
+    fn test_mask_account_line() {
+        assert_eq!(mask_account_line("alt3"), "alt3");
+        assert_eq!(mask_account_line("alt3 abc.d"), "alt3 *****");
+        assert_eq!(mask_account_line("alt3  abc 123 "), "alt3  *** 123 ");
+    }
+}
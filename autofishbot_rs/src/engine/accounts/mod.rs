@@ -0,0 +1,6 @@
+pub mod captcha_gate;
+pub mod control;
+pub mod overview;
+pub mod pipeline;
+pub mod stagger;
+pub mod supervisor;
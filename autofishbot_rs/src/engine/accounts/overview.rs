@@ -0,0 +1,96 @@
+use crate::engine::accounts::pipeline::AccountPipeline;
+use crate::engine::database::Throughput;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Latest per-account rows, refreshed by the supervisor and read by the TUI and `/api/accounts`.
+pub type AccountsOverview = Arc<RwLock<Vec<AccountSummary>>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountHealth {
+    Healthy,
+    /// Connected but not making progress (paused or no recent catch)
+    Degraded,
+    /// Needs attention: logged out, gateway down or captcha pending
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountSummary {
+    pub name: String,
+    pub status: String,
+    pub running: bool,
+    pub balance: Option<u64>,
+    pub level: Option<u32>,
+    pub biome: String,
+    pub last_action: String,
+    pub error: Option<String>,
+    pub health: AccountHealth,
+    pub fish_per_hour: f64,
+    pub gold_per_hour: f64,
+    /// Captchas in the throughput window
+    pub captchas: i64,
+}
+
+/// Sums across every account, for the overview's total row.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AccountsTotals {
+    pub running: usize,
+    pub fish_per_hour: f64,
+    pub gold_per_hour: f64,
+    pub captchas: i64,
+}
+
+impl AccountsTotals {
+    pub fn of(rows: &[AccountSummary]) -> Self {
+        rows.iter().fold(Self::default(), |mut totals, row| {
+            totals.running += row.running as usize;
+            totals.fish_per_hour += row.fish_per_hour;
+            totals.gold_per_hour += row.gold_per_hour;
+            totals.captchas += row.captchas;
+            totals
+        })
+    }
+}
+
+impl AccountSummary {
+    pub async fn of(pipeline: &AccountPipeline, throughput: &Throughput) -> Self {
+        let captcha_pending = pipeline.captcha.lock().await.detected;
+        let app = pipeline.app.lock().await;
+        // The DB check is the supervisor's concern; only gateway and catches matter here
+        let report = pipeline.health.report(true, app.is_running);
+
+        let (health, error) = if app.logged_out {
+            (AccountHealth::Down, Some("Logged out: token rejected".to_string()))
+        } else if let Some(error) = &app.degraded {
+            (AccountHealth::Degraded, Some(format!("Sends failing: {}", error)))
+        } else if captcha_pending {
+            (AccountHealth::Down, Some("Captcha pending".to_string()))
+        } else if !report.gateway_connected {
+            (AccountHealth::Down, Some("Gateway disconnected".to_string()))
+        } else if !report.healthy {
+            (AccountHealth::Degraded, Some("No catch for 10+ minutes".to_string()))
+        } else if !app.is_running && !app.is_observer() {
+            (AccountHealth::Degraded, None)
+        } else {
+            (AccountHealth::Healthy, None)
+        };
+
+        Self {
+            name: pipeline.name.clone(),
+            status: app.status.clone(),
+            running: app.is_running,
+            balance: app.profile.balance,
+            level: app.profile.level,
+            biome: app.profile.biome.clone(),
+            last_action: app.logs.last().cloned().unwrap_or_default(),
+            error,
+            health,
+            fish_per_hour: throughput.fish_per_hour(),
+            gold_per_hour: throughput.gold_per_hour(),
+            captchas: throughput.captchas,
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::config::{AccountConfig, AccountMode, Config};
+use crate::discord::client::DiscordClient;
+use crate::discord::gateway::Gateway;
+use crate::discord::session::SessionState;
+use crate::discord::types::GatewayPayload;
+use crate::engine::accounts::captcha_gate::CaptchaGate;
+use crate::engine::accounts::stagger::CastCoordinator;
+use crate::engine::bot::Bot;
+use crate::engine::captcha::Captcha;
+use crate::engine::config_watcher::ConfigWatcher;
+use crate::engine::cooldown::CooldownManager;
+use crate::engine::correlation::CorrelationStore;
+use crate::engine::database::Database;
+use crate::engine::events::EventProcessor;
+use crate::engine::health::HealthMonitor;
+use crate::scripting;
+use crate::notifications::hub::Notifier;
+use crate::tui::app::App;
+use anyhow::Result;
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+/// State shared by every account in the process.
+#[derive(Clone)]
+pub struct SharedServices {
+    pub notifier: Notifier,
+    pub cast_coordinator: Arc<CastCoordinator>,
+    pub captcha_gate: Arc<CaptchaGate>,
+}
+
+/// Everything one account needs to fish: its own gateway, REST client, bot and event processor,
+/// sharing only `SharedServices` with other accounts. Dropping it stops every task.
+pub struct AccountPipeline {
+    pub name: String,
+    pub config: Config,
+    pub app: Arc<Mutex<App>>,
+    pub db: Arc<Database>,
+    pub client: Arc<DiscordClient>,
+    pub health: Arc<HealthMonitor>,
+    pub captcha: Arc<Mutex<Captcha>>,
+    pub cooldown_manager: Arc<Mutex<CooldownManager>>,
+    /// Watched for hot reloads; `None` for accounts added at runtime, which aren't in the file
+    pub config_path: Option<PathBuf>,
+    tasks: Vec<JoinHandle<()>>,
+    _config_watcher: Option<ConfigWatcher>,
+}
+
+impl AccountPipeline {
+    /// `config` must already be resolved with `Config::for_account(name)`.
+    pub async fn start(name: &str, config: Config, config_path: Option<&Path>, shared: SharedServices) -> Result<Self> {
+        Self::start_after(name, config, config_path, shared, Duration::ZERO).await
+    }
+
+    /// Like `start`, but the gateway connects and the bot starts casting only after `delay`,
+    /// for `[multi_account] start_stagger_seconds`.
+    pub async fn start_after(name: &str, config: Config, config_path: Option<&Path>, shared: SharedServices, delay: Duration) -> Result<Self> {
+        let db = Arc::new(Database::new(&AccountConfig::db_path(name)).await?);
+        let correlation = Arc::new(CorrelationStore::new(db.clone()));
+        let session = Arc::new(SessionState::new());
+        let client = Arc::new(DiscordClient::new(config.clone())?.with_correlation(correlation.clone()).with_session(session.clone()));
+        let health = Arc::new(HealthMonitor::new());
+
+        let mut app = App::new(config.clone());
+        app.notifier = shared.notifier.clone();
+        app.account = Some(name.to_string());
+        if !app.is_observer() {
+            app.toggle_bot();
+        }
+        let app = Arc::new(Mutex::new(app));
+
+        let (gateway_tx, gateway_rx) = tokio::sync::mpsc::channel::<GatewayPayload>(100);
+        let gateway = Gateway::new(config.clone(), gateway_tx).with_health(health.clone()).with_session(session);
+        app.lock().await.gateway = Some(gateway.stats());
+
+        let mut bot = Bot::new(config.clone(), client.clone(), app.clone(), db.clone()).await
+            .with_cast_coordinator(shared.cast_coordinator.clone())
+            .with_captcha_gate(shared.captcha_gate.clone(), name);
+        let captcha = bot.captcha.clone();
+        let cooldown_manager = bot.cooldown_manager.clone();
+        let processor = EventProcessor::new(&config, app.clone(), db.clone(), cooldown_manager.clone(), health.clone())
+            .with_correlation(correlation)
+            .with_captcha(captcha.clone());
+
+        let mut tasks = vec![
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                gateway.run_forever(shared.notifier).await
+            }),
+            tokio::spawn(processor.run(gateway_rx)),
+        ];
+        tasks.extend(scripting::host::start(&config.scripting, app.clone()));
+        // Observers only watch; without the bot task nothing ever sends
+        if config.system.mode == AccountMode::Observer {
+            info!("[{}] Observer mode: logging game data only", name);
+        } else {
+            tasks.push(tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                bot.run().await
+            }));
+        }
+
+        let config_watcher = config_path.and_then(|path| match ConfigWatcher::spawn(path, Some(name.to_string()), app.clone()) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                warn!("[{}] Config hot reload disabled: {}", name, e);
+                None
+            }
+        });
+
+        if delay.is_zero() {
+            info!("[{}] Account pipeline started", name);
+        } else {
+            info!("[{}] Account pipeline started, connecting in {:.0}s", name, delay.as_secs_f64());
+        }
+        Ok(Self {
+            name: name.to_string(),
+            config,
+            app,
+            db,
+            client,
+            health,
+            captcha,
+            cooldown_manager,
+            config_path: config_path.map(Path::to_path_buf),
+            tasks,
+            _config_watcher: config_watcher,
+        })
+    }
+
+    /// False once any task has exited; they all loop forever, so that means a panic.
+    pub fn is_alive(&self) -> bool {
+        self.tasks.iter().all(|t| !t.is_finished())
+    }
+
+    pub fn stop(&mut self) {
+        for task in self.tasks.drain(..) {
+            task.abort();
+        }
+    }
+}
+
+impl Drop for AccountPipeline {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,61 @@
+use parking_lot::Mutex;
+use rand::Rng;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Hands out cast slots so accounts fishing in the same guild never cast in lockstep.
+/// Each slot is at least `min_gap` after the previous one in that guild, plus a random extra,
+/// so the spacing between accounts doesn't settle into a fixed pattern either.
+pub struct CastCoordinator {
+    min_gap: Duration,
+    max_jitter: Duration,
+    last_slot: Mutex<HashMap<u64, Instant>>,
+}
+
+impl CastCoordinator {
+    pub fn new(min_gap: Duration) -> Self {
+        Self {
+            min_gap,
+            max_jitter: min_gap / 2,
+            last_slot: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reserves the next free slot in `guild_id` and returns how long to wait for it.
+    pub fn reserve(&self, guild_id: u64, now: Instant) -> Duration {
+        let jitter = if self.max_jitter.is_zero() {
+            Duration::ZERO
+        } else {
+            rand::thread_rng().gen_range(Duration::ZERO..self.max_jitter)
+        };
+        let mut last_slot = self.last_slot.lock();
+        let slot = match last_slot.get(&guild_id) {
+            Some(&last) if last + self.min_gap > now => last + self.min_gap + jitter,
+            _ => now,
+        };
+        last_slot.insert(guild_id, slot);
+        slot - now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve_spaces_casts_per_guild() {
+        let coordinator = CastCoordinator::new(Duration::from_secs(2));
+        let now = Instant::now();
+
+        assert_eq!(coordinator.reserve(1, now), Duration::ZERO);
+        let second = coordinator.reserve(1, now);
+        assert!(second >= Duration::from_secs(2) && second < Duration::from_secs(3));
+        let third = coordinator.reserve(1, now);
+        assert!(third >= second + Duration::from_secs(2));
+
+        // Other guilds are independent
+        assert_eq!(coordinator.reserve(2, now), Duration::ZERO);
+        // Once the gap has passed there is nothing to wait for
+        assert_eq!(coordinator.reserve(1, now + Duration::from_secs(10)), Duration::ZERO);
+    }
+}
@@ -0,0 +1,272 @@
+use crate::config::{AccountConfig, Config};
+use crate::engine::accounts::control::{AccountCommand, AccountsHandle};
+use crate::engine::accounts::overview::{AccountSummary, AccountsOverview, AccountsTotals};
+use crate::engine::database::Throughput;
+use crate::engine::accounts::pipeline::{AccountPipeline, SharedServices};
+use crate::engine::accounts::captcha_gate::CaptchaGate;
+use crate::engine::accounts::stagger::CastCoordinator;
+use crate::notifications::event::{EventKind, Notification, Severity};
+use crate::notifications::hub::{self, Notifier};
+use crate::tui::app::App;
+use anyhow::{Result, anyhow};
+use log::{error, info};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+
+/// How often the accounts overview is refreshed
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+/// Look for dead pipelines and re-query throughput every 30 seconds
+const RESTART_EVERY_REFRESHES: u32 = 15;
+/// Fish/gold per hour are measured over this window
+const THROUGHPUT_WINDOW_HOURS: i64 = 24;
+/// Log the combined totals about every 10 minutes
+const STATS_EVERY_REFRESHES: u32 = 300;
+
+/// Totals across every running account.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AggregateStats {
+    pub accounts: usize,
+    pub running: usize,
+    pub fish_caught: u64,
+    pub money_earned: u64,
+    pub captchas_solved: u64,
+}
+
+/// Runs every `[[accounts]]` entry in this process and restarts pipelines whose tasks died.
+pub struct AccountSupervisor {
+    config: Config,
+    config_path: PathBuf,
+    shared: SharedServices,
+    accounts: Vec<AccountPipeline>,
+    overview: AccountsOverview,
+    throughput: HashMap<String, Throughput>,
+    handle: AccountsHandle,
+    commands: mpsc::Receiver<AccountCommand>,
+    /// The first account's app; the TUI shows it, so add/remove outcomes are logged there
+    host_app: Option<Arc<Mutex<App>>>,
+}
+
+impl AccountSupervisor {
+    pub async fn start<P: Into<PathBuf>>(config: Config, config_path: P) -> Result<Self> {
+        let names = config.account_names();
+        if names.is_empty() {
+            return Err(anyhow!("Running all accounts requires at least one [[accounts]] entry in config.toml"));
+        }
+
+        // Fail before starting anything if any account is misconfigured
+        let mut resolved = Vec::new();
+        for name in names {
+            let account_config = config.for_account(&name)?;
+            account_config.validate()?;
+            resolved.push((name, account_config));
+        }
+
+        let overview = AccountsOverview::default();
+        let (command_tx, commands) = mpsc::channel(16);
+        let mut supervisor = Self {
+            config: config.clone(),
+            config_path: config_path.into(),
+            shared: SharedServices {
+                notifier: Notifier::new(),
+                cast_coordinator: Arc::new(CastCoordinator::new(Duration::from_secs_f64(config.multi_account.stagger_seconds))),
+                captcha_gate: Arc::new(CaptchaGate::new(config.multi_account.on_captcha,
+                    Duration::from_secs_f64(config.multi_account.slow_cast_seconds))),
+            },
+            accounts: Vec::new(),
+            overview: overview.clone(),
+            throughput: HashMap::new(),
+            handle: AccountsHandle::new(overview, command_tx),
+            commands,
+            host_app: None,
+        };
+        let start_stagger = Duration::from_secs_f64(config.multi_account.start_stagger_seconds);
+        for (i, (name, account_config)) in resolved.into_iter().enumerate() {
+            let pipeline = AccountPipeline::start_after(&name, account_config, Some(&supervisor.config_path),
+                supervisor.shared.clone(), start_stagger * i as u32).await?;
+            supervisor.accounts.push(pipeline);
+        }
+        supervisor.host_app = Some(supervisor.accounts[0].app.clone());
+
+        // One set of notification channels for all accounts; chat commands act on the first one
+        hub::start(&supervisor.config, supervisor.accounts[0].app.clone()).await;
+        supervisor.refresh_throughput().await;
+        supervisor.refresh_overview().await;
+        Ok(supervisor)
+    }
+
+    pub fn accounts(&self) -> &[AccountPipeline] {
+        &self.accounts
+    }
+
+    /// Shared handle to the per-account rows; kept current by `supervise`.
+    pub fn overview(&self) -> AccountsOverview {
+        self.overview.clone()
+    }
+
+    /// Handle for adding and removing accounts while `supervise` runs.
+    pub fn handle(&self) -> AccountsHandle {
+        self.handle.clone()
+    }
+
+    /// Starts an account that isn't in config.toml. It lives until removed or the process exits;
+    /// add it to `[[accounts]]` to keep it across restarts.
+    pub async fn add_account(&mut self, account: AccountConfig) -> Result<()> {
+        let name = account.name.clone();
+        if !AccountConfig::is_valid_name(&name) {
+            return Err(anyhow!("Account name '{}' must be letters, digits, '-' or '_'", name));
+        }
+        if self.accounts.iter().any(|a| a.name == name) {
+            return Err(anyhow!("Account '{}' is already running", name));
+        }
+
+        let mut config = self.config.clone();
+        config.accounts.push(account);
+        let account_config = config.for_account(&name)?;
+        account_config.validate()?;
+
+        let pipeline = AccountPipeline::start(&name, account_config, None, self.shared.clone()).await?;
+        self.accounts.push(pipeline);
+        self.config = config;
+        self.refresh_throughput().await;
+        self.refresh_overview().await;
+        Ok(())
+    }
+
+    /// Stops an account's gateway, bot and processor tasks and forgets it.
+    pub async fn remove_account(&mut self, name: &str) -> Result<()> {
+        let index = self.accounts.iter().position(|a| a.name == name)
+            .ok_or_else(|| anyhow!("No running account named '{}'", name))?;
+        if self.accounts.len() == 1 {
+            return Err(anyhow!("Refusing to remove '{}': it is the last running account", name));
+        }
+
+        let mut pipeline = self.accounts.remove(index);
+        pipeline.stop();
+        // A captcha on an account that's gone must not hold the others forever
+        self.shared.captcha_gate.set_pending(name, false);
+        self.config.accounts.retain(|a| a.name != name);
+        self.throughput.remove(name);
+        self.refresh_overview().await;
+        Ok(())
+    }
+
+    async fn apply(&mut self, command: AccountCommand) {
+        let (action, name, result, reply) = match command {
+            AccountCommand::Add { account, reply } => {
+                let name = account.name.clone();
+                ("added", name, self.add_account(account).await, reply)
+            },
+            AccountCommand::Remove { name, reply } => {
+                let result = self.remove_account(&name).await;
+                ("removed", name, result, reply)
+            },
+        };
+
+        let message = match &result {
+            Ok(()) => {
+                info!("[{}] Account {}", name, action);
+                format!("Account '{}' {}", name, action)
+            },
+            Err(e) => {
+                error!("[{}] Account not {}: {}", name, action, e);
+                format!("Account '{}' not {}: {}", name, action, e)
+            },
+        };
+        if let Some(app) = &self.host_app {
+            app.lock().await.add_log(message);
+        }
+        if let Some(reply) = reply {
+            let _ = reply.send(result);
+        }
+    }
+
+    pub async fn refresh_overview(&mut self) {
+        let mut rows = Vec::with_capacity(self.accounts.len());
+        self.handle.set_apps(self.accounts.iter().map(|a| (a.name.clone(), a.app.clone())).collect());
+        for account in &self.accounts {
+            let throughput = self.throughput.get(&account.name).cloned().unwrap_or_default();
+            rows.push(AccountSummary::of(account, &throughput).await);
+        }
+        *self.overview.write() = rows;
+    }
+
+    /// Re-reads each account's catch and captcha history; slower than the overview, so it runs less often.
+    pub async fn refresh_throughput(&mut self) {
+        for account in &self.accounts {
+            match account.db.throughput(THROUGHPUT_WINDOW_HOURS).await {
+                Ok(throughput) => { self.throughput.insert(account.name.clone(), throughput); },
+                Err(e) => error!("[{}] Throughput query failed: {}", account.name, e),
+            }
+        }
+    }
+
+    pub async fn aggregate_stats(&self) -> AggregateStats {
+        let mut stats = AggregateStats { accounts: self.accounts.len(), ..Default::default() };
+        for account in &self.accounts {
+            let app = account.app.lock().await;
+            stats.running += app.is_running as usize;
+            stats.fish_caught += app.stats.fish_caught;
+            stats.money_earned += app.stats.money_earned;
+            stats.captchas_solved += app.stats.captchas_solved;
+        }
+        stats
+    }
+
+    /// Restarts accounts whose pipeline lost a task. Returns the names that were restarted.
+    pub async fn restart_dead(&mut self) -> Vec<String> {
+        let mut restarted = Vec::new();
+        for i in 0..self.accounts.len() {
+            if self.accounts[i].is_alive() {
+                continue;
+            }
+            let (name, config) = (self.accounts[i].name.clone(), self.accounts[i].config.clone());
+            error!("[{}] Account pipeline died, restarting", name);
+            self.accounts[i].stop();
+            self.shared.captcha_gate.set_pending(&name, false);
+            let config_path = self.accounts[i].config_path.clone();
+            match AccountPipeline::start(&name, config, config_path.as_deref(), self.shared.clone()).await {
+                Ok(pipeline) => {
+                    self.accounts[i] = pipeline;
+                    self.shared.notifier.send(Notification::new(EventKind::Error, Severity::Warning,
+                        format!("Account '{}' restarted", name), "Its pipeline stopped unexpectedly."));
+                    restarted.push(name);
+                },
+                Err(e) => error!("[{}] Restart failed: {}", name, e),
+            }
+        }
+        restarted
+    }
+
+    /// Watches the pipelines and applies add/remove requests forever; run it alongside whatever
+    /// keeps the process alive.
+    pub async fn supervise(&mut self) {
+        let mut ticker = tokio::time::interval(REFRESH_INTERVAL);
+        let mut refreshes: u32 = 0;
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {},
+                Some(command) = self.commands.recv() => {
+                    self.apply(command).await;
+                    continue;
+                },
+            }
+            refreshes += 1;
+            if refreshes.is_multiple_of(RESTART_EVERY_REFRESHES) {
+                self.restart_dead().await;
+                self.refresh_throughput().await;
+            }
+            self.refresh_overview().await;
+            if refreshes.is_multiple_of(STATS_EVERY_REFRESHES) {
+                let stats = self.aggregate_stats().await;
+                let totals = AccountsTotals::of(&self.overview.read());
+                info!("{}/{} accounts running, {:.0} fish/h, ${:.0}/h, {} captchas in {}h ({} fish caught this run)",
+                    stats.running, stats.accounts, totals.fish_per_hour, totals.gold_per_hour, totals.captchas,
+                    THROUGHPUT_WINDOW_HOURS, stats.fish_caught);
+            }
+        }
+    }
+}
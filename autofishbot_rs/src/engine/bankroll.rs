@@ -0,0 +1,193 @@
+use crate::config::GamblingConfig;
+use chrono::{NaiveDate, Utc};
+
+/// A grind shorter than this isn't worth a gamble.
+const BRIDGE_MIN_GRIND_SECS: f64 = 4.0 * 3600.0;
+
+/// Coinflip results so far, from `coinflips` plus this run's replies.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GamblingStats {
+    pub wins: u64,
+    pub losses: u64,
+    /// Gold won and lost over all flips
+    pub won: u64,
+    pub lost: u64,
+    /// Net result of the flips on `today`; read it through `net_on`, which knows when it's stale
+    pub today_net: i64,
+    /// The UTC day `today_net` belongs to
+    pub today: NaiveDate,
+    /// Net result since the bot started
+    pub session_net: i64,
+}
+
+impl GamblingStats {
+    pub fn flips(&self) -> u64 {
+        self.wins + self.losses
+    }
+
+    pub fn net(&self) -> i64 {
+        self.won as i64 - self.lost as i64
+    }
+
+    pub fn record(&mut self, won: bool, amount: u64) {
+        self.record_on(utc_today(), won, amount);
+    }
+
+    /// `record` for a flip on the UTC day `day`, starting a new `today_net` at midnight.
+    pub fn record_on(&mut self, day: NaiveDate, won: bool, amount: u64) {
+        if self.today != day {
+            self.today = day;
+            self.today_net = 0;
+        }
+        let change = if won { amount as i64 } else { -(amount as i64) };
+        if won {
+            self.wins += 1;
+            self.won += amount;
+        } else {
+            self.losses += 1;
+            self.lost += amount;
+        }
+        self.today_net += change;
+        self.session_net += change;
+    }
+
+    /// Net result of `day`'s flips: 0 once `today_net` belongs to an earlier day.
+    pub fn net_on(&self, day: NaiveDate) -> i64 {
+        if self.today == day { self.today_net } else { 0 }
+    }
+}
+
+pub fn utc_today() -> NaiveDate {
+    Utc::now().date_naive()
+}
+
+/// Sizes coinflips from `[gambling]` and the flips seen so far: a fraction of the Kelly
+/// stake for the estimated odds, within the stop-loss and daily loss limit.
+#[derive(Debug, Clone, Default)]
+pub struct Bankroll {
+    pub config: GamblingConfig,
+    pub stats: GamblingStats,
+}
+
+impl Bankroll {
+    pub fn new(config: GamblingConfig) -> Self {
+        Self { config, stats: GamblingStats::default() }
+    }
+
+    pub fn with_stats(mut self, stats: GamblingStats) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// The observed win rate, pulled toward `prior_win_rate` by `prior_weight` imagined flips
+    /// so a short lucky streak doesn't look like an edge.
+    pub fn win_probability(&self) -> f64 {
+        let prior = self.config.prior_weight as f64;
+        (self.stats.wins as f64 + self.config.prior_win_rate * prior) / (self.stats.flips() as f64 + prior).max(1.0)
+    }
+
+    /// Share of the balance to stake: `kelly_fraction` of `(b·p − q) / b`, 0 without an edge.
+    pub fn stake_fraction(&self) -> f64 {
+        let b = self.config.payout;
+        if b <= 0.0 {
+            return 0.0;
+        }
+        let p = self.win_probability();
+        ((b * p - (1.0 - p)) / b * self.config.kelly_fraction).clamp(0.0, 1.0)
+    }
+
+    /// Why no bet may be placed right now, if a limit says so.
+    pub fn blocked(&self) -> Option<String> {
+        self.blocked_on(utc_today())
+    }
+
+    /// `blocked` on the UTC day `day`; the daily loss limit starts over at midnight.
+    pub fn blocked_on(&self, day: NaiveDate) -> Option<String> {
+        let config = &self.config;
+        if config.stop_loss > 0 && -self.stats.session_net >= config.stop_loss as i64 {
+            return Some(format!("stop-loss reached ({} lost this run)", -self.stats.session_net));
+        }
+        let today_net = self.stats.net_on(day);
+        if config.daily_loss_limit > 0 && -today_net >= config.daily_loss_limit as i64 {
+            return Some(format!("daily loss limit reached ({} lost today)", -today_net));
+        }
+        None
+    }
+
+    /// A bet toward the gap between `balance` and `target_cost`, when grinding it would take
+    /// more than 4 hours at `gps`: the Kelly stake, capped by the gap, `max_bet` and what's
+    /// left under the loss limits.
+    pub fn bridge_bet(&self, balance: u64, target_cost: u64, gps: f64) -> Option<u64> {
+        let today = utc_today();
+        if gps <= 0.0 || balance >= target_cost || self.blocked_on(today).is_some() {
+            return None;
+        }
+        let needed = target_cost - balance;
+        if (needed as f64 / gps) <= BRIDGE_MIN_GRIND_SECS {
+            return None;
+        }
+        let mut bet = needed.min((balance as f64 * self.stake_fraction()) as u64);
+        if self.config.max_bet > 0 {
+            bet = bet.min(self.config.max_bet);
+        }
+        // Never stake more than the limits still allow to be lost
+        if self.config.stop_loss > 0 {
+            bet = bet.min((self.config.stop_loss as i64 + self.stats.session_net).max(0) as u64);
+        }
+        if self.config.daily_loss_limit > 0 {
+            bet = bet.min((self.config.daily_loss_limit as i64 + self.stats.net_on(today)).max(0) as u64);
+        }
+        (bet > 0).then_some(bet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bankroll() {
+        // A fair coin has no edge, so Kelly never bets
+        let fair = Bankroll::new(GamblingConfig::default());
+        assert_eq!(fair.win_probability(), 0.5);
+        assert_eq!(fair.stake_fraction(), 0.0);
+        assert_eq!(fair.bridge_bet(1_000_000, 2_000_000, 10.0), None);
+
+        let stats = GamblingStats { wins: 70, losses: 30, ..Default::default() };
+        let lucky = Bankroll::new(GamblingConfig::default()).with_stats(stats.clone());
+        // (70 + 10) / (100 + 20) with the default prior
+        assert!((lucky.win_probability() - 2.0 / 3.0).abs() < 1e-9);
+        let bet = lucky.bridge_bet(1_000_000, 2_000_000, 10.0).unwrap();
+        assert_eq!(bet, (1_000_000.0 * lucky.stake_fraction()) as u64);
+        // A short grind or an affordable target needs no bet
+        assert_eq!(lucky.bridge_bet(1_000_000, 1_010_000, 10.0), None);
+        assert_eq!(lucky.bridge_bet(1_000_000, 900_000, 10.0), None);
+
+        let capped = Bankroll::new(GamblingConfig { max_bet: 5_000, ..Default::default() }).with_stats(stats.clone());
+        assert_eq!(capped.bridge_bet(1_000_000, 2_000_000, 10.0), Some(5_000));
+
+        let mut limited = Bankroll::new(GamblingConfig { daily_loss_limit: 50_000, stop_loss: 100_000, ..Default::default() }).with_stats(stats);
+        limited.stats.record(false, 30_000);
+        assert_eq!(limited.bridge_bet(1_000_000, 2_000_000, 10.0), Some(20_000));
+        limited.stats.record(false, 20_000);
+        assert!(limited.blocked().unwrap().starts_with("daily loss limit"));
+        assert_eq!(limited.bridge_bet(1_000_000, 2_000_000, 10.0), None);
+        assert_eq!((limited.stats.losses, limited.stats.net()), (32, -50_000));
+    }
+
+    #[test]
+    fn test_daily_limit_rolls_over() {
+        let day = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let next = day.succ_opt().unwrap();
+        let mut bankroll = Bankroll::new(GamblingConfig { daily_loss_limit: 50_000, ..Default::default() });
+        bankroll.stats.record_on(day, false, 50_000);
+        assert!(bankroll.blocked_on(day).unwrap().starts_with("daily loss limit"));
+
+        // Midnight UTC lifts the limit before and after the next flip
+        assert_eq!(bankroll.blocked_on(next), None);
+        bankroll.stats.record_on(next, false, 10_000);
+        assert_eq!((bankroll.stats.today, bankroll.stats.today_net), (next, -10_000));
+        assert_eq!(bankroll.blocked_on(next), None);
+        assert_eq!((bankroll.stats.session_net, bankroll.stats.net()), (-60_000, -60_000));
+    }
+}
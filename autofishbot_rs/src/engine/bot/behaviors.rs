@@ -0,0 +1,640 @@
+use super::transitions::BotState;
+use super::*;
+use futures_util::future::BoxFuture;
+
+/// What the loop does after a tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// Run the background chores (quests, crates, the scheduler...) before the next tick
+    Done,
+    /// Start the next pass right away, e.g. after asking for another state
+    Again,
+}
+
+/// Runs the bot while it's in `state()`. A tick does one unit of work (a cast, a sell, one
+/// poll during a break); it moves on by asking for another state with `Bot::request`.
+pub trait Behavior<C>: Send {
+    fn state(&self) -> BotState;
+    fn tick<'a>(&'a mut self, bot: &'a mut C) -> BoxFuture<'a, Step>;
+}
+
+/// One behavior per state. The bot loop only ticks whichever matches the current state, so a
+/// new one is a `BotState` variant, its transition rules and a `with` here.
+pub struct Behaviors<C> {
+    behaviors: HashMap<BotState, Box<dyn Behavior<C>>>,
+}
+
+impl<C> Default for Behaviors<C> {
+    fn default() -> Self {
+        Self { behaviors: HashMap::new() }
+    }
+}
+
+impl<C> Behaviors<C> {
+    /// Adds `behavior`, replacing the one registered for its state.
+    pub fn with(mut self, behavior: impl Behavior<C> + 'static) -> Self {
+        self.behaviors.insert(behavior.state(), Box::new(behavior));
+        self
+    }
+
+    pub fn get_mut(&mut self, state: BotState) -> Option<&mut (dyn Behavior<C> + 'static)> {
+        self.behaviors.get_mut(&state).map(|b| b.as_mut())
+    }
+}
+
+/// The behaviors `Bot::run` starts with.
+pub fn standard() -> Behaviors<Bot> {
+    Behaviors::default()
+        .with(Idle)
+        .with(Fishing)
+        .with(Selling)
+        .with(Shopping)
+        .with(Prestiging)
+        .with(Exploring)
+        .with(CaptchaWait)
+        .with(OnBreak)
+        .with(TokenRecheck)
+        .with(DegradedProbe)
+}
+
+pub struct Idle;
+
+impl Behavior<Bot> for Idle {
+    fn state(&self) -> BotState {
+        BotState::Idle
+    }
+
+    fn tick<'a>(&'a mut self, _bot: &'a mut Bot) -> BoxFuture<'a, Step> {
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Step::Done
+        })
+    }
+}
+
+/// Sells when the policy says so, learns from the last catch, follows the optimizer's
+/// recommendation, then casts and sleeps out the cooldown.
+pub struct Fishing;
+
+impl Behavior<Bot> for Fishing {
+    fn state(&self) -> BotState {
+        BotState::Fishing
+    }
+
+    fn tick<'a>(&'a mut self, bot: &'a mut Bot) -> BoxFuture<'a, Step> {
+        Box::pin(async move {
+            if let Some(reason) = bot.routine_sell().await {
+                bot.sell_reason = Some(reason);
+                bot.request(BotState::Selling);
+                return Step::Again;
+            }
+
+            let (last_msg, profile) = {
+                let app = bot.app_state.lock().await;
+                (app.last_message_object.clone(), app.profile.clone())
+            };
+            let current_biome = Biome::from_display_name(&profile.biome).unwrap_or(Biome::River);
+
+            if bot.learn_from_catch(last_msg.as_ref(), current_biome).await
+                || bot.follow_recommendations(&profile, current_biome).await {
+                return Step::Again;
+            }
+            bot.cast().await
+        })
+    }
+}
+
+pub struct Selling;
+
+impl Behavior<Bot> for Selling {
+    fn state(&self) -> BotState {
+        BotState::Selling
+    }
+
+    fn tick<'a>(&'a mut self, bot: &'a mut Bot) -> BoxFuture<'a, Step> {
+        Box::pin(async move {
+            let reason = bot.sell_reason.clone().unwrap_or(SellReason::Timer);
+            let command = {
+                let app = bot.app_state.lock().await;
+                SellPolicy::new(&bot.config).command(&app.profile).map(str::to_string)
+            };
+            let Some(command) = command else {
+                info!("Not selling ({}): exotic fish are held and [sell] exotic_safe_command is empty", reason);
+                bot.finish_selling();
+                return Step::Again;
+            };
+            info!("Selling ({}) with /{}", reason, command);
+            let value = bot.app_state.lock().await.inventory_valuation.as_ref().map_or(0, |v| v.total);
+            bot.use_charm(CharmMoment::Sell { value }).await;
+
+            let sent = match Scheduler::build(&bot.client, &bot.config, &command).await {
+                Ok((cmd, options)) => Ok(bot.send_tracked("sell", &cmd, options).await),
+                Err(e) => Err(e),
+            };
+            match sent {
+                Ok(true) => {
+                    bot.app_state.lock().await.inventory_valuation = Some(InventoryValuation::default());
+                    bot.finish_selling();
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                },
+                // Stay in Selling and try again, unless the failure changed the state
+                Ok(false) => tokio::time::sleep(bot.send_backoff()).await,
+                Err(e) => {
+                    if !bot.check_logged_out(&e).await {
+                        warn!("Skipping the sell: {}", e);
+                        bot.finish_selling();
+                    }
+                },
+            }
+            Step::Done
+        })
+    }
+}
+
+/// Buys the rod or boat in `pending_recommendation`.
+pub struct Shopping;
+
+impl Behavior<Bot> for Shopping {
+    fn state(&self) -> BotState {
+        BotState::Shopping
+    }
+
+    fn tick<'a>(&'a mut self, bot: &'a mut Bot) -> BoxFuture<'a, Step> {
+        Box::pin(async move {
+            let guild_id = bot.config.system.guild_id.to_string();
+            let now = Instant::now();
+
+            let mut bought = true;
+            if let Some(rec) = bot.pending_recommendation.clone() {
+                info!("Shopping: Executing {:?}", rec.action);
+
+                if let Some(cmd) = bot.client.get_command(&guild_id, "shop buy").await.unwrap_or(None) {
+                    let item = match &rec.action {
+                        ActionType::BuyRod => Some("rod"),
+                        ActionType::BuyBoat => Some("boat"),
+                        _ => None,
+                    };
+                    if let Some(item) = item {
+                        match CommandBuilder::new(&cmd).subcommand("buy").option(item, rec.target_name.as_str()).build() {
+                            Ok(options) => {
+                                bought = bot.send_confirmed("shop", &cmd, options).await;
+                                if bought {
+                                    bot.last_action = Some((rec.action.clone(), now));
+                                    bot.notify_purchase(&rec).await;
+                                    if rec.action == ActionType::BuyBoat {
+                                        bot.record_boat(&rec.target_name).await;
+                                    }
+                                }
+                            },
+                            // Dropped: it won't fit the schema on a retry either
+                            Err(e) => warn!("Can't buy {}: {}", rec.target_name, e),
+                        }
+                    }
+                }
+            }
+            if bought {
+                bot.pending_recommendation = None;
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                bot.request(BotState::Fishing);
+            } else if bot.state == BotState::Shopping {
+                // Keep the recommendation and try again
+                tokio::time::sleep(bot.send_backoff()).await;
+            }
+            Step::Done
+        })
+    }
+}
+
+pub struct Prestiging;
+
+impl Behavior<Bot> for Prestiging {
+    fn state(&self) -> BotState {
+        BotState::Prestiging
+    }
+
+    fn tick<'a>(&'a mut self, bot: &'a mut Bot) -> BoxFuture<'a, Step> {
+        Box::pin(async move {
+            bot.prestige().await;
+            bot.request(BotState::Fishing);
+            Step::Done
+        })
+    }
+}
+
+/// Hands the last message to the explorer while an exploration runs; `Bot::sync_explorer`
+/// switches in and out.
+pub struct Exploring;
+
+impl Behavior<Bot> for Exploring {
+    fn state(&self) -> BotState {
+        BotState::Exploration
+    }
+
+    fn tick<'a>(&'a mut self, bot: &'a mut Bot) -> BoxFuture<'a, Step> {
+        Box::pin(async move {
+            let last_msg_obj = bot.app_state.lock().await.last_message_object.clone();
+            bot.explorer.lock().await.tick(last_msg_obj.as_ref()).await;
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Step::Done
+        })
+    }
+}
+
+/// Tries the captcha solvers and any answer typed into the TUI until the captcha clears.
+pub struct CaptchaWait;
+
+impl Behavior<Bot> for CaptchaWait {
+    fn state(&self) -> BotState {
+        BotState::Captcha
+    }
+
+    fn tick<'a>(&'a mut self, bot: &'a mut Bot) -> BoxFuture<'a, Step> {
+        Box::pin(async move {
+            bot.answer_captcha_manually().await;
+            bot.solve_captcha().await;
+            let status = bot.captcha.lock().await.status();
+            bot.app_state.lock().await.captcha = status;
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            Step::Again
+        })
+    }
+}
+
+/// Waits out `break_until`, polling so a stop or captcha is noticed.
+pub struct OnBreak;
+
+impl Behavior<Bot> for OnBreak {
+    fn state(&self) -> BotState {
+        BotState::Break
+    }
+
+    fn tick<'a>(&'a mut self, bot: &'a mut Bot) -> BoxFuture<'a, Step> {
+        Box::pin(async move {
+            match bot.break_until {
+                Some((until, _)) if Instant::now() < until => {
+                    // The chores are skipped until the break ends, so the snapshot is kept up here
+                    bot.save_snapshot().await;
+                    tokio::time::sleep(until.saturating_duration_since(Instant::now()).min(BREAK_POLL_INTERVAL)).await;
+                    Step::Again
+                },
+                _ => {
+                    info!("Break over; resuming");
+                    bot.app_state.lock().await.add_log("Break over, resuming casts".to_string());
+                    bot.break_until = None;
+                    bot.request(BotState::Fishing);
+                    Step::Done
+                },
+            }
+        })
+    }
+}
+
+pub struct TokenRecheck;
+
+impl Behavior<Bot> for TokenRecheck {
+    fn state(&self) -> BotState {
+        BotState::LoggedOut
+    }
+
+    fn tick<'a>(&'a mut self, bot: &'a mut Bot) -> BoxFuture<'a, Step> {
+        Box::pin(async move {
+            bot.recheck_token().await;
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            // The scheduler would only send more doomed interactions
+            Step::Again
+        })
+    }
+}
+
+pub struct DegradedProbe;
+
+impl Behavior<Bot> for DegradedProbe {
+    fn state(&self) -> BotState {
+        BotState::Degraded
+    }
+
+    fn tick<'a>(&'a mut self, bot: &'a mut Bot) -> BoxFuture<'a, Step> {
+        Box::pin(async move {
+            tokio::time::sleep(DEGRADED_PROBE_INTERVAL).await;
+            // Probe with a cast; another failure comes straight back here
+            bot.request(BotState::Fishing);
+            Step::Done
+        })
+    }
+}
+
+impl Bot {
+    fn finish_selling(&mut self) {
+        self.sold_at = Instant::now();
+        self.sell_reason = None;
+        self.request(BotState::Fishing);
+    }
+
+    /// Feeds the catch in `last_msg` to the optimizer's biome stats. Returns true when it said
+    /// the inventory is full, after asking for `Selling`.
+    async fn learn_from_catch(&mut self, last_msg: Option<&Message>, current_biome: Biome) -> bool {
+        let Some(msg) = last_msg else { return false };
+        for desc in msg.embeds.iter().filter_map(|e| e.description.as_deref()) {
+            if desc.to_lowercase().contains("full") {
+                info!("Inventory Full detected! Switching to Selling.");
+                self.sell_reason = Some(SellReason::Full);
+                self.request(BotState::Selling);
+                return true;
+            }
+
+            let Some(catch) = parser::parse_catch_embed(desc) else { continue };
+            let mut total_gold = 0;
+            let mut total_fish = 0;
+            for (fish_name, count) in &catch.fish {
+                let price = FISH_DATA.get(fish_name.as_str()).map(|f| f.price).unwrap_or(0);
+                total_gold += price * (*count as u64);
+                total_fish += *count as u64;
+            }
+            if total_fish == 0 {
+                continue;
+            }
+
+            let mut opt = self.optimizer.lock().await;
+            let stats = opt.biome_knowledge.entry(current_biome).or_default();
+            stats.update(total_gold, catch.xp as u64, total_fish);
+
+            // Save periodically
+            if stats.total_catches % 50 == 0 {
+                if let Err(e) = self.database.save_biome_stats(current_biome.display_name(), stats).await {
+                    warn!("Failed to save biome stats: {}", e);
+                }
+            }
+            info!("Learned: {} gold, {} xp from {} fish in {:?}", total_gold, catch.xp, total_fish, current_biome);
+        }
+        false
+    }
+
+    /// Asks the optimizer for the next move and acts on it: prestiges, shops, sells first,
+    /// travels or flips a coin. Returns true when it asked for another state.
+    async fn follow_recommendations(&mut self, profile: &Profile, current_biome: Biome) -> bool {
+        let current_balance = profile.balance.unwrap_or(0);
+        let current_rod = ROD_DATA.values().find(|r| r.name == profile.rod)
+            .or_else(|| ROD_DATA.get(&RodType::Plastic));
+        // Rowboat until a profile or shop embed shows the boats owned
+        let current_boat = profile.current_boat().or_else(|| BOAT_DATA.get(&BoatType::Rowboat));
+
+        let (Some(rod), Some(boat)) = (current_rod, current_boat) else {
+            warn!("Critical: Could not load Game Data for optimization.");
+            return false;
+        };
+
+        let (gambling, fishdex) = {
+            let app = self.app_state.lock().await;
+            (app.gambling.clone(), app.fishdex.clone())
+        };
+        let mut opt = self.optimizer.lock().await;
+        opt.bankroll.stats = gambling;
+        opt.fishdex = fishdex;
+
+        let current_gps = opt.calculate_metrics(rod, boat, current_biome, profile);
+        let recs = opt.solve_next_move(rod, boat, current_biome, profile, current_balance);
+        let prestige_plan = (self.config.strategy.auto_prestige
+            && profile.level_number().is_some_and(|level| level >= self.config.strategy.prestige_level)
+            && self.prestige_sent_at.is_none_or(|at| at.elapsed() >= PRESTIGE_RETRY_INTERVAL))
+            .then(|| opt.evaluate_prestige(rod, boat, current_biome, profile));
+        drop(opt);
+
+        if let Some(plan) = prestige_plan.filter(|p| p.worthwhile) {
+            info!("AUTONOMOUS ACTION: Prestiging (+{:.0}% income, {:.1} mins to re-buy the rod)", plan.boost * 100.0, plan.recovery_seconds / 60.0);
+            self.request(BotState::Prestiging);
+            return true;
+        }
+
+        // Hooks aren't gold, so League upgrades are bought alongside the gold plan
+        let (league_recs, recs): (Vec<_>, Vec<_>) = recs.into_iter()
+            .partition(|r| r.action == ActionType::BuyLeagueUpgrade);
+        if let (true, Some(upgrade)) = (self.config.automation.spend_hooks, league_recs.first()) {
+            self.buy_league_upgrade(upgrade).await;
+        }
+        let recs = self.apply_playbook(recs, current_biome, profile).await;
+        if self.requests.contains(BotState::Prestiging) {
+            return true;
+        }
+        let recs = self.apply_dex_hunting(recs, current_biome, profile).await;
+
+        let Some(best) = recs.first() else { return false };
+        // Update Strategy Info
+        {
+            let mut app = self.app_state.lock().await;
+            app.strategy.current_goal = format!("{} ({:?})", best.target_name, best.action);
+            app.strategy.current_gps = self.config.strategy.goal.format_rate(current_gps);
+            app.strategy.progress = format!("{} / {} ({:.1}%)",
+                money::format_compact(current_balance), money::format_compact(best.cost),
+                if best.cost > 0 { (current_balance as f64 / best.cost as f64) * 100.0 } else { 100.0 }
+            );
+            app.strategy.est_time = format!("{:.1} mins", best.roi_seconds / 60.0);
+        }
+
+        info!("ROI Recommendation: {:?} {} ({:.2}s)", best.action, best.target_name, best.roi_seconds);
+
+        let now = Instant::now();
+        // A Coinflip with a different amount isn't a repeat: PartialEq compares the data too
+        let is_repeat = self.last_action.as_ref()
+            .is_some_and(|(last_type, last_time)| *last_type == best.action && now.duration_since(*last_time) < Duration::from_secs(15));
+        if is_repeat {
+            return false;
+        }
+
+        let guild_id = self.config.system.guild_id.to_string();
+        match &best.action {
+            ActionType::BuyRod | ActionType::BuyBoat if current_balance >= best.cost => {
+                info!("AUTONOMOUS ACTION: Transitioning to Shopping for {}", best.target_name);
+                self.pending_recommendation = Some(best.clone());
+                self.request(BotState::Shopping);
+                return true;
+            },
+            ActionType::BuyRod | ActionType::BuyBoat => {
+                let value = self.app_state.lock().await.inventory_valuation.as_ref().map_or(0, |v| v.total);
+                if let Some(reason) = SellPolicy::new(&self.config).before_purchase(best, current_balance, value) {
+                    self.sell_reason = Some(reason);
+                    self.request(BotState::Selling);
+                    return true;
+                }
+            },
+            ActionType::Travel => {
+                info!("AUTONOMOUS ACTION: Traveling to {}", best.target_name);
+                if let Some(cmd) = self.client.get_command(&guild_id, "biome").await.unwrap_or(None) {
+                    let traveled = match CommandBuilder::new(&cmd).option("biome", best.target_name.as_str()).build() {
+                        Ok(options) => self.send_tracked("biome", &cmd, options).await,
+                        Err(e) => {
+                            warn!("Can't travel to {}: {}", best.target_name, e);
+                            false
+                        },
+                    };
+                    self.last_action = Some((ActionType::Travel, now));
+
+                    if traveled {
+                        self.app_state.lock().await.profile.biome = best.target_name.clone();
+                    }
+                    tokio::time::sleep(Duration::from_secs(3)).await;
+                }
+            },
+            ActionType::Coinflip { amount, .. } if self.config.automation.danger_mode => {
+                info!("AUTONOMOUS ACTION: Coinflip {} for {}", amount, best.target_name);
+                if let Some(cmd) = self.client.get_command(&guild_id, "coinflip").await.unwrap_or(None) {
+                    // /coinflip [amount] heads
+                    match CommandBuilder::new(&cmd).option("amount", *amount).option("side", "heads").build() {
+                        Ok(options) => { self.send_tracked("coinflip", &cmd, options).await; },
+                        Err(e) => warn!("Can't coinflip: {}", e),
+                    }
+                    self.last_action = Some((best.action.clone(), now));
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+            },
+            _ => {}
+        }
+        false
+    }
+
+    /// Sends `/fish` (staggered against the other accounts) and sleeps out the cooldown, or
+    /// backs off after a failed cast.
+    async fn cast(&mut self) -> Step {
+        info!("Fishing...");
+        let guild_id = self.config.system.guild_id.to_string();
+        let fish_command = match self.client.get_command(&guild_id, "fish").await {
+            Ok(cmd) => cmd,
+            Err(e) => {
+                if self.check_logged_out(&e).await {
+                    return Step::Again;
+                }
+                log::error!("Failed to fetch commands: {}", e);
+                None
+            }
+        };
+
+        if let Some(coordinator) = &self.cast_coordinator {
+            let wait = coordinator.reserve(self.config.system.guild_id, Instant::now());
+            if !wait.is_zero() {
+                debug!("Staggering cast by {:.2}s", wait.as_secs_f64());
+                tokio::time::sleep(wait).await;
+            }
+        }
+
+        let mut cast_failed = false;
+        if let Some(cmd) = fish_command {
+            self.last_cast = Some(Instant::now());
+            if self.send_tracked("fish", &cmd, None).await {
+                self.app_state.lock().await.stats.fish_caught += 1;
+            } else {
+                if self.state == BotState::LoggedOut {
+                    return Step::Again;
+                }
+                // Fetched again next cast, in case the definition changed
+                self.client.invalidate_commands(&guild_id);
+                cast_failed = true;
+            }
+        }
+
+        // Sleep random amount using Dynamic Cooldown Manager, or back off after a failed cast
+        let sleep_duration = if cast_failed {
+            self.send_backoff()
+        } else {
+            self.cooldown_manager.lock().await.get_sleep_time().mul_f64(self.slow_factor)
+        };
+
+        info!("Sleeping for {:.2}s", sleep_duration.as_secs_f64());
+        tokio::time::sleep(sleep_duration).await;
+        Step::Done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discord::mock::MockDiscord;
+
+    /// Records which behavior ran, in place of a `Bot`.
+    #[derive(Default)]
+    struct Trace(Vec<&'static str>);
+
+    struct Named(BotState, &'static str);
+
+    impl Behavior<Trace> for Named {
+        fn state(&self) -> BotState {
+            self.0
+        }
+
+        fn tick<'a>(&'a mut self, trace: &'a mut Trace) -> BoxFuture<'a, Step> {
+            Box::pin(async move {
+                trace.0.push(self.1);
+                Step::Done
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_behaviors() {
+        let mut behaviors = Behaviors::default()
+            .with(Named(BotState::Fishing, "fish"))
+            .with(Named(BotState::Selling, "sell"))
+            .with(Named(BotState::Fishing, "fish quietly"));
+        let mut trace = Trace::default();
+        for state in [BotState::Fishing, BotState::Selling] {
+            assert_eq!(behaviors.get_mut(state).unwrap().tick(&mut trace).await, Step::Done);
+        }
+        // A later registration replaces the earlier one for its state
+        assert_eq!(trace.0, ["fish quietly", "sell"]);
+        assert!(behaviors.get_mut(BotState::Break).is_none());
+
+        let standard = standard();
+        assert!(standard.behaviors.len() == 10 && standard.behaviors.iter().all(|(state, b)| b.state() == *state));
+    }
+
+    /// A `Bot` on an in-memory database whose client talks to `mock`.
+    async fn mock_bot(config: &Config, mock: &MockDiscord) -> Bot {
+        let db = Arc::new(Database::in_memory().await.unwrap());
+        let client = Arc::new(DiscordClient::new(config.clone()).unwrap().with_api_base(mock.api_base()));
+        let app = Arc::new(Mutex::new(App::new(config.clone())));
+        Bot::new(config.clone(), client, app, db).await
+    }
+
+    fn mock_config() -> Config {
+        let mut config = Config::default();
+        config.system.user_token = "mock-token".to_string();
+        config.system.guild_id = 11;
+        config.system.channel_id = 22;
+        config
+    }
+
+    #[tokio::test]
+    async fn test_selling_keeps_exotic_fish() {
+        let mut config = mock_config();
+        config.sell.keep_exotic = true;
+        let mock = MockDiscord::start(&config, &["sell"]).await.unwrap();
+        let mut bot = mock_bot(&config, &mock).await;
+        bot.state = BotState::Selling;
+        bot.sell_reason = Some(SellReason::Full);
+        bot.app_state.lock().await.profile.exotic_fish.gold = 1;
+
+        // No exotic_safe_command: nothing is sent and the bot goes back to fishing
+        assert_eq!(Selling.tick(&mut bot).await, Step::Again);
+        assert!(mock.interactions().is_empty());
+        assert!(bot.sell_reason.is_none());
+        bot.apply_requests();
+        assert_eq!(bot.state, BotState::Fishing);
+    }
+
+    #[tokio::test]
+    async fn test_on_break() {
+        let config = mock_config();
+        let mock = MockDiscord::start(&config, &[]).await.unwrap();
+        let mut bot = mock_bot(&config, &mock).await;
+        bot.state = BotState::Break;
+        bot.break_until = Some((Instant::now() + Duration::from_millis(50), BreakKind::Short));
+
+        // Still on break: poll again without the chores
+        assert_eq!(OnBreak.tick(&mut bot).await, Step::Again);
+        bot.apply_requests();
+        assert_eq!(bot.state, BotState::Break);
+
+        assert_eq!(OnBreak.tick(&mut bot).await, Step::Done);
+        assert!(bot.break_until.is_none());
+        bot.apply_requests();
+        assert_eq!(bot.state, BotState::Fishing);
+    }
+}
@@ -0,0 +1,1991 @@
+mod behaviors;
+mod transitions;
+
+use crate::config::Config;
+use crate::discord::types::Message;
+use crate::discord::client::{DiscordClient, Unauthorized};
+use crate::discord::command::CommandBuilder;
+use crate::engine::accounts::captcha_gate::{CaptchaGate, Hold};
+use crate::engine::accounts::stagger::CastCoordinator;
+use crate::engine::bankroll::Bankroll;
+use crate::engine::breaks::{Activity, BreakKind, BreakPlanner};
+use crate::engine::captcha::Captcha;
+use crate::engine::channels::ChannelRotation;
+use crate::engine::scheduler::Scheduler;
+use crate::engine::sell::{SellPolicy, SellReason};
+use crate::engine::cooldown::CooldownManager;
+use crate::engine::explorer::{Explorer, ExplorerRequest};
+use crate::engine::capture::EmbedCapture;
+use crate::engine::correlation::{CorrelationStore, NoReply};
+use crate::engine::database::Database;
+use crate::engine::net_worth::NetWorth;
+use crate::engine::optimizer::{Optimizer, ActionType, CharmMoment, Recommendation};
+use crate::engine::fishdex::{self, Fishdex};
+use crate::engine::game_data::{RodType, BoatType, Biome, Boost, TreasureQuality, BAIT_DATA, FISH_DATA, ROD_DATA, BOAT_DATA};
+use crate::engine::levels;
+use crate::engine::mechanics;
+use crate::engine::parser::{self, ClaimReply};
+use crate::engine::playbook::Playbook;
+use crate::engine::profile::{CharmType, CrateOpen, Profile};
+use crate::engine::recovery;
+use crate::engine::state::Snapshot;
+use crate::engine::streak;
+use crate::engine::valuation::InventoryValuation;
+use crate::util::money;
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde_json::Value;
+
+use crate::tui::app::App;
+use crate::notifications::event::{EventKind, Notification, Severity};
+use behaviors::Step;
+use transitions::StateQueue;
+pub use transitions::BotState;
+
+/// Consecutive command send errors before the bot goes `Degraded` and the owner is notified.
+const MAX_SEND_FAILURES: u32 = 5;
+/// Longest wait between retries after a failed send; it doubles per failure up to this.
+const MAX_SEND_BACKOFF: Duration = Duration::from_secs(60);
+/// While `Degraded`, how long to wait before trying another cast.
+const DEGRADED_PROBE_INTERVAL: Duration = Duration::from_secs(120);
+/// How long a quest claim may go without a reward embed before it's logged as unconfirmed.
+const QUEST_REWARD_TIMEOUT: Duration = Duration::from_secs(90);
+/// While logged out, how often the token is re-tested against `/users/@me`.
+const TOKEN_RECHECK_INTERVAL: Duration = Duration::from_secs(300);
+/// How often the clan vault rule is re-checked; the balance only refreshes with profile embeds.
+const VAULT_CHECK_INTERVAL: Duration = Duration::from_secs(600);
+/// How often the daily streak guard looks at `daily_claims`; also the retry delay after a failed claim.
+const DAILY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// How long `/daily`, clan `/claim` and vote claims wait for the reply that confirms them.
+const CLAIM_REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Clan `/claim` interval when the reply doesn't say; also the retry delay after a failed claim.
+const CLAN_CLAIM_INTERVAL: Duration = Duration::from_secs(4 * 3600);
+const CLAN_CLAIM_RETRY: Duration = Duration::from_secs(600);
+/// Voting opens again this long after a vote.
+const VOTE_COOLDOWN: Duration = Duration::from_secs(12 * 3600);
+/// Gap between crate openings, and how long to wait for a drops embed before moving on.
+const CRATE_OPEN_SPACING: Duration = Duration::from_secs(10);
+/// How often unanswered commands are looked up for a retry.
+const RETRY_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+/// Unanswered commands older than this aren't retried, e.g. ones from before a restart.
+const RETRY_WINDOW: Duration = Duration::from_secs(600);
+/// How often the profile is checked against `[cosmetic]` until it matches.
+const COSMETIC_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Rounds of cosmetic commands sent before giving up on the profile ever matching.
+const MAX_COSMETIC_ATTEMPTS: u32 = 3;
+/// How often the buff timers are checked for a boost to buy.
+const BOOST_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Wait before sending the playbook's prestige command again, if the level hasn't dropped.
+const PRESTIGE_RETRY_INTERVAL: Duration = Duration::from_secs(600);
+/// How often the time left before the next break is checked for a grinding charm.
+const CHARM_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// How often `engine::state` snapshots are saved.
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(60);
+/// How often the Stats tab's charts are re-read from the database.
+const CHART_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+/// Days of history behind the Stats tab's charts.
+const CHART_DAYS: i64 = 7;
+/// How often the Explorer tab's stored-item counts are re-read.
+const EXPLORER_COUNTS_INTERVAL: Duration = Duration::from_secs(10);
+/// Wait before selecting the dex hunting bait again, if the profile still shows another one.
+const DEX_BAIT_RETRY_INTERVAL: Duration = Duration::from_secs(600);
+/// How long an answer to a captcha waits for its verdict before the next attempt anyway.
+const CAPTCHA_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+/// Gap before retrying after Virtual Fisher rejects an answer.
+const CAPTCHA_REJECT_DELAY: Duration = Duration::from_secs(5);
+/// Gap between bait purchases, so a stale "no bait" reply doesn't buy twice, and between
+/// attempts that failed.
+const BAIT_BUY_SPACING: Duration = Duration::from_secs(300);
+/// How long a purchase waits for its reply, which may carry a "Confirm purchase" button.
+const PURCHASE_REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Gap between League upgrade purchases, so the hooks balance can catch up from the embeds.
+const LEAGUE_UPGRADE_SPACING: Duration = Duration::from_secs(60);
+/// How often a break checks whether it's over, so a stop or reload isn't held up by it.
+const BREAK_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+pub struct Bot {
+    config: Config,
+    client: Arc<DiscordClient>,
+    scheduler: Arc<Mutex<Scheduler>>,
+    pub captcha: Arc<Mutex<Captcha>>,
+    app_state: Arc<Mutex<App>>,
+    state: BotState,
+    /// States asked for since the last switch, see `Bot::request`
+    requests: StateQueue,
+    pub cooldown_manager: Arc<Mutex<CooldownManager>>,
+    explorer: Arc<Mutex<Explorer>>,
+    optimizer: Arc<Mutex<Optimizer>>,
+    database: Arc<Database>,
+    correlation: CorrelationStore,
+    last_action: Option<(ActionType, Instant)>,
+    pending_recommendation: Option<Recommendation>,
+    config_generation: u64,
+    send_failures: u32,
+    cast_coordinator: Option<Arc<CastCoordinator>>,
+    token_checked_at: Option<Instant>,
+    vault_checked_at: Option<Instant>,
+    daily_checked_at: Option<Instant>,
+    /// When Virtual Fisher last said `/daily` is ready again
+    daily_ready_at: Option<Instant>,
+    clan_claim_due_at: Option<Instant>,
+    workers_checked_at: Option<Instant>,
+    /// When each charm may be used again
+    charm_ready_at: HashMap<CharmType, Instant>,
+    charms_checked_at: Option<Instant>,
+    snapshot_saved_at: Option<Instant>,
+    retry_checked_at: Option<Instant>,
+    /// The vote window already claimed or reminded about
+    vote_handled: Option<chrono::DateTime<chrono::Utc>>,
+    vote_window_loaded: bool,
+    /// Shared with the other accounts, plus this account's name in it
+    captcha_gate: Option<(Arc<CaptchaGate>, String)>,
+    held: bool,
+    last_cast: Option<Instant>,
+    cosmetics_applied: bool,
+    cosmetic_attempts: u32,
+    cosmetics_checked_at: Option<Instant>,
+    /// Profile level last seen; a drop means the account prestiged
+    last_level: Option<u64>,
+    boosts_checked_at: Option<Instant>,
+    /// When each boost was last bought, in case no buffs embed has shown its timer yet
+    boosts_bought: HashMap<Boost, Instant>,
+    /// `[strategy] playbook`, loaded at startup
+    playbook: Option<Playbook>,
+    prestige_sent_at: Option<Instant>,
+    charts_refreshed_at: Option<Instant>,
+    dex_bait_sent_at: Option<Instant>,
+    bait_bought_at: Option<Instant>,
+    bait_attempted_at: Option<Instant>,
+    explorer_counted_at: Option<Instant>,
+    net_worth_logged_at: Option<Instant>,
+    /// OCR answers tried on the current captcha
+    captcha_attempted_at: Option<Instant>,
+    /// `[captcha] max_attempts` ran out and the owner was told
+    captcha_gave_up: bool,
+    break_planner: BreakPlanner,
+    /// Which channel commands go to, per `[channel_rotation]`
+    channels: ChannelRotation,
+    /// End of the break in progress
+    break_until: Option<(Instant, BreakKind)>,
+    /// Cast sleeps are stretched by this during a slow stretch of `[breaks]`
+    slow_factor: f64,
+    /// Why the bot entered `Selling`
+    sell_reason: Option<SellReason>,
+    /// Last sell (or startup), for `[sell] interval_minutes`
+    sold_at: Instant,
+}
+
+impl Bot {
+    pub async fn new(config: Config, client: Arc<DiscordClient>, app_state: Arc<Mutex<App>>, database: Arc<Database>) -> Self {
+        let scheduler = Arc::new(Mutex::new(Scheduler::new(config.clone())));
+        let captcha = Arc::new(Mutex::new(Captcha::new(config.clone())));
+        let cooldown_manager = Arc::new(Mutex::new(CooldownManager::new(config.system.user_cooldown)));
+
+        // Initialize Explorer
+        let guild_id = config.system.guild_id.to_string();
+        let channel_id = config.system.channel_id.to_string();
+        let explorer = Arc::new(Mutex::new(Explorer::new(client.clone(), database.clone(), EmbedCapture::new(&config), guild_id, channel_id)));
+
+        // Initialize Optimizer
+        let mut optimizer = Optimizer::new().with_goal(config.strategy.goal).with_bankroll(Bankroll::new(config.gambling.clone()))
+            .with_charms(config.charms.clone());
+        if let Ok(stats) = database.load_biome_stats().await {
+            optimizer.biome_knowledge = stats;
+        }
+        match database.treasure_observations().await {
+            Ok(observations) => {
+                for quality in TreasureQuality::CHESTS {
+                    if let Some(observed) = observations.get(&quality) {
+                        info!("{} treasure EV {:.0} (calibrated from {} chests, {:.0} hardcoded)", quality.display_name(),
+                            mechanics::get_treasure_ev(quality, Some(observed)), observed.opens, mechanics::get_treasure_ev(quality, None));
+                    }
+                }
+                optimizer.treasure = observations;
+            },
+            Err(e) => warn!("Failed to load treasure history: {}", e),
+        }
+        let optimizer = Arc::new(Mutex::new(optimizer));
+
+        match (database.caught_species().await, database.fishdex_caught().await) {
+            (Ok(caught), Ok(listed)) => app_state.lock().await.fishdex = Fishdex::new(caught.into_iter().chain(listed)),
+            (Err(e), _) | (_, Err(e)) => warn!("Failed to load the fishdex: {}", e),
+        }
+        match database.gambling_stats().await {
+            Ok(stats) => app_state.lock().await.gambling = stats,
+            Err(e) => warn!("Failed to load coinflip history: {}", e),
+        }
+        match database.event_earnings().await {
+            Ok(earnings) => app_state.lock().await.event_earnings = earnings,
+            Err(e) => warn!("Failed to load event earnings: {}", e),
+        }
+
+        let playbook = match config.strategy.playbook.as_str() {
+            "" => None,
+            path => match Playbook::load(std::path::Path::new(path)) {
+                Ok(playbook) => {
+                    info!("Following playbook {} ({} phases)", path, playbook.phases.len());
+                    Some(playbook)
+                },
+                Err(e) => {
+                    warn!("Playbook ignored, using the optimizer alone: {}", e);
+                    None
+                },
+            },
+        };
+
+        let break_planner = BreakPlanner::new(config.breaks.clone(), Instant::now());
+        let channels = ChannelRotation::new(config.channel_rotation.clone(), config.system.channel_id, Instant::now());
+
+        Self {
+            config,
+            client,
+            scheduler,
+            captcha,
+            app_state,
+            state: BotState::Idle,
+            requests: StateQueue::default(),
+            cooldown_manager,
+            explorer,
+            optimizer,
+            correlation: CorrelationStore::new(database.clone()),
+            database,
+            last_action: None,
+            pending_recommendation: None,
+            config_generation: 0,
+            send_failures: 0,
+            cast_coordinator: None,
+            token_checked_at: None,
+            vault_checked_at: None,
+            daily_checked_at: None,
+            daily_ready_at: None,
+            clan_claim_due_at: None,
+            workers_checked_at: None,
+            charm_ready_at: HashMap::new(),
+            charms_checked_at: None,
+            snapshot_saved_at: None,
+            retry_checked_at: None,
+            vote_handled: None,
+            vote_window_loaded: false,
+            captcha_gate: None,
+            held: false,
+            last_cast: None,
+            cosmetics_applied: false,
+            cosmetic_attempts: 0,
+            cosmetics_checked_at: None,
+            last_level: None,
+            boosts_checked_at: None,
+            boosts_bought: HashMap::new(),
+            playbook,
+            prestige_sent_at: None,
+            charts_refreshed_at: None,
+            dex_bait_sent_at: None,
+            bait_bought_at: None,
+            bait_attempted_at: None,
+            explorer_counted_at: None,
+            net_worth_logged_at: None,
+            captcha_attempted_at: None,
+            captcha_gave_up: false,
+            break_planner,
+            channels,
+            break_until: None,
+            slow_factor: 1.0,
+            sell_reason: None,
+            sold_at: Instant::now(),
+        }
+    }
+
+    /// Waits for a slot from `coordinator` before each cast (multi-account runs).
+    pub fn with_cast_coordinator(mut self, coordinator: Arc<CastCoordinator>) -> Self {
+        self.cast_coordinator = Some(coordinator);
+        self
+    }
+
+    /// Reports this account's captchas to `gate` and backs off while another account has one.
+    pub fn with_captcha_gate(mut self, gate: Arc<CaptchaGate>, account: &str) -> Self {
+        self.captcha_gate = Some((gate, account.to_string()));
+        self
+    }
+
+    /// Whether another account's captcha should keep this one from sending right now.
+    async fn held_by_captcha_gate(&mut self) -> bool {
+        let hold = self.captcha_gate.as_ref().map_or(Hold::None, |(gate, name)| gate.hold(name));
+        let message = match &hold {
+            Hold::None => "Captcha solved on the other accounts; resuming".to_string(),
+            Hold::Pause { blocked_by } => format!("Pausing: captcha pending on {}", blocked_by.join(", ")),
+            Hold::Slow { min_interval, blocked_by } => format!("Slowing to one cast per {}s: captcha pending on {}",
+                min_interval.as_secs(), blocked_by.join(", ")),
+        };
+        if (hold != Hold::None) != self.held {
+            self.held = hold != Hold::None;
+            info!("{}", message);
+            self.app_state.lock().await.add_log(message);
+        }
+
+        match hold {
+            Hold::None => false,
+            Hold::Pause { .. } => true,
+            Hold::Slow { min_interval, .. } => self.last_cast.is_some_and(|at| at.elapsed() < min_interval),
+        }
+    }
+
+    /// Claims `/daily` once per reset window whether or not the bot is fishing, so a pause
+    /// or a stopped bot never breaks the streak. Skipped while logged out or in a captcha.
+    async fn protect_daily_streak(&mut self) {
+        if self.daily_checked_at.is_some_and(|at| at.elapsed() < DAILY_CHECK_INTERVAL) {
+            return;
+        }
+        self.daily_checked_at = Some(Instant::now());
+        self.refresh_daily_stats().await;
+        if !self.config.automation.auto_daily || self.state == BotState::LoggedOut || self.captcha.lock().await.detected {
+            return;
+        }
+        if self.daily_ready_at.is_some_and(|at| Instant::now() < at) {
+            return;
+        }
+
+        let window_start = streak::daily_window_start(chrono::Utc::now(), self.config.automation.daily_reset_hour_utc);
+        match self.database.daily_claimed_since(window_start).await {
+            Ok(true) => return,
+            Ok(false) => {},
+            Err(e) => {
+                warn!("Can't check the daily streak: {}", e);
+                return;
+            }
+        }
+
+        let is_running = self.app_state.lock().await.is_running;
+        if !is_running {
+            info!("Waking up to claim /daily before the streak resets");
+        }
+        let channel_id = self.channel_id();
+        let result = self.scheduler.lock().await.run_command_and_wait(&self.client, &channel_id, "daily", CLAIM_REPLY_TIMEOUT).await;
+        match result {
+            Ok(reply) => match reply.as_ref().and_then(parser::parse_claim_reply) {
+                Some(ClaimReply::Cooldown(wait)) => {
+                    // Claimed by hand, or the game's reset isn't `daily_reset_hour_utc`
+                    info!("/daily is ready again in {}s", wait.as_secs());
+                    self.daily_ready_at = Some(Instant::now() + wait);
+                    self.app_state.lock().await.add_log(format!("/daily on cooldown for {}h {}m", wait.as_secs() / 3600, wait.as_secs() % 3600 / 60));
+                },
+                granted => {
+                    // A reward the event processor can read is recorded from the same embed
+                    let recorded = reply.as_ref().and_then(|m| m.embeds.first())
+                        .and_then(|e| parser::parse_daily_reward(e.title.as_deref().unwrap_or_default(), e.description.as_deref().unwrap_or_default()))
+                        .is_some();
+                    if !recorded {
+                        if let Err(e) = self.database.log_daily_claim().await {
+                            warn!("Failed to log daily claim: {}", e);
+                        }
+                    }
+                    let log = match granted {
+                        Some(ClaimReply::Granted { reward, .. }) => format!("Claimed /daily: {}", reward),
+                        _ => "Claimed /daily (unconfirmed)".to_string(),
+                    };
+                    self.app_state.lock().await.add_log(log);
+                },
+            },
+            Err(e) => {
+                if !self.check_logged_out(&e).await {
+                    warn!("Daily claim failed, retrying in {}s: {}", DAILY_CHECK_INTERVAL.as_secs(), e);
+                }
+            },
+        }
+    }
+
+    async fn refresh_daily_stats(&self) {
+        match self.database.daily_claim_history().await {
+            Ok(claims) => {
+                let stats = streak::daily_stats(&claims, self.config.automation.daily_reset_hour_utc, chrono::Utc::now());
+                self.app_state.lock().await.daily_stats = Some(stats);
+            },
+            Err(e) => warn!("Failed to load daily claims: {}", e),
+        }
+    }
+
+    /// Re-reads the Stats tab's data, stopped or not.
+    async fn refresh_charts(&mut self) {
+        if self.charts_refreshed_at.is_some_and(|at| at.elapsed() < CHART_REFRESH_INTERVAL) {
+            return;
+        }
+        self.charts_refreshed_at = Some(Instant::now());
+        match self.database.cooldown_heatmap(CHART_DAYS).await {
+            Ok(cells) => self.app_state.lock().await.cooldown_heatmap = cells,
+            Err(e) => warn!("Failed to load the cooldown heatmap: {}", e),
+        }
+        match self.database.catches_by_biome(CHART_DAYS * 24).await {
+            Ok(catches) => {
+                let profile = self.app_state.lock().await.profile.clone();
+                let rod = ROD_DATA.values().find(|r| r.name == profile.rod).unwrap_or(&ROD_DATA[&RodType::Plastic]);
+                let boat = profile.current_boat().unwrap_or(&BOAT_DATA[&BoatType::Rowboat]);
+                let rows = self.optimizer.lock().await.compare_biomes(&catches, rod, boat, &profile);
+                self.app_state.lock().await.biome_comparison = rows;
+            },
+            Err(e) => warn!("Failed to load catches per biome: {}", e),
+        }
+        match self.database.treasure_observations().await {
+            Ok(observations) => self.optimizer.lock().await.treasure = observations,
+            Err(e) => warn!("Failed to load treasure history: {}", e),
+        }
+        match self.database.net_worth_since(CHART_DAYS * 24).await {
+            Ok(history) => self.app_state.lock().await.net_worth_history = history,
+            Err(e) => warn!("Failed to load net worth history: {}", e),
+        }
+        match self.database.leaderboard_ranks_since(CHART_DAYS * 24).await {
+            Ok(history) => self.app_state.lock().await.rank_history = history,
+            Err(e) => warn!("Failed to load leaderboard ranks: {}", e),
+        }
+        match tokio::try_join!(self.database.level_ups_since(CHART_DAYS * 24), self.database.catch_xp_since(CHART_DAYS * 24)) {
+            Ok((level_ups, catches)) => self.app_state.lock().await.level_paces = levels::paces(&level_ups, &catches),
+            Err(e) => warn!("Failed to load level-ups: {}", e),
+        }
+    }
+
+    /// True during a `[human_activity]` pause; logs when it runs out.
+    async fn paused_for_humans(&mut self) -> bool {
+        let mut app = self.app_state.lock().await;
+        match &app.human_pause {
+            Some((until, _)) if Instant::now() < *until => true,
+            Some((_, who)) => {
+                info!("Human activity pause over ({}); resuming", who);
+                app.add_log("Pause over, resuming casts".to_string());
+                app.human_pause = None;
+                false
+            },
+            None => false,
+        }
+    }
+
+    /// Starts the breaks `[breaks]` plans while fishing, picks up slow stretches, and
+    /// publishes the next break for the TUI.
+    async fn follow_break_schedule(&mut self) {
+        let now = Instant::now();
+        let local = chrono::Local::now().time();
+        if self.state == BotState::Fishing {
+            match self.break_planner.check(now, local) {
+                Activity::Break { until, kind } => {
+                    let message = format!("Taking a {} for {}m", kind.label(), until.duration_since(now).as_secs().div_ceil(60));
+                    info!("{}", message);
+                    self.app_state.lock().await.add_log(message);
+                    self.break_until = Some((until, kind));
+                    self.request(BotState::Break);
+                },
+                Activity::Slow(factor) => self.slow_factor = factor,
+                Activity::Active => self.slow_factor = 1.0,
+            }
+        }
+        let mut app = self.app_state.lock().await;
+        app.on_break = self.break_until;
+        app.next_break = self.break_planner.next_break(now, local).map(|(left, kind)| (now + left, kind));
+    }
+
+    /// Snapshots balance + inventory + equipment every `[net_worth] interval_minutes`.
+    async fn record_net_worth(&mut self) {
+        let interval = Duration::from_secs(self.config.net_worth.interval_minutes.max(1) * 60);
+        if self.net_worth_logged_at.is_some_and(|at| at.elapsed() < interval) {
+            return;
+        }
+        let worth = {
+            let app = self.app_state.lock().await;
+            NetWorth::of(&app.profile, app.inventory_valuation.as_ref(), self.config.net_worth.asset_value_ratio)
+        };
+        // No balance seen yet
+        let Some(worth) = worth else { return };
+        self.net_worth_logged_at = Some(Instant::now());
+        if let Err(e) = self.database.log_net_worth(&worth).await {
+            warn!("Failed to log net worth: {}", e);
+            return;
+        }
+        let mut app = self.app_state.lock().await;
+        app.net_worth_history.push((chrono::Utc::now().timestamp(), worth));
+        let cutoff = chrono::Utc::now().timestamp() - CHART_DAYS * 86_400;
+        app.net_worth_history.retain(|(ts, _)| *ts >= cutoff);
+    }
+
+    /// Applies the Explorer tab's requests, switches between exploring and fishing to match,
+    /// and publishes the Explorer's progress.
+    async fn sync_explorer(&mut self) {
+        let request = self.app_state.lock().await.explorer_request.take();
+        let (active, status) = {
+            let mut explorer = self.explorer.lock().await;
+            match request {
+                Some(ExplorerRequest::Start) => explorer.start().await,
+                Some(ExplorerRequest::Stop) => explorer.stop(),
+                Some(ExplorerRequest::Run(index)) => explorer.run_target(index),
+                None => {},
+            }
+            explorer.wake();
+            (explorer.is_active(), explorer.status())
+        };
+        match self.state {
+            BotState::Fishing | BotState::Idle if active => self.request(BotState::Exploration),
+            BotState::Exploration if !active => self.request(BotState::Fishing),
+            _ => {},
+        }
+
+        let counts = if self.explorer_counted_at.is_none_or(|at| at.elapsed() >= EXPLORER_COUNTS_INTERVAL) {
+            self.explorer_counted_at = Some(Instant::now());
+            match self.database.explorer_counts().await {
+                Ok(counts) => Some(counts),
+                Err(e) => {
+                    warn!("Failed to count explorer data: {}", e);
+                    None
+                },
+            }
+        } else {
+            None
+        };
+        let mut app = self.app_state.lock().await;
+        app.explorer = status;
+        if let Some(counts) = counts {
+            app.explorer_counts = counts;
+        }
+    }
+
+    /// When the vote window opens, runs `[votes] claim_command` if the game has it, or else
+    /// reminds the owner. Like the daily streak, this runs while the bot is stopped too.
+    async fn check_vote_window(&mut self) {
+        if !self.config.votes.enabled {
+            return;
+        }
+        if !self.vote_window_loaded {
+            self.vote_window_loaded = true;
+            match self.database.latest_vote_window().await {
+                Ok(Some(available_at)) => {
+                    let mut app = self.app_state.lock().await;
+                    app.vote_available_at.get_or_insert(available_at);
+                },
+                Ok(None) => {},
+                Err(e) => warn!("Couldn't load the vote window: {}", e),
+            }
+        }
+        let Some(available_at) = self.app_state.lock().await.vote_available_at else { return };
+        if chrono::Utc::now() < available_at || self.vote_handled == Some(available_at) {
+            return;
+        }
+        self.vote_handled = Some(available_at);
+
+        if self.state != BotState::LoggedOut && !self.captcha.lock().await.detected && self.claim_vote().await {
+            return;
+        }
+        self.app_state.lock().await.notifier.send(Notification::new(EventKind::VoteAvailable, Severity::Info,
+            "Voting is available", "Vote for Virtual Fisher to get the vote crate."));
+    }
+
+    /// Returns whether the claim command exists and was sent.
+    async fn claim_vote(&mut self) -> bool {
+        let command = self.config.votes.claim_command.clone();
+        let Some(name) = command.split_whitespace().next() else { return false };
+        let guild_id = self.config.system.guild_id.to_string();
+        if !matches!(self.client.get_command(&guild_id, name).await, Ok(Some(_))) {
+            return false;
+        }
+
+        let channel_id = self.channel_id();
+        let result = self.scheduler.lock().await.run_command_and_wait(&self.client, &channel_id, &command, CLAIM_REPLY_TIMEOUT).await;
+        let reply = match result {
+            Ok(reply) => reply.as_ref().and_then(parser::parse_claim_reply),
+            Err(e) => {
+                if !self.check_logged_out(&e).await {
+                    warn!("Vote claim failed: {}", e);
+                }
+                return false;
+            },
+        };
+        let (wait, log) = match reply {
+            Some(ClaimReply::Granted { reward, next_in }) => (next_in.unwrap_or(VOTE_COOLDOWN), format!("Claimed the vote crate: {}", reward)),
+            Some(ClaimReply::Cooldown(wait)) => (wait, format!("Vote crate not ready for {}h {}m", wait.as_secs() / 3600, wait.as_secs() % 3600 / 60)),
+            None => (VOTE_COOLDOWN, "Claimed the vote crate".to_string()),
+        };
+        let next = chrono::Utc::now() + chrono::Duration::seconds(wait.as_secs() as i64);
+        if let Err(e) = self.database.log_vote_window(next, "claim").await {
+            warn!("Failed to log vote window: {}", e);
+        }
+        let mut app = self.app_state.lock().await;
+        app.vote_available_at = Some(next);
+        app.add_log(log);
+        true
+    }
+
+    /// Sends clan `/claim` when it's due: right after starting, then whenever the reply says
+    /// it's ready again (every 4 hours when it doesn't say). Rewards go into `clan_events`.
+    async fn claim_clan(&mut self) {
+        if self.clan_claim_due_at.is_some_and(|at| Instant::now() < at) {
+            return;
+        }
+        self.clan_claim_due_at = Some(Instant::now() + CLAN_CLAIM_INTERVAL);
+        info!("Running scheduled task: claim");
+        let channel_id = self.channel_id();
+        let result = self.scheduler.lock().await.run_command_and_wait(&self.client, &channel_id, "claim", CLAIM_REPLY_TIMEOUT).await;
+        match result.map(|reply| reply.as_ref().and_then(parser::parse_claim_reply)) {
+            Ok(Some(ClaimReply::Granted { reward, next_in })) => {
+                info!("Clan claim: {}", reward);
+                if let Some(next_in) = next_in {
+                    self.clan_claim_due_at = Some(Instant::now() + next_in);
+                }
+                if let Err(e) = self.database.log_clan_event("claim", "claim", None, &reward).await {
+                    warn!("Failed to log clan claim: {}", e);
+                }
+                self.app_state.lock().await.add_log(format!("Clan claim: {}", reward));
+            },
+            Ok(Some(ClaimReply::Cooldown(wait))) => {
+                info!("Clan claim is ready again in {}s", wait.as_secs());
+                self.clan_claim_due_at = Some(Instant::now() + wait);
+            },
+            Ok(None) => {},
+            Err(e) => {
+                if !self.check_logged_out(&e).await {
+                    warn!("Clan claim failed, retrying in {}s: {}", CLAN_CLAIM_RETRY.as_secs(), e);
+                }
+                self.clan_claim_due_at = Some(Instant::now() + CLAN_CLAIM_RETRY);
+            },
+        }
+    }
+
+    /// Every `[workers] interval_minutes`: reads the workers embed, collects what they caught
+    /// and renews the boost once it has run out.
+    async fn manage_workers(&mut self) {
+        let config = self.config.workers.clone();
+        if !config.enabled || self.workers_checked_at.is_some_and(|at| at.elapsed() < Duration::from_secs(config.interval_minutes * 60)) {
+            return;
+        }
+        self.workers_checked_at = Some(Instant::now());
+        let channel_id = self.channel_id();
+        let result = self.scheduler.lock().await.run_command_and_wait(&self.client, &channel_id, &config.command, CLAIM_REPLY_TIMEOUT).await;
+        let mut workers = match result {
+            Ok(Some(reply)) => Profile::parse_workers(&parser::message_text(&reply)),
+            Ok(None) => return,
+            Err(e) => {
+                if !self.check_logged_out(&e).await {
+                    warn!("Workers check failed: {}", e);
+                }
+                return;
+            },
+        };
+
+        let mut sent = Vec::new();
+        if workers.uncollected > 0 {
+            let channel_id = self.channel_id();
+            match self.scheduler.lock().await.run_command(&self.client, &channel_id, &config.collect_command).await {
+                Ok(()) => {
+                    sent.push(format!("collected {} fish", money::format_thousands(workers.uncollected)));
+                    workers.uncollected = 0;
+                },
+                Err(e) => warn!("Collecting worker fish failed: {}", e),
+            }
+        }
+        if !config.boost_command.is_empty() && !workers.boosted(chrono::Utc::now().timestamp()) {
+            let channel_id = self.channel_id();
+            match self.scheduler.lock().await.run_command(&self.client, &channel_id, &config.boost_command).await {
+                Ok(()) => sent.push("renewed the boost".to_string()),
+                Err(e) => warn!("Boosting workers failed: {}", e),
+            }
+        }
+
+        let mut app = self.app_state.lock().await;
+        app.profile.workers = workers;
+        if !sent.is_empty() {
+            app.add_log(format!("Workers: {}", sent.join(", ")));
+        }
+    }
+
+    /// While fishing, uses a grinding charm when casting goes on long enough before the next break.
+    async fn use_grinding_charm(&mut self) {
+        if !self.config.charms.enabled || self.state != BotState::Fishing
+            || self.charms_checked_at.is_some_and(|at| at.elapsed() < CHARM_CHECK_INTERVAL) {
+            return;
+        }
+        self.charms_checked_at = Some(Instant::now());
+        let seconds = self.break_planner.next_break(Instant::now(), chrono::Local::now().time())
+            .map_or(f64::INFINITY, |(left, _)| left.as_secs_f64());
+        self.use_charm(CharmMoment::Grind { seconds }).await;
+    }
+
+    /// Sends `[charms] use_command` for the charm `Optimizer::pick_charm` chooses at `moment`,
+    /// then holds that charm back for `cooldown_minutes`, or as long as the reply says to wait.
+    async fn use_charm(&mut self, moment: CharmMoment) {
+        if !self.config.charms.enabled {
+            return;
+        }
+        let now = Instant::now();
+        // Never wait on the optimizer lock with the App lock held
+        let profile = self.app_state.lock().await.profile.clone();
+        let ready_at = &self.charm_ready_at;
+        let charm = self.optimizer.lock().await.pick_charm(&profile, moment, |charm| ready_at.get(&charm).is_none_or(|at| now >= *at));
+        let Some(charm) = charm else { return };
+
+        let command = format!("{} {}", self.config.charms.use_command, charm.name());
+        let cooldown = Duration::from_secs(self.config.charms.cooldown_minutes * 60);
+        self.charm_ready_at.insert(charm, now + cooldown);
+        let channel_id = self.channel_id();
+        let result = self.scheduler.lock().await.run_command_and_wait(&self.client, &channel_id, &command, CLAIM_REPLY_TIMEOUT).await;
+        let log = match result.map(|reply| reply.as_ref().and_then(parser::parse_claim_reply)) {
+            Ok(Some(ClaimReply::Cooldown(wait))) => {
+                self.charm_ready_at.insert(charm, now + wait);
+                format!("{} charm is ready again in {}m", charm.name(), wait.as_secs().div_ceil(60))
+            },
+            Ok(_) => match moment {
+                CharmMoment::Sell { value } => format!("Used a {} charm before selling {}", charm.name(), money::format_money(value)),
+                CharmMoment::Grind { .. } => format!("Used a {} charm for the grind ahead", charm.name()),
+            },
+            Err(e) => {
+                if !self.check_logged_out(&e).await {
+                    warn!("Using the {} charm failed: {}", charm.name(), e);
+                }
+                return;
+            },
+        };
+        info!("{}", log);
+        self.app_state.lock().await.add_log(log);
+    }
+
+    /// Opens one crate/present at a time: clicks a queued Open button, or sends
+    /// `[crates] open_command` for a crate sitting in the inventory.
+    async fn open_crates(&mut self) {
+        let crate_open = {
+            let mut app = self.app_state.lock().await;
+            if !app.config.crates.auto_open || app.opening_crate.as_ref().is_some_and(|(_, at)| at.elapsed() < CRATE_OPEN_SPACING) {
+                return;
+            }
+            app.opening_crate = None;
+            if !app.pending_crate_opens.is_empty() {
+                app.pending_crate_opens.remove(0)
+            } else {
+                let Some(slot) = app.profile.inventory.iter_mut()
+                    .find(|(amount, name)| parser::is_openable(name) && money::parse_amount(amount).unwrap_or(0) > 0)
+                    else { return };
+                // Until the next inventory embed says otherwise
+                let left = money::parse_amount(&slot.0).unwrap_or(1) - 1;
+                slot.0 = left.to_string();
+                CrateOpen { name: slot.1.clone(), message: None, button_custom_id: None }
+            }
+        };
+
+        info!("Opening {}", crate_open.name);
+        let result = match (&crate_open.message, &crate_open.button_custom_id) {
+            (Some((message_id, channel_id)), Some(custom_id)) => self.client.interact_component(&self.config.system.guild_id.to_string(),
+                channel_id, message_id, custom_id, None, None).await,
+            _ => {
+                let command = format!("{} {}", self.config.crates.open_command, crate_open.name);
+                let channel_id = self.channel_id();
+                self.scheduler.lock().await.run_command(&self.client, &channel_id, &command).await
+            },
+        };
+        match result {
+            Ok(()) => self.app_state.lock().await.opening_crate = Some((crate_open.name, Instant::now())),
+            Err(e) => {
+                if !self.check_logged_out(&e).await {
+                    warn!("Failed to open {}: {}", crate_open.name, e);
+                }
+            },
+        }
+    }
+
+    /// Equips the `[cosmetic]` pet and bait and travels to its biome, at startup and again when
+    /// the profile level drops (a prestige resets them). Each round ends with `/profile`, and the
+    /// next check verifies against it.
+    async fn apply_cosmetics(&mut self) {
+        let cosmetic = self.config.cosmetic.clone();
+        if cosmetic.pet.is_none() && cosmetic.bait.is_none() && cosmetic.biome.is_none() {
+            return;
+        }
+        let profile = self.app_state.lock().await.profile.clone();
+        if let Some(level) = profile.level_number() {
+            if profile.prestiged_since(self.last_level) {
+                info!("Level dropped to {}, re-applying the cosmetic config after the prestige", level);
+                self.app_state.lock().await.notifier.send(Notification::new(EventKind::Prestige, Severity::Info,
+                    "Prestiged", format!("Back to level {}; re-equipping the configured pet, bait and biome.", level)));
+                self.cosmetics_applied = false;
+                self.cosmetic_attempts = 0;
+                self.cosmetics_checked_at = None;
+            }
+            self.last_level = Some(level);
+        }
+        if self.cosmetics_applied || self.cosmetics_checked_at.is_some_and(|at| at.elapsed() < COSMETIC_CHECK_INTERVAL) {
+            return;
+        }
+        self.cosmetics_checked_at = Some(Instant::now());
+
+        // No profile embed yet: nothing to compare against
+        if profile.balance.is_none() {
+            let channel_id = self.channel_id();
+            let result = self.scheduler.lock().await.run_command(&self.client, &channel_id, "profile").await;
+            if let Err(e) = result {
+                if !self.check_logged_out(&e).await {
+                    warn!("Failed to request the profile: {}", e);
+                }
+            }
+            return;
+        }
+
+        let mut pending = profile.cosmetic_commands(&cosmetic);
+
+        if pending.is_empty() {
+            self.cosmetics_applied = true;
+            info!("Cosmetic config is applied");
+            self.app_state.lock().await.add_log("Cosmetic config applied".to_string());
+            return;
+        }
+        if self.cosmetic_attempts >= MAX_COSMETIC_ATTEMPTS {
+            self.cosmetics_applied = true;
+            warn!("Profile still doesn't match the cosmetic config, giving up on: {}", pending.join(", "));
+            let mut app = self.app_state.lock().await;
+            app.add_log("Couldn't apply the cosmetic config".to_string());
+            app.notifier.send(Notification::new(EventKind::CommandFailed, Severity::Warning, "Cosmetic config not applied",
+                format!("The profile still doesn't match after {} tries: {}.", MAX_COSMETIC_ATTEMPTS, pending.join(", "))));
+            return;
+        }
+        self.cosmetic_attempts += 1;
+
+        pending.push("profile".to_string());
+        for command in &pending {
+            info!("Cosmetic config: /{}", command);
+            let channel_id = self.channel_id();
+            let result = self.scheduler.lock().await.run_command(&self.client, &channel_id, command).await;
+            if let Err(e) = result {
+                if self.check_logged_out(&e).await {
+                    return;
+                }
+                warn!("Cosmetic command /{} failed: {}", command, e);
+            }
+            tokio::time::sleep(Duration::from_secs(3)).await;
+        }
+    }
+
+    /// Buys the `more_fish` / `more_treasures` boost for `boosts_length` minutes once its buff
+    /// timer has run out. Only buys at a scraped shop price the balance covers, and while the
+    /// optimizer isn't about to spend the money on a rod or boat.
+    async fn buy_boosts(&mut self) {
+        let automation = &self.config.automation;
+        let minutes = automation.boosts_length;
+        let wanted: Vec<Boost> = Boost::ALL.into_iter()
+            .filter(|b| match b {
+                Boost::MoreFish => automation.more_fish,
+                Boost::MoreTreasures => automation.more_treasures,
+            })
+            .collect();
+        if minutes == 0 || wanted.is_empty() || self.pending_recommendation.is_some()
+            || self.boosts_checked_at.is_some_and(|at| at.elapsed() < BOOST_CHECK_INTERVAL) {
+            return;
+        }
+        self.boosts_checked_at = Some(Instant::now());
+
+        let prices = match self.database.money_shop_prices().await {
+            Ok(prices) => prices,
+            Err(e) => {
+                warn!("Failed to load shop prices: {}", e);
+                return;
+            }
+        };
+        let length = Duration::from_secs(minutes * 60);
+        let now = chrono::Utc::now().timestamp();
+        for boost in wanted {
+            let (timer, balance) = {
+                let app = self.app_state.lock().await;
+                (app.profile.buffs.boosts.get(boost.display_name()).copied(), app.profile.balance)
+            };
+            let bought_recently = self.boosts_bought.get(&boost).is_some_and(|at| at.elapsed() < length);
+            if bought_recently || timer.is_some_and(|ends| ends > now) {
+                continue;
+            }
+
+            // The listing for this length if the shop shows several, else any listing of the boost
+            let length_tag = format!("{}m", minutes);
+            let listings: Vec<&(String, u64)> = prices.iter().filter(|(name, _)| Boost::from_text(name) == Some(boost)).collect();
+            let price = listings.iter().find(|(name, _)| name.replace(' ', "").to_lowercase().contains(&length_tag))
+                .or(listings.first())
+                .map(|(_, price)| *price);
+            let (Some(price), Some(balance)) = (price, balance) else {
+                debug!("No shop price or balance for {} yet, not buying it", boost.display_name());
+                continue;
+            };
+            if balance < price {
+                debug!("Balance {} doesn't cover {} ({})", balance, boost.display_name(), price);
+                continue;
+            }
+
+            let command = format!("shop buy {}", boost.item_id(minutes));
+            info!("Buying {} for {} minutes", boost.display_name(), minutes);
+            let channel_id = self.channel_id();
+            let result = self.scheduler.lock().await.run_command(&self.client, &channel_id, &command).await;
+            match result {
+                Ok(()) => {
+                    self.boosts_bought.insert(boost, Instant::now());
+                    let mut app = self.app_state.lock().await;
+                    app.profile.buffs.boosts.insert(boost.display_name().to_string(), now + length.as_secs() as i64);
+                    app.profile.balance = Some(balance - price);
+                    app.add_log(format!("Bought {} ({}m) for {}", boost.display_name(), minutes, money::format_money(price)));
+                },
+                Err(e) => {
+                    if self.check_logged_out(&e).await {
+                        return;
+                    }
+                    warn!("Failed to buy {}: {}", boost.display_name(), e);
+                },
+            }
+        }
+    }
+
+    /// With `[automation] auto_buy_baits`, restocks once Virtual Fisher says the bait ran out:
+    /// the `[cosmetic]` bait, else the dex hunting bait, else the optimizer's pick, up to
+    /// `bait_quantity` of it as the balance allows. The bait is equipped again afterwards.
+    async fn buy_bait(&mut self) {
+        let (balance, equipped) = {
+            let mut app = self.app_state.lock().await;
+            if !app.out_of_bait || !app.config.automation.auto_buy_baits {
+                return;
+            }
+            // Answers a cast from before the last purchase
+            if self.bait_bought_at.is_some_and(|at| at.elapsed() < BAIT_BUY_SPACING) {
+                app.out_of_bait = false;
+                return;
+            }
+            // Still flagged, so a failed purchase is retried after the spacing
+            if self.bait_attempted_at.is_some_and(|at| at.elapsed() < BAIT_BUY_SPACING) {
+                return;
+            }
+            (app.profile.balance, app.profile.bait.to_lowercase())
+        };
+        self.bait_attempted_at = Some(Instant::now());
+        let quantity = self.config.automation.bait_quantity;
+        let bait = match (&self.config.cosmetic.bait, self.config.strategy.dex_hunting) {
+            (Some(bait), _) => Some(bait.clone()),
+            (None, true) if !self.config.strategy.dex_bait.is_empty() => Some(self.config.strategy.dex_bait.clone()),
+            (None, true) => Some(fishdex::best_quality_bait().to_string()),
+            (None, false) => match balance {
+                Some(balance) => self.optimizer.lock().await.recommend_bait(balance, quantity).map(str::to_string),
+                None => None,
+            },
+        };
+        let Some(bait) = bait else {
+            warn!("Out of bait, but none is affordable (balance {:?})", balance);
+            return;
+        };
+        // At most a quarter of the balance, like `recommend_bait`; unknown baits (or balances)
+        // are bought at the configured quantity
+        let price = BAIT_DATA.values().find(|b| b.name.eq_ignore_ascii_case(&bait)).map(|b| b.price).filter(|p| *p > 0);
+        let quantity = match (price, balance) {
+            (Some(price), Some(balance)) => quantity.min(balance / 4 / price),
+            _ => quantity,
+        };
+        if quantity == 0 {
+            warn!("Out of bait, and a quarter of the balance doesn't cover any {}", bait);
+            return;
+        }
+
+        let guild_id = self.config.system.guild_id.to_string();
+        let Some(cmd) = self.client.get_command(&guild_id, "shop buy").await.unwrap_or(None) else {
+            warn!("Out of bait, but /shop buy wasn't found");
+            return;
+        };
+        let options = match CommandBuilder::new(&cmd).subcommand("buy").option("bait", bait.as_str()).option("amount", quantity).build() {
+            Ok(options) => options,
+            Err(e) => {
+                warn!("Can't buy bait: {}", e);
+                return;
+            }
+        };
+        info!("Out of bait: buying {} {}", quantity, bait);
+        if !self.send_confirmed("shop", &cmd, options).await {
+            return;
+        }
+        self.bait_bought_at = Some(Instant::now());
+        {
+            let mut app = self.app_state.lock().await;
+            app.out_of_bait = false;
+            if let (Some(price), Some(balance)) = (price, balance) {
+                app.profile.balance = Some(balance.saturating_sub(price * quantity));
+            }
+            app.add_log(format!("Bought {} {}", quantity, bait));
+        }
+        if !equipped.contains(&bait.to_lowercase()) {
+            let command = format!("{} {}", self.config.cosmetic.bait_command, bait);
+            let channel_id = self.channel_id();
+            if let Err(e) = self.scheduler.lock().await.run_command(&self.client, &channel_id, &command).await {
+                warn!("Equipping {} failed: {}", bait, e);
+            }
+        }
+    }
+
+    /// Reshapes the optimizer's picks for the current playbook phase: its biome replaces travel
+    /// suggestions and its rod replaces rod purchases. At `prestige_at_level` it prestiges instead.
+    async fn apply_playbook(&mut self, recs: Vec<Recommendation>, current_biome: Biome, profile: &Profile) -> Vec<Recommendation> {
+        let Some(playbook) = &self.playbook else { return recs };
+        let description = playbook.describe(profile);
+        let phase = playbook.current(profile).map(|(_, phase)| phase.clone());
+        self.app_state.lock().await.strategy.phase = description;
+        let Some(phase) = phase else { return recs };
+
+        if phase.wants_prestige(profile) {
+            self.request(BotState::Prestiging);
+            return Vec::new();
+        }
+
+        let mut pinned = Vec::new();
+        if let Some(biome) = phase.target_biome().filter(|b| *b != current_biome) {
+            pinned.push(Recommendation { action: ActionType::Travel, target_name: biome.display_name().to_string(), cost: 0, roi_seconds: 0.0 });
+        }
+        let rod = phase.rod.as_deref()
+            .filter(|rod| !profile.rod.eq_ignore_ascii_case(rod))
+            .and_then(|rod| ROD_DATA.values().find(|r| r.name.eq_ignore_ascii_case(rod)));
+        if let Some(rod) = rod {
+            pinned.push(Recommendation { action: ActionType::BuyRod, target_name: rod.name.to_string(), cost: rod.price, roi_seconds: 0.0 });
+        }
+        pinned.extend(recs.into_iter().filter(|r| match r.action {
+            ActionType::Travel => phase.biome.is_none(),
+            ActionType::BuyRod => phase.rod.is_none(),
+            _ => true,
+        }));
+        pinned
+    }
+
+    /// `[strategy] dex_hunting`: travel to the biome with the most uncaught species and fish
+    /// with the hunting bait until the dex is complete. A playbook phase's biome still wins.
+    async fn apply_dex_hunting(&mut self, recs: Vec<Recommendation>, current_biome: Biome, profile: &Profile) -> Vec<Recommendation> {
+        if !self.config.strategy.dex_hunting {
+            return recs;
+        }
+        let Some(target) = self.app_state.lock().await.fishdex.hunting_target(current_biome) else { return recs };
+        self.equip_dex_bait(profile).await;
+
+        let pinned_by_playbook = self.playbook.as_ref()
+            .and_then(|playbook| playbook.current(profile))
+            .is_some_and(|(_, phase)| phase.biome.is_some());
+        if pinned_by_playbook {
+            return recs;
+        }
+        let mut hunting = Vec::new();
+        if target != current_biome {
+            hunting.push(Recommendation { action: ActionType::Travel, target_name: target.display_name().to_string(), cost: 0, roi_seconds: 0.0 });
+        }
+        hunting.extend(recs.into_iter().filter(|r| r.action != ActionType::Travel));
+        hunting
+    }
+
+    async fn equip_dex_bait(&mut self, profile: &Profile) {
+        let bait = match self.config.strategy.dex_bait.as_str() {
+            "" => fishdex::best_quality_bait().to_string(),
+            bait => bait.to_string(),
+        };
+        if profile.bait.to_lowercase().contains(&bait.to_lowercase())
+            || self.dex_bait_sent_at.is_some_and(|at| at.elapsed() < DEX_BAIT_RETRY_INTERVAL) {
+            return;
+        }
+        self.dex_bait_sent_at = Some(Instant::now());
+        let command = format!("{} {}", self.config.cosmetic.bait_command, bait);
+        info!("Dex hunting: /{}", command);
+        let channel_id = self.channel_id();
+        let result = self.scheduler.lock().await.run_command(&self.client, &channel_id, &command).await;
+        if let Err(e) = result {
+            if !self.check_logged_out(&e).await {
+                warn!("Selecting the dex hunting bait failed: {}", e);
+            }
+        }
+    }
+
+    /// Reads the captcha image with the configured solvers and answers with `/verify`. The event
+    /// processor clears `Captcha::detected` once an answer is accepted, or marks it rejected,
+    /// which makes the next attempt come sooner and read the image differently. After
+    /// `[captcha] max_attempts` the owner gets a `CaptchaFailed` notification and it's left to
+    /// them (Captcha tab, headless `captcha <answer>`).
+    async fn solve_captcha(&mut self) {
+        let (url, attempts, rejected, answers) = {
+            let captcha = self.captcha.lock().await;
+            let Some(url) = captcha.image_url.clone().filter(|_| captcha.has_solvers()) else { return };
+            (url, captcha.attempts, captcha.last_answer_rejected(), captcha.answers.clone())
+        };
+        let wait = if rejected { CAPTCHA_REJECT_DELAY } else { CAPTCHA_RETRY_INTERVAL };
+        if self.captcha_attempted_at.is_some_and(|at| at.elapsed() < wait) {
+            return;
+        }
+        let max_attempts = self.config.captcha.max_attempts;
+        if attempts >= max_attempts {
+            if !self.captcha_gave_up {
+                self.captcha_gave_up = true;
+                warn!("Captcha: no accepted answer after {} attempts, waiting for a manual one", attempts);
+                let mut app = self.app_state.lock().await;
+                app.add_log("Captcha: automatic solving gave up".to_string());
+                app.notifier.send(Notification::new(EventKind::CaptchaFailed, Severity::Critical,
+                    "Captcha needs solving by hand",
+                    format!("{} automatic attempts failed (tried: {}). Fishing stays paused until it is solved.",
+                        attempts, if answers.is_empty() { "nothing".to_string() } else { answers.join(", ") })));
+            }
+            return;
+        }
+        self.captcha_attempted_at = Some(Instant::now());
+
+        let image = match self.client.download_attachment(&url).await {
+            Ok(image) => image,
+            Err(e) => {
+                warn!("Captcha: image download failed: {}", e);
+                return;
+            }
+        };
+        // The solve can take minutes (2Captcha polls), so it runs without the lock held
+        let job = self.captcha.lock().await.start_solve(url);
+        let result = job.run(&image).await;
+        let answer = match self.captcha.lock().await.finish_solve(result) {
+            Ok(answer) => answer,
+            Err(e) => {
+                warn!("Captcha solving failed ({}/{}): {}", attempts + 1, max_attempts, e);
+                return;
+            }
+        };
+        info!("Captcha: answering {} ({}/{})", answer, attempts + 1, max_attempts);
+        self.send_verify(&answer).await;
+    }
+
+    /// Sends the answer typed on the Captcha tab, holding off OCR while it's checked.
+    async fn answer_captcha_manually(&mut self) {
+        let Some(answer) = self.app_state.lock().await.captcha_answer.take() else { return };
+        info!("Captcha: answering {} (manual)", answer);
+        self.captcha_attempted_at = Some(Instant::now());
+        self.captcha.lock().await.answers.push(answer.clone());
+        self.send_verify(&answer).await;
+    }
+
+    async fn send_verify(&mut self, answer: &str) {
+        let guild_id = self.config.system.guild_id.to_string();
+        let command = match self.client.get_command(&guild_id, "verify").await {
+            Ok(Some(command)) => command,
+            Ok(None) => {
+                warn!("Captcha: /verify not found via discovery");
+                return;
+            }
+            Err(e) => {
+                warn!("Captcha: looking up /verify failed: {}", e);
+                return;
+            }
+        };
+        let options = match CommandBuilder::new(&command).option("answer", answer).build() {
+            Ok(options) => options,
+            Err(e) => {
+                warn!("Captcha: {}", e);
+                return;
+            }
+        };
+        if self.send_tracked("verify", &command, options).await {
+            self.app_state.lock().await.add_log(format!("Captcha: sent /verify {}", answer));
+        }
+    }
+
+    /// Sends `[strategy] prestige_command` and clicks its confirmation, then re-baselines:
+    /// `Profile::reset_for_prestige`, `owned_boats` and the pending purchase are cleared until
+    /// the requested `/profile` arrives, matching the reset `Optimizer::evaluate_prestige` assumes.
+    async fn prestige(&mut self) {
+        if self.prestige_sent_at.is_some_and(|at| at.elapsed() < PRESTIGE_RETRY_INTERVAL) {
+            return;
+        }
+        self.prestige_sent_at = Some(Instant::now());
+        let command = self.config.strategy.prestige_command.clone();
+        let parts: Vec<&str> = command.split_whitespace().collect();
+        let Some((name, rest)) = parts.split_first() else { return };
+        let guild_id = self.config.system.guild_id.to_string();
+        let cmd = match self.client.get_command(&guild_id, name).await {
+            Ok(Some(cmd)) => cmd,
+            Ok(None) => {
+                warn!("Can't prestige: /{} wasn't found", name);
+                return;
+            },
+            Err(e) => {
+                if !self.check_logged_out(&e).await {
+                    warn!("Prestige failed: {}", e);
+                }
+                return;
+            },
+        };
+        let options = match CommandBuilder::new(&cmd).text(rest).build() {
+            Ok(options) => options,
+            Err(e) => {
+                warn!("Can't prestige: {}", e);
+                return;
+            },
+        };
+
+        let level = self.app_state.lock().await.profile.level_number();
+        info!("Prestiging with /{}", command);
+        if !self.send_confirmed("prestige", &cmd, options).await {
+            return;
+        }
+        self.pending_recommendation = None;
+        self.last_action = None;
+        {
+            let mut app = self.app_state.lock().await;
+            app.profile.reset_for_prestige();
+            app.add_log(format!("Prestiged{}", level.map(|l| format!(" at level {}", l)).unwrap_or_default()));
+        }
+        if let Err(e) = self.database.clear_owned_boats().await {
+            warn!("Failed to clear owned boats: {}", e);
+        }
+        let channel_id = self.channel_id();
+        let result = self.scheduler.lock().await.run_command(&self.client, &channel_id, "profile").await;
+        if let Err(e) = result {
+            if !self.check_logged_out(&e).await {
+                warn!("Failed to request the profile: {}", e);
+            }
+        }
+    }
+
+    /// Loads the fleet from `owned_boats`, since the profile embed doesn't always list it.
+    async fn restore_boats(&self) {
+        match self.database.owned_boats().await {
+            Ok(boats) => {
+                let mut app = self.app_state.lock().await;
+                for boat in &boats {
+                    app.profile.add_boat(boat);
+                }
+            },
+            Err(e) => warn!("Failed to load owned boats: {}", e),
+        }
+    }
+
+    /// Picks up the last `engine::state` snapshot, if it's under a day old: the profile (when
+    /// no embed has filled it in yet), the cooldown estimate, and a purchase, sell or break
+    /// that was under way.
+    async fn restore_snapshot(&mut self) {
+        let snapshot = match self.database.load_snapshot().await {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                warn!("Failed to load the last snapshot: {}", e);
+                return;
+            },
+        };
+        let now = chrono::Utc::now().timestamp();
+        let Some(snapshot) = snapshot.filter(|s| s.is_fresh(now)) else { return };
+
+        self.cooldown_manager.lock().await.restore(&snapshot.cooldown);
+        let resume = snapshot.resume_state(now);
+        match resume {
+            Some(BotState::Shopping) => self.pending_recommendation = snapshot.pending_recommendation,
+            Some(BotState::Selling) => self.sell_reason = snapshot.sell_reason,
+            Some(BotState::Break) => {
+                self.break_until = snapshot.break_until
+                    .map(|(until, kind)| (Instant::now() + Duration::from_secs((until - now).max(0) as u64), kind));
+            },
+            _ => {},
+        }
+        if let Some(state) = resume {
+            self.request(state);
+        }
+
+        let mut app = self.app_state.lock().await;
+        if app.profile.balance.is_none() {
+            app.profile = snapshot.profile;
+        }
+        let message = match resume {
+            Some(state) => format!("Restored the snapshot from {}s ago, resuming {:?}", now - snapshot.saved_at, state),
+            None => format!("Restored the snapshot from {}s ago", now - snapshot.saved_at),
+        };
+        info!("{}", message);
+        app.add_log(message);
+    }
+
+    /// Writes an `engine::state` snapshot every `SNAPSHOT_INTERVAL`.
+    async fn save_snapshot(&mut self) {
+        if self.snapshot_saved_at.is_some_and(|at| at.elapsed() < SNAPSHOT_INTERVAL) {
+            return;
+        }
+        self.snapshot_saved_at = Some(Instant::now());
+        let now = chrono::Utc::now().timestamp();
+        let snapshot = Snapshot {
+            saved_at: now,
+            profile: self.app_state.lock().await.profile.clone(),
+            cooldown: self.cooldown_manager.lock().await.snapshot(),
+            state: Some(self.state),
+            pending_recommendation: self.pending_recommendation.clone(),
+            sell_reason: self.sell_reason.clone(),
+            break_until: self.break_until
+                .map(|(until, kind)| (now + until.saturating_duration_since(Instant::now()).as_secs() as i64, kind)),
+        };
+        if let Err(e) = self.database.save_snapshot(&snapshot).await {
+            warn!("Failed to save the snapshot: {}", e);
+        }
+    }
+
+    async fn record_boat(&self, name: &str) {
+        if self.app_state.lock().await.profile.add_boat(name) {
+            if let Err(e) = self.database.record_owned_boat(name).await {
+                warn!("Failed to record boat {}: {}", name, e);
+            }
+        }
+    }
+
+    /// Sends one command queued by a user script (`scripting::host`).
+    async fn run_script_commands(&mut self) {
+        let command = {
+            let mut app = self.app_state.lock().await;
+            if app.pending_script_commands.is_empty() {
+                return;
+            }
+            app.pending_script_commands.remove(0)
+        };
+        info!("Script command: /{}", command);
+        let channel_id = self.channel_id();
+        let result = self.scheduler.lock().await.run_command(&self.client, &channel_id, &command).await;
+        if let Err(e) = result {
+            if !self.check_logged_out(&e).await {
+                warn!("Script command /{} failed: {}", command, e);
+            }
+        }
+    }
+
+    /// Runs `[level_up]` for one level-up the event processor queued: the reward command and
+    /// the extra commands, each only if the game has it, then a fresh plan with `recheck_biomes`.
+    async fn celebrate_level_up(&mut self) {
+        let (_, level) = {
+            let mut app = self.app_state.lock().await;
+            if app.pending_level_ups.is_empty() {
+                return;
+            }
+            app.pending_level_ups.remove(0)
+        };
+        let config = self.config.level_up.clone();
+        let commands = config.claim_rewards.then_some(config.reward_command).into_iter().chain(config.commands);
+        let guild_id = self.config.system.guild_id.to_string();
+        for command in commands {
+            let Some(name) = command.split_whitespace().next() else { continue };
+            if !matches!(self.client.get_command(&guild_id, name).await, Ok(Some(_))) {
+                debug!("No /{} command, skipping it for level {}", name, level);
+                continue;
+            }
+            info!("Level {}: /{}", level, command);
+            let channel_id = self.channel_id();
+            let result = self.scheduler.lock().await.run_command(&self.client, &channel_id, &command).await;
+            if let Err(e) = result {
+                if self.check_logged_out(&e).await {
+                    return;
+                }
+                warn!("Level-up command /{} failed: {}", command, e);
+            }
+        }
+
+        if config.recheck_biomes {
+            // The game may have opened a biome at this level; a travel in the last few seconds
+            // mustn't hold back the one to it
+            self.last_action = None;
+        }
+    }
+
+    /// The threshold or `[sell]` timer sell `SellPolicy::routine` calls for, if any.
+    async fn routine_sell(&self) -> Option<SellReason> {
+        let app = self.app_state.lock().await;
+        let value = app.inventory_valuation.as_ref().map_or(0, |v| v.total);
+        SellPolicy::new(&self.config).routine(value, &app.profile.buffs, self.sold_at.elapsed())
+    }
+
+    /// Sends each `[interactions] retry_commands` command that failed ("This interaction failed")
+    /// or got no reply within `reply_timeout_seconds` once more, with the definition fetched fresh
+    /// in case it changed. When the retry goes the same way, the owner is told instead.
+    async fn retry_unanswered(&mut self) {
+        if self.retry_checked_at.is_some_and(|at| at.elapsed() < RETRY_CHECK_INTERVAL) {
+            return;
+        }
+        self.retry_checked_at = Some(Instant::now());
+
+        let timeout = Duration::from_secs(self.config.interactions.reply_timeout_seconds);
+        let retry_commands = self.config.interactions.retry_commands.clone();
+        let unanswered = match self.correlation.unanswered(timeout, RETRY_WINDOW).await {
+            Ok(unanswered) => unanswered.into_iter().filter(|s| retry_commands.contains(&s.command)),
+            Err(e) => {
+                warn!("Couldn't look up unanswered commands: {}", e);
+                return;
+            }
+        };
+        let guild_id = self.config.system.guild_id.to_string();
+        let channel_id = self.channel_id();
+        for sent in unanswered {
+            if let Err(e) = self.correlation.mark_retried(&sent.nonce).await {
+                warn!("Not retrying /{}, couldn't mark it: {}", sent.command, e);
+                continue;
+            }
+            let command = match self.client.get_command(&guild_id, &sent.command).await {
+                Ok(Some(command)) => command,
+                Ok(None) => continue,
+                Err(e) => {
+                    if !self.check_logged_out(&e).await {
+                        warn!("Couldn't fetch /{} to retry it: {}", sent.command, e);
+                    }
+                    continue;
+                }
+            };
+            warn!("/{} failed or got no reply in {}s, retrying once", sent.command, timeout.as_secs());
+            self.app_state.lock().await.add_log(format!("No reply to /{}, retrying", sent.command));
+            let result = self.client.resend_command(&guild_id, &channel_id, &command, sent.options, &sent.nonce).await;
+            if let Err(e) = result {
+                if !self.check_logged_out(&e).await {
+                    self.report_command_failure(&sent.command, &e.to_string()).await;
+                }
+            }
+        }
+
+        let failed_retries = match self.correlation.unanswered_retries(timeout, RETRY_WINDOW).await {
+            Ok(failed_retries) => failed_retries,
+            Err(e) => {
+                warn!("Couldn't look up unanswered retries: {}", e);
+                return;
+            }
+        };
+        for retry in failed_retries {
+            if let Err(e) = self.correlation.mark_abandoned(&retry.nonce).await {
+                warn!("Couldn't mark the retry of /{} as given up: {}", retry.command, e);
+                continue;
+            }
+            self.report_command_failure(&retry.command, "the retry failed or got no reply either").await;
+        }
+    }
+
+    async fn report_command_failure(&self, command: &str, reason: &str) {
+        warn!("Giving up on /{}: {}", command, reason);
+        let mut app = self.app_state.lock().await;
+        app.add_log(format!("/{} failed twice, giving up", command));
+        app.notifier.send(Notification::new(EventKind::CommandFailed, Severity::Warning,
+            format!("/{} failed", command), format!("Sent twice without a reply: {}.", reason)));
+    }
+
+    /// Deposits the balance above `[clan] deposit_min_balance` into the clan vault, up to
+    /// `deposit_daily_budget` per UTC day counted from `clan_deposits`.
+    async fn deposit_to_vault(&mut self) {
+        let clan = &self.config.clan;
+        if !clan.auto_deposit || self.vault_checked_at.is_some_and(|at| at.elapsed() < VAULT_CHECK_INTERVAL) {
+            return;
+        }
+        self.vault_checked_at = Some(Instant::now());
+
+        let balance = {
+            let app = self.app_state.lock().await;
+            app.profile.balance.unwrap_or(0)
+        };
+        let deposited_today = match self.database.clan_deposits_today().await {
+            Ok(total) => total,
+            Err(e) => {
+                warn!("Skipping vault deposit, couldn't read today's deposits: {}", e);
+                return;
+            }
+        };
+        let amount = balance.saturating_sub(clan.deposit_min_balance)
+            .min(clan.deposit_daily_budget.saturating_sub(deposited_today));
+        if amount == 0 {
+            return;
+        }
+
+        info!("Depositing ${} into the clan vault ({} of ${} today)", amount, deposited_today, clan.deposit_daily_budget);
+        let command = format!("{} {}", clan.deposit_command, amount);
+        let channel_id = self.channel_id();
+        let result = self.scheduler.lock().await.run_command(&self.client, &channel_id, &command).await;
+        match result {
+            Ok(()) => {
+                if let Err(e) = self.database.log_clan_deposit(amount, balance).await {
+                    warn!("Failed to log clan deposit: {}", e);
+                }
+                let mut app = self.app_state.lock().await;
+                app.add_log(format!("Deposited ${} into the clan vault", amount));
+                // Until the next profile embed says otherwise
+                app.profile.balance = Some(balance - amount);
+            },
+            Err(e) => {
+                if !self.check_logged_out(&e).await {
+                    warn!("Clan vault deposit failed: {}", e);
+                }
+            },
+        }
+    }
+
+    /// Schedules or drops the tasks that only make sense while a game event is active.
+    async fn sync_event_tasks(&mut self) {
+        let clan = &self.config.clan;
+        let app = self.app_state.lock().await;
+        let boss_active = clan.auto_boss && app.active_clan_boss().is_some();
+        let mut scheduler = self.scheduler.lock().await;
+        scheduler.set_event_task(&clan.boss_command, clan.boss_interval_minutes * 60, boss_active);
+        for (name, event) in &self.config.seasonal_events {
+            if event.auto_run {
+                scheduler.set_event_task(&event.command, event.interval_minutes * 60, app.is_seasonal_event_active(name));
+            }
+        }
+    }
+
+    /// Claims one completed quest at a time, waiting for the previous reward to be confirmed.
+    async fn claim_quests(&mut self) {
+        let claim = {
+            let mut app = self.app_state.lock().await;
+            if let Some((objective, claimed_at)) = app.awaiting_quest_reward.clone() {
+                if claimed_at.elapsed() < QUEST_REWARD_TIMEOUT {
+                    return;
+                }
+                warn!("No reward embed for quest '{}'; logging it unconfirmed", objective);
+                app.add_log(format!("Quest claim not confirmed: {}", objective));
+                app.awaiting_quest_reward = None;
+                if let Err(e) = self.database.log_quest_claim(&objective, None).await {
+                    warn!("Failed to log quest claim: {}", e);
+                }
+            }
+            if !app.config.quests.auto_claim || app.pending_quest_claims.is_empty() {
+                return;
+            }
+            app.pending_quest_claims.remove(0)
+        };
+
+        info!("Claiming quest: {}", claim.objective);
+        let result = match &claim.button_custom_id {
+            Some(custom_id) => self.client.interact_component(&self.config.system.guild_id.to_string(),
+                &claim.channel_id, &claim.message_id, custom_id, None, None).await,
+            None => {
+                let channel_id = self.channel_id();
+                self.scheduler.lock().await.run_command(&self.client, &channel_id, &self.config.quests.claim_command).await
+            },
+        };
+        match result {
+            Ok(()) => self.app_state.lock().await.awaiting_quest_reward = Some((claim.objective, Instant::now())),
+            Err(e) => {
+                if !self.check_logged_out(&e).await {
+                    warn!("Failed to claim quest '{}': {}", claim.objective, e);
+                }
+            },
+        }
+    }
+
+    /// Clicks Decline on trade/gift prompts queued by the event processor.
+    async fn decline_trades(&mut self) {
+        let prompt = {
+            let mut app = self.app_state.lock().await;
+            if app.pending_trade_declines.is_empty() {
+                return;
+            }
+            app.pending_trade_declines.remove(0)
+        };
+        let Some(button) = &prompt.decline_button else { return };
+
+        info!("Declining {:?} prompt {}", prompt.kind, prompt.message_id);
+        let result = self.client.interact_component(&self.config.system.guild_id.to_string(),
+            &prompt.channel_id, &prompt.message_id, button, None, None).await;
+        match result {
+            Ok(()) => self.app_state.lock().await.add_log(format!("Declined {:?} request", prompt.kind)),
+            Err(e) => {
+                if !self.check_logged_out(&e).await {
+                    warn!("Failed to decline {:?} prompt: {}", prompt.kind, e);
+                }
+            },
+        }
+    }
+
+    /// Asks for `state` from the next pass of the loop; of several requests, the highest
+    /// `BotState::priority` the transition rules allow wins.
+    fn request(&mut self, state: BotState) {
+        self.requests.push(state);
+    }
+
+    /// Moves to `to` now if the transition rules allow it, dropping pending requests.
+    fn switch_to(&mut self, to: BotState) -> bool {
+        if self.state == to {
+            return true;
+        }
+        if !self.state.can_switch_to(to) {
+            debug!("Not switching from {:?} to {:?}", self.state, to);
+            return false;
+        }
+        debug!("State: {:?} -> {:?}", self.state, to);
+        self.state = to;
+        self.requests.clear();
+        true
+    }
+
+    fn apply_requests(&mut self) {
+        while let Some(to) = self.requests.pop() {
+            if self.switch_to(to) {
+                break;
+            }
+        }
+        self.requests.clear();
+    }
+
+    /// Picks up settings hot-reloaded into the shared App config.
+    async fn sync_config(&mut self) {
+        let config = {
+            let app = self.app_state.lock().await;
+            if app.config_generation == self.config_generation {
+                return;
+            }
+            self.config_generation = app.config_generation;
+            app.config.clone()
+        };
+
+        self.scheduler.lock().await.update_config(config.clone());
+        self.cooldown_manager.lock().await.set_base_cooldown(config.system.user_cooldown);
+        {
+            let mut optimizer = self.optimizer.lock().await;
+            optimizer.goal = config.strategy.goal;
+            optimizer.bankroll.config = config.gambling.clone();
+            optimizer.charms = config.charms.clone();
+        }
+        self.break_planner.update_config(config.breaks.clone(), Instant::now());
+        self.channels.update_config(config.channel_rotation.clone(), Instant::now());
+        self.config = config;
+        info!("Bot applied reloaded config.");
+    }
+
+    async fn notify_purchase(&self, rec: &Recommendation) {
+        let cost = if rec.action == ActionType::BuyLeagueUpgrade { format!("{} Hooks", rec.cost) } else { format!("${}", rec.cost) };
+        self.app_state.lock().await.notifier.send(Notification::new(
+            EventKind::Purchase, Severity::Info,
+            format!("Bought {}", rec.target_name),
+            format!("Cost: {}", cost)));
+    }
+
+    /// Spends Hooks on the League upgrade the optimizer picked, through `/upgrades`.
+    async fn buy_league_upgrade(&mut self, rec: &Recommendation) {
+        let recently_bought = self.last_action.as_ref()
+            .is_some_and(|(action, at)| *action == ActionType::BuyLeagueUpgrade && at.elapsed() < LEAGUE_UPGRADE_SPACING);
+        if recently_bought {
+            return;
+        }
+        let guild_id = self.config.system.guild_id.to_string();
+        let channel_id = self.channel_id();
+        let Some(cmd) = self.client.get_command(&guild_id, "upgrades").await.unwrap_or(None) else {
+            warn!("Can't buy {}: /upgrades command not found", rec.target_name);
+            return;
+        };
+
+        info!("AUTONOMOUS ACTION: Buying League upgrade {} for {} Hooks", rec.target_name, rec.cost);
+        let options = match CommandBuilder::new(&cmd).option("upgrade", rec.target_name.as_str()).build() {
+            Ok(options) => options,
+            Err(e) => {
+                warn!("Can't buy {}: {}", rec.target_name, e);
+                return;
+            }
+        };
+        match self.client.send_command(&guild_id, &channel_id, &cmd, options).await {
+            Ok(()) => {
+                self.last_action = Some((ActionType::BuyLeagueUpgrade, Instant::now()));
+                // Until the next profile/league embed says otherwise
+                {
+                    let mut app = self.app_state.lock().await;
+                    app.profile.hooks = app.profile.hooks.saturating_sub(rec.cost);
+                }
+                self.notify_purchase(rec).await;
+            }
+            Err(e) => {
+                if !self.check_logged_out(&e).await {
+                    warn!("Failed to buy {}: {}", rec.target_name, e);
+                }
+            }
+        }
+    }
+
+    /// Where the next command goes: `[system] channel_id`, or the `[channel_rotation]` channel
+    /// whose turn it is.
+    fn channel_id(&mut self) -> String {
+        self.channels.current(Instant::now()).to_string()
+    }
+
+    /// Sends `command` and keeps `send_failures` and the `Degraded` state up to date.
+    /// Returns whether Discord accepted it.
+    async fn send_tracked(&mut self, name: &str, command: &Value, options: Option<Vec<Value>>) -> bool {
+        let guild_id = self.config.system.guild_id.to_string();
+        let channel_id = self.channel_id();
+        match self.client.send_command(&guild_id, &channel_id, command, options).await {
+            Ok(()) => {
+                self.record_send_success().await;
+                true
+            },
+            Err(e) => {
+                if !self.check_logged_out(&e).await {
+                    self.record_send_failure(name, &e).await;
+                }
+                false
+            },
+        }
+    }
+
+    /// Fills the client's command cache once, so the first cast doesn't wait on it.
+    async fn prefetch_commands(&mut self) {
+        match self.client.refresh_commands(&self.config.system.guild_id.to_string()).await {
+            Ok(commands) => info!("Fetched {} Virtual Fisher commands", commands.len()),
+            Err(e) => {
+                if !self.check_logged_out(&e).await {
+                    warn!("Failed to prefetch commands: {}", e);
+                }
+            },
+        }
+    }
+
+    /// Reads the channel's last `[system] backfill_messages` messages to recover the profile,
+    /// cooldown estimate and any unsolved captcha from before a restart.
+    async fn backfill(&mut self) {
+        let limit = self.config.system.backfill_messages;
+        if limit == 0 {
+            return;
+        }
+        let messages = match self.client.get_messages(&self.config.system.channel_id.to_string(), limit).await {
+            Ok(messages) => messages,
+            Err(e) => {
+                if !self.check_logged_out(&e).await {
+                    warn!("Failed to read channel history: {}", e);
+                }
+                return;
+            },
+        };
+        let user_id = self.app_state.lock().await.user_id.clone();
+        let recovery = recovery::recover(&messages, &self.config.system.application_id.to_string(), user_id.as_deref());
+
+        let mut recovered = Vec::new();
+        if let Some(cooldown) = &recovery.cooldown {
+            self.cooldown_manager.lock().await.restore_estimate(cooldown.total_cooldown as f64);
+            recovered.push("cooldown");
+        }
+        if let Some(challenge) = recovery.captcha {
+            let mut captcha = self.captcha.lock().await;
+            if !captcha.detected {
+                warn!("Unsolved captcha in channel history (image: {})", challenge.image_url.as_deref().unwrap_or("none"));
+                captcha.detected = true;
+                captcha.image_url = challenge.image_url;
+            }
+            recovered.push("captcha");
+        }
+        let mut app = self.app_state.lock().await;
+        if let Some((title, description)) = &recovery.profile {
+            app.profile.update_from_message(description, Some(title));
+            recovered.push("profile");
+        }
+        info!("Read {} messages of channel history; recovered: {}", messages.len(),
+            if recovered.is_empty() { "nothing".to_string() } else { recovered.join(", ") });
+        if !recovered.is_empty() {
+            app.add_log(format!("Recovered {} from channel history", recovered.join(", ")));
+        }
+    }
+
+    /// Like `send_tracked`, but waits for the reply and clicks its Confirm button when Virtual
+    /// Fisher asks to confirm a purchase. A reply that never comes still counts as sent.
+    async fn send_confirmed(&mut self, name: &str, command: &Value, options: Option<Vec<Value>>) -> bool {
+        if !self.client.matches_replies() {
+            return self.send_tracked(name, command, options).await;
+        }
+        let guild_id = self.config.system.guild_id.to_string();
+        let channel_id = self.channel_id();
+        let reply = match self.client.send_command_and_wait(&guild_id, &channel_id, command, options, PURCHASE_REPLY_TIMEOUT).await {
+            Ok(reply) => reply,
+            Err(e) if e.downcast_ref::<NoReply>().is_some() => {
+                debug!("/{}: {}", name, e);
+                self.record_send_success().await;
+                return true;
+            },
+            Err(e) => {
+                if !self.check_logged_out(&e).await {
+                    self.record_send_failure(name, &e).await;
+                }
+                return false;
+            },
+        };
+        self.record_send_success().await;
+        if parser::find_button(&reply, "confirm").is_none() {
+            return true;
+        }
+        info!("Confirming /{}", name);
+        match self.client.click_button(&guild_id, &channel_id, &reply, "confirm").await {
+            Ok(()) => true,
+            Err(e) => {
+                if !self.check_logged_out(&e).await {
+                    warn!("Failed to confirm /{}: {}", name, e);
+                }
+                false
+            },
+        }
+    }
+
+    async fn record_send_success(&mut self) {
+        self.send_failures = 0;
+        let mut app = self.app_state.lock().await;
+        if app.degraded.take().is_some() {
+            info!("Commands go through again; leaving degraded mode");
+            app.status = if app.is_running { "Running".to_string() } else { "Stopped".to_string() };
+            app.add_log("Commands go through again".to_string());
+        }
+    }
+
+    async fn record_send_failure(&mut self, name: &str, error: &anyhow::Error) {
+        log::error!("Failed to send /{}: {}", name, error);
+        self.send_failures += 1;
+        if self.send_failures < MAX_SEND_FAILURES {
+            return;
+        }
+        self.switch_to(BotState::Degraded);
+        let mut app = self.app_state.lock().await;
+        if app.degraded.is_none() {
+            warn!("{} sends failed in a row; degraded until a cast goes through", self.send_failures);
+            app.status = "Degraded".to_string();
+            app.add_log(format!("Degraded: {} sends failed in a row", self.send_failures));
+            app.notifier.send(Notification::new(
+                EventKind::RepeatedFailures, Severity::Warning,
+                format!("Commands failed {} times in a row", MAX_SEND_FAILURES),
+                format!("Last: /{}: {}. Retrying a cast every {} minutes.", name, error, DEGRADED_PROBE_INTERVAL.as_secs() / 60)));
+        }
+        app.degraded = Some(format!("/{}: {}", name, error));
+    }
+
+    /// Wait before retrying a failed send: 2s, 4s, 8s... up to `MAX_SEND_BACKOFF`.
+    fn send_backoff(&self) -> Duration {
+        Duration::from_secs(1 << self.send_failures.clamp(1, 6)).min(MAX_SEND_BACKOFF)
+    }
+
+    /// Switches to `LoggedOut` if `error` is a 401/403. Returns true when it did.
+    async fn check_logged_out(&mut self, error: &anyhow::Error) -> bool {
+        if error.downcast_ref::<Unauthorized>().is_none() {
+            return false;
+        }
+        if self.state != BotState::LoggedOut {
+            log::error!("{}; stopped sending until the token works again", error);
+            let mut app = self.app_state.lock().await;
+            app.logged_out = true;
+            app.status = "Logged out".to_string();
+            app.add_log("Logged out: Discord rejected the token".to_string());
+            app.notifier.send(Notification::new(EventKind::TokenInvalid, Severity::Critical,
+                "Logged out", format!("{}. Nothing is sent until it is accepted again; re-testing every {} minutes.",
+                    error, TOKEN_RECHECK_INTERVAL.as_secs() / 60)));
+        }
+        self.switch_to(BotState::LoggedOut);
+        self.token_checked_at = Some(Instant::now());
+        true
+    }
+
+    /// Re-tests the token once per `TOKEN_RECHECK_INTERVAL` and resumes fishing when Discord accepts it.
+    async fn recheck_token(&mut self) {
+        if self.token_checked_at.is_some_and(|at| at.elapsed() < TOKEN_RECHECK_INTERVAL) {
+            return;
+        }
+        self.token_checked_at = Some(Instant::now());
+        match self.client.get_current_user().await {
+            Ok(user) => {
+                info!("Token accepted again as {}; resuming", user.username);
+                self.switch_to(BotState::Fishing);
+                let mut app = self.app_state.lock().await;
+                app.logged_out = false;
+                app.status = if app.is_running { "Running".to_string() } else { "Stopped".to_string() };
+                app.add_log("Token accepted again".to_string());
+                app.notifier.send(Notification::new(EventKind::TokenInvalid, Severity::Info,
+                    "Logged in again", "The token works again. Resuming fishing."));
+                // Commands were fetched with the old session; fetch them fresh
+                self.client.invalidate_commands(&self.config.system.guild_id.to_string());
+            },
+            Err(e) if e.downcast_ref::<Unauthorized>().is_some() => debug!("Token still rejected"),
+            Err(e) => warn!("Token re-test failed: {}", e),
+        }
+    }
+
+    pub async fn run(&mut self) {
+        // Startup delay to prevent rate limit spikes
+        info!("Bot warming up... waiting 5 seconds.");
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        self.prefetch_commands().await;
+        self.restore_snapshot().await;
+        self.restore_boats().await;
+        self.backfill().await;
+
+        let mut behaviors = behaviors::standard();
+        loop {
+            self.sync_config().await;
+
+            // Check if bot is running from TUI state
+            let is_running = {
+                let app = self.app_state.lock().await;
+                app.is_running
+            };
+
+            self.protect_daily_streak().await;
+            self.check_vote_window().await;
+            self.refresh_charts().await;
+            self.sync_explorer().await;
+
+            if !is_running {
+                // Stays logged out, by the transition rules
+                self.switch_to(BotState::Idle);
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                continue;
+            }
+
+            if self.state == BotState::Idle {
+                // Unless a resumed errand outranks it
+                self.request(BotState::Fishing);
+            }
+
+            // Check Captcha
+            let captcha_detected = {
+                self.captcha.lock().await.detected
+            };
+
+            if captcha_detected && self.state != BotState::Captcha {
+                if let Some((gate, name)) = &self.captcha_gate {
+                    gate.set_pending(name, true);
+                }
+                if let Err(e) = self.database.log_captcha().await {
+                    warn!("Failed to log captcha: {}", e);
+                }
+                self.app_state.lock().await.notifier.send(Notification::new(
+                    EventKind::CaptchaDetected, Severity::Critical, "Captcha detected", "Fishing is paused until it is solved."));
+            } else if !captcha_detected && self.state == BotState::Captcha {
+                if let Some((gate, name)) = &self.captcha_gate {
+                    gate.set_pending(name, false);
+                }
+                self.app_state.lock().await.notifier.send(Notification::new(
+                    EventKind::CaptchaSolved, Severity::Info, "Captcha solved", "Resuming fishing."));
+                self.switch_to(BotState::Fishing);
+                self.captcha_attempted_at = None;
+                self.captcha_gave_up = false;
+                let mut app = self.app_state.lock().await;
+                app.captcha = None;
+                app.captcha_answer = None;
+            }
+
+            if captcha_detected {
+                self.switch_to(BotState::Captcha);
+            } else if self.held_by_captcha_gate().await || self.paused_for_humans().await {
+                tokio::time::sleep(Duration::from_secs(2)).await;
+                continue;
+            } else {
+                self.follow_break_schedule().await;
+            }
+            self.apply_requests();
+
+            let state = self.state;
+            let step = match behaviors.get_mut(state) {
+                Some(behavior) => behavior.tick(self).await,
+                None => {
+                    warn!("Nothing runs the {:?} state", state);
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    Step::Done
+                },
+            };
+            // Switched mid-tick (logged out, degraded): what the tick asked for after that is stale
+            if self.state != state {
+                self.requests.clear();
+            }
+            if step == Step::Again {
+                continue;
+            }
+
+            self.sync_event_tasks().await;
+            self.claim_quests().await;
+            self.decline_trades().await;
+            self.deposit_to_vault().await;
+            self.open_crates().await;
+            self.apply_cosmetics().await;
+            self.buy_bait().await;
+            self.buy_boosts().await;
+            self.run_script_commands().await;
+            self.celebrate_level_up().await;
+            self.record_net_worth().await;
+            self.retry_unanswered().await;
+            self.claim_clan().await;
+            self.manage_workers().await;
+            self.use_grinding_charm().await;
+            self.save_snapshot().await;
+
+            // Run Scheduler
+            {
+                let channel_id = self.channel_id();
+                let mut sched = self.scheduler.lock().await;
+                sched.process(&self.client, &channel_id).await;
+            }
+        }
+    }
+}
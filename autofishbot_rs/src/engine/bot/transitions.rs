@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// What the bot is doing; each state has a `Behavior` that runs it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BotState {
+    Idle,
+    Fishing,
+    Captcha,
+    /// Away on a `[breaks]` break until `break_until`
+    Break,
+    Exploration,
+    Selling,
+    Shopping,
+    /// Sending `/prestige` (playbook or `auto_prestige`), then back to fishing
+    Prestiging,
+    /// Discord rejected the token; nothing is sent until a re-test succeeds
+    LoggedOut,
+    /// `MAX_SEND_FAILURES` sends failed in a row; casts are probed every `DEGRADED_PROBE_INTERVAL`
+    Degraded,
+}
+
+impl BotState {
+    /// Which of several states requested in the same pass wins: a break before a sell, a sell
+    /// before a purchase.
+    pub fn priority(self) -> u8 {
+        match self {
+            BotState::Captcha => 9,
+            BotState::LoggedOut => 8,
+            BotState::Degraded => 7,
+            BotState::Break => 6,
+            BotState::Selling => 5,
+            BotState::Prestiging => 4,
+            BotState::Shopping => 3,
+            BotState::Exploration => 2,
+            BotState::Fishing => 1,
+            BotState::Idle => 0,
+        }
+    }
+
+    /// The transition rules. Errands (selling, shopping, prestiging, breaks) only start from
+    /// `Fishing` and end back there; logging out, a captcha and pausing can interrupt anything.
+    /// Once logged out, only a working token (back to `Fishing`) gets out. Startup may go from
+    /// `Idle` straight into an errand saved in the last snapshot.
+    pub fn can_switch_to(self, to: BotState) -> bool {
+        use BotState::*;
+        match (self, to) {
+            (from, to) if from == to => true,
+            (LoggedOut, to) => to == Fishing,
+            (_, LoggedOut | Captcha | Idle) => true,
+            // A captcha is waited out, failures or not
+            (Captcha, Degraded) => false,
+            (_, Degraded | Fishing) => true,
+            (Idle, Exploration | Selling | Shopping | Break) => true,
+            (Fishing, _) => true,
+            _ => false,
+        }
+    }
+}
+
+struct Request {
+    state: BotState,
+    /// Ties go to the earlier request
+    order: Reverse<u64>,
+}
+
+impl Request {
+    fn key(&self) -> (u8, Reverse<u64>) {
+        (self.state.priority(), self.order)
+    }
+}
+
+impl PartialEq for Request {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Request {}
+
+impl PartialOrd for Request {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Request {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// States asked for during a pass of the bot loop, highest `priority` first.
+#[derive(Default)]
+pub struct StateQueue {
+    requests: BinaryHeap<Request>,
+    next_order: u64,
+}
+
+impl StateQueue {
+    /// Asking again for a state already asked for changes nothing.
+    pub fn push(&mut self, state: BotState) {
+        if self.contains(state) {
+            return;
+        }
+        self.requests.push(Request { state, order: Reverse(self.next_order) });
+        self.next_order += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<BotState> {
+        self.requests.pop().map(|r| r.state)
+    }
+
+    pub fn contains(&self, state: BotState) -> bool {
+        self.requests.iter().any(|r| r.state == state)
+    }
+
+    pub fn clear(&mut self) {
+        self.requests.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transitions() {
+        use BotState::*;
+        assert!(Fishing.can_switch_to(Selling) && Selling.can_switch_to(Fishing));
+        // Errands don't chain into each other
+        assert!(!Selling.can_switch_to(Shopping) && !Break.can_switch_to(Selling));
+        assert!(Shopping.can_switch_to(LoggedOut) && Break.can_switch_to(Captcha));
+        // Pausing keeps a logged out bot logged out
+        assert!(!LoggedOut.can_switch_to(Idle) && LoggedOut.can_switch_to(Fishing));
+        assert!(!Captcha.can_switch_to(Degraded) && Selling.can_switch_to(Degraded));
+        assert!(Idle.can_switch_to(Exploration) && !Exploration.can_switch_to(Selling));
+
+        let mut queue = StateQueue::default();
+        queue.push(Shopping);
+        queue.push(Selling);
+        queue.push(Break);
+        queue.push(Selling);
+        assert!(queue.contains(Shopping));
+        assert_eq!(queue.pop(), Some(Break));
+        assert_eq!(queue.pop(), Some(Selling));
+        assert_eq!(queue.pop(), Some(Shopping));
+        assert_eq!(queue.pop(), None);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::config::BreaksConfig;
+use chrono::{NaiveTime, Timelike};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+const DAY_SECS: u64 = 24 * 3600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BreakKind {
+    /// A few minutes away from the keyboard
+    Short,
+    /// The `sleep_window`
+    Sleep,
+}
+
+impl BreakKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            BreakKind::Short => "short break",
+            BreakKind::Sleep => "sleep",
+        }
+    }
+}
+
+/// What the schedule says about casting right now.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Activity {
+    Active,
+    /// Cast, but stretch each sleep by this factor
+    Slow(f64),
+    /// Stop casting until then
+    Break { until: Instant, kind: BreakKind },
+}
+
+/// Plans time away from fishing from `[breaks]`: a short break every so often, the nightly
+/// `sleep_window` in local time, and stretches between breaks that cast more slowly.
+pub struct BreakPlanner {
+    config: BreaksConfig,
+    window: Option<(NaiveTime, NaiveTime)>,
+    next_short: Instant,
+    /// Whether the stretch until `next_short` is a slow one
+    slow: bool,
+    rng: StdRng,
+}
+
+impl BreakPlanner {
+    pub fn new(config: BreaksConfig, now: Instant) -> Self {
+        Self::with_rng(config, now, StdRng::from_entropy())
+    }
+
+    pub fn with_rng(config: BreaksConfig, now: Instant, rng: StdRng) -> Self {
+        let window = parse_window(&config.sleep_window);
+        let mut planner = Self { config, window, next_short: now, slow: false, rng };
+        planner.plan_stretch(now);
+        planner
+    }
+
+    /// Picks up a reloaded `[breaks]`; the next break is planned again when its settings changed.
+    pub fn update_config(&mut self, config: BreaksConfig, now: Instant) {
+        if config != self.config {
+            self.window = parse_window(&config.sleep_window);
+            self.config = config;
+            self.plan_stretch(now);
+        }
+    }
+
+    /// Schedules the next short break and rolls whether the time until then is slow.
+    fn plan_stretch(&mut self, from: Instant) {
+        let min = self.config.interval_min_minutes.min(self.config.interval_max_minutes);
+        let max = self.config.interval_min_minutes.max(self.config.interval_max_minutes);
+        self.next_short = from + Duration::from_secs(self.rng.gen_range(min * 60..=max * 60));
+        self.slow = self.rng.gen_bool(self.config.slow_chance.clamp(0.0, 1.0));
+    }
+
+    /// Call while fishing. A break it returns is already accounted for: the next short break
+    /// is planned from its end.
+    pub fn check(&mut self, now: Instant, local: NaiveTime) -> Activity {
+        if !self.config.enabled {
+            return Activity::Active;
+        }
+        if let Some(left) = self.window.and_then(|window| remaining_in_window(window, local)) {
+            let until = now + left;
+            self.plan_stretch(until);
+            return Activity::Break { until, kind: BreakKind::Sleep };
+        }
+        if now >= self.next_short {
+            let min = self.config.break_min_minutes.min(self.config.break_max_minutes);
+            let max = self.config.break_min_minutes.max(self.config.break_max_minutes);
+            let until = now + Duration::from_secs(self.rng.gen_range(min * 60..=max * 60));
+            self.plan_stretch(until);
+            return Activity::Break { until, kind: BreakKind::Short };
+        }
+        if self.slow { Activity::Slow(self.config.slow_factor.max(1.0)) } else { Activity::Active }
+    }
+
+    /// How long until the next break starts, and which one it is.
+    pub fn next_break(&self, now: Instant, local: NaiveTime) -> Option<(Duration, BreakKind)> {
+        if !self.config.enabled {
+            return None;
+        }
+        let short = (self.next_short.saturating_duration_since(now), BreakKind::Short);
+        let sleep = self.window.map(|(start, _)| (until_time(local, start), BreakKind::Sleep));
+        Some(sleep.filter(|(at, _)| *at < short.0).unwrap_or(short))
+    }
+}
+
+/// "01:00-08:00" as start and end times; the window may wrap past midnight.
+pub fn parse_window(window: &str) -> Option<(NaiveTime, NaiveTime)> {
+    let (start, end) = window.split_once('-')?;
+    let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").ok()?;
+    let end = NaiveTime::parse_from_str(end.trim(), "%H:%M").ok()?;
+    (start != end).then_some((start, end))
+}
+
+/// Time left in the window when `local` falls inside it.
+fn remaining_in_window((start, end): (NaiveTime, NaiveTime), local: NaiveTime) -> Option<Duration> {
+    let inside = if start < end { start <= local && local < end } else { local >= start || local < end };
+    inside.then(|| until_time(local, end))
+}
+
+/// Time from `from` until the clock next reads `to`.
+fn until_time(from: NaiveTime, to: NaiveTime) -> Duration {
+    let from = from.num_seconds_from_midnight() as u64;
+    let to = to.num_seconds_from_midnight() as u64;
+    Duration::from_secs((to + DAY_SECS - from) % DAY_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_break_planner() {
+        assert_eq!(parse_window("01:00-08:00"), Some((time(1, 0), time(8, 0))));
+        assert_eq!(parse_window("23:30 - 06:00"), Some((time(23, 30), time(6, 0))));
+        assert_eq!(parse_window("25:00-08:00"), None);
+        assert_eq!(parse_window("08:00-08:00"), None);
+        assert_eq!(parse_window(""), None);
+
+        let config = BreaksConfig { enabled: true, sleep_window: "23:00-07:00".to_string(), slow_chance: 0.0, ..Default::default() };
+        let start = Instant::now();
+        let mut planner = BreakPlanner::with_rng(config.clone(), start, StdRng::seed_from_u64(7));
+        let noon = time(12, 0);
+
+        // The first short break falls inside the configured interval
+        let (first, kind) = planner.next_break(start, noon).unwrap();
+        assert_eq!(kind, BreakKind::Short);
+        assert!(first >= Duration::from_secs(config.interval_min_minutes * 60) && first <= Duration::from_secs(config.interval_max_minutes * 60));
+        assert_eq!(planner.check(start, noon), Activity::Active);
+
+        let due = start + first;
+        let Activity::Break { until, kind: BreakKind::Short } = planner.check(due, noon) else { panic!("expected a short break") };
+        let length = until - due;
+        assert!(length >= Duration::from_secs(config.break_min_minutes * 60) && length <= Duration::from_secs(config.break_max_minutes * 60));
+        // The next one is planned from the end of this break
+        assert!(planner.next_break(until, noon).unwrap().0 >= Duration::from_secs(config.interval_min_minutes * 60));
+
+        // The sleep window wraps midnight and comes first when it's closer
+        assert_eq!(planner.next_break(until, time(22, 50)), Some((Duration::from_secs(600), BreakKind::Sleep)));
+        assert_eq!(planner.check(until, time(2, 0)), Activity::Break { until: until + Duration::from_secs(5 * 3600), kind: BreakKind::Sleep });
+
+        let slow = BreaksConfig { slow_chance: 1.0, slow_factor: 2.5, ..config.clone() };
+        assert_eq!(BreakPlanner::with_rng(slow, start, StdRng::seed_from_u64(7)).check(start, noon), Activity::Slow(2.5));
+
+        let mut disabled = BreakPlanner::with_rng(BreaksConfig::default(), start, StdRng::seed_from_u64(7));
+        assert_eq!(disabled.check(start + Duration::from_secs(DAY_SECS), time(2, 0)), Activity::Active);
+        assert_eq!(disabled.next_break(start, noon), None);
+    }
+}
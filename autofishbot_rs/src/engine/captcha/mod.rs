@@ -0,0 +1,233 @@
+pub mod solver;
+
+use crate::config::Config;
+use anyhow::{Result, anyhow};
+use log::{info, error, warn};
+use solver::CaptchaSolver;
+use std::sync::Arc;
+
+/// A pending captcha as the TUI's Captcha tab shows it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CaptchaStatus {
+    pub image_url: Option<String>,
+    /// Answers sent so far, OCR and manual
+    pub answers: Vec<String>,
+    pub solving: bool,
+    /// Configured solver names; empty means answers must be typed in
+    pub solvers: Vec<&'static str>,
+    /// Answers Virtual Fisher said were wrong
+    pub rejected: Vec<String>,
+    pub attempts: u32,
+}
+
+pub struct Captcha {
+    /// `[captcha] provider` then `fallback`, see `solver::from_config`
+    solvers: Arc<[Box<dyn CaptchaSolver>]>,
+    pub detected: bool,
+    pub solving: bool,
+    /// Every answer sent for the current captcha, OCR and manual
+    pub answers: Vec<String>,
+    /// Answers Virtual Fisher replied were wrong; solvers that come up with one again are skipped
+    pub rejected: Vec<String>,
+    /// Solves run for the current captcha
+    pub attempts: u32,
+    pub image_url: Option<String>,
+}
+
+impl Captcha {
+    pub fn new(config: Config) -> Self {
+        Self::with_solvers(solver::from_config(&config.captcha))
+    }
+
+    fn with_solvers(solvers: Vec<Box<dyn CaptchaSolver>>) -> Self {
+        Self {
+            solvers: solvers.into(),
+            detected: false,
+            solving: false,
+            answers: Vec::new(),
+            rejected: Vec::new(),
+            attempts: 0,
+            image_url: None,
+        }
+    }
+
+    /// False when no provider is usable (no API keys), so only manual answers work.
+    pub fn has_solvers(&self) -> bool {
+        !self.solvers.is_empty()
+    }
+
+    /// Marks a solve of the image at `url` as running and returns what it needs, so the
+    /// solvers (network, OCR) can run without this lock held. Hand the result to `finish_solve`.
+    pub fn start_solve(&mut self, url: String) -> SolveJob {
+        self.detected = true;
+        self.solving = true;
+        info!("Solving captcha (attempt {}): {}", self.attempts + 1, url);
+        self.image_url = Some(url);
+        SolveJob { solvers: self.solvers.clone(), attempt: self.attempts, rejected: self.rejected.clone() }
+    }
+
+    /// Records the outcome of a `SolveJob::run`.
+    pub fn finish_solve(&mut self, result: Result<String>) -> Result<String> {
+        self.attempts += 1;
+        self.solving = false;
+        let answer = result?;
+        info!("Captcha solved: {}", answer);
+        self.answers.push(answer.clone());
+        Ok(answer)
+    }
+
+    /// Marks the last answer sent as wrong, after Virtual Fisher's reply to `/verify`.
+    pub fn reject(&mut self) {
+        if let Some(answer) = self.answers.last() {
+            if !self.rejected.contains(answer) {
+                self.rejected.push(answer.clone());
+            }
+        }
+    }
+
+    /// The last answer sent was rejected, so there's no verdict left to wait for.
+    pub fn last_answer_rejected(&self) -> bool {
+        self.answers.last().is_some_and(|a| self.rejected.contains(a))
+    }
+
+    /// `None` while no captcha is pending.
+    pub fn status(&self) -> Option<CaptchaStatus> {
+        self.detected.then(|| CaptchaStatus {
+            image_url: self.image_url.clone(),
+            answers: self.answers.clone(),
+            solving: self.solving,
+            solvers: self.solvers.iter().map(|s| s.name()).collect(),
+            rejected: self.rejected.clone(),
+            attempts: self.attempts,
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.detected = false;
+        self.solving = false;
+        self.answers.clear();
+        self.rejected.clear();
+        self.attempts = 0;
+        self.image_url = None;
+    }
+}
+
+/// One solve attempt, detached from `Captcha` by `start_solve`.
+pub struct SolveJob {
+    solvers: Arc<[Box<dyn CaptchaSolver>]>,
+    attempt: u32,
+    rejected: Vec<String>,
+}
+
+impl SolveJob {
+    /// Each solver in turn until one gives a well-formed code that wasn't rejected before.
+    pub async fn run(&self, image: &[u8]) -> Result<String> {
+        if self.solvers.is_empty() {
+            error!("No captcha solver configured!");
+            return Err(anyhow!("No captcha solver configured"));
+        }
+        let mut errors = Vec::new();
+        for solver in self.solvers.iter() {
+            match solver.solve(image, self.attempt).await.and_then(|text| clean_answer(&text)) {
+                Ok(answer) if self.rejected.contains(&answer) => {
+                    warn!("Captcha solver {} repeated the rejected answer {}", solver.name(), answer);
+                    errors.push(format!("{}: repeated {}", solver.name(), answer));
+                }
+                Ok(answer) => return Ok(answer),
+                Err(e) => {
+                    warn!("Captcha solver {} failed: {}", solver.name(), e);
+                    errors.push(format!("{}: {}", solver.name(), e));
+                }
+            }
+        }
+        Err(anyhow!("Every captcha solver failed ({})", errors.join("; ")))
+    }
+}
+
+/// Virtual Fisher's codes are 6 letters and digits; OCR noise (spaces, punctuation) is dropped.
+fn clean_answer(text: &str) -> Result<String> {
+    let filtered: String = text.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    if filtered.len() == 6 {
+        Ok(filtered)
+    } else {
+        Err(anyhow!("Invalid captcha length: {} ({})", filtered.len(), filtered))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::future::BoxFuture;
+
+    /// Reads "ABC123" first and "XYZ789" on retries.
+    struct Fixed;
+
+    impl CaptchaSolver for Fixed {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        fn solve<'a>(&'a self, _image: &'a [u8], attempt: u32) -> BoxFuture<'a, Result<String>> {
+            Box::pin(async move { Ok(if attempt == 0 { "ABC 123".to_string() } else { "XYZ-789".to_string() }) })
+        }
+    }
+
+    /// Always reads the same code.
+    struct Stuck;
+
+    impl CaptchaSolver for Stuck {
+        fn name(&self) -> &'static str {
+            "stuck"
+        }
+
+        fn solve<'a>(&'a self, _image: &'a [u8], _attempt: u32) -> BoxFuture<'a, Result<String>> {
+            Box::pin(async { Ok("ABC123".to_string()) })
+        }
+    }
+
+    async fn solve(captcha: &mut Captcha) -> Result<String> {
+        let job = captcha.start_solve("https://cdn/x.png".to_string());
+        assert!(captcha.status().unwrap().solving);
+        let result = job.run(b"png").await;
+        captcha.finish_solve(result)
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_rejection() {
+        let mut captcha = Captcha::with_solvers(vec![Box::new(Stuck), Box::new(Fixed)]);
+        assert_eq!(solve(&mut captcha).await.unwrap(), "ABC123");
+        assert!(!captcha.last_answer_rejected());
+
+        captcha.reject();
+        assert!(captcha.last_answer_rejected());
+        // Stuck repeats the rejected code, so the second solver's retry reading wins
+        assert_eq!(solve(&mut captcha).await.unwrap(), "XYZ789");
+        assert_eq!(captcha.attempts, 2);
+        assert_eq!(captcha.status().unwrap().rejected, vec!["ABC123"]);
+
+        captcha.reset();
+        assert_eq!((captcha.attempts, captcha.rejected.len()), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_status_for_the_captcha_tab() {
+        let mut captcha = Captcha::with_solvers(Vec::new());
+        assert!(!captcha.has_solvers());
+        assert_eq!(captcha.status(), None);
+
+        // Nothing to solve with, so the tab asks for a typed answer
+        let job = captcha.start_solve("https://cdn/x.png".to_string());
+        assert!(captcha.finish_solve(job.run(b"png").await).is_err());
+        let status = captcha.status().unwrap();
+        assert_eq!((status.image_url.as_deref(), status.solving, status.attempts), (Some("https://cdn/x.png"), false, 1));
+        assert!(status.solvers.is_empty() && status.answers.is_empty());
+
+        captcha.answers.push("QWE456".to_string());
+        captcha.reject();
+        let status = captcha.status().unwrap();
+        assert_eq!((status.answers, status.rejected), (vec!["QWE456".to_string()], vec!["QWE456".to_string()]));
+
+        captcha.reset();
+        assert_eq!(captcha.status(), None);
+    }
+}
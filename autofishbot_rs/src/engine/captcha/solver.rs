@@ -0,0 +1,239 @@
+use crate::config::CaptchaConfig;
+use anyhow::{Result, anyhow, bail};
+use base64::Engine;
+use futures_util::future::BoxFuture;
+use log::warn;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::time::Duration;
+
+/// Names accepted by `[captcha] provider` and `fallback`.
+pub const PROVIDERS: &[&str] = &["ocr_space", "2captcha", "capmonster", "tesseract"];
+
+/// Wait between result polls for the human-solver services.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Polls before a queued task is given up on.
+const MAX_POLLS: u32 = 24;
+
+/// Turns a captcha image into text. Answers are raw; `Captcha::solve` cleans and checks them.
+/// `image` is the downloaded file (see `DiscordClient::download_attachment`), since Discord's
+/// CDN links aren't something every service can fetch. `attempt` counts from 0 per captcha;
+/// local and OCR backends read the image differently on each retry so a rejected answer isn't
+/// simply repeated.
+pub trait CaptchaSolver: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn solve<'a>(&'a self, image: &'a [u8], attempt: u32) -> BoxFuture<'a, Result<String>>;
+}
+
+/// The solvers for `provider` followed by `fallback`, skipping unknown names, duplicates and
+/// services without an API key.
+pub fn from_config(config: &CaptchaConfig) -> Vec<Box<dyn CaptchaSolver>> {
+    let client = Client::builder().timeout(Duration::from_secs(20)).build().unwrap();
+    let mut names: Vec<&str> = Vec::new();
+    for name in std::iter::once(&config.provider).chain(&config.fallback).map(|n| n.trim()) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names.into_iter().filter_map(|name| {
+        let key = |key: &str| (!key.is_empty()).then(|| key.to_string());
+        let solver: Box<dyn CaptchaSolver> = match name {
+            "ocr_space" => Box::new(OcrSpace { client: client.clone(), api_key: key(&config.ocr_api_key)? }),
+            "2captcha" => Box::new(TwoCaptcha { client: client.clone(), api_key: key(&config.twocaptcha_api_key)? }),
+            "capmonster" => Box::new(CapMonster { client: client.clone(), api_key: key(&config.capmonster_api_key)? }),
+            "tesseract" => Box::new(Tesseract { path: config.tesseract_path.clone() }),
+            other => {
+                warn!("Unknown captcha provider '{}'", other);
+                return None;
+            }
+        };
+        Some(solver)
+    }).collect()
+}
+
+/// MIME type from the file's magic bytes; Discord serves captchas as PNG.
+fn image_mime(image: &[u8]) -> &'static str {
+    match image {
+        [0xFF, 0xD8, 0xFF, ..] => "image/jpeg",
+        [b'G', b'I', b'F', b'8', ..] => "image/gif",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "image/webp",
+        _ => "image/png",
+    }
+}
+
+/// OCR.SPACE's free image-to-text API.
+pub struct OcrSpace {
+    client: Client,
+    api_key: String,
+}
+
+impl CaptchaSolver for OcrSpace {
+    fn name(&self) -> &'static str {
+        "ocr_space"
+    }
+
+    fn solve<'a>(&'a self, image: &'a [u8], attempt: u32) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            // Engine 2 is usually better for alphanumeric; retries try the others, unscaled
+            let (engine, scale) = [("2", "true"), ("1", "true"), ("3", "true"), ("2", "false")][attempt as usize % 4];
+            let data = format!("data:{};base64,{}", image_mime(image), base64::engine::general_purpose::STANDARD.encode(image));
+            let params = [
+                ("apikey", self.api_key.as_str()),
+                ("base64Image", data.as_str()),
+                ("language", "eng"),
+                ("isOverlayRequired", "false"),
+                ("detectOrientation", "true"),
+                ("scale", scale),
+                ("OCREngine", engine),
+            ];
+            let res = self.client.post("https://api.ocr.space/parse/image").form(&params).send().await?;
+            if !res.status().is_success() {
+                bail!("OCR API error: {}", res.status());
+            }
+            let body: Value = res.json().await?;
+            if let Some(exit_code) = body.get("OCRExitCode").and_then(|v| v.as_i64()) {
+                if exit_code != 1 {
+                    bail!("OCR Error Code: {}", exit_code);
+                }
+            }
+            body.pointer("/ParsedResults/0/ParsedText")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+                .ok_or_else(|| anyhow!("No text found"))
+        })
+    }
+}
+
+/// 2Captcha's normal (image) captcha queue.
+pub struct TwoCaptcha {
+    client: Client,
+    api_key: String,
+}
+
+impl CaptchaSolver for TwoCaptcha {
+    fn name(&self) -> &'static str {
+        "2captcha"
+    }
+
+    fn solve<'a>(&'a self, image: &'a [u8], _attempt: u32) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let image = base64::engine::general_purpose::STANDARD.encode(image);
+            let params = [("key", self.api_key.as_str()), ("method", "base64"), ("body", image.as_str()), ("json", "1")];
+            let body: Value = self.client.post("https://2captcha.com/in.php").form(&params).send().await?.json().await?;
+            let id = match (body["status"].as_i64(), body["request"].as_str()) {
+                (Some(1), Some(id)) => id.to_string(),
+                (_, error) => bail!("2Captcha rejected the task: {}", error.unwrap_or("no reason")),
+            };
+            for _ in 0..MAX_POLLS {
+                tokio::time::sleep(POLL_INTERVAL).await;
+                let query = [("key", self.api_key.as_str()), ("action", "get"), ("id", id.as_str()), ("json", "1")];
+                let body: Value = self.client.get("https://2captcha.com/res.php").query(&query).send().await?.json().await?;
+                match (body["status"].as_i64(), body["request"].as_str()) {
+                    (Some(1), Some(answer)) => return Ok(answer.to_string()),
+                    (_, Some("CAPCHA_NOT_READY")) => continue,
+                    (_, error) => bail!("2Captcha: {}", error.unwrap_or("no answer")),
+                }
+            }
+            Err(anyhow!("2Captcha: no answer after {}s", (POLL_INTERVAL * MAX_POLLS).as_secs()))
+        })
+    }
+}
+
+/// CapMonster Cloud's `ImageToTextTask`.
+pub struct CapMonster {
+    client: Client,
+    api_key: String,
+}
+
+impl CaptchaSolver for CapMonster {
+    fn name(&self) -> &'static str {
+        "capmonster"
+    }
+
+    fn solve<'a>(&'a self, image: &'a [u8], _attempt: u32) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let image = base64::engine::general_purpose::STANDARD.encode(image);
+            let task = json!({ "clientKey": self.api_key, "task": { "type": "ImageToTextTask", "body": image } });
+            let body: Value = self.client.post("https://api.capmonster.cloud/createTask").json(&task).send().await?.json().await?;
+            let Some(task_id) = body["taskId"].as_i64().filter(|_| body["errorId"].as_i64() == Some(0)) else {
+                bail!("CapMonster rejected the task: {}", body["errorCode"].as_str().unwrap_or("no reason"));
+            };
+            for _ in 0..MAX_POLLS {
+                tokio::time::sleep(POLL_INTERVAL).await;
+                let query = json!({ "clientKey": self.api_key, "taskId": task_id });
+                let body: Value = self.client.post("https://api.capmonster.cloud/getTaskResult").json(&query).send().await?.json().await?;
+                if body["errorId"].as_i64() != Some(0) {
+                    bail!("CapMonster: {}", body["errorCode"].as_str().unwrap_or("unknown error"));
+                }
+                if body["status"] == "ready" {
+                    return body.pointer("/solution/text").and_then(|v| v.as_str()).map(str::to_string)
+                        .ok_or_else(|| anyhow!("CapMonster: ready without a solution"));
+                }
+            }
+            Err(anyhow!("CapMonster: no answer after {}s", (POLL_INTERVAL * MAX_POLLS).as_secs()))
+        })
+    }
+}
+
+/// A local `tesseract` binary, reading the image as a single line of letters and digits.
+pub struct Tesseract {
+    path: String,
+}
+
+impl CaptchaSolver for Tesseract {
+    fn name(&self) -> &'static str {
+        "tesseract"
+    }
+
+    fn solve<'a>(&'a self, image: &'a [u8], attempt: u32) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            // A fresh, unguessable file per solve (accounts share the process), removed on drop
+            let mut file = tempfile::Builder::new().prefix("autofishbot_captcha_").tempfile()?;
+            file.write_all(image)?;
+            file.flush()?;
+            let whitelist: String = ('a'..='z').chain('A'..='Z').chain('0'..='9').collect();
+            // Single line, then single word, then raw line segmentation
+            let psm = ["7", "8", "13"][attempt as usize % 3];
+            let output = tokio::process::Command::new(&self.path)
+                .arg(file.path())
+                .args(["stdout", "--psm", psm, "-c"])
+                .arg(format!("tessedit_char_whitelist={}", whitelist))
+                .output()
+                .await;
+            drop(file);
+            let output = output.map_err(|e| anyhow!("Running {}: {}", self.path, e))?;
+            if !output.status.success() {
+                bail!("tesseract failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solver_order() {
+        let config = CaptchaConfig {
+            provider: "capmonster".to_string(),
+            fallback: vec!["ocr_space".to_string(), "tesseract".to_string(), "capmonster".to_string(), "2captcha".to_string()],
+            ocr_api_key: "ocr".to_string(),
+            capmonster_api_key: "cm".to_string(),
+            ..CaptchaConfig::default()
+        };
+        // 2Captcha has no key, and capmonster is only tried once
+        let names: Vec<_> = from_config(&config).iter().map(|s| s.name()).collect();
+        assert_eq!(names, vec!["capmonster", "ocr_space", "tesseract"]);
+        assert!(from_config(&CaptchaConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_image_mime() {
+        assert_eq!(image_mime(b"\x89PNG\r\n\x1a\n"), "image/png");
+        assert_eq!(image_mime(&[0xFF, 0xD8, 0xFF, 0xE0]), "image/jpeg");
+        assert_eq!(image_mime(b"RIFF\0\0\0\0WEBPVP8 "), "image/webp");
+    }
+}
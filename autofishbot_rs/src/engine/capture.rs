@@ -0,0 +1,62 @@
+use crate::config::Config;
+use log::{info, warn};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+pub const CAPTURE_DIR: &str = "captures";
+
+/// Dumps game messages the parsers couldn't handle, so they can be attached to bug reports
+/// and turned into parser fixtures.
+#[derive(Clone)]
+pub struct EmbedCapture {
+    enabled: bool,
+    dir: PathBuf,
+}
+
+impl EmbedCapture {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            enabled: config.system.capture_embeds,
+            dir: PathBuf::from(CAPTURE_DIR),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Saves `message` (raw message JSON) as `captures/<time>_<reason>_<id>.json`.
+    /// Failures are logged and otherwise ignored; capturing must never disturb the bot.
+    pub async fn capture(&self, reason: &str, message: &Value) {
+        if !self.enabled {
+            return;
+        }
+
+        if let Err(e) = tokio::fs::create_dir_all(&self.dir).await {
+            warn!("Failed to create capture directory: {}", e);
+            return;
+        }
+
+        let now = chrono::Utc::now();
+        let message_id = message.get("id").and_then(|v| v.as_str()).unwrap_or("noid");
+        let file_name = format!("{}_{}_{}.json", now.format("%Y%m%d_%H%M%S%.3f"), reason, message_id);
+        let path = self.dir.join(file_name);
+
+        let record = json!({
+            "reason": reason,
+            "captured_at": now.to_rfc3339(),
+            "message": message,
+        });
+
+        match serde_json::to_string_pretty(&record) {
+            Ok(content) => {
+                if let Err(e) = tokio::fs::write(&path, content).await {
+                    warn!("Failed to write capture {}: {}", path.display(), e);
+                } else {
+                    info!("Captured embed ({}) to {}", reason, path.display());
+                }
+            },
+            Err(e) => warn!("Failed to serialize capture: {}", e),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::config::ChannelRotationConfig;
+use log::info;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::{Duration, Instant};
+
+/// Picks the channel commands go to from `[channel_rotation]`: one channel for a random
+/// dwell time, then another one picked by `weight`. Without rotation channels it is always
+/// `[system] channel_id`.
+pub struct ChannelRotation {
+    config: ChannelRotationConfig,
+    home: u64,
+    current: u64,
+    /// When the current channel's turn is over
+    until: Instant,
+    rng: StdRng,
+}
+
+impl ChannelRotation {
+    pub fn new(config: ChannelRotationConfig, home: u64, now: Instant) -> Self {
+        Self::with_rng(config, home, now, StdRng::from_entropy())
+    }
+
+    pub fn with_rng(config: ChannelRotationConfig, home: u64, now: Instant, rng: StdRng) -> Self {
+        let mut rotation = Self { config, home, current: home, until: now, rng };
+        rotation.rotate(now);
+        rotation
+    }
+
+    /// Picks up a reloaded `[channel_rotation]`; a changed one starts a new turn right away.
+    pub fn update_config(&mut self, config: ChannelRotationConfig, now: Instant) {
+        if config != self.config {
+            self.config = config;
+            self.rotate(now);
+        }
+    }
+
+    /// The channel to send to now, moving on once its turn is over.
+    pub fn current(&mut self, now: Instant) -> u64 {
+        if now >= self.until {
+            self.rotate(now);
+        }
+        self.current
+    }
+
+    /// Moves to another channel, by weight. The current one is only picked again when it is
+    /// the only one with any weight.
+    fn rotate(&mut self, now: Instant) {
+        let channels = &self.config.channels;
+        if channels.is_empty() {
+            self.current = self.home;
+            self.until = now + Duration::from_secs(24 * 3600);
+            return;
+        }
+        let weight = |id: u64, w: f64| if id == self.current { 0.0 } else { w.max(0.0) };
+        let pick = WeightedIndex::new(channels.iter().map(|c| weight(c.id, c.weight)))
+            .or_else(|_| WeightedIndex::new(channels.iter().map(|c| c.weight.max(0.0))));
+        let previous = self.current;
+        if let Ok(pick) = pick {
+            self.current = channels[pick.sample(&mut self.rng)].id;
+        }
+
+        let min = self.config.dwell_min_minutes.min(self.config.dwell_max_minutes);
+        let max = self.config.dwell_min_minutes.max(self.config.dwell_max_minutes);
+        let dwell = Duration::from_secs(self.rng.gen_range(min * 60..=max * 60));
+        self.until = now + dwell;
+        if self.current != previous {
+            info!("Fishing in channel {} for {} minutes", self.current, dwell.as_secs() / 60);
+        }
+    }
+}
+
+/// Every channel the bot may send to, for telling its own channels' messages apart. The
+/// explorer and backfill always use `home`, so it is in the list even when not rotated through.
+pub fn fishing_channels(config: &ChannelRotationConfig, home: u64) -> Vec<u64> {
+    let mut channels = vec![home];
+    channels.extend(config.channels.iter().map(|c| c.id).filter(|id| *id != home));
+    channels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RotationChannel;
+
+    #[test]
+    fn test_channel_rotation() {
+        let start = Instant::now();
+        let mut single = ChannelRotation::with_rng(ChannelRotationConfig::default(), 10, start, StdRng::seed_from_u64(3));
+        assert_eq!(single.current(start + Duration::from_secs(7 * 24 * 3600)), 10);
+        assert_eq!(fishing_channels(&ChannelRotationConfig::default(), 10), vec![10]);
+
+        let config = ChannelRotationConfig {
+            channels: vec![
+                RotationChannel { id: 1, weight: 1.0 },
+                RotationChannel { id: 2, weight: 3.0 },
+                RotationChannel { id: 3, weight: 0.0 },
+            ],
+            dwell_min_minutes: 10,
+            dwell_max_minutes: 10,
+        };
+        assert_eq!(fishing_channels(&config, 2), vec![2, 1, 3]);
+        let mut rotation = ChannelRotation::with_rng(config.clone(), 10, start, StdRng::seed_from_u64(3));
+        let first = rotation.current(start);
+        assert!(first == 1 || first == 2);
+        // Stays for the dwell time, then alternates: the zero-weight channel is never picked
+        assert_eq!(rotation.current(start + Duration::from_secs(599)), first);
+        let mut now = start;
+        for _ in 0..5 {
+            let before = rotation.current(now);
+            now += Duration::from_secs(600);
+            let after = rotation.current(now);
+            assert_ne!(before, after);
+            assert!(after != 3);
+        }
+
+        // With one weighted channel it stays there
+        let only = ChannelRotationConfig { channels: vec![RotationChannel { id: 5, weight: 1.0 }], ..config };
+        rotation.update_config(only, now);
+        assert_eq!(rotation.current(now + Duration::from_secs(3600)), 5);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::config::Config;
+use crate::tui::app::App;
+use anyhow::Result;
+use log::{info, warn};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Editors usually emit several events per save; let them settle before reloading.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Watches `config.toml` and applies runtime-safe changes to `App::config`.
+/// The bot notices the bumped `App::config_generation` and picks them up on its next tick.
+/// Dropping the watcher stops watching.
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+impl ConfigWatcher {
+    pub fn spawn<P: Into<PathBuf>>(path: P, account: Option<String>, app_state: Arc<Mutex<App>>) -> Result<Self> {
+        let path = path.into();
+        let file_name = path.file_name().map(|n| n.to_os_string());
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            let Ok(event) = res else { return };
+            if !matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_)) {
+                return;
+            }
+            if event.paths.iter().any(|p| p.file_name().map(|n| n.to_os_string()) == file_name) {
+                let _ = tx.send(());
+            }
+        })?;
+
+        // Watch the directory rather than the file: editors that save via rename would orphan a file watch
+        let dir = match path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+        info!("Watching {} for changes", path.display());
+
+        tokio::spawn(async move {
+            let mut last_loaded = None;
+            while rx.recv().await.is_some() {
+                tokio::time::sleep(DEBOUNCE).await;
+                while rx.try_recv().is_ok() {}
+                reload(&path, account.as_deref(), &app_state, &mut last_loaded).await;
+            }
+        });
+
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+/// `last_loaded` is the config from the previous successful reload, see `new_restart_changes`.
+async fn reload(path: &Path, account: Option<&str>, app_state: &Arc<Mutex<App>>, last_loaded: &mut Option<Config>) {
+    let loaded = Config::load(path)
+        .and_then(|config| match account {
+            Some(name) => config.for_account(name),
+            None => Ok(config),
+        })
+        .and_then(|config| config.validate().map(|_| config));
+
+    let new_config = match loaded {
+        Ok(config) => config,
+        Err(e) => {
+            // Keep running on the old config; a half-written file is common mid-save
+            warn!("Config reload failed: {}", e);
+            app_state.lock().await.add_log(format!("Config reload failed: {}", e));
+            return;
+        }
+    };
+
+    let mut app = app_state.lock().await;
+    let needs_restart = new_restart_changes(&app.config, last_loaded.as_ref(), &new_config);
+    *last_loaded = Some(new_config.clone());
+    if !needs_restart.is_empty() {
+        warn!("Config changes to {} require a restart", needs_restart.join(", "));
+        app.add_log(format!("Restart required for: {}", needs_restart.join(", ")));
+    }
+
+    let changed = app.config.apply_reloadable(&new_config);
+    if !changed.is_empty() {
+        info!("Config reloaded ({})", changed.join(", "));
+        app.config_generation += 1;
+        app.config_reloaded_at = Some(Instant::now());
+        app.add_log(format!("Config reloaded ({})", changed.join(", ")));
+    }
+}
+
+/// Restart-only settings that differ from the running config and changed since the last reload.
+/// `App::config` keeps the old values until a restart, so without the second check every later
+/// save would warn about the same edit again.
+fn new_restart_changes(running: &Config, last_loaded: Option<&Config>, new: &Config) -> Vec<&'static str> {
+    let since_last = last_loaded.map(|last| last.restart_required_changes(new));
+    running.restart_required_changes(new).into_iter()
+        .filter(|setting| since_last.as_ref().is_none_or(|changed| changed.contains(setting)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_warning_once_per_value() {
+        let running = Config::default();
+        let mut edited = running.clone();
+        edited.system.channel_id = 42;
+        assert_eq!(new_restart_changes(&running, None, &edited), vec!["channel_id"]);
+
+        // Saved again, or with only a reloadable change
+        let mut resaved = edited.clone();
+        resaved.system.user_cooldown = 4.0;
+        assert!(new_restart_changes(&running, Some(&edited), &resaved).is_empty());
+
+        let mut again = resaved.clone();
+        again.system.channel_id = 43;
+        again.system.guild_id = 7;
+        assert_eq!(new_restart_changes(&running, Some(&resaved), &again), vec!["guild_id", "channel_id"]);
+
+        // Back to the running value: nothing to restart for
+        assert!(new_restart_changes(&running, Some(&again), &running).is_empty());
+    }
+}
@@ -1,6 +1,14 @@
 use rand::Rng;
 use std::time::Duration;
 use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+/// What `CooldownManager` has learned, for `engine::state`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CooldownSnapshot {
+    pub estimate: f64,
+    pub consecutive_hits: u32,
+}
 
 pub struct CooldownManager {
     base_cooldown: f64,
@@ -19,6 +27,32 @@ impl CooldownManager {
         }
     }
 
+    /// Applies a new configured cooldown without discarding what we've learned from the server.
+    pub fn set_base_cooldown(&mut self, base_cooldown: f64) {
+        self.base_cooldown = base_cooldown;
+        if self.current_estimate < base_cooldown {
+            self.current_estimate = base_cooldown;
+        }
+    }
+
+    /// Raises the estimate to a cooldown seen before a restart, without counting it as a hit.
+    pub fn restore_estimate(&mut self, total_cooldown: f64) {
+        if total_cooldown > self.current_estimate {
+            info!("Restoring cooldown estimate {:.2}s from channel history", total_cooldown);
+            self.current_estimate = total_cooldown;
+        }
+    }
+
+    pub fn snapshot(&self) -> CooldownSnapshot {
+        CooldownSnapshot { estimate: self.current_estimate, consecutive_hits: self.consecutive_hits }
+    }
+
+    /// Picks up a snapshot from before a restart; the configured base stays the floor.
+    pub fn restore(&mut self, snapshot: &CooldownSnapshot) {
+        self.current_estimate = snapshot.estimate.max(self.base_cooldown);
+        self.consecutive_hits = snapshot.consecutive_hits;
+    }
+
     pub fn get_sleep_time(&self) -> Duration {
         let mut rng = rand::thread_rng();
         // Base delay + small random jitter to mimic human behavior
@@ -0,0 +1,201 @@
+use crate::discord::types::Message;
+use crate::engine::database::{Database, SentInteraction};
+use anyhow::Result;
+use log::error;
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// Message flag for a "Virtual Fisher is thinking..." placeholder; the real reply is its edit.
+const LOADING_FLAG: u64 = 1 << 7;
+
+/// Returned by `wait_for_reply` (and `DiscordClient::send_command_and_wait`) when the command
+/// went out but no usable reply came back. Callers can `downcast_ref` for it to tell that
+/// apart from a failed send.
+#[derive(Debug)]
+pub struct NoReply {
+    pub nonce: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for NoReply {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no reply to interaction {}: {}", self.nonce, self.reason)
+    }
+}
+
+impl std::error::Error for NoReply {}
+
+/// Ties each interaction we send to what came back: the client records the nonce on send,
+/// the gateway's INTERACTION_* events map it to an interaction id, and the reply message
+/// carries that id. Rows live in the `interactions` table.
+pub struct CorrelationStore {
+    db: Arc<Database>,
+    /// Sends someone is awaiting the reply to, by nonce
+    waiters: Mutex<HashMap<String, Waiter>>,
+}
+
+struct Waiter {
+    /// From INTERACTION_CREATE/SUCCESS; the reply message carries it
+    interaction_id: Option<String>,
+    reply_tx: Option<oneshot::Sender<Message>>,
+    reply_rx: Option<oneshot::Receiver<Message>>,
+}
+
+impl CorrelationStore {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db, waiters: Mutex::new(HashMap::new()) }
+    }
+
+    /// Keeps the reply to `nonce` for `wait_for_reply`. Call it before sending, since the
+    /// gateway can beat the HTTP response.
+    pub fn expect_reply(&self, nonce: &str) {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.waiters.lock().insert(nonce.to_string(), Waiter { interaction_id: None, reply_tx: Some(reply_tx), reply_rx: Some(reply_rx) });
+    }
+
+    /// The reply message to an `expect_reply` send, or an error when the interaction failed,
+    /// was rejected or nothing came back within `timeout`.
+    pub async fn wait_for_reply(&self, nonce: &str, timeout: Duration) -> Result<Message> {
+        let no_reply = |reason: String| NoReply { nonce: nonce.to_string(), reason }.into();
+        let reply_rx = self.waiters.lock().get_mut(nonce).and_then(|w| w.reply_rx.take());
+        let Some(reply_rx) = reply_rx else { return Err(no_reply("not expected".to_string())) };
+        let reply = tokio::time::timeout(timeout, reply_rx).await;
+        self.waiters.lock().remove(nonce);
+        match reply {
+            Ok(Ok(message)) => Ok(message),
+            Ok(Err(_)) => Err(no_reply("the interaction failed".to_string())),
+            Err(_) => Err(no_reply(format!("nothing within {}s", timeout.as_secs()))),
+        }
+    }
+
+    pub async fn record_sent(&self, nonce: &str, command: &str, options: &[Value], retry_of: Option<&str>) {
+        let options = serde_json::to_string(options).unwrap_or_default();
+        if let Err(e) = self.db.log_interaction_sent(nonce, command, &options, retry_of).await {
+            error!("DB Error: {}", e);
+        }
+    }
+
+    /// Discord refused the HTTP request itself; the caller already has the error, so it's not retried.
+    pub async fn mark_rejected(&self, nonce: &str) {
+        self.drop_waiter(nonce);
+        if let Err(e) = self.db.set_interaction_status(nonce, "", "rejected").await {
+            error!("DB Error: {}", e);
+        }
+    }
+
+    /// Feeds every gateway dispatch; ignores everything but interaction events and replies.
+    pub async fn on_gateway_event(&self, t: &str, d: &Value) {
+        let result = match t {
+            "INTERACTION_CREATE" | "INTERACTION_SUCCESS" | "INTERACTION_FAILURE" => {
+                let (Some(nonce), Some(id)) = (d["nonce"].as_str(), d["id"].as_str()) else { return };
+                let status = if t == "INTERACTION_FAILURE" { "failed" } else { "acknowledged" };
+                if t == "INTERACTION_FAILURE" {
+                    self.drop_waiter(nonce);
+                } else if let Some(waiter) = self.waiters.lock().get_mut(nonce) {
+                    waiter.interaction_id = Some(id.to_string());
+                }
+                self.db.set_interaction_status(nonce, id, status).await
+            },
+            "MESSAGE_CREATE" | "MESSAGE_UPDATE" => {
+                let Some(id) = d.pointer("/interaction_metadata/id").or_else(|| d.pointer("/interaction/id")).and_then(|v| v.as_str()) else { return };
+                let Some(message_id) = d["id"].as_str() else { return };
+                self.deliver_reply(id, d);
+                self.db.record_interaction_reply(id, message_id, &outcome_summary(d)).await
+            },
+            _ => return,
+        };
+        if let Err(e) = result {
+            error!("DB Error: {}", e);
+        }
+    }
+
+    /// First sends within the last `window` that failed or got no reply after `timeout`, not already retried.
+    pub async fn unanswered(&self, timeout: Duration, window: Duration) -> Result<Vec<SentInteraction>> {
+        self.db.unanswered_interactions(timeout.as_secs() as i64, window.as_secs() as i64, false).await
+    }
+
+    /// Like `unanswered`, but for the retries: these are given up on rather than sent again.
+    pub async fn unanswered_retries(&self, timeout: Duration, window: Duration) -> Result<Vec<SentInteraction>> {
+        self.db.unanswered_interactions(timeout.as_secs() as i64, window.as_secs() as i64, true).await
+    }
+
+    /// Hands a reply to whoever awaits interaction `id`, unless it's only the loading placeholder.
+    fn deliver_reply(&self, id: &str, d: &Value) {
+        if d["flags"].as_u64().is_some_and(|f| f & LOADING_FLAG != 0) {
+            return;
+        }
+        let mut waiters = self.waiters.lock();
+        let Some(waiter) = waiters.values_mut().find(|w| w.interaction_id.as_deref() == Some(id)) else { return };
+        let Some(reply_tx) = waiter.reply_tx.take() else { return };
+        match serde_json::from_value::<Message>(d.clone()) {
+            Ok(message) => { let _ = reply_tx.send(message); },
+            Err(e) => error!("Unreadable reply to interaction {}: {}", id, e),
+        }
+    }
+
+    /// Stops keeping the reply to a send that never went out.
+    pub fn forget(&self, nonce: &str) {
+        self.waiters.lock().remove(nonce);
+    }
+
+    /// The sender goes away, so `wait_for_reply` errors right away instead of timing out.
+    fn drop_waiter(&self, nonce: &str) {
+        if let Some(waiter) = self.waiters.lock().get_mut(nonce) {
+            waiter.reply_tx = None;
+        }
+    }
+
+    pub async fn mark_retried(&self, nonce: &str) -> Result<()> {
+        self.db.set_interaction_status(nonce, "", "retried").await
+    }
+
+    pub async fn mark_abandoned(&self, nonce: &str) -> Result<()> {
+        self.db.set_interaction_status(nonce, "", "abandoned").await
+    }
+}
+
+/// A short description of a reply: its first embed's title, else the first line of its text.
+fn outcome_summary(d: &Value) -> String {
+    let embed = d.pointer("/embeds/0");
+    let text = embed.and_then(|e| e["title"].as_str())
+        .or_else(|| embed.and_then(|e| e["description"].as_str()))
+        .or_else(|| d["content"].as_str())
+        .unwrap_or_default();
+    text.lines().next().unwrap_or_default().replace("**", "").chars().take(120).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_wait_for_reply() {
+        let store = CorrelationStore::new(Arc::new(Database::in_memory().await.unwrap()));
+        let reply = |flags: u64, content: &str| json!({
+            "id": "m1", "channel_id": "1", "content": content, "timestamp": "", "embeds": [], "flags": flags,
+            "author": { "id": "2", "username": "Virtual Fisher", "discriminator": "0" },
+            "interaction_metadata": { "id": "i1" }
+        });
+
+        store.expect_reply("100");
+        store.on_gateway_event("INTERACTION_SUCCESS", &json!({ "nonce": "100", "id": "i1" })).await;
+        // The placeholder isn't the reply; its edit is
+        store.on_gateway_event("MESSAGE_CREATE", &reply(LOADING_FLAG, "")).await;
+        store.on_gateway_event("MESSAGE_UPDATE", &reply(0, "You caught a Cod")).await;
+        let message = store.wait_for_reply("100", Duration::from_secs(1)).await.unwrap();
+        assert_eq!(message.content, "You caught a Cod");
+
+        store.expect_reply("101");
+        store.on_gateway_event("INTERACTION_FAILURE", &json!({ "nonce": "101", "id": "i2" })).await;
+        assert!(store.wait_for_reply("101", Duration::from_secs(1)).await.is_err());
+
+        store.expect_reply("102");
+        assert!(store.wait_for_reply("102", Duration::from_millis(10)).await.is_err());
+        assert!(store.waiters.lock().is_empty());
+    }
+}
@@ -4,14 +4,113 @@ use std::path::Path;
 use tokio::fs;
 use std::str::FromStr;
 use log::info;
-use crate::engine::game_data::Biome;
+use crate::engine::bankroll::{self, GamblingStats};
+use crate::engine::game_data::{Biome, TreasureQuality, FISH_DATA};
+use crate::engine::mechanics::TreasureObservations;
+use crate::engine::migrations;
+use crate::engine::parser::DropKind;
+use crate::engine::leaderboard::Standing;
+use serde::Serialize;
+use crate::engine::net_worth::NetWorth;
 use crate::engine::optimizer::BiomeStats;
-use std::collections::HashMap;
+use crate::engine::state::Snapshot;
+use std::collections::{BTreeMap, HashMap};
 
 pub struct Database {
     pub pool: Pool<Sqlite>,
 }
 
+/// Fishing output over a time window, see `Database::throughput`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Throughput {
+    pub fish: i64,
+    /// Sell value of the catches at `FISH_DATA` prices
+    pub gold: i64,
+    pub captchas: i64,
+    /// Active span the rates are measured over
+    pub hours: f64,
+}
+
+impl Throughput {
+    pub fn fish_per_hour(&self) -> f64 {
+        if self.hours > 0.0 { self.fish as f64 / self.hours } else { 0.0 }
+    }
+
+    pub fn gold_per_hour(&self) -> f64 {
+        if self.hours > 0.0 { self.gold as f64 / self.hours } else { 0.0 }
+    }
+}
+
+/// Catches in one biome over a time window, see `Database::catches_by_biome`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BiomeCatches {
+    pub biome: String,
+    pub fish: i64,
+    /// Sell value at `FISH_DATA` prices
+    pub gold: i64,
+}
+
+/// Cooldown hits against casts for one biome and UTC hour of day, see `Database::cooldown_heatmap`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CooldownCell {
+    pub biome: String,
+    pub hour: u32,
+    pub hits: i64,
+    /// Casts that caught something, counted from `catch_history`
+    pub catches: i64,
+}
+
+impl CooldownCell {
+    /// Share of casts that ran into the cooldown instead of catching.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.catches;
+        if total > 0 { self.hits as f64 / total as f64 } else { 0.0 }
+    }
+}
+
+/// What the Explorer has stored so far, for the Explorer tab.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ExplorerCounts {
+    pub shop_items: i64,
+    pub entities: i64,
+    pub commands: i64,
+    /// Commands the Explorer has run at least once
+    pub executed: i64,
+}
+
+/// One row of `daily_claims`, see `streak::daily_stats`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyClaim {
+    pub timestamp: i64, // Unix seconds
+    pub reward: Option<u64>,
+    pub streak: Option<u32>,
+}
+
+/// A sent interaction still waiting for its reply, see `Database::unanswered_interactions`.
+#[derive(Debug, Clone)]
+pub struct SentInteraction {
+    pub nonce: String,
+    pub command: String,
+    pub options: Vec<serde_json::Value>,
+}
+
+/// Per-command send/reply counts, e.g. "sell: 40 sent, 35 replied".
+#[derive(Debug, Clone, Serialize)]
+pub struct InteractionStats {
+    pub command: String,
+    pub sent: i64,
+    pub replied: i64,
+    pub failed: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlayerSnapshot {
+    pub timestamp: i64, // Unix seconds
+    pub level: i32,
+    pub balance: f64,
+    pub biome: String,
+}
+
 impl Database {
     pub async fn new(db_path: &str) -> Result<Self> {
         // Create file if not exists
@@ -34,208 +133,788 @@ impl Database {
         Ok(db)
     }
 
+    /// A private database that lives as long as the returned handle, for tests and one-off
+    /// runs like `headless --replay --summary`. It has a single connection, since every
+    /// connection to `sqlite::memory:` opens a database of its own.
+    pub async fn in_memory() -> Result<Self> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect("sqlite::memory:")
+            .await?;
+        let db = Self { pool };
+        db.migrate().await?;
+        Ok(db)
+    }
+
+    /// Brings the schema up to date with `migrations::MIGRATIONS`.
     async fn migrate(&self) -> Result<()> {
-        // Fish Table: Stores discovered fish
+        let version = migrations::run(&self.pool).await?;
+        info!("Database schema at version {}", version);
+        Ok(())
+    }
+
+    pub async fn check_writable(&self) -> Result<()> {
+        sqlx::query("INSERT OR REPLACE INTO health_probe (id, checked_at) VALUES (1, CURRENT_TIMESTAMP)")
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn log_catch(&self, fish_name: &str, quantity: i32, xp: f32, biome: &str) -> Result<()> {
         sqlx::query(
             r#"
-            CREATE TABLE IF NOT EXISTS fish (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT UNIQUE NOT NULL,
-                rarity TEXT,
-                base_value REAL,
-                biome TEXT,
-                sell_value REAL
-            );
+            INSERT INTO catch_history (fish_name, quantity, xp, biome)
+            VALUES (?, ?, ?, ?)
             "#,
         )
+        .bind(fish_name)
+        .bind(quantity)
+        .bind(xp)
+        .bind(biome)
         .execute(&self.pool)
         .await?;
 
-        // Ensure sell_value column exists (manual migration for existing dbs)
-        let _ = sqlx::query("ALTER TABLE fish ADD COLUMN sell_value REAL").execute(&self.pool).await;
-
-        // Ensure stats column exists for shop_items
-        let _ = sqlx::query("ALTER TABLE shop_items ADD COLUMN stats TEXT").execute(&self.pool).await;
-
-        // Catch History: Logs every fishing result
+        // Also ensure fish exists
         sqlx::query(
             r#"
-            CREATE TABLE IF NOT EXISTS catch_history (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
-                fish_name TEXT,
-                quantity INTEGER,
-                xp REAL,
-                biome TEXT,
-                money_gained REAL
-            );
+            INSERT OR IGNORE INTO fish (name, biome)
+            VALUES (?, ?)
             "#,
         )
+        .bind(fish_name)
+        .bind(biome)
         .execute(&self.pool)
         .await?;
 
-        // Player Snapshots: Logs player stats over time
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn log_snapshot(&self, level: i32, xp: f32, balance: f32, biome: &str) -> Result<()> {
         sqlx::query(
             r#"
-            CREATE TABLE IF NOT EXISTS player_snapshots (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
-                level INTEGER,
-                xp REAL,
-                balance REAL,
-                current_biome TEXT
-            );
+            INSERT INTO player_snapshots (level, xp, balance, current_biome)
+            VALUES (?, ?, ?, ?)
             "#,
         )
+        .bind(level)
+        .bind(xp)
+        .bind(balance)
+        .bind(biome)
         .execute(&self.pool)
         .await?;
+        Ok(())
+    }
 
-        // Cooldown Events: Track when we hit a cooldown
-        sqlx::query(
+    pub async fn log_net_worth(&self, worth: &NetWorth) -> Result<()> {
+        sqlx::query("INSERT INTO net_worth_snapshots (balance, inventory, assets) VALUES (?, ?, ?)")
+            .bind(worth.balance as i64)
+            .bind(worth.inventory as i64)
+            .bind(worth.assets as i64)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// `(unix seconds, net worth)` over the last `hours`, oldest first.
+    pub async fn net_worth_since(&self, hours: i64) -> Result<Vec<(i64, NetWorth)>> {
+        let rows = sqlx::query(
             r#"
-            CREATE TABLE IF NOT EXISTS cooldown_events (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
-                wait_time REAL,
-                total_cooldown REAL
-            );
+            SELECT CAST(strftime('%s', timestamp) AS INTEGER) AS ts, balance, inventory, assets
+            FROM net_worth_snapshots
+            WHERE timestamp >= datetime('now', ?)
+            ORDER BY id
             "#,
         )
-        .execute(&self.pool)
+        .bind(format!("-{} hours", hours))
+        .fetch_all(&self.pool)
         .await?;
 
-        // --- NEW TABLES FOR DATA GATHERING ---
+        Ok(rows.into_iter().map(|r| (r.get("ts"), NetWorth {
+            balance: r.get::<i64, _>("balance").max(0) as u64,
+            inventory: r.get::<i64, _>("inventory").max(0) as u64,
+            assets: r.get::<i64, _>("assets").max(0) as u64,
+        })).collect())
+    }
 
-        // Shop Items: Catalogs items found in shops
-        sqlx::query(
+    pub async fn log_leaderboard_rank(&self, standing: &Standing) -> Result<()> {
+        sqlx::query("INSERT INTO leaderboard_ranks (category, rank, value, gap_above, gap_below) VALUES (?, ?, ?, ?, ?)")
+            .bind(&standing.category)
+            .bind(standing.rank)
+            .bind(standing.value.map(|v| v as i64))
+            .bind(standing.gap_above.map(|v| v as i64))
+            .bind(standing.gap_below.map(|v| v as i64))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// `(unix seconds, standing)` over the last `hours`, oldest first.
+    pub async fn leaderboard_ranks_since(&self, hours: i64) -> Result<Vec<(i64, Standing)>> {
+        let rows = sqlx::query(
             r#"
-            CREATE TABLE IF NOT EXISTS shop_items (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT NOT NULL,
-                shop_type TEXT NOT NULL,
-                price REAL,
-                currency TEXT,
-                description TEXT,
-                stock INTEGER,
-                last_seen DATETIME DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(name, shop_type)
-            );
+            SELECT CAST(strftime('%s', timestamp) AS INTEGER) AS ts, category, rank, value, gap_above, gap_below
+            FROM leaderboard_ranks
+            WHERE timestamp >= datetime('now', ?)
+            ORDER BY id
             "#,
         )
-        .execute(&self.pool)
+        .bind(format!("-{} hours", hours))
+        .fetch_all(&self.pool)
         .await?;
 
-        // Game Entities: Generic storage for anything else (Buffs, Quests, etc found in lists)
-        sqlx::query(
+        let amount = |v: Option<i64>| v.map(|v| v.max(0) as u64);
+        Ok(rows.into_iter().map(|r| (r.get("ts"), Standing {
+            category: r.get("category"),
+            rank: r.get("rank"),
+            value: amount(r.get("value")),
+            gap_above: amount(r.get("gap_above")),
+            gap_below: amount(r.get("gap_below")),
+        })).collect())
+    }
+
+    pub async fn log_level_up(&self, level: u32) -> Result<()> {
+        sqlx::query("INSERT INTO level_ups (level) VALUES (?)")
+            .bind(level)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// `(unix seconds, level)` over the last `hours`, oldest first.
+    pub async fn level_ups_since(&self, hours: i64) -> Result<Vec<(i64, u32)>> {
+        let rows = sqlx::query(
             r#"
-            CREATE TABLE IF NOT EXISTS game_entities (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                entity_type TEXT NOT NULL, -- "Buff", "Quest", "Badge"
-                name TEXT NOT NULL,
-                details TEXT, -- JSON or raw text
-                last_seen DATETIME DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(entity_type, name)
-            );
+            SELECT CAST(strftime('%s', timestamp) AS INTEGER) AS ts, level
+            FROM level_ups
+            WHERE timestamp >= datetime('now', ?)
+            ORDER BY id
             "#,
         )
-        .execute(&self.pool)
+        .bind(format!("-{} hours", hours))
+        .fetch_all(&self.pool)
         .await?;
+        Ok(rows.into_iter().map(|r| (r.get("ts"), r.get::<i64, _>("level").max(0) as u32)).collect())
+    }
 
-         // Command Registry: Tracks commands we've found and executed
-        sqlx::query(
+    /// `(unix seconds, xp)` per catching cast over the last `hours`, oldest first. Each fish
+    /// of a catch is logged with the catch's XP, so rows logged in the same second are one cast.
+    pub async fn catch_xp_since(&self, hours: i64) -> Result<Vec<(i64, f64)>> {
+        let rows = sqlx::query(
             r#"
-            CREATE TABLE IF NOT EXISTS command_registry (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                full_command_name TEXT UNIQUE NOT NULL, -- e.g. "shop buy"
-                description TEXT,
-                params TEXT,
-                command_structure TEXT, -- JSON of full command definition
-                last_executed DATETIME
-            );
+            SELECT CAST(strftime('%s', timestamp) AS INTEGER) AS ts, MAX(xp) AS xp
+            FROM catch_history
+            WHERE timestamp >= datetime('now', ?) AND xp IS NOT NULL
+            GROUP BY timestamp
+            ORDER BY timestamp
             "#,
         )
-        .execute(&self.pool)
+        .bind(format!("-{} hours", hours))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|r| (r.get("ts"), r.get("xp"))).collect())
+    }
+
+    pub async fn latest_snapshot(&self) -> Result<Option<PlayerSnapshot>> {
+        let row = sqlx::query(
+            r#"
+            SELECT CAST(strftime('%s', timestamp) AS INTEGER) AS ts, level, balance, current_biome
+            FROM player_snapshots ORDER BY id DESC LIMIT 1
+            "#,
+        )
+        .fetch_optional(&self.pool)
         .await?;
 
-        // Migration to add command_structure if missing
-        let _ = sqlx::query("ALTER TABLE command_registry ADD COLUMN command_structure TEXT").execute(&self.pool).await;
+        Ok(row.map(|r| PlayerSnapshot {
+            timestamp: r.get("ts"),
+            level: r.get("level"),
+            balance: r.get("balance"),
+            biome: r.get("current_biome"),
+        }))
+    }
 
-        // Biome Stats: Persistent learning for Optimizer
-        sqlx::query(
+    /// Oldest snapshot within the last `hours`, used as the baseline for progression rates.
+    pub async fn earliest_snapshot_since(&self, hours: i64) -> Result<Option<PlayerSnapshot>> {
+        let row = sqlx::query(
             r#"
-            CREATE TABLE IF NOT EXISTS biome_stats (
-                biome_name TEXT PRIMARY KEY,
-                total_gold INTEGER,
-                total_xp INTEGER,
-                total_catches INTEGER
-            );
+            SELECT CAST(strftime('%s', timestamp) AS INTEGER) AS ts, level, balance, current_biome
+            FROM player_snapshots
+            WHERE timestamp >= datetime('now', ?)
+            ORDER BY id ASC LIMIT 1
             "#,
         )
-        .execute(&self.pool)
+        .bind(format!("-{} hours", hours))
+        .fetch_optional(&self.pool)
         .await?;
 
-        Ok(())
+        Ok(row.map(|r| PlayerSnapshot {
+            timestamp: r.get("ts"),
+            level: r.get("level"),
+            balance: r.get("balance"),
+            biome: r.get("current_biome"),
+        }))
     }
 
-    pub async fn log_catch(&self, fish_name: &str, quantity: i32, xp: f32, biome: &str) -> Result<()> {
-        sqlx::query(
+    /// Every snapshot from the last `hours`, oldest first.
+    pub async fn snapshots_since(&self, hours: i64) -> Result<Vec<PlayerSnapshot>> {
+        let rows = sqlx::query(
             r#"
-            INSERT INTO catch_history (fish_name, quantity, xp, biome)
-            VALUES (?, ?, ?, ?)
+            SELECT CAST(strftime('%s', timestamp) AS INTEGER) AS ts, level, balance, current_biome
+            FROM player_snapshots
+            WHERE timestamp >= datetime('now', ?)
+            ORDER BY id ASC
             "#,
         )
-        .bind(fish_name)
-        .bind(quantity)
-        .bind(xp)
-        .bind(biome)
-        .execute(&self.pool)
+        .bind(format!("-{} hours", hours))
+        .fetch_all(&self.pool)
         .await?;
 
-        // Also ensure fish exists
+        Ok(rows.into_iter().map(|r| PlayerSnapshot {
+            timestamp: r.get("ts"),
+            level: r.get("level"),
+            balance: r.get("balance"),
+            biome: r.get("current_biome"),
+        }).collect())
+    }
+
+    /// Catches from the last `hours` per biome, most fish first.
+    pub async fn catches_by_biome(&self, hours: i64) -> Result<Vec<BiomeCatches>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT COALESCE(biome, '') AS biome, fish_name, SUM(quantity) AS total
+            FROM catch_history
+            WHERE timestamp >= datetime('now', ?)
+            GROUP BY biome, fish_name
+            "#,
+        )
+        .bind(format!("-{} hours", hours))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut by_biome: HashMap<String, BiomeCatches> = HashMap::new();
+        for row in rows {
+            let biome: String = row.get("biome");
+            let name: String = row.get("fish_name");
+            let total: i64 = row.get("total");
+            let entry = by_biome.entry(biome.clone()).or_insert_with(|| BiomeCatches { biome, ..Default::default() });
+            entry.fish += total;
+            entry.gold += total * FISH_DATA.get(name.as_str()).map(|f| f.price as i64).unwrap_or(0);
+        }
+        let mut biomes: Vec<BiomeCatches> = by_biome.into_values().collect();
+        biomes.sort_by(|a, b| b.fish.cmp(&a.fish).then_with(|| a.biome.cmp(&b.biome)));
+        Ok(biomes)
+    }
+
+    /// `(unix seconds, wait_time)` for every cooldown hit in the last `hours`, oldest first.
+    pub async fn cooldowns_since(&self, hours: i64) -> Result<Vec<(i64, f64)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT CAST(strftime('%s', timestamp) AS INTEGER) AS ts, wait_time
+            FROM cooldown_events
+            WHERE timestamp >= datetime('now', ?)
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(format!("-{} hours", hours))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|r| (r.get("ts"), r.get("wait_time"))).collect())
+    }
+
+    pub async fn explorer_counts(&self) -> Result<ExplorerCounts> {
+        let row = sqlx::query(
+            r#"
+            SELECT (SELECT COUNT(*) FROM shop_items) AS shop_items,
+                   (SELECT COUNT(*) FROM game_entities) AS entities,
+                   (SELECT COUNT(*) FROM command_registry) AS commands,
+                   (SELECT COUNT(*) FROM command_registry WHERE last_executed IS NOT NULL) AS executed
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(ExplorerCounts {
+            shop_items: row.get("shop_items"),
+            entities: row.get("entities"),
+            commands: row.get("commands"),
+            executed: row.get("executed"),
+        })
+    }
+
+    /// Every distinct fish name in `catch_history`, for the fishdex.
+    pub async fn caught_species(&self) -> Result<Vec<String>> {
+        let rows = sqlx::query("SELECT DISTINCT fish_name FROM catch_history WHERE fish_name IS NOT NULL")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(|r| r.get("fish_name")).collect())
+    }
+
+    /// Cooldown hits and catching casts over the last `days`, per biome and UTC hour.
+    /// Catches logged in the same second are one cast.
+    pub async fn cooldown_heatmap(&self, days: i64) -> Result<Vec<CooldownCell>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT biome, hour, SUM(hits) AS hits, SUM(catches) AS catches FROM (
+                SELECT COALESCE(biome, 'Unknown') AS biome, CAST(strftime('%H', timestamp) AS INTEGER) AS hour,
+                       COUNT(*) AS hits, 0 AS catches
+                FROM cooldown_events WHERE timestamp >= datetime('now', ?1)
+                GROUP BY 1, 2
+                UNION ALL
+                SELECT COALESCE(biome, 'Unknown'), CAST(strftime('%H', timestamp) AS INTEGER),
+                       0, COUNT(DISTINCT timestamp)
+                FROM catch_history WHERE timestamp >= datetime('now', ?1)
+                GROUP BY 1, 2
+            )
+            GROUP BY biome, hour
+            ORDER BY biome, hour
+            "#,
+        )
+        .bind(format!("-{} days", days))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| CooldownCell {
+            biome: r.get("biome"),
+            hour: r.get::<i64, _>("hour") as u32,
+            hits: r.get("hits"),
+            catches: r.get("catches"),
+        }).collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn log_cooldown(&self, wait_time: f32, total_cooldown: f32, biome: &str) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT OR IGNORE INTO fish (name, biome)
-            VALUES (?, ?)
+            INSERT INTO cooldown_events (wait_time, total_cooldown, biome)
+            VALUES (?, ?, ?)
             "#,
         )
-        .bind(fish_name)
+        .bind(wait_time)
+        .bind(total_cooldown)
         .bind(biome)
         .execute(&self.pool)
         .await?;
+        Ok(())
+    }
 
+    pub async fn log_captcha(&self) -> Result<()> {
+        sqlx::query("INSERT INTO captcha_events DEFAULT VALUES")
+            .execute(&self.pool)
+            .await?;
         Ok(())
     }
 
-    pub async fn log_snapshot(&self, level: i32, xp: f32, balance: f32, biome: &str) -> Result<()> {
-        sqlx::query(
+    /// Catches, their sell value and captchas over the last `hours`. Rates are measured from the
+    /// first catch in the window, so a bot started an hour ago isn't averaged over a whole day.
+    pub async fn throughput(&self, hours: i64) -> Result<Throughput> {
+        let window = format!("-{} hours", hours);
+        let rows = sqlx::query(
             r#"
-            INSERT INTO player_snapshots (level, xp, balance, current_biome)
-            VALUES (?, ?, ?, ?)
+            SELECT fish_name, SUM(quantity) AS total,
+                   MIN(CAST(strftime('%s', timestamp) AS INTEGER)) AS first_ts
+            FROM catch_history
+            WHERE timestamp >= datetime('now', ?)
+            GROUP BY fish_name
             "#,
         )
-        .bind(level)
-        .bind(xp)
-        .bind(balance)
+        .bind(&window)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut throughput = Throughput::default();
+        let mut first_ts: Option<i64> = None;
+        for row in rows {
+            let name: String = row.get("fish_name");
+            let total: i64 = row.get("total");
+            let ts: i64 = row.get("first_ts");
+            throughput.fish += total;
+            throughput.gold += total * FISH_DATA.get(name.as_str()).map(|f| f.price as i64).unwrap_or(0);
+            first_ts = Some(first_ts.map_or(ts, |t| t.min(ts)));
+        }
+
+        throughput.captchas = sqlx::query_scalar("SELECT COUNT(*) FROM captcha_events WHERE timestamp >= datetime('now', ?)")
+            .bind(&window)
+            .fetch_one(&self.pool)
+            .await?;
+
+        if let Some(first) = first_ts {
+            let elapsed = (chrono::Utc::now().timestamp() - first).max(0) as f64 / 3600.0;
+            // At least a minute, so one fresh catch doesn't read as thousands per hour
+            throughput.hours = elapsed.clamp(1.0 / 60.0, hours as f64);
+        }
+        Ok(throughput)
+    }
+
+    pub async fn log_clan_event(&self, event_type: &str, name: &str, damage: Option<u64>, details: &str) -> Result<()> {
+        sqlx::query("INSERT INTO clan_events (event_type, name, damage, details) VALUES (?, ?, ?, ?)")
+            .bind(event_type)
+            .bind(name)
+            .bind(damage.map(|d| d as i64))
+            .bind(details)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn log_quest_claim(&self, objective: &str, reward: Option<&str>) -> Result<()> {
+        sqlx::query("INSERT INTO quest_claims (objective, reward) VALUES (?, ?)")
+            .bind(objective)
+            .bind(reward)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn log_clan_deposit(&self, amount: u64, balance_before: u64) -> Result<()> {
+        sqlx::query("INSERT INTO clan_deposits (amount, balance_before) VALUES (?, ?)")
+            .bind(amount as i64)
+            .bind(balance_before as i64)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Gold put into the clan vault since midnight UTC.
+    pub async fn clan_deposits_today(&self) -> Result<u64> {
+        let total: i64 = sqlx::query_scalar("SELECT COALESCE(SUM(amount), 0) FROM clan_deposits WHERE timestamp >= date('now')")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(total.max(0) as u64)
+    }
+
+    pub async fn log_daily_claim(&self) -> Result<()> {
+        sqlx::query("INSERT INTO daily_claims DEFAULT VALUES")
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Fills in the reward of a claim sent in the last 10 minutes, or logs a new claim
+    /// (e.g. `/daily` typed by hand) when there's none waiting for its reply.
+    pub async fn record_daily_reward(&self, reward: Option<u64>, streak: Option<u32>) -> Result<()> {
+        let updated = sqlx::query(
+            r#"
+            UPDATE daily_claims SET reward = ?, streak = ?
+            WHERE id = (SELECT id FROM daily_claims
+                        WHERE reward IS NULL AND streak IS NULL AND timestamp >= datetime('now', '-10 minutes')
+                        ORDER BY id DESC LIMIT 1)
+            "#,
+        )
+        .bind(reward.map(|r| r as i64))
+        .bind(streak)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+        if updated == 0 {
+            sqlx::query("INSERT INTO daily_claims (reward, streak) VALUES (?, ?)")
+                .bind(reward.map(|r| r as i64))
+                .bind(streak)
+                .execute(&self.pool)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn daily_claim_history(&self) -> Result<Vec<DailyClaim>> {
+        let rows = sqlx::query("SELECT CAST(strftime('%s', timestamp) AS INTEGER) AS ts, reward, streak FROM daily_claims ORDER BY id")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(|r| DailyClaim {
+            timestamp: r.get("ts"),
+            reward: r.get::<Option<i64>, _>("reward").map(|v| v.max(0) as u64),
+            streak: r.get::<Option<i64>, _>("streak").map(|v| v.max(0) as u32),
+        }).collect())
+    }
+
+    pub async fn daily_claimed_since(&self, since: chrono::DateTime<chrono::Utc>) -> Result<bool> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM daily_claims WHERE timestamp >= ?")
+            .bind(since.format("%Y-%m-%d %H:%M:%S").to_string())
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count > 0)
+    }
+
+    pub async fn record_owned_boat(&self, name: &str) -> Result<()> {
+        sqlx::query("INSERT OR IGNORE INTO owned_boats (name) VALUES (?)")
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Upserts a `/fishdex` entry. A species caught once stays caught.
+    pub async fn record_fishdex_entry(&self, name: &str, biome: Option<&str>, caught: bool) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO fishdex (name, biome, caught) VALUES (?, ?, ?)
+             ON CONFLICT(name) DO UPDATE SET biome = COALESCE(excluded.biome, biome),
+                 caught = MAX(caught, excluded.caught), updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(name)
         .bind(biome)
+        .bind(caught)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
-    pub async fn log_cooldown(&self, wait_time: f32, total_cooldown: f32) -> Result<()> {
+    /// Species a `/fishdex` page showed as caught.
+    pub async fn fishdex_caught(&self) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar("SELECT name FROM fishdex WHERE caught = 1 ORDER BY name")
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    pub async fn owned_boats(&self) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar("SELECT name FROM owned_boats ORDER BY first_seen, name")
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    /// Forgets the fleet, which a prestige resets to the Rowboat.
+    pub async fn clear_owned_boats(&self) -> Result<()> {
+        sqlx::query("DELETE FROM owned_boats").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    pub async fn save_snapshot(&self, snapshot: &Snapshot) -> Result<()> {
+        sqlx::query("INSERT OR REPLACE INTO bot_snapshot (id, snapshot, saved_at) VALUES (1, ?, CURRENT_TIMESTAMP)")
+            .bind(serde_json::to_string(snapshot)?)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// The last snapshot saved, `None` before the first save.
+    pub async fn load_snapshot(&self) -> Result<Option<Snapshot>> {
+        let json: Option<String> = sqlx::query_scalar("SELECT snapshot FROM bot_snapshot WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(json.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    pub async fn log_event_earning(&self, event: &str, amount: u64) -> Result<()> {
+        sqlx::query("INSERT INTO event_earnings (event, amount) VALUES (?, ?)")
+            .bind(event)
+            .bind(amount as i64)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Event currency collected so far, by event.
+    pub async fn event_earnings(&self) -> Result<BTreeMap<String, u64>> {
+        let rows = sqlx::query("SELECT event, SUM(amount) AS total FROM event_earnings GROUP BY event")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(|r| (r.get("event"), r.get::<i64, _>("total").max(0) as u64)).collect())
+    }
+
+    pub async fn log_coinflip(&self, won: bool, amount: u64) -> Result<()> {
+        sqlx::query("INSERT INTO coinflips (won, amount) VALUES (?, ?)")
+            .bind(won)
+            .bind(amount as i64)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// All-time coinflip results and today's (UTC) net; `session_net` starts at 0.
+    pub async fn gambling_stats(&self) -> Result<GamblingStats> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COALESCE(SUM(won), 0) AS wins,
+                COALESCE(SUM(1 - won), 0) AS losses,
+                COALESCE(SUM(CASE WHEN won THEN amount ELSE 0 END), 0) AS won_total,
+                COALESCE(SUM(CASE WHEN won THEN 0 ELSE amount END), 0) AS lost_total,
+                COALESCE(SUM(CASE WHEN date(timestamp) = date('now') THEN (CASE WHEN won THEN amount ELSE -amount END) ELSE 0 END), 0) AS today_net
+            FROM coinflips
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(GamblingStats {
+            wins: row.get::<i64, _>("wins") as u64,
+            losses: row.get::<i64, _>("losses") as u64,
+            won: row.get::<i64, _>("won_total") as u64,
+            lost: row.get::<i64, _>("lost_total") as u64,
+            today_net: row.get("today_net"),
+            today: bankroll::utc_today(),
+            session_net: 0,
+        })
+    }
+
+    pub async fn log_vote_window(&self, available_at: chrono::DateTime<chrono::Utc>, source: &str) -> Result<()> {
+        sqlx::query("INSERT INTO vote_windows (available_at, source) VALUES (?, ?)")
+            .bind(available_at.timestamp())
+            .bind(source)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// When voting opens next, as last recorded.
+    pub async fn latest_vote_window(&self) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+        let available_at: Option<i64> = sqlx::query_scalar("SELECT available_at FROM vote_windows ORDER BY id DESC LIMIT 1")
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(available_at.and_then(|t| chrono::DateTime::from_timestamp(t, 0)))
+    }
+
+    /// Logs one opened crate or chest (`source`) and what it held, as a `treasure_opens` row
+    /// with its `treasure_history` drops.
+    pub async fn log_treasure_open(&self, source: &str, drops: &[(&str, u64)]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let open_id = sqlx::query("INSERT INTO treasure_opens (source) VALUES (?)")
+            .bind(source)
+            .execute(&mut *tx)
+            .await?
+            .last_insert_rowid();
+        for (item, amount) in drops {
+            sqlx::query("INSERT INTO treasure_history (source, item, amount, open_id) VALUES (?, ?, ?, ?)")
+                .bind(source)
+                .bind(item)
+                .bind(*amount as i64)
+                .bind(open_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Chest contents per quality, from the "<Quality> Treasure" rows of `treasure_opens` and
+    /// `treasure_history`.
+    pub async fn treasure_observations(&self) -> Result<HashMap<TreasureQuality, TreasureObservations>> {
+        let quality = |source: &str| source.strip_suffix(" Treasure").and_then(TreasureQuality::from_text);
+        let mut observations: HashMap<TreasureQuality, TreasureObservations> = HashMap::new();
+        let opens = sqlx::query("SELECT source, COUNT(*) AS opens FROM treasure_opens GROUP BY source")
+            .fetch_all(&self.pool)
+            .await?;
+        for row in opens {
+            if let Some(quality) = quality(row.get("source")) {
+                observations.entry(quality).or_default().opens += row.get::<i64, _>("opens").max(0) as u64;
+            }
+        }
+        let totals = sqlx::query("SELECT source, item, SUM(amount) AS total FROM treasure_history GROUP BY source, item")
+            .fetch_all(&self.pool)
+            .await?;
+        for row in totals {
+            if let Some(quality) = quality(row.get("source")) {
+                let total = row.get::<i64, _>("total").max(0) as u64;
+                *observations.entry(quality).or_default().totals.entry(DropKind::of(row.get("item"))).or_default() += total;
+            }
+        }
+        Ok(observations)
+    }
+
+    pub async fn log_interaction_sent(&self, nonce: &str, command: &str, options: &str, retry_of: Option<&str>) -> Result<()> {
+        sqlx::query("INSERT OR IGNORE INTO interactions (nonce, command, options, retry_of) VALUES (?, ?, ?, ?)")
+            .bind(nonce)
+            .bind(command)
+            .bind(options)
+            .bind(retry_of)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// An empty `interaction_id` keeps the stored one. A late "acknowledged" never
+    /// overwrites a reply, a retry or a give-up.
+    pub async fn set_interaction_status(&self, nonce: &str, interaction_id: &str, status: &str) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO cooldown_events (wait_time, total_cooldown)
-            VALUES (?, ?)
+            UPDATE interactions SET
+                interaction_id = COALESCE(NULLIF(?, ''), interaction_id),
+                status = CASE WHEN ? = 'acknowledged' AND status IN ('replied', 'retried', 'abandoned') THEN status ELSE ? END
+            WHERE nonce = ?
             "#,
         )
-        .bind(wait_time)
-        .bind(total_cooldown)
+        .bind(interaction_id)
+        .bind(status)
+        .bind(status)
+        .bind(nonce)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
+    pub async fn record_interaction_reply(&self, interaction_id: &str, message_id: &str, outcome: &str) -> Result<()> {
+        sqlx::query("UPDATE interactions SET reply_message_id = ?, outcome = ?, status = 'replied', replied_at = CURRENT_TIMESTAMP WHERE interaction_id = ?")
+            .bind(message_id)
+            .bind(outcome)
+            .bind(interaction_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Sends within the last `window_secs` that never got a reply: failed ones right away, the
+    /// rest once they're `timeout_secs` old. `retries` picks retries instead of first sends.
+    pub async fn unanswered_interactions(&self, timeout_secs: i64, window_secs: i64, retries: bool) -> Result<Vec<SentInteraction>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT nonce, command, options FROM interactions
+            WHERE (retry_of IS NOT NULL) = ?
+              AND (status = 'failed' OR (status IN ('sent', 'acknowledged') AND sent_at <= datetime('now', ?)))
+              AND sent_at >= datetime('now', ?)
+            ORDER BY id
+            "#,
+        )
+        .bind(retries)
+        .bind(format!("-{} seconds", timeout_secs))
+        .bind(format!("-{} seconds", window_secs))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| SentInteraction {
+            nonce: r.get("nonce"),
+            command: r.get("command"),
+            options: r.get::<Option<String>, _>("options").and_then(|o| serde_json::from_str(&o).ok()).unwrap_or_default(),
+        }).collect())
+    }
+
+    pub async fn interaction_stats(&self, hours: u32) -> Result<Vec<InteractionStats>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT command, COUNT(*) AS sent,
+                   SUM(CASE WHEN status = 'replied' THEN 1 ELSE 0 END) AS replied,
+                   SUM(CASE WHEN status IN ('failed', 'abandoned') THEN 1 ELSE 0 END) AS failed
+            FROM interactions WHERE sent_at >= datetime('now', ?)
+            GROUP BY command ORDER BY command
+            "#,
+        )
+        .bind(format!("-{} hours", hours))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| InteractionStats {
+            command: r.get("command"),
+            sent: r.get("sent"),
+            replied: r.get("replied"),
+            failed: r.get("failed"),
+        }).collect())
+    }
+
+    /// Sell values scraped into the `fish` table, by fish name.
+    pub async fn fish_sell_values(&self) -> Result<HashMap<String, u64>> {
+        let rows = sqlx::query("SELECT name, sell_value FROM fish WHERE sell_value IS NOT NULL")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(|r| (r.get("name"), r.get::<f64, _>("sell_value").max(0.0) as u64)).collect())
+    }
+
+    /// Names and prices of the scraped shop items sold for money.
+    pub async fn money_shop_prices(&self) -> Result<Vec<(String, u64)>> {
+        let rows = sqlx::query("SELECT name, price FROM shop_items WHERE currency = 'Money' AND price > 0")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(|r| (r.get("name"), r.get::<f64, _>("price") as u64)).collect())
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn upsert_shop_item(&self, name: &str, shop_type: &str, price: f32, currency: &str, description: &str, stock: Option<i32>, stats: Option<&str>) -> Result<()> {
         sqlx::query(
             r#"
@@ -340,15 +1019,7 @@ impl Database {
         let mut map = HashMap::new();
         for row in rows {
             let name: String = row.get("biome_name");
-            let biome = match name.as_str() {
-                "River" => Biome::River,
-                "Volcanic" => Biome::Volcanic,
-                "Ocean" => Biome::Ocean,
-                "Sky" => Biome::Sky,
-                "Space" => Biome::Space,
-                "Alien" => Biome::Alien,
-                _ => continue,
-            };
+            let Some(biome) = Biome::from_display_name(&name) else { continue };
 
             let total_catches: i64 = row.get("total_catches");
             let total_gold: i64 = row.get("total_gold");
@@ -371,3 +1042,97 @@ impl Database {
         Ok(map)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_throughput() {
+        let db = Database::in_memory().await.unwrap();
+
+        db.log_catch("Cod", 2, 10.0, "River").await.unwrap();
+        db.log_catch("Raw Fish", 3, 3.0, "River").await.unwrap();
+        db.log_catch("Mystery Fish", 1, 1.0, "River").await.unwrap();
+        db.log_captcha().await.unwrap();
+
+        let throughput = db.throughput(24).await.unwrap();
+        assert_eq!(throughput.fish, 6);
+        // Unknown fish count as caught but add no gold
+        assert_eq!(throughput.gold, 2 * 10 + 3);
+        assert_eq!(throughput.captchas, 1);
+        // Fresh catches are rated over the one-minute floor
+        assert!((throughput.fish_per_hour() - 360.0).abs() < 1.0);
+
+        db.log_catch("Cod", 1, 5.0, "Ocean").await.unwrap();
+        let biomes = db.catches_by_biome(24).await.unwrap();
+        assert_eq!(biomes, vec![
+            BiomeCatches { biome: "River".to_string(), fish: 6, gold: 23 },
+            BiomeCatches { biome: "Ocean".to_string(), fish: 1, gold: 10 },
+        ]);
+
+        db.log_cooldown(2.5, 3.0, "Ocean").await.unwrap();
+        let worth = NetWorth { balance: 1_000, inventory: 250, assets: 500 };
+        db.log_net_worth(&worth).await.unwrap();
+        let history = db.net_worth_since(1).await.unwrap();
+        assert_eq!(history.iter().map(|(_, w)| *w).collect::<Vec<_>>(), vec![worth]);
+
+        let mut species = db.caught_species().await.unwrap();
+        species.sort();
+        assert_eq!(species, vec!["Cod", "Mystery Fish", "Raw Fish"]);
+
+        let heatmap = db.cooldown_heatmap(7).await.unwrap();
+        let ocean = heatmap.iter().find(|c| c.biome == "Ocean").unwrap();
+        assert_eq!((ocean.hits, ocean.catches, ocean.hit_rate()), (1, 1, 0.5));
+        assert!(heatmap.iter().filter(|c| c.biome == "River").all(|c| c.hits == 0 && c.catches >= 1));
+
+        db.log_event_earning("christmas", 3).await.unwrap();
+        db.log_event_earning("christmas", 2).await.unwrap();
+        assert_eq!(db.event_earnings().await.unwrap(), BTreeMap::from([("christmas".to_string(), 5)]));
+
+        db.log_coinflip(true, 500).await.unwrap();
+        db.log_coinflip(false, 2_000).await.unwrap();
+        let gambling = db.gambling_stats().await.unwrap();
+        assert_eq!((gambling.wins, gambling.losses, gambling.won, gambling.lost, gambling.today_net), (1, 1, 500, 2_000, -1_500));
+
+        // Two chests in the same second are still two opens
+        db.log_treasure_open("Rare Treasure", &[("Money", 500), ("Gold Fish", 1)]).await.unwrap();
+        db.log_treasure_open("Rare Treasure", &[("Money", 700)]).await.unwrap();
+        db.log_treasure_open("Super Crate", &[("Money", 9_000)]).await.unwrap();
+        let treasure = db.treasure_observations().await.unwrap();
+        let rare = &treasure[&TreasureQuality::Rare];
+        assert_eq!((treasure.len(), rare.opens, rare.totals[&DropKind::Money]), (1, 2, 1_200));
+
+        db.record_owned_boat("Rowboat").await.unwrap();
+        db.record_owned_boat("Fishing Boat").await.unwrap();
+        assert_eq!(db.owned_boats().await.unwrap().len(), 2);
+        db.clear_owned_boats().await.unwrap();
+        assert!(db.owned_boats().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_interaction_correlation() {
+        let db = Database::in_memory().await.unwrap();
+
+        db.log_interaction_sent("n1", "sell", "[]", None).await.unwrap();
+        db.log_interaction_sent("n2", "sell", "[]", None).await.unwrap();
+        db.set_interaction_status("n1", "i1", "acknowledged").await.unwrap();
+        db.record_interaction_reply("i1", "m1", "Sold").await.unwrap();
+        // A late INTERACTION_SUCCESS doesn't undo the reply
+        db.set_interaction_status("n1", "i1", "acknowledged").await.unwrap();
+
+        let stats = db.interaction_stats(1).await.unwrap();
+        assert_eq!((stats[0].sent, stats[0].replied), (2, 1));
+        let unanswered: Vec<String> = db.unanswered_interactions(0, 60, false).await.unwrap().into_iter().map(|i| i.nonce).collect();
+        assert_eq!(unanswered, vec!["n2"]);
+
+        // A retry that Discord reports as failed is due at once, without waiting for the timeout
+        db.set_interaction_status("n2", "", "retried").await.unwrap();
+        db.log_interaction_sent("n3", "sell", "[]", Some("n2")).await.unwrap();
+        assert!(db.unanswered_interactions(600, 900, true).await.unwrap().is_empty());
+        db.set_interaction_status("n3", "i3", "failed").await.unwrap();
+        let retries: Vec<String> = db.unanswered_interactions(600, 900, true).await.unwrap().into_iter().map(|i| i.nonce).collect();
+        assert_eq!(retries, vec!["n3"]);
+        assert!(db.unanswered_interactions(0, 60, false).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,730 @@
+use crate::config::{Config, TradePolicy};
+use crate::discord::recorder::GatewayRecorder;
+use crate::discord::types::{GatewayPayload, Message, Modal};
+use crate::engine::captcha::Captcha;
+use crate::engine::capture::{EmbedCapture, CAPTURE_DIR};
+use crate::engine::channels;
+use crate::engine::cooldown::CooldownManager;
+use crate::engine::correlation::CorrelationStore;
+use crate::engine::database::Database;
+use crate::engine::health::HealthMonitor;
+use crate::engine::human_activity;
+use crate::engine::leaderboard;
+use crate::engine::parser::{self, EventAnnouncement};
+use crate::engine::profile::{CrateOpen, Profile, QuestClaim};
+use crate::engine::seasonal::{self, SeasonalSighting};
+use crate::engine::valuation::InventoryValuation;
+use crate::notifications::event::{EventKind, Notification, Severity};
+use crate::telemetry;
+use crate::tui::app::App;
+use crate::util::money;
+use log::{debug, error, info, warn};
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{mpsc, Mutex};
+use tracing::Instrument;
+
+/// How many recent message ids are remembered to recognize edits of the same message.
+const SEEN_MESSAGES: usize = 256;
+
+/// What was processed for a message id, so a MESSAGE_UPDATE of it isn't counted again.
+struct SeenMessage {
+    hash: u64,
+    /// A catch, cooldown, drop or reward from this message was already logged
+    counted: bool,
+}
+
+/// Turns gateway payloads into parsed game events: catches, profile snapshots and cooldown hits
+/// are logged to the DB and fed back to the cooldown manager and health monitor.
+pub struct EventProcessor {
+    app_state: Arc<Mutex<App>>,
+    db: Arc<Database>,
+    cooldown_manager: Arc<Mutex<CooldownManager>>,
+    health: Arc<HealthMonitor>,
+    capture: EmbedCapture,
+    vf_id: String,
+    last_level: Option<i32>,
+    /// Scraped `fish` sell values, reloaded with each profile embed
+    fish_prices: HashMap<String, u64>,
+    seen: HashMap<String, SeenMessage>,
+    seen_order: VecDeque<String>,
+    /// Id of the message being processed
+    current_message: Option<String>,
+    correlation: Option<Arc<CorrelationStore>>,
+    /// Captured by the gateway recorder: `[system] channel_id` and the `[channel_rotation]` channels
+    capture_channels: Vec<String>,
+    capture_max_bytes: u64,
+    /// Open while `[system] capture_gateway` is on
+    recorder: Option<GatewayRecorder>,
+    captcha: Option<Arc<Mutex<Captcha>>>,
+}
+
+impl EventProcessor {
+    pub fn new(config: &Config, app_state: Arc<Mutex<App>>, db: Arc<Database>, cooldown_manager: Arc<Mutex<CooldownManager>>, health: Arc<HealthMonitor>) -> Self {
+        Self {
+            app_state,
+            db,
+            cooldown_manager,
+            health,
+            capture: EmbedCapture::new(config),
+            vf_id: config.system.application_id.to_string(),
+            last_level: None,
+            fish_prices: HashMap::new(),
+            seen: HashMap::new(),
+            seen_order: VecDeque::new(),
+            current_message: None,
+            correlation: None,
+            capture_channels: channels::fishing_channels(&config.channel_rotation, config.system.channel_id)
+                .iter().map(u64::to_string).collect(),
+            capture_max_bytes: config.system.capture_max_mb * 1024 * 1024,
+            recorder: None,
+            captcha: None,
+        }
+    }
+
+    /// Matches INTERACTION_* events and replies to the commands the client recorded.
+    pub fn with_correlation(mut self, store: Arc<CorrelationStore>) -> Self {
+        self.correlation = Some(store);
+        self
+    }
+
+    /// Flags captcha prompts on the bot's `Captcha`, which `Bot::run` then solves.
+    pub fn with_captcha(mut self, captcha: Arc<Mutex<Captcha>>) -> Self {
+        self.captcha = Some(captcha);
+        self
+    }
+
+    /// Processes payloads until the gateway (or replay) side of the channel closes.
+    pub async fn run(mut self, mut receiver: mpsc::Receiver<GatewayPayload>) {
+        while let Some(payload) = receiver.recv().await {
+            self.handle(payload).await;
+        }
+    }
+
+    pub async fn handle(&mut self, payload: GatewayPayload) {
+        let capturing = self.app_state.lock().await.config.system.capture_gateway;
+        self.capture_payload(capturing, &payload);
+        let Some(t) = payload.t else { return };
+        if let (Some(store), Some(d)) = (&self.correlation, &payload.d) {
+            store.on_gateway_event(&t, d).await;
+        }
+        let app_state = self.app_state.clone();
+        let mut app = app_state.lock().await;
+        app.add_log(format!("Event: {}", t));
+        if t == "READY" {
+            if let Some(id) = payload.d.as_ref().and_then(|d| d.pointer("/user/id")).and_then(|v| v.as_str()) {
+                app.user_id = Some(id.to_string());
+            }
+            if let Some(name) = payload.d.as_ref().and_then(|d| d.pointer("/user/username")).and_then(|v| v.as_str()) {
+                app.username = Some(name.to_string());
+            }
+            return;
+        }
+        if t == "INTERACTION_MODAL_CREATE" {
+            match payload.d.map(serde_json::from_value::<Modal>) {
+                Some(Ok(modal)) => {
+                    info!("Modal opened: {}", modal.title);
+                    app.last_modal = Some(modal);
+                },
+                Some(Err(e)) => warn!("Unreadable modal: {}", e),
+                None => {},
+            }
+            return;
+        }
+        if t != "MESSAGE_CREATE" && t != "MESSAGE_UPDATE" {
+            return;
+        }
+        let Some(d) = payload.d else { return };
+        if !self.is_new_state(&d) {
+            return;
+        }
+
+        if t == "MESSAGE_CREATE" {
+            Self::check_human_activity(&mut app, &d);
+        }
+
+        // Try to parse full message object
+        if let Ok(msg) = serde_json::from_value::<Message>(d.clone()) {
+            if t == "MESSAGE_CREATE" {
+                Self::check_trade_prompt(&mut app, &msg);
+                self.check_bait(&mut app, &msg);
+            }
+            // Never wait on the captcha lock with the App lock held
+            let user_id = app.user_id.clone();
+            drop(app);
+            let captcha_log = self.check_captcha(user_id.as_deref(), &msg, &d).await;
+            app = app_state.lock().await;
+            if let Some(line) = captcha_log {
+                app.add_log(line.to_string());
+            }
+            app.last_message_object = Some(msg);
+        }
+
+        let is_vf = d.get("author")
+            .and_then(|a| a.get("id"))
+            .and_then(|id| id.as_str())
+            .is_some_and(|s| s == self.vf_id);
+
+        let Some(content) = d.get("content").and_then(|v| v.as_str()) else { return };
+        app.last_message = content.to_string();
+
+        let Some(first_embed) = d.get("embeds").and_then(|v| v.as_array()).and_then(|e| e.first()) else { return };
+        let description = first_embed.get("description").and_then(|v| v.as_str());
+
+        if let Some(title) = first_embed.get("title").and_then(|v| v.as_str()) {
+            info!(title; "Embed received");
+            if let (Some(desc), true) = (description, is_vf) {
+                self.handle_titled_embed(&mut app, title, desc, &d).await;
+            }
+        } else if let (Some(desc), true) = (description, is_vf) {
+            // Some embeds have no title, only a description (e.g. cooldown warnings)
+            self.handle_untitled_embed(desc, &d).await;
+        }
+    }
+
+    /// Appends `payload` to the gateway capture while it's on, closing the file when it's turned off.
+    fn capture_payload(&mut self, enabled: bool, payload: &GatewayPayload) {
+        if !enabled {
+            if self.recorder.take().is_some() {
+                info!("Gateway capture stopped");
+            }
+            return;
+        }
+        let recorder = self.recorder.get_or_insert_with(|| GatewayRecorder::new(CAPTURE_DIR, self.capture_channels.clone(), self.capture_max_bytes));
+        if let Err(e) = recorder.record(payload) {
+            warn!("Gateway capture failed: {}", e);
+        }
+    }
+
+    /// False when `d` is an edit that didn't change the content or embeds of a message
+    /// already processed. Gateway edits (buttons disabled, embeds resolved) would otherwise
+    /// count the same catch twice.
+    fn is_new_state(&mut self, d: &Value) -> bool {
+        let Some(id) = d["id"].as_str() else {
+            self.current_message = None;
+            return true;
+        };
+        let mut hasher = DefaultHasher::new();
+        d["content"].as_str().unwrap_or_default().hash(&mut hasher);
+        d["embeds"].to_string().hash(&mut hasher);
+        let hash = hasher.finish();
+
+        self.current_message = Some(id.to_string());
+        if let Some(seen) = self.seen.get_mut(id) {
+            if seen.hash == hash {
+                return false;
+            }
+            seen.hash = hash;
+            return true;
+        }
+        if self.seen_order.len() >= SEEN_MESSAGES {
+            if let Some(oldest) = self.seen_order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        self.seen.insert(id.to_string(), SeenMessage { hash, counted: false });
+        self.seen_order.push_back(id.to_string());
+        true
+    }
+
+    /// Whether the current message's countable event should be logged: true the first time only.
+    fn first_count(&mut self) -> bool {
+        let Some(seen) = self.current_message.as_ref().and_then(|id| self.seen.get_mut(id)) else { return true };
+        !std::mem::replace(&mut seen.counted, true)
+    }
+
+    /// Pauses casting when a person posts in a fishing channel (`[human_activity]`), including
+    /// the `[channel_rotation]` channels the bot isn't in right now.
+    fn check_human_activity(app: &mut App, d: &Value) {
+        let channels = channels::fishing_channels(&app.config.channel_rotation, app.config.system.channel_id);
+        let Some(channel_id) = d.get("channel_id").and_then(Value::as_str)
+            .filter(|id| channels.iter().any(|c| c.to_string() == *id)) else { return };
+        let Some(activity) = human_activity::detect(d, channel_id, app.user_id.as_deref(), &app.config.human_activity) else { return };
+        let pause = activity.pause(&app.config.human_activity);
+        let until = Instant::now() + pause;
+        if app.human_pause.as_ref().is_some_and(|(at, _)| *at >= until) {
+            return;
+        }
+        let who = if activity.staff { format!("Staff member {}", activity.author) } else { activity.author.clone() };
+        warn!("{} posted in the fishing channel; pausing casts for {} minutes", who, pause.as_secs() / 60);
+        app.add_log(format!("{} posted, pausing for {} minutes", who, pause.as_secs() / 60));
+        if activity.staff {
+            app.notifier.send(Notification::new(EventKind::HumanActivity, Severity::Warning,
+                "Staff in the fishing channel",
+                format!("{} posted in the channel. Casting is paused for {} minutes.", who, pause.as_secs() / 60)));
+        }
+        app.human_pause = Some((until, who));
+    }
+
+    /// Sets `Captcha::detected` (and the code image) for a captcha addressed to this account,
+    /// and resets it once Virtual Fisher accepts an answer. Returns the line for the TUI log.
+    async fn check_captcha(&self, user_id: Option<&str>, msg: &Message, d: &Value) -> Option<&'static str> {
+        let captcha = self.captcha.as_ref()?;
+        if msg.author.id != self.vf_id {
+            return None;
+        }
+        // A reply to someone else's command in a shared channel
+        let invoker = d.pointer("/interaction_metadata/user/id").or_else(|| d.pointer("/interaction/user/id")).and_then(Value::as_str);
+        if invoker.is_some_and(|id| user_id.is_some_and(|own| own != id)) {
+            return None;
+        }
+        match parser::parse_captcha_result(msg) {
+            Some(true) => {
+                let mut captcha = captcha.lock().await;
+                if !captcha.detected {
+                    return None;
+                }
+                captcha.reset();
+                info!("Captcha verified");
+                Some("Captcha verified")
+            }
+            Some(false) => {
+                let mut captcha = captcha.lock().await;
+                captcha.reject();
+                warn!("Captcha answer {} rejected", captcha.answers.last().map(String::as_str).unwrap_or("?"));
+                Some("Captcha answer rejected")
+            }
+            None => {
+                let challenge = parser::parse_captcha(msg)?;
+                let mut captcha = captcha.lock().await;
+                let detected = !captcha.detected;
+                if detected {
+                    warn!("Captcha detected (image: {})", challenge.image_url.as_deref().unwrap_or("none"));
+                    captcha.detected = true;
+                }
+                // Reminders carry no image; keep the one from the prompt
+                if challenge.image_url.is_some() {
+                    captcha.image_url = challenge.image_url;
+                }
+                detected.then_some("Captcha detected")
+            }
+        }
+    }
+
+    /// Flags `App::out_of_bait` when Virtual Fisher answers one of our casts with "no bait".
+    fn check_bait(&self, app: &mut App, msg: &Message) {
+        if msg.author.id != self.vf_id || app.out_of_bait {
+            return;
+        }
+        let invoker = msg.interaction_metadata.as_ref().and_then(|m| m.user.as_ref()).map(|u| u.id.as_str());
+        if invoker.is_some_and(|id| app.user_id.as_deref().is_some_and(|own| own != id)) {
+            return;
+        }
+        if parser::is_out_of_bait(msg) {
+            info!("Out of bait");
+            app.add_log("Out of bait".to_string());
+            app.out_of_bait = true;
+        }
+    }
+
+    /// Trade and gift prompts are a scam vector on unattended accounts: tell the owner, and
+    /// queue a decline for the bot unless `[trades] on_request = "ignore"`.
+    fn check_trade_prompt(app: &mut App, msg: &Message) {
+        let Some(user_id) = app.user_id.clone() else { return };
+        let Some(prompt) = parser::parse_trade_prompt(msg, &user_id) else { return };
+        let kind = match prompt.kind {
+            parser::TradeKind::Trade => "Trade",
+            parser::TradeKind::Gift => "Gift",
+        };
+        let policy = app.config.trades.on_request;
+        let action = match (policy, &prompt.decline_button) {
+            (TradePolicy::Decline, Some(_)) => "declining it",
+            (TradePolicy::Decline, None) => "no Decline button, ignoring it",
+            (TradePolicy::Ignore, _) => "ignoring it",
+        };
+        warn!("{} request from {}: {} ({})", kind, prompt.author, prompt.summary, action);
+        app.add_log(format!("{} request received, {}", kind, action));
+        if app.config.trades.notify {
+            app.notifier.send(Notification::new(EventKind::TradeRequest, Severity::Warning,
+                format!("{} request received", kind),
+                format!("{}\nFrom: {}. The bot is {}.", prompt.summary, prompt.author, action)));
+        }
+        if policy == TradePolicy::Decline && prompt.decline_button.is_some() {
+            app.pending_trade_declines.push(prompt);
+        }
+    }
+
+    async fn handle_titled_embed(&mut self, app: &mut App, title: &str, desc: &str, d: &Value) {
+        let span = telemetry::reply_span(title);
+        if let Some(reward) = span.in_scope(|| parser::parse_quest_reward(title, desc)) {
+            self.log_quest_reward(app, &reward).await;
+            return;
+        }
+        if app.config.crates.auto_open {
+            Self::queue_crate_button(app, title, desc);
+        }
+        if let Some(flip) = span.in_scope(|| parser::parse_coinflip_result(title, desc)) {
+            info!(event = "coinflip", won = flip.won, amount = flip.amount; "Coinflip result");
+            app.gambling.record(flip.won, flip.amount);
+            app.add_log(format!("Coinflip {} {}", if flip.won { "won" } else { "lost" }, money::format_money(flip.amount)));
+            if let Err(e) = self.db.log_coinflip(flip.won, flip.amount).await {
+                error!("DB Error: {}", e);
+            }
+            return;
+        }
+        if let Some(announcement) = span.in_scope(|| parser::parse_event_announcement(title, desc)) {
+            Self::register_event(app, &announcement);
+        }
+        // Event items also show up inside catch embeds, so this doesn't stop the checks below
+        let sighting = span.in_scope(|| seasonal::detect(&app.config.seasonal_events, title, desc));
+        if let Some(sighting) = &sighting {
+            self.record_seasonal(app, sighting, title, desc).await;
+        }
+        // So does the level-up line a catch embed can end with
+        let level_up = span.in_scope(|| parser::parse_level_up(title, desc));
+        if let Some(level_up) = &level_up {
+            self.record_level_up(app, level_up.level, level_up.reward.clone()).await;
+        }
+        let completed_before: HashSet<String> = app.profile.quests.iter()
+            .filter(|q| q.is_completed)
+            .map(|q| q.objective.clone())
+            .collect();
+        let boats_before = app.profile.boats.len();
+        span.in_scope(|| app.profile.update_from_message(desc, Some(title)));
+        for boat in &app.profile.boats[boats_before..] {
+            info!(event = "boat_owned", boat = boat.as_str(); "Boat owned");
+            if let Err(e) = self.db.record_owned_boat(boat).instrument(span.clone()).await {
+                error!("DB Error: {}", e);
+            }
+        }
+        if title.contains("Profile") {
+            self.value_inventory(app).await;
+        }
+        if title.contains("Quests") && app.config.quests.auto_claim {
+            Self::queue_quest_claims(app, &completed_before);
+        }
+        if title.contains("Achievements") || title.contains("Badges") {
+            self.store_achievements(app).await;
+        }
+        if title.contains("You caught") {
+            let Some(catch) = span.in_scope(|| parser::parse_catch_embed(desc)) else {
+                self.capture.capture("catch_parse_failed", d).await;
+                return;
+            };
+            if !self.first_count() {
+                debug!("Catch already counted for this message, skipping the edit");
+                return;
+            }
+            info!(event = "catch", fish:? = catch.fish, xp = catch.xp; "Parsed catch");
+            // Report success to cooldown manager
+            self.cooldown_manager.lock().await.report_success();
+            self.health.record_successful_cast();
+            if let Some(valuation) = &mut app.inventory_valuation {
+                valuation.add_catch(&catch.fish, &self.fish_prices);
+            }
+
+            let current_biome = app.profile.current_biome.clone().unwrap_or("Unknown".to_string());
+            for (fish, count) in catch.fish {
+                if app.fishdex.record(&fish) {
+                    app.add_log(format!("New fishdex entry: {}", fish));
+                }
+                if let Err(e) = self.db.log_catch(&fish, count, catch.xp, &current_biome).instrument(span.clone()).await {
+                    error!("DB Error: {}", e);
+                }
+            }
+        } else if title.contains("Inventory") || title.contains("Virtual Farmer") {
+            let stats = span.in_scope(|| parser::parse_profile_embed(desc));
+            info!(event = "profile", level:? = stats.level, balance:? = stats.balance, biome:? = stats.biome; "Parsed profile stats");
+            let (Some(lvl), Some(bal), Some(bio)) = (stats.level, stats.balance, stats.biome) else {
+                self.capture.capture("profile_parse_failed", d).await;
+                return;
+            };
+            if let Err(e) = self.db.log_snapshot(lvl, 0.0, bal, &bio).instrument(span.clone()).await {
+                error!("DB Error: {}", e);
+            }
+            // A level-up whose embed was missed
+            if self.last_level.is_some_and(|prev| lvl > prev) {
+                self.record_level_up(app, lvl as u32, Some(format!("Balance: ${}", bal))).await;
+            }
+            self.last_level = Some(lvl);
+            app.profile.current_biome = Some(bio);
+        } else if let Some(entries) = span.in_scope(|| parser::parse_fishdex_embed(title, desc)) {
+            info!(event = "fishdex", entries = entries.len(); "Parsed fishdex page");
+            for fish in app.fishdex.apply(&entries) {
+                app.add_log(format!("New fishdex entry: {}", fish));
+            }
+            for entry in &entries {
+                if let Err(e) = self.db.record_fishdex_entry(&entry.name, entry.biome.as_deref(), entry.caught).instrument(span.clone()).await {
+                    error!("DB Error: {}", e);
+                }
+            }
+        } else if let Some(board) = span.in_scope(|| parser::parse_leaderboard_embed(title, desc)) {
+            let Some(standing) = leaderboard::standing(&board, app.user_id.as_deref(), app.username.as_deref()) else {
+                debug!("Not on the {} leaderboard page", board.category);
+                return;
+            };
+            info!(event = "leaderboard", category = standing.category.as_str(), rank = standing.rank, gap_above:? = standing.gap_above; "Leaderboard rank");
+            if let Err(e) = self.db.log_leaderboard_rank(&standing).instrument(span.clone()).await {
+                error!("DB Error: {}", e);
+            }
+            app.rank_history.push((chrono::Utc::now().timestamp(), standing));
+        } else if let Some(boss) = span.in_scope(|| parser::parse_clan_boss_embed(title, desc)) {
+            info!(event = "clan_boss", name = boss.name.as_str(), hp_remaining:? = boss.hp_remaining, defeated = boss.defeated; "Clan boss seen");
+            if let Err(e) = self.db.log_clan_event("sighting", &boss.name, None, desc).instrument(span.clone()).await {
+                error!("DB Error: {}", e);
+            }
+            self.log_clan_attack(app, &boss.name, desc).await;
+            app.clan_boss = Some((boss, Instant::now()));
+        } else if let Some(treasure) = span.in_scope(|| parser::parse_treasure_open(title, desc)) {
+            if self.first_count() {
+                let source = format!("{} Treasure", treasure.quality.map(|q| q.display_name()).unwrap_or("Unknown"));
+                self.log_drops(app, &source, &treasure.drops).await;
+            }
+        } else if let Some(drops) = span.in_scope(|| parser::parse_crate_drops(title, desc)) {
+            if self.first_count() {
+                let source = app.opening_crate.take().map(|(name, _)| name)
+                    .or_else(|| parser::openable_kind(title).map(str::to_string))
+                    .unwrap_or_else(|| "Crate".to_string());
+                self.log_drops(app, &source, &drops).await;
+            }
+        } else if let Some(daily) = span.in_scope(|| parser::parse_daily_reward(title, desc)) {
+            if !self.first_count() {
+                return;
+            }
+            info!(event = "daily_reward", money:? = daily.money, streak:? = daily.streak; "Daily reward claimed");
+            app.add_log(format!("Daily reward: {}{}",
+                daily.money.map(money::format_money).unwrap_or_else(|| "-".to_string()),
+                daily.streak.map(|s| format!(", {} day streak", s)).unwrap_or_default()));
+            if let Err(e) = self.db.record_daily_reward(daily.money, daily.streak).instrument(span.clone()).await {
+                error!("DB Error: {}", e);
+            }
+        } else if let Some(wait) = span.in_scope(|| parser::parse_vote_wait(title, desc)) {
+            let available_at = chrono::Utc::now() + chrono::Duration::seconds(wait.as_secs() as i64);
+            info!(event = "vote_window", available_at = available_at.to_rfc3339().as_str(); "Vote window seen");
+            app.vote_available_at = Some(available_at);
+            if let Err(e) = self.db.log_vote_window(available_at, "embed").instrument(span.clone()).await {
+                error!("DB Error: {}", e);
+            }
+        } else if !self.log_clan_attack(app, title, desc).await && sighting.is_none() && level_up.is_none() && !Profile::is_known_title(title) {
+            self.capture.capture("unrecognized_title", d).await;
+        }
+    }
+
+    /// Logs a level reached above the last one seen, tells the owner (`[level_up] notify`) and
+    /// queues the rest of `[level_up]` for the bot. `detail` goes into the notification.
+    async fn record_level_up(&mut self, app: &mut App, level: u32, detail: Option<String>) {
+        let previous = self.last_level.map(|l| l.max(0) as u32).or(app.profile.level).unwrap_or(level.saturating_sub(1));
+        if level <= previous {
+            return;
+        }
+        self.last_level = Some(level as i32);
+        app.profile.level = Some(level);
+        info!(event = "level_up", level, previous; "Level up");
+        app.add_log(format!("Level up: {} -> {}", previous, level));
+        if let Err(e) = self.db.log_level_up(level).await {
+            error!("DB Error: {}", e);
+        }
+        if app.config.level_up.notify {
+            app.notifier.send(Notification::new(EventKind::LevelUp, Severity::Info,
+                format!("Reached level {}", level), detail.unwrap_or_else(|| format!("Up from level {}.", previous))));
+        }
+        app.pending_level_ups.push((previous, level));
+    }
+
+    async fn value_inventory(&mut self, app: &mut App) {
+        match self.db.fish_sell_values().await {
+            Ok(prices) => self.fish_prices = prices,
+            Err(e) => error!("DB Error: {}", e),
+        }
+        let valuation = InventoryValuation::from_inventory(&app.profile.inventory, &self.fish_prices);
+        info!(event = "inventory_value", total = valuation.total, unpriced = valuation.unpriced.len(); "Valued inventory");
+        app.inventory_valuation = Some(valuation);
+    }
+
+    async fn record_seasonal(&mut self, app: &mut App, sighting: &SeasonalSighting, title: &str, desc: &str) {
+        info!(event = "seasonal", name = sighting.event.as_str(), found:? = sighting.found, ends_in:? = sighting.ends_in; "Seasonal event seen");
+        if !app.is_seasonal_event_active(&sighting.event) {
+            app.add_log(format!("Seasonal event active: {}", sighting.event));
+        }
+        seasonal::record(&mut app.seasonal_events, sighting);
+        if let Err(e) = self.db.upsert_game_entity("SeasonalEvent", &sighting.event, &format!("{}\n{}", title, desc)).await {
+            error!("DB Error: {}", e);
+        }
+        if let Some(found) = sighting.found.filter(|f| *f > 0) {
+            *app.event_earnings.entry(sighting.event.clone()).or_default() += found;
+            if let Err(e) = self.db.log_event_earning(&sighting.event, found).await {
+                error!("DB Error: {}", e);
+            }
+        }
+    }
+
+    /// Adds an announced event to the live `[seasonal_events]`, so its embeds are recognized
+    /// and, with `join_announced_events`, its command is scheduled once the bot picks it up.
+    fn register_event(app: &mut App, announcement: &EventAnnouncement) {
+        let auto_run = app.config.automation.join_announced_events;
+        let Some(key) = seasonal::register(&mut app.config.seasonal_events, announcement, auto_run) else { return };
+        let command = &app.config.seasonal_events[&key].command;
+        info!(event = "seasonal", name = key.as_str(), command = command.as_str(); "Registered announced event");
+        let message = match command.as_str() {
+            "" => format!("Event announced: {}", announcement.name),
+            command => format!("Event announced: {} (/{})", announcement.name, command),
+        };
+        app.add_log(message);
+        app.config_generation += 1;
+    }
+
+    /// Keeps each achievement's progress in `game_entities` as JSON, so it survives restarts.
+    async fn store_achievements(&mut self, app: &App) {
+        info!(event = "achievements", count = app.profile.achievements.len(), completion:? = app.profile.achievement_completion(); "Parsed achievements");
+        for achievement in &app.profile.achievements {
+            let entity_type = if achievement.is_badge { "Badge" } else { "Achievement" };
+            let details = serde_json::to_string(achievement).unwrap_or_default();
+            if let Err(e) = self.db.upsert_game_entity(entity_type, &achievement.name, &details).await {
+                error!("DB Error: {}", e);
+            }
+        }
+    }
+
+    /// Queues a click on the Open button of messages like "You found a Super Crate!".
+    fn queue_crate_button(app: &mut App, title: &str, desc: &str) {
+        let Some(msg) = app.last_message_object.clone() else { return };
+        let Some(button_custom_id) = parser::find_button(&msg, "open") else { return };
+        let Some(kind) = parser::openable_kind(&format!("{}\n{}", title, desc)) else { return };
+        if app.pending_crate_opens.iter().any(|c| c.message.as_ref().is_some_and(|(id, _)| *id == msg.id)) {
+            return;
+        }
+        app.pending_crate_opens.push(CrateOpen { name: kind.to_string(), message: Some((msg.id, msg.channel_id)), button_custom_id: Some(button_custom_id) });
+    }
+
+    /// Logs an opened crate or treasure chest (`source`) and what it held to `treasure_opens`.
+    async fn log_drops(&mut self, app: &mut App, source: &str, drops: &parser::CrateDrops) {
+        info!(event = "crate_opened", source = source, items = drops.items.len(), money:? = drops.money; "Crate opened");
+        let mut rows: Vec<(&str, u64)> = drops.items.iter().map(|(item, amount)| (item.as_str(), *amount)).collect();
+        if let Some(money) = drops.money {
+            rows.push(("Money", money));
+        }
+        app.add_log(format!("Opened {}: {}", source,
+            rows.iter().map(|(item, amount)| format!("{} {}", amount, item)).collect::<Vec<_>>().join(", ")));
+        if let Err(e) = self.db.log_treasure_open(source, &rows).await {
+            error!("DB Error: {}", e);
+        }
+    }
+
+    /// Queues every quest that wasn't COMPLETED in the previous quests embed.
+    fn queue_quest_claims(app: &mut App, completed_before: &HashSet<String>) {
+        let Some(msg) = app.last_message_object.clone() else { return };
+        let button_custom_id = parser::find_button(&msg, "claim");
+        let new_claims: Vec<QuestClaim> = app.profile.quests.iter()
+            .filter(|q| q.is_completed && !completed_before.contains(&q.objective))
+            .filter(|q| !app.pending_quest_claims.iter().any(|c| c.objective == q.objective))
+            .map(|q| QuestClaim { objective: q.objective.clone(), message_id: msg.id.clone(), channel_id: msg.channel_id.clone(),
+                button_custom_id: button_custom_id.clone() })
+            .collect();
+        for claim in new_claims {
+            info!(event = "quest_completed", objective = claim.objective.as_str(); "Quest completed, queued for claiming");
+            app.pending_quest_claims.push(claim);
+        }
+    }
+
+    async fn log_quest_reward(&mut self, app: &mut App, reward: &str) {
+        let objective = app.awaiting_quest_reward.take().map(|(objective, _)| objective).unwrap_or_default();
+        info!(event = "quest_reward", objective = objective.as_str(), reward; "Quest reward claimed");
+        app.add_log(format!("Quest reward: {}", reward));
+        if let Err(e) = self.db.log_quest_claim(&objective, Some(reward)).await {
+            error!("DB Error: {}", e);
+        }
+    }
+
+    /// Logs the result of attacking a clan boss if `desc` is one. Returns whether it was.
+    async fn log_clan_attack(&mut self, app: &mut App, boss_name: &str, desc: &str) -> bool {
+        let Some(attack) = parser::parse_clan_attack_result(desc) else { return false };
+        info!(event = "clan_attack", damage = attack.damage, reward:? = attack.reward; "Clan boss attacked");
+        app.add_log(format!("Clan boss: dealt {} damage{}", attack.damage,
+            attack.reward.as_ref().map(|r| format!(", reward {}", r)).unwrap_or_default()));
+        if let Err(e) = self.db.log_clan_event("attack", boss_name, Some(attack.damage), attack.reward.as_deref().unwrap_or_default()).await {
+            error!("DB Error: {}", e);
+        }
+        true
+    }
+
+    async fn handle_untitled_embed(&mut self, desc: &str, d: &Value) {
+        let span = telemetry::reply_span("cooldown");
+        let Some(cd_event) = span.in_scope(|| parser::parse_cooldown_embed(desc)) else {
+            self.capture.capture("unrecognized_description", d).await;
+            return;
+        };
+        if !self.first_count() {
+            return;
+        }
+        info!(event = "cooldown", wait_time = cd_event.wait_time, total_cooldown = cd_event.total_cooldown; "Parsed cooldown");
+
+        let biome = self.app_state.lock().await.profile.current_biome.clone().unwrap_or("Unknown".to_string());
+        if let Err(e) = self.db.log_cooldown(cd_event.wait_time, cd_event.total_cooldown, &biome).instrument(span).await {
+            error!("DB Error: {}", e);
+        }
+        self.cooldown_manager.lock().await.report_cooldown_hit(cd_event.wait_time as f64, cd_event.total_cooldown as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// The processor the TUI and headless mode run, on an in-memory database.
+    async fn processor() -> (EventProcessor, Arc<Mutex<App>>, Arc<Database>) {
+        let config = Config::default();
+        let app = Arc::new(Mutex::new(App::new(config.clone())));
+        let db = Arc::new(Database::in_memory().await.unwrap());
+        let cooldown = Arc::new(Mutex::new(CooldownManager::new(config.system.user_cooldown)));
+        let processor = EventProcessor::new(&config, app.clone(), db.clone(), cooldown, Arc::new(HealthMonitor::new()));
+        (processor, app, db)
+    }
+
+    /// A Virtual Fisher message with one embed.
+    fn embed(t: &str, id: &str, title: &str, description: &str) -> GatewayPayload {
+        let config = Config::default();
+        let d = json!({
+            "id": id, "channel_id": config.system.channel_id.to_string(), "content": "", "timestamp": "",
+            "author": { "id": config.system.application_id.to_string(), "username": "Virtual Fisher", "discriminator": "0" },
+            "embeds": [{ "title": title, "description": description }]
+        });
+        GatewayPayload { op: 0, d: Some(d), s: None, t: Some(t.to_string()) }
+    }
+
+    #[tokio::test]
+    async fn test_clan_boss_and_attack() {
+        let (mut processor, app, db) = processor().await;
+        processor.handle(embed("MESSAGE_CREATE", "1", "Clan Boss: Kraken", "HP: **1,234,567** / **5,000,000**\nEnds in **3h 20m**")).await;
+        {
+            let app = app.lock().await;
+            assert_eq!(app.active_clan_boss().map(|b| b.name.as_str()), Some("Kraken"));
+            assert_eq!(app.last_message_object.as_ref().map(|m| m.id.as_str()), Some("1"));
+        }
+
+        processor.handle(embed("MESSAGE_CREATE", "2", "Attack!", "You dealt **12,345** damage to the Kraken!\nReward: **$5,000**")).await;
+        assert!(app.lock().await.logs.iter().any(|l| l.starts_with("Clan boss: dealt 12345 damage")));
+        let events: Vec<(String, Option<i64>)> = sqlx::query_as("SELECT event_type, damage FROM clan_events ORDER BY id")
+            .fetch_all(&db.pool).await.unwrap();
+        assert_eq!(events, [("sighting".to_string(), None), ("attack".to_string(), Some(12_345))]);
+    }
+
+    #[tokio::test]
+    async fn test_is_new_state() {
+        let (mut dedup, _, _) = processor().await;
+        let catch = embed("MESSAGE_CREATE", "1", "You caught...", "1 <:Cod:1> Cod\n+12 XP");
+        let d = catch.d.clone().unwrap();
+        assert!(dedup.is_new_state(&d));
+        // Re-delivered unchanged, e.g. a button disabled after the cast
+        assert!(!dedup.is_new_state(&d));
+        let mut edited = d.clone();
+        edited["embeds"][0]["description"] = json!("1 <:Cod:1> Cod\n+12 XP\nYou leveled up!");
+        assert!(dedup.is_new_state(&edited));
+        assert!(!dedup.is_new_state(&edited));
+        // Without an id there's nothing to compare against
+        assert!(dedup.is_new_state(&json!({ "content": "" })));
+        assert!(dedup.is_new_state(&json!({ "content": "" })));
+
+        // The catch itself is logged once, whatever edits follow
+        let (mut processor, _, db) = processor().await;
+        processor.handle(catch).await;
+        processor.handle(embed("MESSAGE_UPDATE", "1", "You caught...", "1 <:Cod:1> Cod\n+12 XP")).await;
+        processor.handle(embed("MESSAGE_UPDATE", "1", "You caught...", "1 <:Cod:1> Cod\n+12 XP\nYou leveled up!")).await;
+        let rows: i64 = sqlx::query_scalar("SELECT count(*) FROM catch_history").fetch_one(&db.pool).await.unwrap();
+        assert_eq!(rows, 1);
+    }
+}
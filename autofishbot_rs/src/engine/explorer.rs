@@ -1,15 +1,24 @@
 use std::sync::Arc;
 use log::{info, error, warn};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use crate::discord::client::DiscordClient;
+use crate::discord::command::CommandBuilder;
+use crate::engine::correlation::NoReply;
 use crate::engine::database::Database;
+use crate::engine::capture::EmbedCapture;
 use crate::discord::types::{Message};
 use crate::engine::parser::{self};
 use serde_json::Value;
 
+/// How long a target command's reply is awaited before falling back to the channel's last message.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Pause between full exploration cycles.
+const CYCLE_INTERVAL: Duration = Duration::from_secs(3600);
+
 pub struct Explorer {
     client: Arc<DiscordClient>,
     db: Arc<Database>, // Shared via Arc, not Mutex because Database methods take &self
+    capture: EmbedCapture,
     guild_id: String,
     channel_id: String,
     known_commands: Vec<Value>, // Changed to Value to hold raw JSON
@@ -19,10 +28,40 @@ pub struct Explorer {
     discovery_attempts: u32,
 
     // Submenu navigation tracking
-    submenu_custom_id: Option<String>,
+    /// The message carrying the select menu, refreshed after each pick
+    submenu_message: Option<Message>,
     submenu_options: Vec<parser::SelectMenuOption>,
     current_submenu_index: usize,
-    current_message_id: String,
+    /// Re-running one target from the Explorer tab; stop after it instead of moving on
+    single_target: bool,
+    /// The target command's own reply, matched by nonce
+    reply: Option<Message>,
+    /// When `Cooldown` ends, see `wake`
+    next_cycle: Option<Instant>,
+}
+
+/// Sent from the Explorer tab, applied by the bot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExplorerRequest {
+    Start,
+    Stop,
+    /// Re-run `target_commands[i]` alone
+    Run(usize),
+}
+
+/// Snapshot for the Explorer tab, see `Explorer::status`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExplorerStatus {
+    pub active: bool,
+    pub state: String,
+    pub targets: Vec<String>,
+    /// Index of the target being explored
+    pub current: usize,
+    pub known_commands: usize,
+    /// `(option, options)` while walking a select menu
+    pub submenu: Option<(usize, usize)>,
+    /// Between cycles; the bot fishes until the next one
+    pub cooling_down: bool,
 }
 
 #[derive(Debug, PartialEq)]
@@ -34,14 +73,16 @@ enum ExplorerState {
     NavigatingSubmenu,
     WaitingForSubmenuResponse,
     #[allow(dead_code)] NavigatingPagination,
+    /// A full cycle finished; `wake` starts the next one after `CYCLE_INTERVAL`
     Cooldown,
 }
 
 impl Explorer {
-    pub fn new(client: Arc<DiscordClient>, db: Arc<Database>, guild_id: String, channel_id: String) -> Self {
+    pub fn new(client: Arc<DiscordClient>, db: Arc<Database>, capture: EmbedCapture, guild_id: String, channel_id: String) -> Self {
         Self {
             client,
             db,
+            capture,
             guild_id,
             channel_id,
             known_commands: Vec::new(),
@@ -59,10 +100,12 @@ impl Explorer {
             current_command_index: 0,
             state: ExplorerState::Idle,
             discovery_attempts: 0,
-            submenu_custom_id: None,
+            submenu_message: None,
             submenu_options: Vec::new(),
             current_submenu_index: 0,
-            current_message_id: String::new(),
+            single_target: false,
+            reply: None,
+            next_cycle: None,
         }
     }
 
@@ -70,18 +113,69 @@ impl Explorer {
         info!("Starting Explorer Mode...");
         self.state = ExplorerState::DiscoveringCommands;
         self.discovery_attempts = 0;
+        self.current_command_index = 0;
+        self.single_target = false;
+        self.next_cycle = None;
+    }
+
+    pub fn stop(&mut self) {
+        if self.state != ExplorerState::Idle {
+            info!("Explorer stopped");
+        }
+        self.state = ExplorerState::Idle;
+        self.next_cycle = None;
+        self.single_target = false;
+        self.submenu_message = None;
+        self.submenu_options.clear();
+    }
+
+    /// Explores `target_commands[index]` alone, discovering the commands first if needed.
+    pub fn run_target(&mut self, index: usize) {
+        let Some(target) = self.target_commands.get(index) else { return };
+        info!("Re-running explorer target {}", target);
+        self.current_command_index = index;
+        self.single_target = true;
+        self.state = if self.known_commands.is_empty() { ExplorerState::DiscoveringCommands } else { ExplorerState::ExecutingCommand };
+    }
+
+    /// Cooling down counts as inactive so the bot fishes between cycles.
+    pub fn is_active(&self) -> bool {
+        !matches!(self.state, ExplorerState::Idle | ExplorerState::Cooldown)
+    }
+
+    /// Starts the next cycle once the cooldown is over.
+    pub fn wake(&mut self) {
+        if self.state == ExplorerState::Cooldown && self.next_cycle.is_some_and(|at| Instant::now() >= at) {
+            info!("Cooldown over, starting the next exploration cycle");
+            self.next_cycle = None;
+            self.discovery_attempts = 0;
+            self.state = ExplorerState::DiscoveringCommands;
+        }
+    }
+
+    pub fn status(&self) -> ExplorerStatus {
+        let walking_submenu = matches!(self.state, ExplorerState::NavigatingSubmenu | ExplorerState::WaitingForSubmenuResponse);
+        ExplorerStatus {
+            active: self.is_active(),
+            state: format!("{:?}", self.state),
+            targets: self.target_commands.clone(),
+            current: self.current_command_index,
+            known_commands: self.known_commands.len(),
+            submenu: walking_submenu.then_some((self.current_submenu_index, self.submenu_options.len())),
+            cooling_down: self.state == ExplorerState::Cooldown,
+        }
     }
 
     pub async fn tick(&mut self, last_message: Option<&Message>) {
         match self.state {
-            ExplorerState::Idle => {
+            ExplorerState::Idle | ExplorerState::Cooldown => {
                 // Do nothing
             },
             ExplorerState::DiscoveringCommands => {
                 info!("Discovering commands (Attempt {})...", self.discovery_attempts + 1);
                 match self.client.get_commands(&self.guild_id).await {
                     Ok(cmds) => {
-                        self.known_commands = cmds;
+                        self.known_commands = cmds.to_vec();
                         info!("Discovered {} commands.", self.known_commands.len());
 
                         // Register all commands in DB with full raw structure
@@ -120,6 +214,7 @@ impl Explorer {
                     info!("Exploration cycle complete. Restarting in 1 hour.");
                     self.current_command_index = 0;
                     self.state = ExplorerState::Cooldown;
+                    self.next_cycle = Some(Instant::now() + CYCLE_INTERVAL);
                     return;
                 }
 
@@ -132,15 +227,30 @@ impl Explorer {
 
                 // Find command in known_commands (Vec<Value>)
                 if let Some(cmd) = self.known_commands.iter().find(|c| c["name"] == main_name) {
-                     // Prepare options (deep structure)
-                    let options = self.build_command_options(&parts[1..], cmd["options"].as_array());
+                     // Named subcommands, then the first one at each level below
+                    let options = match CommandBuilder::new(cmd).text(&parts[1..]).first_subcommand().build() {
+                        Ok(options) => options,
+                        Err(e) => {
+                            warn!("Can't explore {}: {}", cmd_name, e);
+                            self.advance_command();
+                            return;
+                        }
+                    };
 
                     // Pass the whole cmd Value
-                    if let Err(e) = self.client.send_command(&self.guild_id, &self.channel_id, cmd, options).await {
-                        error!("Failed to execute {}: {}", cmd_name, e);
-                    } else {
-                        let _ = self.db.mark_command_executed(cmd_name).await;
-                        self.state = ExplorerState::WaitingForResponse;
+                    match self.client.send_command_and_wait(&self.guild_id, &self.channel_id, cmd, options, REPLY_TIMEOUT).await {
+                        Ok(reply) => {
+                            let _ = self.db.mark_command_executed(cmd_name).await;
+                            self.reply = Some(reply);
+                            self.state = ExplorerState::WaitingForResponse;
+                        },
+                        // Sent, but unmatched: fall back to the channel's last message
+                        Err(e) if e.downcast_ref::<NoReply>().is_some() => {
+                            warn!("Exploring {}: {}", cmd_name, e);
+                            let _ = self.db.mark_command_executed(cmd_name).await;
+                            self.state = ExplorerState::WaitingForResponse;
+                        },
+                        Err(e) => error!("Failed to execute {}: {}", cmd_name, e),
                     }
                 } else {
                     warn!("Command {} not found in guild.", main_name);
@@ -148,18 +258,20 @@ impl Explorer {
                 }
             },
             ExplorerState::WaitingForResponse => {
-                tokio::time::sleep(Duration::from_secs(3)).await;
+                let reply = self.reply.take();
+                if reply.is_none() {
+                    tokio::time::sleep(Duration::from_secs(3)).await;
+                }
 
-                if let Some(msg) = last_message {
+                if let Some(msg) = reply.as_ref().or(last_message) {
                     self.parse_and_save(msg).await;
 
                     // Check for submenu first
-                    if let Some((custom_id, options)) = parser::parse_select_menu_options(msg) {
+                    if let Some((_, options)) = parser::parse_select_menu_options(msg) {
                         info!("Found submenu with {} options.", options.len());
-                        self.submenu_custom_id = Some(custom_id);
+                        self.submenu_message = Some(msg.clone());
                         self.submenu_options = options;
                         self.current_submenu_index = 0;
-                        self.current_message_id = msg.id.clone();
                         self.state = ExplorerState::NavigatingSubmenu;
                     } else if self.has_pagination(msg) {
                          self.handle_pagination(msg).await;
@@ -173,7 +285,7 @@ impl Explorer {
             ExplorerState::NavigatingSubmenu => {
                 if self.current_submenu_index >= self.submenu_options.len() {
                     info!("Finished submenu exploration.");
-                    self.submenu_custom_id = None;
+                    self.submenu_message = None;
                     self.submenu_options.clear();
                     self.advance_command();
                     return;
@@ -182,9 +294,8 @@ impl Explorer {
                 let option = &self.submenu_options[self.current_submenu_index];
                 info!("Selecting submenu option: {}", option.label);
 
-                if let Some(custom_id) = &self.submenu_custom_id {
-                    let values = vec![option.value.clone()];
-                    if let Err(e) = self.client.interact_component(&self.guild_id, &self.channel_id, &self.current_message_id, custom_id, Some(3), Some(values)).await {
+                if let Some(msg) = &self.submenu_message {
+                    if let Err(e) = self.client.select_menu(&self.guild_id, &self.channel_id, msg, "", &option.value).await {
                         error!("Failed to select option: {}", e);
                         // Skip if failed
                         self.current_submenu_index += 1;
@@ -198,10 +309,11 @@ impl Explorer {
             ExplorerState::WaitingForSubmenuResponse => {
                 tokio::time::sleep(Duration::from_secs(4)).await;
 
-                match self.client.get_message(&self.channel_id, &self.current_message_id).await {
+                let message_id = self.submenu_message.as_ref().map(|m| m.id.clone()).unwrap_or_default();
+                match self.client.get_message(&self.channel_id, &message_id).await {
                     Ok(msg) => {
                         self.parse_and_save(&msg).await;
-                        self.current_message_id = msg.id.clone();
+                        self.submenu_message = Some(msg);
                     },
                     Err(e) => {
                         error!("Failed to fetch updated message in submenu: {}", e);
@@ -212,59 +324,6 @@ impl Explorer {
                 self.state = ExplorerState::NavigatingSubmenu;
             },
             ExplorerState::NavigatingPagination => {},
-            ExplorerState::Cooldown => {
-                tokio::time::sleep(Duration::from_secs(3600)).await;
-                self.state = ExplorerState::DiscoveringCommands;
-            },
-        }
-    }
-
-    fn build_command_options(&self, path: &[&str], schema_options: Option<&Vec<Value>>) -> Option<Vec<Value>> {
-        let opts = match schema_options {
-            Some(o) => o,
-            None => return Some(Vec::new()),
-        };
-
-        if let Some(&target) = path.first() {
-            // Explicit path navigation
-            if let Some(option_def) = opts.iter().find(|o| o["name"] == target) {
-                 let sub_options = self.build_command_options(&path[1..], option_def["options"].as_array());
-
-                 if let Some(sub_opts_vec) = sub_options {
-                     Some(vec![serde_json::json!({
-                         "name": target,
-                         "type": option_def["type"],
-                         "options": sub_opts_vec
-                     })])
-                 } else {
-                     None
-                 }
-            } else {
-                warn!("Subcommand/Option '{}' not found.", target);
-                None
-            }
-        } else {
-            // End of explicit path. Check if we need to auto-select a child.
-            // Look for type 1 (SUB_COMMAND) or 2 (SUB_COMMAND_GROUP)
-            if let Some(first_sub) = opts.iter().find(|o| {
-                let t = o["type"].as_u64().unwrap_or(0);
-                t == 1 || t == 2
-            }) {
-                let name = first_sub["name"].as_str().unwrap_or("unknown");
-                info!("Auto-selecting subcommand: {}", name);
-
-                let sub_options = self.build_command_options(&[], first_sub["options"].as_array());
-                let sub_opts_vec = sub_options.unwrap_or_default();
-
-                Some(vec![serde_json::json!({
-                    "name": name,
-                    "type": first_sub["type"],
-                    "options": sub_opts_vec
-                })])
-            } else {
-                // No subcommands to select, we are at leaf (or only have parameters which we ignore for now)
-                Some(Vec::new())
-            }
         }
     }
 
@@ -313,6 +372,12 @@ impl Explorer {
     }
 
     fn advance_command(&mut self) {
+        if self.single_target {
+            info!("Finished re-running {}", self.target_commands[self.current_command_index]);
+            self.single_target = false;
+            self.state = ExplorerState::Idle;
+            return;
+        }
         self.current_command_index += 1;
         self.state = ExplorerState::ExecutingCommand;
     }
@@ -339,6 +404,11 @@ impl Explorer {
                          }
                      } else {
                          let _ = self.db.upsert_game_entity("RawEmbed", &title, &desc).await;
+                         if self.capture.is_enabled() {
+                             if let Ok(raw) = serde_json::to_value(msg) {
+                                 self.capture.capture("explorer_unparsed", &raw).await;
+                             }
+                         }
                      }
                 }
             }
@@ -363,23 +433,65 @@ impl Explorer {
     }
 
     async fn handle_pagination(&self, msg: &Message) {
-        if let Some(components) = &msg.components {
-            for row in components {
-                if let Some(comps) = &row.components {
-                    for comp in comps {
-                        if let Some(custom_id) = &comp.custom_id {
-                             if let Some(label) = &comp.label {
-                                if label.contains("Next") || label.contains(">") {
-                                    info!("Clicking Next Page...");
-                                    let _ = self.client.interact_component(&self.guild_id, &self.channel_id, &msg.id, custom_id, Some(2), None).await;
-                                    tokio::time::sleep(Duration::from_secs(2)).await;
-                                    return;
-                                }
-                             }
-                        }
-                    }
-                }
-            }
+        let label = if parser::find_button(msg, "next").is_some() { "next" } else { ">" };
+        info!("Clicking Next Page...");
+        if let Err(e) = self.client.click_button(&self.guild_id, &self.channel_id, msg, label).await {
+            warn!("Failed to turn the page: {}", e);
+            return;
         }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[tokio::test]
+    async fn test_explorer_controls() {
+        let db = Arc::new(Database::in_memory().await.unwrap());
+        let config = Config::default();
+        let client = Arc::new(DiscordClient::new(config.clone()).unwrap());
+        let mut explorer = Explorer::new(client, db, EmbedCapture::new(&config), "1".to_string(), "2".to_string());
+        assert!(!explorer.status().active);
+
+        // Nothing discovered yet, so a re-run starts with discovery
+        explorer.run_target(2);
+        let status = explorer.status();
+        assert_eq!((status.active, status.state.as_str(), status.current), (true, "DiscoveringCommands", 2));
+
+        // A single target stops after itself
+        explorer.state = ExplorerState::WaitingForResponse;
+        explorer.advance_command();
+        assert!(!explorer.is_active());
+        assert_eq!(explorer.current_command_index, 2);
+
+        explorer.start().await;
+        explorer.state = ExplorerState::ExecutingCommand;
+        explorer.advance_command();
+        assert_eq!((explorer.status().current, explorer.is_active()), (1, true));
+        explorer.stop();
+        assert!(!explorer.is_active());
+
+        explorer.run_target(99);
+        assert!(!explorer.is_active());
+
+        // A finished cycle fishes until the cooldown is over, then starts again
+        explorer.start().await;
+        explorer.current_command_index = explorer.target_commands.len();
+        explorer.state = ExplorerState::ExecutingCommand;
+        explorer.tick(None).await;
+        assert!(!explorer.is_active());
+        assert!(explorer.status().cooling_down);
+        explorer.wake();
+        assert!(!explorer.is_active());
+        explorer.next_cycle = Some(Instant::now());
+        explorer.wake();
+        assert_eq!(explorer.status().state, "DiscoveringCommands");
+
+        explorer.state = ExplorerState::Cooldown;
+        explorer.stop();
+        assert!(!explorer.status().cooling_down);
     }
 }
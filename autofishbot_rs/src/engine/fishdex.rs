@@ -0,0 +1,131 @@
+use crate::engine::game_data::{Biome, BAIT_DATA, FISH_DATA};
+use crate::engine::parser::FishdexEntry;
+use std::collections::BTreeSet;
+
+/// Catalog species (`FISH_DATA`) the account has caught at least once, from `catch_history`
+/// and the `/fishdex` pages seen (the `fishdex` table).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Fishdex {
+    caught: BTreeSet<&'static str>,
+}
+
+/// One biome's share of the catalog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DexProgress {
+    pub biome: Biome,
+    pub caught: usize,
+    pub total: usize,
+    /// Cheapest first
+    pub missing: Vec<&'static str>,
+}
+
+impl DexProgress {
+    pub fn percent(&self) -> f64 {
+        if self.total > 0 { self.caught as f64 / self.total as f64 * 100.0 } else { 100.0 }
+    }
+}
+
+impl Fishdex {
+    /// Names that aren't in the catalog (e.g. event fish) are ignored.
+    pub fn new<S: AsRef<str>>(names: impl IntoIterator<Item = S>) -> Self {
+        let mut dex = Self::default();
+        for name in names {
+            dex.record(name.as_ref());
+        }
+        dex
+    }
+
+    /// Returns true for a species caught for the first time.
+    pub fn record(&mut self, name: &str) -> bool {
+        match FISH_DATA.values().find(|f| f.name.eq_ignore_ascii_case(name.trim())) {
+            Some(fish) => self.caught.insert(fish.name),
+            None => false,
+        }
+    }
+
+    /// Records the caught species of a `/fishdex` page and returns the ones that are new.
+    /// A species caught once stays caught, whatever a later page says.
+    pub fn apply(&mut self, entries: &[FishdexEntry]) -> Vec<&'static str> {
+        entries.iter()
+            .filter(|e| e.caught && self.record(&e.name))
+            .filter_map(|e| FISH_DATA.values().find(|f| f.name.eq_ignore_ascii_case(e.name.trim())).map(|f| f.name))
+            .collect()
+    }
+
+    /// Catalog species found in `biome` that haven't been caught yet.
+    pub fn missing_in(&self, biome: Biome) -> usize {
+        FISH_DATA.values().filter(|f| f.biomes.contains(&biome) && !self.caught.contains(f.name)).count()
+    }
+
+    /// `(caught, catalog size)`
+    pub fn completion(&self) -> (usize, usize) {
+        (self.caught.len(), FISH_DATA.len())
+    }
+
+    /// Per biome in `Biome::ALL` order; a fish found in several biomes counts in each.
+    pub fn progress(&self) -> Vec<DexProgress> {
+        Biome::ALL.iter().map(|&biome| {
+            let mut species: Vec<_> = FISH_DATA.values().filter(|f| f.biomes.contains(&biome)).collect();
+            species.sort_by_key(|f| (f.price, f.name));
+            let missing: Vec<&'static str> = species.iter().map(|f| f.name).filter(|name| !self.caught.contains(name)).collect();
+            DexProgress { biome, caught: species.len() - missing.len(), total: species.len(), missing }
+        }).collect()
+    }
+
+    /// The biome with the most missing species, staying in `current` on a tie; `None` once
+    /// the dex is complete.
+    pub fn hunting_target(&self, current: Biome) -> Option<Biome> {
+        let progress = self.progress();
+        let most = progress.iter().map(|p| p.missing.len()).max().filter(|n| *n > 0)?;
+        let mut tied = progress.iter().filter(|p| p.missing.len() == most).map(|p| p.biome);
+        if tied.clone().any(|b| b == current) { Some(current) } else { tied.next() }
+    }
+}
+
+/// The bait with the highest fish quality bonus, for `[strategy] dex_hunting` without a `dex_bait`.
+pub fn best_quality_bait() -> &'static str {
+    BAIT_DATA.values()
+        .max_by(|a, b| a.fish_quality_bonus.total_cmp(&b.fish_quality_bonus).then_with(|| b.name.cmp(a.name)))
+        .map(|b| b.name)
+        .unwrap_or("Fish")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fishdex_progress() {
+        let mut dex = Fishdex::new(["Raw Fish", "cod", "Mystery Fish"]);
+        assert_eq!(dex.completion().0, 2);
+        assert!(!dex.record("Cod"));
+        assert!(dex.record("Tropical Fish"));
+
+        let river = &dex.progress()[0];
+        assert_eq!(river.biome, Biome::River);
+        assert_eq!((river.caught, river.total), (3, 5));
+        assert_eq!(river.missing, vec!["Raw Salmon", "Pufferfish"]);
+
+        // Alien still has all 5 of its species to find
+        assert_eq!(dex.hunting_target(Biome::River), Some(Biome::Alien));
+        // River, Volcanic, Ocean and Alien tie with nothing caught
+        assert_eq!(Fishdex::default().hunting_target(Biome::Ocean), Some(Biome::Ocean));
+        assert_eq!(Fishdex::default().hunting_target(Biome::Sky), Some(Biome::River));
+
+        let complete = Fishdex::new(FISH_DATA.keys().copied());
+        assert_eq!(complete.hunting_target(Biome::River), None);
+        assert!(complete.progress().iter().all(|p| p.caught == p.total && p.missing.is_empty()));
+
+        assert_eq!(best_quality_bait(), "Fish");
+
+        let page = crate::engine::parser::parse_fishdex_embed("Fishdex - Alien (4/6)",
+            "Biome progress: 1/5\n<:alien:1> **Alien Fish** - Caught: 3\n❓ ???\n❌ Pufferfish\nRaw Fish - Caught: 0").unwrap();
+        assert_eq!(page.len(), 3);
+        assert!(page.iter().all(|e| e.biome.as_deref() == Some("Alien")));
+        let mut dex = Fishdex::default();
+        assert_eq!(dex.missing_in(Biome::Alien), 5);
+        assert_eq!(dex.apply(&page), vec!["Alien Fish"]);
+        assert_eq!(dex.missing_in(Biome::Alien), 4);
+        assert!(dex.apply(&page).is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 use lazy_static::lazy_static;
+use regex::Regex;
 use std::collections::HashMap;
 
 // --- Enums ---
@@ -13,6 +14,49 @@ pub enum Biome {
     Alien,
 }
 
+impl Biome {
+    pub const ALL: [Biome; 6] = [Biome::River, Biome::Volcanic, Biome::Ocean, Biome::Sky, Biome::Space, Biome::Alien];
+
+    /// The name the game shows, e.g. "Volcanic".
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Biome::River => "River",
+            Biome::Volcanic => "Volcanic",
+            Biome::Ocean => "Ocean",
+            Biome::Sky => "Sky",
+            Biome::Space => "Space",
+            Biome::Alien => "Alien",
+        }
+    }
+
+    /// Resolves a biome as embeds, the DB or the user write it: case-insensitive, ignoring
+    /// custom emoji (`<:volcano:123>`), markdown, and words around it ("**Volcanic** Biome").
+    pub fn from_display_name(name: &str) -> Option<Biome> {
+        let without_emoji = CUSTOM_EMOJI.replace_all(name, " ");
+        let words: Vec<String> = without_emoji
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        Self::ALL.into_iter().find(|b| {
+            let wanted = b.display_name().to_lowercase();
+            words.iter().any(|w| *w == wanted || (b == &Biome::Volcanic && w == "volcano"))
+        })
+    }
+
+    /// The biome unlocked after this one, in progression order.
+    pub fn next(self) -> Option<Biome> {
+        match self {
+            Biome::River => Some(Biome::Volcanic),
+            Biome::Volcanic => Some(Biome::Ocean),
+            Biome::Ocean => Some(Biome::Sky),
+            Biome::Sky => Some(Biome::Space),
+            Biome::Space => Some(Biome::Alien),
+            Biome::Alien => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RodType {
     Plastic,
@@ -66,6 +110,49 @@ pub enum BaitType {
     SupportBait,
 }
 
+/// Timed shop boosts, bought with `[automation] more_fish` / `more_treasures`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Boost {
+    MoreFish,
+    MoreTreasures,
+}
+
+impl Boost {
+    pub const ALL: [Boost; 2] = [Boost::MoreFish, Boost::MoreTreasures];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Boost::MoreFish => "More Fish",
+            Boost::MoreTreasures => "More Treasures",
+        }
+    }
+
+    fn keyword(&self) -> &'static str {
+        match self {
+            Boost::MoreFish => "fish",
+            Boost::MoreTreasures => "treasure",
+        }
+    }
+
+    /// Shop id of the boost lasting `minutes`, e.g. "fish5m".
+    pub fn item_id(&self, minutes: u64) -> String {
+        format!("{}{}m", self.keyword(), minutes)
+    }
+
+    /// Recognizes a boost in a buff line or shop listing: "More Fish", "Treasure Boost (20m)", "fish5m".
+    pub fn from_text(text: &str) -> Option<Boost> {
+        let lower = text.to_lowercase();
+        Self::ALL.into_iter().find(|boost| {
+            let keyword = boost.keyword();
+            lower.contains(&boost.display_name().to_lowercase())
+                || (lower.contains(keyword) && lower.contains("boost"))
+                || lower.split(|c: char| !c.is_ascii_alphanumeric()).any(|word| word.strip_prefix(keyword)
+                    .and_then(|rest| rest.strip_suffix('m'))
+                    .is_some_and(|minutes| !minutes.is_empty() && minutes.chars().all(|c| c.is_ascii_digit())))
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TreasureQuality {
     Common,
@@ -79,6 +166,33 @@ pub enum TreasureQuality {
     Super,
 }
 
+impl TreasureQuality {
+    /// The chests a catch can bring up, rarest last.
+    pub const CHESTS: [TreasureQuality; 6] = [TreasureQuality::Common, TreasureQuality::Uncommon, TreasureQuality::Rare,
+        TreasureQuality::Epic, TreasureQuality::Legendary, TreasureQuality::Artifact];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            TreasureQuality::Common => "Common",
+            TreasureQuality::Uncommon => "Uncommon",
+            TreasureQuality::Rare => "Rare",
+            TreasureQuality::Epic => "Epic",
+            TreasureQuality::Legendary => "Legendary",
+            TreasureQuality::Artifact => "Artifact",
+            TreasureQuality::VoteUnder100 => "Vote",
+            TreasureQuality::VoteOver100 => "Vote 100+",
+            TreasureQuality::Super => "Super",
+        }
+    }
+
+    /// The chest quality a text names: "You found a **Rare** treasure!". "Uncommon" isn't "Common".
+    pub fn from_text(text: &str) -> Option<TreasureQuality> {
+        let lower = text.to_lowercase();
+        let words: Vec<&str> = lower.split(|c: char| !c.is_ascii_alphanumeric()).collect();
+        Self::CHESTS.into_iter().rev().find(|q| words.contains(&q.display_name().to_lowercase().as_str()))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum FishRarity {
     Common,
@@ -132,6 +246,8 @@ pub struct BiomeStats {
     pub catch_rate: f64,
     pub base_cooldown: f64, // Inferred as 3.0 for River
     pub unlock_cost: u64,
+    // No level requirement: the game gates biomes by level, but nothing here records the levels
+    // yet (the explorer keeps `/biomes` replies only as raw embeds), so travel isn't gated on it
 }
 
 #[derive(Debug, Clone)]
@@ -181,6 +297,8 @@ pub struct Pet {
 // --- Data Loading ---
 
 lazy_static! {
+    static ref CUSTOM_EMOJI: Regex = Regex::new(r"<a?:\w+:\d+>").unwrap();
+
     pub static ref FISH_DATA: HashMap<&'static str, Fish> = {
         let mut m = HashMap::new();
         // River
@@ -305,7 +423,7 @@ lazy_static! {
         // Artifact Magnet: XP +30% -> 1.3
         m.insert(BaitType::ArtifactMagnet, Bait { name: "Artifact Magnet", price: 75, fish_catch_bonus: -0.30, fish_quality_bonus: -0.30, treasure_chance_bonus: 0.40, treasure_quality_bonus: 0.50, xp_multiplier: 1.3, ..default });
         // Magic Bait: XP -20% -> 0.8
-        m.insert(BaitType::MagicBait, Bait { name: "Magic Bait", price: 250, fish_catch_bonus: 1.00, fish_quality_bonus: 0.50, treasure_chance_bonus: 0.15, treasure_quality_bonus: 0.15, xp_multiplier: 0.8, extra_fish_per_cast: 2.0, ..default });
+        m.insert(BaitType::MagicBait, Bait { name: "Magic Bait", price: 250, fish_catch_bonus: 1.00, fish_quality_bonus: 0.50, treasure_chance_bonus: 0.15, treasure_quality_bonus: 0.15, xp_multiplier: 0.8, extra_fish_per_cast: 2.0 });
         // Support Bait: Pet XP +35% (Not strictly global XP multiplier, but stored as 1.0 for global logic per instruction "Sanity Check")
         m.insert(BaitType::SupportBait, Bait { name: "Support Bait", price: 500, ..default });
         m
@@ -386,3 +504,32 @@ lazy_static! {
         m
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_biome_from_display_name() {
+        assert_eq!(Biome::from_display_name("Volcanic"), Some(Biome::Volcanic));
+        assert_eq!(Biome::from_display_name("  ocean "), Some(Biome::Ocean));
+        assert_eq!(Biome::from_display_name("<:volcano:8123> **Volcanic** Biome"), Some(Biome::Volcanic));
+        // The emoji name alone doesn't count: <:sky:1> next to "Alien" is the Alien biome
+        assert_eq!(Biome::from_display_name("<:sky:1> Alien"), Some(Biome::Alien));
+        assert_eq!(Biome::from_display_name("🌊 Ocean"), Some(Biome::Ocean));
+        assert_eq!(Biome::from_display_name("Swamp"), None);
+        for biome in Biome::ALL {
+            assert_eq!(Biome::from_display_name(&format!("{:?}", biome)), Some(biome));
+        }
+    }
+
+    #[test]
+    fn test_boost_from_text() {
+        assert_eq!(Boost::from_text("More Fish: 4m 30s left"), Some(Boost::MoreFish));
+        assert_eq!(Boost::from_text("Treasure Boost (20m)"), Some(Boost::MoreTreasures));
+        assert_eq!(Boost::from_text("fish5m"), Some(Boost::MoreFish));
+        assert_eq!(Boost::MoreTreasures.item_id(20), "treasure20m");
+        assert_eq!(Boost::from_text("Fish quality: 1.2x"), None);
+        assert_eq!(Boost::from_text("Fishing cooldown: 3s"), None);
+    }
+}
@@ -0,0 +1,96 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+/// A running bot that hasn't landed a catch for this long is considered stuck.
+const STALE_CAST_SECS: u64 = 10 * 60;
+
+/// Liveness signals recorded by the gateway and event processor, read by `/healthz`.
+pub struct HealthMonitor {
+    started_at: Instant,
+    gateway_connected: AtomicBool,
+    last_gateway_activity: Mutex<Option<Instant>>,
+    last_successful_cast: Mutex<Option<Instant>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub gateway_connected: bool,
+    pub last_gateway_activity_secs: Option<u64>,
+    pub last_successful_cast_secs: Option<u64>,
+    pub db_writable: bool,
+    pub bot_running: bool,
+    pub uptime_secs: u64,
+}
+
+impl Default for HealthMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HealthMonitor {
+    pub fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            gateway_connected: AtomicBool::new(false),
+            last_gateway_activity: Mutex::new(None),
+            last_successful_cast: Mutex::new(None),
+        }
+    }
+
+    pub fn set_gateway_connected(&self, connected: bool) {
+        self.gateway_connected.store(connected, Ordering::Relaxed);
+    }
+
+    pub fn record_gateway_activity(&self) {
+        *self.last_gateway_activity.lock() = Some(Instant::now());
+    }
+
+    pub fn record_successful_cast(&self) {
+        *self.last_successful_cast.lock() = Some(Instant::now());
+    }
+
+    /// `bot_running` is false while paused from the UI; a paused bot isn't expected to cast.
+    pub fn report(&self, db_writable: bool, bot_running: bool) -> HealthReport {
+        let gateway_connected = self.gateway_connected.load(Ordering::Relaxed);
+        let last_gateway_activity_secs = self.last_gateway_activity.lock().map(|t| t.elapsed().as_secs());
+        let last_successful_cast_secs = self.last_successful_cast.lock().map(|t| t.elapsed().as_secs());
+        let uptime_secs = self.started_at.elapsed().as_secs();
+
+        // Before the first catch, give the bot the same window measured from startup
+        let cast_age = last_successful_cast_secs.unwrap_or(uptime_secs);
+        let casting_ok = !bot_running || cast_age < STALE_CAST_SECS;
+
+        HealthReport {
+            healthy: gateway_connected && db_writable && casting_ok,
+            gateway_connected,
+            last_gateway_activity_secs,
+            last_successful_cast_secs,
+            db_writable,
+            bot_running,
+            uptime_secs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let health = HealthMonitor::new();
+        assert!(!health.report(true, true).healthy, "gateway not connected yet");
+
+        health.set_gateway_connected(true);
+        assert!(health.report(true, true).healthy, "fresh start is within the cast window");
+        assert!(!health.report(false, true).healthy);
+
+        *health.last_successful_cast.lock() = Instant::now().checked_sub(std::time::Duration::from_secs(STALE_CAST_SECS + 1));
+        assert!(!health.report(true, true).healthy);
+        assert!(health.report(true, false).healthy, "paused bots aren't expected to cast");
+    }
+}
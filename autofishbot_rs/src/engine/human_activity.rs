@@ -0,0 +1,70 @@
+use crate::config::HumanActivityConfig;
+use serde_json::Value;
+use std::time::Duration;
+
+/// A person (not a bot or webhook) talking in the fishing channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HumanActivity {
+    pub author: String,
+    /// Has one of `[human_activity] staff_roles`
+    pub staff: bool,
+}
+
+impl HumanActivity {
+    /// How long casting stops for this message.
+    pub fn pause(&self, config: &HumanActivityConfig) -> Duration {
+        let minutes = if self.staff { config.staff_pause_minutes } else { config.pause_minutes };
+        Duration::from_secs(minutes * 60)
+    }
+}
+
+/// Checks a MESSAGE_CREATE `d` for someone other than this account, bots, webhooks and
+/// `ignore_users` posting in `channel_id`.
+pub fn detect(d: &Value, channel_id: &str, own_user_id: Option<&str>, config: &HumanActivityConfig) -> Option<HumanActivity> {
+    if !config.enabled || d.get("channel_id").and_then(Value::as_str) != Some(channel_id) || d.get("webhook_id").is_some() {
+        return None;
+    }
+    let author = d.get("author")?;
+    let id = author.get("id").and_then(Value::as_str)?;
+    let is_bot = author.get("bot").and_then(Value::as_bool).unwrap_or(false);
+    if is_bot || Some(id) == own_user_id || config.ignore_users.iter().any(|u| u == id) {
+        return None;
+    }
+    let roles: Vec<&str> = d.pointer("/member/roles").and_then(Value::as_array)
+        .map(|roles| roles.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    Some(HumanActivity {
+        author: author.get("username").and_then(Value::as_str).unwrap_or(id).to_string(),
+        staff: roles.iter().any(|role| config.staff_roles.iter().any(|staff| staff == role)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_detect_human_activity() {
+        let config = HumanActivityConfig { staff_roles: vec!["900".to_string()], ignore_users: vec!["55".to_string()], ..Default::default() };
+        let message = |author: Value, roles: Value| json!({ "channel_id": "10", "author": author, "member": { "roles": roles }, "content": "hi" });
+
+        let player = message(json!({ "id": "2", "username": "angler" }), json!(["1"]));
+        assert_eq!(detect(&player, "10", Some("1"), &config), Some(HumanActivity { author: "angler".to_string(), staff: false }));
+        assert_eq!(detect(&player, "10", Some("1"), &config).unwrap().pause(&config), Duration::from_secs(config.pause_minutes * 60));
+
+        let moderator = message(json!({ "id": "3", "username": "mod" }), json!(["900"]));
+        let activity = detect(&moderator, "10", Some("1"), &config).unwrap();
+        assert!(activity.staff);
+        assert_eq!(activity.pause(&config), Duration::from_secs(config.staff_pause_minutes * 60));
+
+        // Our own messages, Virtual Fisher, ignored users and other channels don't count
+        assert_eq!(detect(&message(json!({ "id": "1" }), json!([])), "10", Some("1"), &config), None);
+        assert_eq!(detect(&message(json!({ "id": "574", "bot": true }), json!([])), "10", Some("1"), &config), None);
+        assert_eq!(detect(&message(json!({ "id": "55" }), json!([])), "10", Some("1"), &config), None);
+        assert_eq!(detect(&player, "11", Some("1"), &config), None);
+
+        let disabled = HumanActivityConfig { enabled: false, ..config.clone() };
+        assert_eq!(detect(&player, "10", Some("1"), &disabled), None);
+    }
+}
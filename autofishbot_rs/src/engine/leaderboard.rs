@@ -0,0 +1,89 @@
+use crate::engine::parser::Leaderboard;
+use serde::Serialize;
+
+/// Where the account stood on one leaderboard, as logged to `leaderboard_ranks`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Standing {
+    pub category: String,
+    pub rank: u32,
+    /// `None` when only "Your rank: #57" was shown
+    pub value: Option<u64>,
+    /// How far behind the player one rank up
+    pub gap_above: Option<u64>,
+    /// How far ahead of the player one rank down
+    pub gap_below: Option<u64>,
+}
+
+/// Finds the account on `board`, by mention or (case-insensitive) name, else by the board's
+/// "Your rank". `None` when it is on neither.
+pub fn standing(board: &Leaderboard, user_id: Option<&str>, username: Option<&str>) -> Option<Standing> {
+    let is_own = |name: &str, id: Option<&str>| {
+        (id.is_some() && id == user_id) || username.is_some_and(|u| name.trim_start_matches('@').eq_ignore_ascii_case(u))
+    };
+    let Some(index) = board.entries.iter().position(|e| is_own(&e.name, e.user_id.as_deref())) else {
+        return board.own_rank.map(|rank| Standing { category: board.category.clone(), rank, value: None, gap_above: None, gap_below: None });
+    };
+    let own = &board.entries[index];
+    let neighbour = |i: Option<usize>| i.and_then(|i| board.entries.get(i));
+    Some(Standing {
+        category: board.category.clone(),
+        rank: own.rank,
+        value: Some(own.value),
+        gap_above: neighbour(index.checked_sub(1)).map(|above| above.value.saturating_sub(own.value)),
+        gap_below: neighbour(Some(index + 1)).map(|below| own.value.saturating_sub(below.value)),
+    })
+}
+
+/// How a category's rank moved over a history, oldest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankTrend {
+    pub latest: Standing,
+    /// Places climbed since the oldest point; negative when dropping
+    pub change: i64,
+}
+
+/// One trend per category in `history` (`(unix seconds, standing)`, oldest first), in
+/// category order.
+pub fn trends(history: &[(i64, Standing)]) -> Vec<RankTrend> {
+    let mut categories: Vec<&str> = history.iter().map(|(_, s)| s.category.as_str()).collect();
+    categories.sort_unstable();
+    categories.dedup();
+    categories.into_iter().filter_map(|category| {
+        let mut points = history.iter().map(|(_, s)| s).filter(|s| s.category == category);
+        let first = points.next()?;
+        let latest = points.next_back().unwrap_or(first);
+        Some(RankTrend { latest: latest.clone(), change: first.rank as i64 - latest.rank as i64 })
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::parser::parse_leaderboard_embed;
+
+    #[test]
+    fn test_standing_and_trend() {
+        let board = parse_leaderboard_embed("🏆 Balance Leaderboard",
+            "**1.** Whale - $9,000,000\n`2` <@42> — **$1,250,000**\n3. Angler: $1,000,000\n4. Bob | 12k\nPage 1/10").unwrap();
+        assert_eq!(board.category, "Balance");
+        assert_eq!(board.entries.len(), 4);
+        assert_eq!(board.entries[1].user_id.as_deref(), Some("42"));
+        assert_eq!(board.entries[3].value, 12_000);
+
+        let by_id = standing(&board, Some("42"), None).unwrap();
+        assert_eq!((by_id.rank, by_id.value, by_id.gap_above, by_id.gap_below), (2, Some(1_250_000), Some(7_750_000), Some(250_000)));
+        let by_name = standing(&board, Some("7"), Some("angler")).unwrap();
+        assert_eq!((by_name.rank, by_name.gap_below), (3, Some(988_000)));
+        assert!(standing(&board, Some("7"), Some("someone")).is_none());
+
+        let below = parse_leaderboard_embed("Leaderboard - Level", "1. Whale - Level 300\nYour rank: #57").unwrap();
+        assert_eq!(standing(&below, None, Some("angler")).map(|s| (s.category, s.rank, s.value)), Some(("Level".to_string(), 57, None)));
+        assert!(parse_leaderboard_embed("You caught", "1. Cod - 3").is_none());
+
+        let history = vec![(1, by_name), (2, by_id.clone()), (3, standing(&below, None, None).unwrap())];
+        let trends = trends(&history);
+        assert_eq!(trends.len(), 2);
+        assert_eq!((trends[0].latest.category.as_str(), trends[0].latest.rank, trends[0].change), ("Balance", 2, 1));
+        assert_eq!((trends[1].latest.rank, trends[1].change), (57, 0));
+    }
+}
@@ -0,0 +1,49 @@
+/// How long a level took, from the `level_ups` log and the XP of the catches in between.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelPace {
+    /// The level reached
+    pub level: u32,
+    /// Unix seconds
+    pub reached_at: i64,
+    pub hours: f64,
+    pub xp: f64,
+}
+
+impl LevelPace {
+    pub fn xp_per_hour(&self) -> f64 {
+        if self.hours > 0.0 { self.xp / self.hours } else { 0.0 }
+    }
+}
+
+/// One pace per level-up after the first. `level_ups` is `(unix seconds, level)` and `catches`
+/// `(unix seconds, xp)` per cast, both oldest first; a catch counts towards the level-up at or
+/// after it.
+pub fn paces(level_ups: &[(i64, u32)], catches: &[(i64, f64)]) -> Vec<LevelPace> {
+    level_ups.windows(2).map(|pair| {
+        let ((from, _), (to, level)) = (pair[0], pair[1]);
+        LevelPace {
+            level,
+            reached_at: to,
+            hours: (to - from).max(0) as f64 / 3600.0,
+            xp: catches.iter().filter(|(at, _)| *at > from && *at <= to).map(|(_, xp)| xp).sum(),
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pace() {
+        let level_ups = [(0, 30), (7_200, 31), (9_000, 32)];
+        let catches = [(0, 500.0), (3_600, 1_000.0), (7_200, 1_000.0), (8_000, 300.0), (9_500, 50.0)];
+        let paces = paces(&level_ups, &catches);
+        assert_eq!(paces.len(), 2);
+        assert_eq!((paces[0].level, paces[0].hours, paces[0].xp), (31, 2.0, 2_000.0));
+        assert_eq!(paces[0].xp_per_hour(), 1_000.0);
+        assert_eq!((paces[1].level, paces[1].xp), (32, 300.0));
+        assert_eq!(paces[1].xp_per_hour(), 600.0);
+        assert!(super::paces(&level_ups[..1], &catches).is_empty());
+    }
+}
@@ -1,6 +1,8 @@
 use crate::engine::game_data::*;
+use crate::engine::parser::DropKind;
 use regex::Regex;
 use lazy_static::lazy_static;
+use std::collections::HashMap;
 
 // --- Constants & Configuration ---
 
@@ -15,6 +17,8 @@ const VAL_DIAMOND_FISH: f64 = 5000.0;
 const VAL_CHARM: f64 = 10_000.0;
 const VAL_SUPER_CRATE: f64 = 50_000.0;
 const VAL_MONEY_UNIT: f64 = 1.0; // Base value of $1
+/// How many opens the hardcoded drop chances count for when blended with observed ones
+const TREASURE_PRIOR_OPENS: f64 = 20.0;
 
 lazy_static! {
     static ref BENEFIT_PERCENTAGE_PATTERN: Regex = Regex::new(r"(\d+(\.\d+)?)%").unwrap();
@@ -96,25 +100,42 @@ pub fn calculate_cooldown(
     t_total.max(0.0)
 }
 
+/// What opened chests of one quality actually held, summed from `treasure_history`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TreasureObservations {
+    pub opens: u64,
+    /// Total amount per kind over all `opens`
+    pub totals: HashMap<DropKind, u64>,
+}
+
 /// Calculates the expected monetary value (EV) of a treasure chest.
 /// Accounts for conditional probabilities like Artifact charm counts.
-pub fn get_treasure_ev(tier: TreasureQuality) -> f64 {
+///
+/// With `observed` chests, each kind's amount per open is blended with the hardcoded chance,
+/// which counts for `TREASURE_PRIOR_OPENS` opens: a few chests nudge the estimate, hundreds
+/// replace it.
+pub fn get_treasure_ev(tier: TreasureQuality, observed: Option<&TreasureObservations>) -> f64 {
     let drop = &TREASURE_DATA[&tier];
 
-    // Calculate EV of contents
-    // EV = Sum(Probability * Value)
-
-    let ev_contents =
-        (drop.chance_gold_fish * VAL_GOLD_FISH) +
-        (drop.chance_emerald_fish * VAL_EMERALD_FISH) +
-        (drop.chance_lava_fish * VAL_LAVA_FISH) +
-        (drop.chance_diamond_fish * VAL_DIAMOND_FISH) +
-        (drop.chance_charm * VAL_CHARM * drop.expected_charms_count) + // Adjusted for expected count (e.g. 2.0 for Artifact)
-        (drop.chance_super_crate * VAL_SUPER_CRATE) +
-        (drop.chance_money_xp * VAL_MONEY_UNIT * 1000.0);
-
-    // Apply the Treasure Quality Multiplier (e.g. 1.4x for Rare)
-    ev_contents * drop.multiplier
+    // EV = Sum(Amount per open * Value), the Treasure Quality Multiplier (e.g. 1.4x for Rare)
+    // scaling the hardcoded amounts
+    let kinds = [
+        (DropKind::GoldFish, drop.chance_gold_fish, VAL_GOLD_FISH),
+        (DropKind::EmeraldFish, drop.chance_emerald_fish, VAL_EMERALD_FISH),
+        (DropKind::LavaFish, drop.chance_lava_fish, VAL_LAVA_FISH),
+        (DropKind::DiamondFish, drop.chance_diamond_fish, VAL_DIAMOND_FISH),
+        // Adjusted for expected count (e.g. 2.0 for Artifact)
+        (DropKind::Charm, drop.chance_charm * drop.expected_charms_count, VAL_CHARM),
+        (DropKind::Crate, drop.chance_super_crate, VAL_SUPER_CRATE),
+        (DropKind::Money, drop.chance_money_xp * 1000.0, VAL_MONEY_UNIT),
+    ];
+    let opens = observed.map_or(0, |o| o.opens) as f64;
+    kinds.iter().map(|(kind, chance, value)| {
+        let prior = chance * drop.multiplier;
+        let seen = observed.and_then(|o| o.totals.get(kind)).copied().unwrap_or(0) as f64;
+        let per_open = (prior * TREASURE_PRIOR_OPENS + seen) / (TREASURE_PRIOR_OPENS + opens);
+        per_open * value
+    }).sum()
 }
 
 // --- Optimization Heuristic ---
@@ -142,18 +163,16 @@ fn estimate_upgrade_benefit(upgrade: &Upgrade) -> f64 {
     let desc_lower = upgrade.description.to_lowercase();
     let weight = if desc_lower.contains("sell price") {
         1.0 // Direct income boost
-    } else if desc_lower.contains("fish quality") {
-        0.8 // Increases average sell price, but not strictly 1:1
-    } else if desc_lower.contains("fish catch") {
-        0.8 // More fish = more money, but limited by cooldown/cast time
+    } else if desc_lower.contains("fish quality") || desc_lower.contains("fish catch") {
+        // Quality raises the average sell price, catch adds fish limited by cooldown/cast time
+        0.8
     } else if desc_lower.contains("treasure chance") {
         0.3 // Treasure is valuable but occasional
     } else if desc_lower.contains("treasure rewards") || desc_lower.contains("treasure quality") {
         0.2 // Higher value treasure
-    } else if desc_lower.contains("xp gain") {
-        0.1 // Helps unlocking biomes, but low direct income value
-    } else if desc_lower.contains("consuming bait") {
-        0.1 // Saves costs
+    } else if desc_lower.contains("xp gain") || desc_lower.contains("consuming bait") {
+        // XP helps unlocking biomes and bait savings cut costs, both low direct income value
+        0.1
     } else if desc_lower.contains("daily rewards") {
         0.05 // Minor bonus
     } else {
@@ -196,14 +215,7 @@ pub fn calculate_next_best_action(state: &GameState) -> Action {
     }
 
     // 3. Evaluate "Unlock Next Biome"
-    let next_biome = match state.current_biome {
-        Biome::River => Some(Biome::Volcanic),
-        Biome::Volcanic => Some(Biome::Ocean),
-        Biome::Ocean => Some(Biome::Sky),
-        Biome::Sky => Some(Biome::Space),
-        Biome::Space => Some(Biome::Alien),
-        Biome::Alien => None,
-    };
+    let next_biome = state.current_biome.next();
 
     let mut best_action = Action::SaveMoney;
     let mut max_utility = max_upgrade_utility;
@@ -274,6 +286,17 @@ fn get_fish_in_biome(biome: Biome) -> Vec<&'static Fish> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_treasure_ev_calibration() {
+        let prior = get_treasure_ev(TreasureQuality::Rare, None);
+        assert!((prior - get_treasure_ev(TreasureQuality::Rare, Some(&TreasureObservations::default()))).abs() < 1e-9);
+
+        // 180 chests that only ever held $500: 90% observed, 10% prior
+        let observed = TreasureObservations { opens: 180, totals: HashMap::from([(DropKind::Money, 90_000)]) };
+        let calibrated = get_treasure_ev(TreasureQuality::Rare, Some(&observed));
+        assert!((calibrated - (prior * 0.1 + 500.0 * 0.9)).abs() < 1e-6, "{} vs {}", calibrated, prior);
+    }
+
     #[test]
     fn test_biome_unlock_comparison() {
         // Setup a state where unlocking the biome is clearly the best option
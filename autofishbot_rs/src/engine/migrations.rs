@@ -0,0 +1,133 @@
+use anyhow::{bail, Result};
+use log::info;
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+
+/// A numbered schema change from `migrations/`, applied once and never undone.
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+/// Every migration, oldest first. A schema change is a new `NNNN_name.sql` with the next
+/// version added at the end; one that has shipped is never edited.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "initial", sql: include_str!("../../migrations/0001_initial.sql") },
+    Migration { version: 2, name: "treasure_opens", sql: include_str!("../../migrations/0002_treasure_opens.sql") },
+];
+
+/// Columns builds from before `schema_version` added with ALTER TABLE after creating the
+/// table, so their databases may lack them: `(table, column, type)`.
+const LEGACY_COLUMNS: &[(&str, &str, &str)] = &[
+    ("fish", "sell_value", "REAL"),
+    ("shop_items", "stats", "TEXT"),
+    ("cooldown_events", "biome", "TEXT"),
+    ("command_registry", "command_structure", "TEXT"),
+    ("daily_claims", "reward", "INTEGER"),
+    ("daily_claims", "streak", "INTEGER"),
+];
+
+/// Applies the migrations newer than the database's `schema_version`, each in its own
+/// transaction, and returns the version reached. Refuses a database from a newer build.
+pub async fn run(pool: &Pool<Sqlite>) -> Result<u32> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    let current: u32 = sqlx::query("SELECT COALESCE(MAX(version), 0) AS version FROM schema_version")
+        .fetch_one(pool)
+        .await?
+        .get::<i64, _>("version") as u32;
+    let latest = MIGRATIONS.last().map_or(0, |m| m.version);
+    if current > latest {
+        bail!("database schema version {} is newer than this build's {}; run a newer build", current, latest);
+    }
+    // Tables without a `schema_version` row come from before versioning
+    let legacy = current == 0 && sqlx::query("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'catch_history'")
+        .fetch_optional(pool)
+        .await?
+        .is_some();
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let mut tx = pool.begin().await?;
+        sqlx::query(migration.sql).execute(&mut *tx).await?;
+        if migration.version == 1 && legacy {
+            add_legacy_columns(&mut tx).await?;
+        }
+        sqlx::query("INSERT INTO schema_version (version, name) VALUES (?, ?)")
+            .bind(migration.version)
+            .bind(migration.name)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        info!("Applied database migration {:04}_{}", migration.version, migration.name);
+    }
+    Ok(latest)
+}
+
+async fn add_legacy_columns(conn: &mut SqliteConnection) -> Result<()> {
+    for &(table, column, kind) in LEGACY_COLUMNS {
+        let exists = sqlx::query("SELECT 1 FROM pragma_table_info(?) WHERE name = ?")
+            .bind(table)
+            .bind(column)
+            .fetch_optional(&mut *conn)
+            .await?
+            .is_some();
+        if !exists {
+            sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, kind)).execute(&mut *conn).await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn memory_pool() -> Pool<Sqlite> {
+        SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap()
+    }
+
+    async fn columns(pool: &Pool<Sqlite>, table: &str) -> Vec<String> {
+        sqlx::query("SELECT name FROM pragma_table_info(?)").bind(table).fetch_all(pool).await.unwrap()
+            .into_iter().map(|r| r.get("name")).collect()
+    }
+
+    #[tokio::test]
+    async fn test_migrations() {
+        assert!(MIGRATIONS.iter().enumerate().all(|(i, m)| m.version as usize == i + 1), "versions must run 1, 2, 3...");
+        let latest = MIGRATIONS.last().unwrap().version;
+
+        let fresh = memory_pool().await;
+        assert_eq!(run(&fresh).await.unwrap(), latest);
+        assert_eq!(run(&fresh).await.unwrap(), latest);
+        assert!(columns(&fresh, "shop_items").await.contains(&"stats".to_string()));
+        assert!(!columns(&fresh, "level_ups").await.is_empty(), "every statement of a script runs");
+        let applied: i64 = sqlx::query("SELECT COUNT(*) AS n FROM schema_version").fetch_one(&fresh).await.unwrap().get("n");
+        assert_eq!(applied, MIGRATIONS.len() as i64);
+
+        // A database from before versioning, created before `cooldown_events.biome` existed
+        let legacy = memory_pool().await;
+        sqlx::raw_sql("CREATE TABLE catch_history (id INTEGER PRIMARY KEY, fish_name TEXT);
+            CREATE TABLE cooldown_events (id INTEGER PRIMARY KEY, wait_time REAL, total_cooldown REAL);
+            INSERT INTO cooldown_events (wait_time, total_cooldown) VALUES (1.5, 3.0);")
+            .execute(&legacy).await.unwrap();
+        assert_eq!(run(&legacy).await.unwrap(), latest);
+        assert!(columns(&legacy, "cooldown_events").await.contains(&"biome".to_string()));
+        let kept: i64 = sqlx::query("SELECT COUNT(*) AS n FROM cooldown_events").fetch_one(&legacy).await.unwrap().get("n");
+        assert_eq!(kept, 1);
+
+        sqlx::query("INSERT INTO schema_version (version, name) VALUES (?, 'from_the_future')").bind(latest + 1)
+            .execute(&fresh).await.unwrap();
+        assert!(run(&fresh).await.is_err());
+    }
+}
@@ -1,13 +1,37 @@
 // Placeholder for engine module
+pub mod bankroll;
 pub mod bot;
+pub mod breaks;
 pub mod captcha;
+pub mod capture;
+pub mod channels;
+pub mod config_watcher;
+pub mod correlation;
+pub mod health;
+pub mod human_activity;
+pub mod watchdog;
+pub mod events;
+pub mod accounts;
+pub mod update_check;
 pub mod scheduler;
+pub mod sell;
+pub mod session_replay;
+pub mod seasonal;
 pub mod state;
+pub mod streak;
 pub mod profile;
 pub mod database;
 pub mod parser;
 pub mod cooldown;
 pub mod explorer;
+pub mod fishdex;
 pub mod game_data;
+pub mod leaderboard;
+pub mod levels;
 pub mod mechanics;
+pub mod migrations;
+pub mod net_worth;
 pub mod optimizer;
+pub mod playbook;
+pub mod recovery;
+pub mod valuation;
@@ -0,0 +1,54 @@
+use crate::engine::game_data::{BOAT_DATA, ROD_DATA};
+use crate::engine::profile::Profile;
+use crate::engine::valuation::InventoryValuation;
+use serde::Serialize;
+
+/// Balance plus what the inventory and equipment are worth, recorded every
+/// `[net_worth] interval_minutes` in `net_worth_snapshots`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct NetWorth {
+    pub balance: u64,
+    pub inventory: u64,
+    /// The equipped rod and the boats owned, at `asset_value_ratio` of their shop prices
+    pub assets: u64,
+}
+
+impl NetWorth {
+    /// `None` until a profile embed has shown the balance.
+    pub fn of(profile: &Profile, valuation: Option<&InventoryValuation>, asset_value_ratio: f64) -> Option<Self> {
+        let balance = profile.balance?;
+        let inventory = valuation.map(|v| v.total)
+            .or(profile.inventory_value)
+            .unwrap_or(0);
+        let rod_price = ROD_DATA.values().find(|r| r.name.eq_ignore_ascii_case(&profile.rod)).map(|r| r.price).unwrap_or(0);
+        let fleet_price: u64 = profile.current_boat()
+            .map(|boat| BOAT_DATA.values().filter(|b| b.price <= boat.price).map(|b| b.price).sum())
+            .unwrap_or(0);
+        let assets = ((rod_price + fleet_price) as f64 * asset_value_ratio.clamp(0.0, 1.0)) as u64;
+        Some(Self { balance, inventory, assets })
+    }
+
+    pub fn total(&self) -> u64 {
+        self.balance + self.inventory + self.assets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_net_worth() {
+        let mut profile = Profile { rod: "Steel Rod".to_string(), inventory_value: Some(2_000), ..Default::default() };
+        assert_eq!(NetWorth::of(&profile, None, 1.0), None);
+
+        profile.balance = Some(10_000);
+        let worth = NetWorth::of(&profile, None, 0.5).unwrap();
+        assert_eq!(worth, NetWorth { balance: 10_000, inventory: 2_000, assets: 4_000 });
+        assert_eq!(worth.total(), 16_000);
+
+        // The running valuation is fresher than the profile's inventory line
+        let valuation = InventoryValuation { total: 3_500, unpriced: Vec::new() };
+        assert_eq!(NetWorth::of(&profile, Some(&valuation), 1.0).unwrap().inventory, 3_500);
+    }
+}
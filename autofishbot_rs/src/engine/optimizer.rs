@@ -1,6 +1,12 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::engine::game_data::{Rod, Boat, Biome, ROD_DATA, BOAT_DATA, BIOME_DATA, UPGRADE_DATA, UpgradeCurrency};
+use crate::config::{CharmsConfig, StrategyGoal};
+use crate::engine::bankroll::Bankroll;
+use crate::engine::fishdex::Fishdex;
+use crate::engine::game_data::{Bait, Rod, Boat, Biome, RodType, BoatType, TreasureQuality, ROD_DATA, BOAT_DATA, BIOME_DATA, BAIT_DATA, FISH_DATA, UPGRADE_DATA, UpgradeCurrency};
+use crate::engine::mechanics::{self, TreasureObservations};
 use crate::engine::profile::{Profile, CharmType};
+use crate::engine::database::BiomeCatches;
 
 #[derive(Debug, Default, Clone)]
 pub struct BiomeStats {
@@ -25,20 +31,41 @@ impl BiomeStats {
 
 pub struct Optimizer {
     pub biome_knowledge: HashMap<Biome, BiomeStats>,
+    /// What `calculate_metrics` measures, see `StrategyGoal`
+    pub goal: StrategyGoal,
+    /// Sizes the coinflips that bridge a long grind
+    pub bankroll: Bankroll,
+    /// Thresholds for `pick_charm`
+    pub charms: CharmsConfig,
+    /// Species still to catch, for `CompleteFishdex`
+    pub fishdex: Fishdex,
+    /// Opened chests per quality, from `Database::treasure_observations`
+    pub treasure: HashMap<TreasureQuality, TreasureObservations>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// A point where a charm could pay off, for `Optimizer::pick_charm`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CharmMoment {
+    /// Casting is about to go on this long without a break
+    Grind { seconds: f64 },
+    /// An inventory worth this much is about to be sold
+    Sell { value: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ActionType {
     BuyRod,
     BuyBoat,
     Travel,
     BuyUpgrade,
+    /// A League upgrade; `Recommendation::cost` is in Hooks, not gold
+    BuyLeagueUpgrade,
     Sell,
     Wait,
     Coinflip { amount: u64, reason: String },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Recommendation {
     pub action: ActionType,
     pub target_name: String,
@@ -46,9 +73,83 @@ pub struct Recommendation {
     pub roi_seconds: f64,
 }
 
+/// A biome's recent catches next to what the optimizer expects from it, for the Stats tab.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BiomeComparison {
+    pub biome: Biome,
+    /// Fish caught in the chart window, from `catch_history`
+    pub fish: i64,
+    /// Average sell value of those fish
+    pub avg_value: f64,
+    /// All-time average the optimizer learned (`biome_stats`), 0 before any catch
+    pub learned_avg: f64,
+    /// `calculate_metrics` for the current rod and boat
+    pub expected_gps: f64,
+    /// Highest `expected_gps`, i.e. where the optimizer would send the bot
+    pub preferred: bool,
+}
+
+/// Cooldown cut by owning `boat`: boats are bought cheapest first and each one takes its
+/// `cooldown_reduction` off ("Each boat decreases your cooldown by 0.25s").
+pub fn fleet_reduction(boat: &Boat) -> f64 {
+    BOAT_DATA.values().filter(|b| b.price <= boat.price).map(|b| b.cooldown_reduction).sum()
+}
+
+/// The boat after `boat` in the shop, `None` once the fleet is complete.
+pub fn next_boat(boat: &Boat) -> Option<&'static Boat> {
+    BOAT_DATA.values().filter(|b| b.price > boat.price).min_by_key(|b| b.price)
+}
+
+/// Prestiging pays off when its boost out-earns the lost gear within this long.
+const PRESTIGE_HORIZON_SECS: f64 = 48.0 * 3600.0;
+/// Azure Fish handed out per prestige.
+const AZURE_FISH_PER_PRESTIGE: f64 = 1.0;
+
+/// Whether prestiging now beats fishing on, from `Optimizer::evaluate_prestige`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrestigePlan {
+    /// Gold income the Azure Fish buy, e.g. 0.4 for +40%
+    pub boost: f64,
+    /// Time to earn the current rod back after the reset
+    pub recovery_seconds: f64,
+    pub worthwhile: bool,
+}
+
+impl Default for Optimizer {
+    fn default() -> Self { Self::new() }
+}
+
 impl Optimizer {
-    pub fn new() -> Self { Self { biome_knowledge: HashMap::new() } }
+    pub fn new() -> Self {
+        Self {
+            biome_knowledge: HashMap::new(),
+            goal: StrategyGoal::default(),
+            bankroll: Bankroll::default(),
+            charms: CharmsConfig::default(),
+            fishdex: Fishdex::default(),
+            treasure: HashMap::new(),
+        }
+    }
 
+    pub fn with_goal(mut self, goal: StrategyGoal) -> Self {
+        self.goal = goal;
+        self
+    }
+
+    pub fn with_bankroll(mut self, bankroll: Bankroll) -> Self {
+        self.bankroll = bankroll;
+        self
+    }
+
+    pub fn with_charms(mut self, charms: CharmsConfig) -> Self {
+        self.charms = charms;
+        self
+    }
+
+    /// Progress per second toward `goal`: gold, XP, fish weighted by the biome's uncaught
+    /// species count, or treasures. Only `MaxGold` is gold, so coinflips and prestiges are judged on it alone.
+    /// Under `MaxGold` the workers' fish count too, sold at the same price, and so do chests once
+    /// some have been opened (`chest_value`).
     pub fn calculate_metrics(&self, rod: &Rod, boat: &Boat, biome: Biome, profile: &Profile) -> f64 {
         let stats = self.biome_knowledge.get(&biome);
         let avg_val = stats.map(|s| s.avg_gold_per_fish).unwrap_or(15.0);
@@ -68,7 +169,7 @@ impl Optimizer {
         // Note: Buffs in profile are strings (e.g., "+15%"). Parsing them here or in Profile::get_charm_bonus
         // We stick to charm_bonus for now as it's cleaner.
 
-        let (pet_catch, _pet_xp) = profile.get_pet_mults();
+        let (pet_catch, pet_xp) = profile.get_pet_mults();
 
         // Apply multipliers
         // GPS = (Base_Yield * (1.0 + Charm% + Pet% + Buff%)) / Cooldown
@@ -76,42 +177,67 @@ impl Optimizer {
         let total_val = avg_val * (1.0 + sell_bonus);
         let total_fish = rod.expected_fish * biome_data.catch_rate * (1.0 + catch_bonus + pet_catch);
 
-        let boat_cd = boat.cooldown_reduction;
+        let boat_cd = fleet_reduction(boat);
         let haste_reduction = base_cd * cooldown_bonus;
 
         let total_cd = (base_cd + biome_data.cooldown_penalty - boat_cd - haste_reduction).max(2.0);
 
-        (total_fish * total_val) / total_cd
+        let per_cast = match self.goal {
+            StrategyGoal::MaxGold => total_fish * total_val + rod.treasure_chance * self.chest_value().unwrap_or(0.0),
+            StrategyGoal::MaxXp => total_fish * self.avg_xp(biome) * (1.0 + pet_xp + profile.get_charm_bonus(CharmType::Experience)),
+            StrategyGoal::CompleteFishdex => total_fish * self.fishdex.missing_in(biome) as f64,
+            StrategyGoal::FarmCharms => rod.treasure_chance * (1.0 + rod.treasure_quality_bonus + profile.get_charm_bonus(CharmType::Treasure)),
+        };
+        let idle = match self.goal {
+            StrategyGoal::MaxGold => profile.workers.fish_per_hour.unwrap_or(0) as f64 / 3600.0 * total_val,
+            _ => 0.0,
+        };
+        per_cast / total_cd + idle
     }
 
-    fn evaluate_risk_asymmetry(&self, current_gold: u64, target_cost: u64, gps: f64) -> Option<u64> {
-        if gps <= 0.0 { return None; }
-        if current_gold >= target_cost { return None; }
-
-        let time_to_grind = (target_cost - current_gold) as f64 / gps;
-        let four_hours = 4.0 * 3600.0;
-
-        if time_to_grind > four_hours {
-            // "Bridge Bet": Bet exactly what we need
-            let needed = target_cost - current_gold;
-            // Can't bet more than we have
-            if needed > current_gold { return None; }
-
-            // Asymmetry Check: Win Time Saved > 3 * Loss Time Recovery
-            // Time Saved = Time to grind 'needed' amount
-            // let time_saved = needed as f64 / gps;
+    /// Average gold a chest is worth: each quality's calibrated `get_treasure_ev`, weighted by
+    /// how often it was opened. `None` before any chest, when the mix of qualities is unknown.
+    pub fn chest_value(&self) -> Option<f64> {
+        let opened: Vec<(TreasureQuality, u64)> = TreasureQuality::CHESTS.iter()
+            .filter_map(|q| self.treasure.get(q).map(|o| (*q, o.opens)))
+            .filter(|(_, opens)| *opens > 0)
+            .collect();
+        let total: u64 = opened.iter().map(|(_, opens)| opens).sum();
+        (total > 0).then(|| opened.iter()
+            .map(|(q, opens)| mechanics::get_treasure_ev(*q, self.treasure.get(q)) * *opens as f64)
+            .sum::<f64>() / total as f64)
+    }
 
-            // Loss Time Recovery = Time to grind 'needed' amount (to get back to current_gold)
-            // This is 1:1.
-            // However, implementing the "Game Theory" request strictly:
-            // Maybe user implies "Win Time Saved" includes value of acquiring item earlier?
-            // I will relax the check to allow the feature to work for demonstration
-            // or assume high risk tolerance for long grinds.
+    /// Learned XP per fish in `biome`, else the catalog average for its species.
+    fn avg_xp(&self, biome: Biome) -> f64 {
+        if let Some(avg) = self.biome_knowledge.get(&biome).map(|s| s.avg_xp_per_fish).filter(|avg| *avg > 0.0) {
+            return avg;
+        }
+        let (sum, count) = FISH_DATA.values().filter(|f| f.biomes.contains(&biome))
+            .fold((0.0, 0), |(sum, count), f| (sum + f.xp as f64, count + 1));
+        if count > 0 { sum / count as f64 } else { 1.0 }
+    }
 
-            // Let's assume if grind > 4h, we accept 1:1 risk because "Time Utility" of 4h block is high cost.
-            return Some(needed);
+    /// Every biome's actual catches against the optimizer's expectation, in `Biome::ALL` order.
+    pub fn compare_biomes(&self, catches: &[BiomeCatches], rod: &Rod, boat: &Boat, profile: &Profile) -> Vec<BiomeComparison> {
+        let mut rows: Vec<BiomeComparison> = Biome::ALL.iter().map(|&biome| {
+            let (fish, gold) = catches.iter()
+                .filter(|c| Biome::from_display_name(&c.biome) == Some(biome))
+                .fold((0, 0), |(fish, gold), c| (fish + c.fish, gold + c.gold));
+            BiomeComparison {
+                biome,
+                fish,
+                avg_value: if fish > 0 { gold as f64 / fish as f64 } else { 0.0 },
+                learned_avg: self.biome_knowledge.get(&biome).map(|s| s.avg_gold_per_fish).unwrap_or(0.0),
+                expected_gps: self.calculate_metrics(rod, boat, biome, profile),
+                preferred: false,
+            }
+        }).collect();
+        let best = rows.iter().map(|r| r.expected_gps).fold(f64::MIN, f64::max);
+        if let Some(row) = rows.iter_mut().find(|r| r.expected_gps >= best) {
+            row.preferred = true;
         }
-        None
+        rows
     }
 
     pub fn solve_next_move(&self, current_rod: &Rod, current_boat: &Boat, current_biome: Biome, profile: &Profile, current_gold: u64) -> Vec<Recommendation> {
@@ -132,7 +258,7 @@ impl Optimizer {
                     let roi = if gain > 0.0 { cost as f64 / gain } else { f64::INFINITY };
 
                     // Coinflip Check
-                    if let Some(bet) = self.evaluate_risk_asymmetry(current_gold, cost, current_gps) {
+                    if let Some(bet) = self.gold_gps(current_gps).and_then(|gps| self.bankroll.bridge_bet(current_gold, cost, gps)) {
                          recommendations.push(Recommendation {
                             action: ActionType::Coinflip { amount: bet, reason: format!("Bridge gap for {}", rod.name) },
                             target_name: "Heads".to_string(),
@@ -151,16 +277,16 @@ impl Optimizer {
             }
         }
 
-        // Evaluate Boats
-        for boat in BOAT_DATA.values() {
-             if boat.price > current_boat.price {
+        // Evaluate Boats: they're bought in order, so only the next one is for sale
+        if let Some(boat) = next_boat(current_boat) {
+             {
                 let new_gps = self.calculate_metrics(current_rod, boat, current_biome, profile);
                 if new_gps > current_gps {
                     let cost = boat.price;
                     let gain = new_gps - current_gps;
                     let roi = if gain > 0.0 { cost as f64 / gain } else { f64::INFINITY };
 
-                    if let Some(bet) = self.evaluate_risk_asymmetry(current_gold, cost, current_gps) {
+                    if let Some(bet) = self.gold_gps(current_gps).and_then(|gps| self.bankroll.bridge_bet(current_gold, cost, gps)) {
                          recommendations.push(Recommendation {
                             action: ActionType::Coinflip { amount: bet, reason: format!("Bridge gap for {}", boat.name) },
                             target_name: "Heads".to_string(),
@@ -181,8 +307,9 @@ impl Optimizer {
 
         // Evaluate Upgrades
         for upgrade in UPGRADE_DATA.values() {
-             if upgrade.currency != UpgradeCurrency::Money {
-                 continue; // Only optimize Money upgrades for now
+             // Fish-currency upgrades aren't tracked yet
+             if !matches!(upgrade.currency, UpgradeCurrency::Money | UpgradeCurrency::Hooks) {
+                 continue;
              }
 
              // Check current status
@@ -207,12 +334,28 @@ impl Optimizer {
                  upgrade.max_cost / upgrade.max_level as u64
              };
 
+             // Hooks only buy League upgrades, so they never compete with gold purchases;
+             // just skip the ones we can't afford yet
+             if upgrade.currency == UpgradeCurrency::Hooks && cost > profile.hooks {
+                 continue;
+             }
+
              // Estimate Benefit
              // We reuse the heuristic logic: Salesman ~5% income boost, others ~1%
              // This is a simplification but enables the recommendation engine.
-             let estimated_boost = if upgrade.name.contains("Salesman") || upgrade.name.contains("Business") {
+             // Other goals favour the upgrades whose description names what they maximize.
+             let focus: &[&str] = match self.goal {
+                 StrategyGoal::MaxGold => &[],
+                 StrategyGoal::MaxXp => &["XP"],
+                 StrategyGoal::CompleteFishdex => &["Fish Catch"],
+                 StrategyGoal::FarmCharms => &["Charms", "Treasure"],
+             };
+             let estimated_boost = if !focus.is_empty() {
+                 if focus.iter().any(|word| upgrade.description.contains(word)) { 0.05 } else { 0.01 }
+             } else if upgrade.name.contains("Salesman") || upgrade.name.contains("Business") {
                  0.05
-             } else if upgrade.name.contains("Efficiency") || upgrade.name.contains("Motivation") {
+             } else if upgrade.name.contains("Efficiency") || upgrade.name.contains("Motivation")
+                 || upgrade.name.contains("Duplicator") || upgrade.name.contains("Bait Helper") {
                  0.02
              } else {
                  0.01
@@ -226,7 +369,7 @@ impl Optimizer {
                  let roi = cost as f64 / gain;
 
                  recommendations.push(Recommendation {
-                    action: ActionType::BuyUpgrade,
+                    action: if upgrade.currency == UpgradeCurrency::Hooks { ActionType::BuyLeagueUpgrade } else { ActionType::BuyUpgrade },
                     target_name: upgrade.name.to_string(),
                     cost,
                     roi_seconds: roi,
@@ -249,7 +392,353 @@ impl Optimizer {
             }
         }
 
-        recommendations.sort_by(|a, b| a.roi_seconds.partial_cmp(&b.roi_seconds).unwrap_or(std::cmp::Ordering::Equal));
+        // Tie-break on name so equal ROIs (e.g. several free travels) don't depend on HashMap order
+        recommendations.sort_by(|a, b| {
+            a.roi_seconds.partial_cmp(&b.roi_seconds).unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.target_name.cmp(&b.target_name))
+        });
         recommendations
     }
+
+    /// Weighs a prestige: the reset drops the rod back to Plastic, and the Azure Fish it pays
+    /// go into the best unmaxed prestige upgrade. Continuing earns `gps * H` over the horizon;
+    /// prestiging spends the recovery time re-buying the rod, then earns `(1 + boost) * gps`.
+    pub fn evaluate_prestige(&self, rod: &Rod, boat: &Boat, biome: Biome, profile: &Profile) -> PrestigePlan {
+        let per_fish = UPGRADE_DATA.values()
+            .filter(|u| u.currency == UpgradeCurrency::AzureFish)
+            .filter(|u| profile.upgrades.get(u.name).map(|s| s.level).unwrap_or(0) < u.max_level)
+            .map(|u| {
+                // Per tier, for gold; Ancient One only adds XP
+                if u.name.contains("Business") { 0.40 }
+                else if u.name.contains("Whisperer") { 0.25 }
+                else if u.name.contains("Ties") || u.name.contains("Virtual Fisher") { 0.10 }
+                else { 0.0 }
+            })
+            .fold(0.0, f64::max);
+        let boost = per_fish * AZURE_FISH_PER_PRESTIGE;
+
+        let start_rod = ROD_DATA.get(&RodType::Plastic).unwrap_or(rod);
+        let start_boat = BOAT_DATA.get(&BoatType::Rowboat).unwrap_or(boat);
+        let reset_gps = self.calculate_metrics(start_rod, start_boat, biome, profile) * (1.0 + boost);
+        let rebuy = rod.price.saturating_sub(start_rod.price) as f64;
+        let recovery_seconds = if rebuy <= 0.0 { 0.0 } else if reset_gps > 0.0 { rebuy / reset_gps } else { f64::INFINITY };
+
+        let worthwhile = boost > 0.0 && (1.0 + boost) * (PRESTIGE_HORIZON_SECS - recovery_seconds) > PRESTIGE_HORIZON_SECS;
+        PrestigePlan { boost, recovery_seconds, worthwhile }
+    }
+
+    /// The charm worth using at `moment`: Marketing before a sell of `min_sell_value` or more,
+    /// and before a grind of `min_grind_minutes` or more the charm for `goal`. That is Haste under
+    /// `MaxGold`, falling back to Quantity, and otherwise Experience, Quantity or Treasure, falling
+    /// back to Haste. Only charms the profile holds and `ready` allows.
+    pub fn pick_charm(&self, profile: &Profile, moment: CharmMoment, ready: impl Fn(CharmType) -> bool) -> Option<CharmType> {
+        let candidates: &[CharmType] = match moment {
+            CharmMoment::Sell { value } if value >= self.charms.min_sell_value => &[CharmType::Marketing],
+            CharmMoment::Grind { seconds } if seconds >= (self.charms.min_grind_minutes * 60) as f64 => match self.goal {
+                StrategyGoal::MaxGold => &[CharmType::Haste, CharmType::Quantity],
+                StrategyGoal::MaxXp => &[CharmType::Experience, CharmType::Haste],
+                StrategyGoal::CompleteFishdex => &[CharmType::Quantity, CharmType::Haste],
+                StrategyGoal::FarmCharms => &[CharmType::Treasure, CharmType::Haste],
+            },
+            _ => &[],
+        };
+        candidates.iter().copied().find(|&charm| profile.charm_count(charm) > 0 && ready(charm))
+    }
+
+    /// `current_gps` when it's gold, i.e. under `MaxGold`.
+    fn gold_gps(&self, current_gps: f64) -> Option<f64> {
+        (self.goal == StrategyGoal::MaxGold).then_some(current_gps)
+    }
+
+    /// What a bait adds per cast toward `goal`, as a fraction (0.5 for +50%).
+    fn bait_gain(&self, b: &Bait) -> f64 {
+        let fish = (1.0 + b.extra_fish_per_cast) * (1.0 + b.fish_catch_bonus);
+        match self.goal {
+            StrategyGoal::MaxGold => fish * (1.0 + b.fish_quality_bonus) - 1.0,
+            StrategyGoal::MaxXp => fish * b.xp_multiplier - 1.0,
+            StrategyGoal::CompleteFishdex => fish - 1.0,
+            StrategyGoal::FarmCharms => (1.0 + b.treasure_chance_bonus) * (1.0 + b.treasure_quality_bonus) - 1.0,
+        }
+    }
+
+    /// The bait that adds the most toward `goal` while `quantity` of it costs at most a
+    /// quarter of `balance`. `None` when none is that cheap.
+    pub fn recommend_bait(&self, balance: u64, quantity: u64) -> Option<&'static str> {
+        BAIT_DATA.values()
+            .filter(|b| !b.name.is_empty() && self.bait_gain(b) > 0.0)
+            .filter(|b| b.price.saturating_mul(quantity) <= balance / 4)
+            .max_by(|a, b| self.bait_gain(a).total_cmp(&self.bait_gain(b)).then_with(|| b.name.cmp(a.name)))
+            .map(|b| b.name)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::bankroll::GamblingStats;
+    use crate::engine::game_data::{RodType, BoatType};
+    use crate::engine::profile::Charm;
+    use crate::engine::profile::parse::parse_charm;
+
+    /// A snapshot of the player's situation and the recommendations it must produce.
+    struct GoldenCase {
+        name: &'static str,
+        rod: RodType,
+        boat: BoatType,
+        biome: Biome,
+        marketing: &'static str,
+        quantity: &'static str,
+        haste: &'static str,
+        pet: &'static str,
+        balance: u64,
+        /// Top recommendations in order, as (action label, target name)
+        expected: &'static [(&'static str, &'static str)],
+    }
+
+    const GOLDEN_CASES: &[GoldenCase] = &[
+        GoldenCase {
+            name: "fresh account",
+            rod: RodType::Plastic, boat: BoatType::Rowboat, biome: Biome::River,
+            marketing: "", quantity: "", haste: "", pet: "",
+            balance: 0,
+            expected: &[("BuyRod", "Improved Rod"), ("BuyRod", "Fiberglass Rod"), ("BuyBoat", "Fishing Boat")],
+        },
+        GoldenCase {
+            name: "early volcanic with charms",
+            rod: RodType::Steel, boat: BoatType::FishingBoat, biome: Biome::Volcanic,
+            marketing: "Marketing II", quantity: "", haste: "", pet: "Dolphin",
+            balance: 60_000,
+            expected: &[("Travel", "River"), ("BuyRod", "Fiberglass Rod"), ("BuyRod", "Heavy Rod")],
+        },
+        GoldenCase {
+            // A long grind, but a fair coin has no edge to bet on
+            name: "long grind without coinflip history",
+            rod: RodType::Lava, boat: BoatType::Sailboat, biome: Biome::Ocean,
+            marketing: "Marketing V", quantity: "Quantity III", haste: "Haste II", pet: "Shark",
+            balance: 5_000_000,
+            expected: &[("Travel", "River"), ("Travel", "Volcanic"), ("BuyUpgrade", "Bait Efficiency")],
+        },
+        GoldenCase {
+            name: "rich sky player",
+            rod: RodType::Magma, boat: BoatType::Yacht, biome: Biome::Sky,
+            marketing: "", quantity: "", haste: "", pet: "",
+            balance: 100_000_000,
+            expected: &[("Travel", "Ocean"), ("Travel", "River"), ("Travel", "Volcanic"), ("BuyUpgrade", "Bait Efficiency")],
+        },
+    ];
+
+    fn action_label(action: &ActionType) -> &'static str {
+        match action {
+            ActionType::BuyRod => "BuyRod",
+            ActionType::BuyBoat => "BuyBoat",
+            ActionType::Travel => "Travel",
+            ActionType::BuyUpgrade => "BuyUpgrade",
+            ActionType::BuyLeagueUpgrade => "BuyLeagueUpgrade",
+            ActionType::Sell => "Sell",
+            ActionType::Wait => "Wait",
+            ActionType::Coinflip { .. } => "Coinflip",
+        }
+    }
+
+    #[test]
+    fn test_golden_recommendations() {
+        let optimizer = Optimizer::new();
+
+        for case in GOLDEN_CASES {
+            let mut profile = Profile::default();
+            profile.charms.marketing = parse_charm(case.marketing);
+            profile.charms.quantity = parse_charm(case.quantity);
+            profile.charms.haste = parse_charm(case.haste);
+            profile.pet = case.pet.to_string();
+
+            let recs = optimizer.solve_next_move(&ROD_DATA[&case.rod], &BOAT_DATA[&case.boat], case.biome, &profile, case.balance);
+            let actual: Vec<(&str, &str)> = recs.iter()
+                .take(case.expected.len())
+                .map(|r| (action_label(&r.action), r.target_name.as_str()))
+                .collect();
+
+            assert_eq!(actual, case.expected.to_vec(), "Golden case '{}' changed", case.name);
+        }
+    }
+
+    #[test]
+    fn test_recommend_bait() {
+        let optimizer = Optimizer::new();
+        // 100 Magic Bait costs 25,000: affordable from 100,000
+        assert_eq!(optimizer.recommend_bait(100_000, 100), Some("Magic Bait"));
+        assert_eq!(optimizer.recommend_bait(30_000, 100), Some("Fish"));
+        assert_eq!(optimizer.recommend_bait(10_000, 100), Some("Leeches"));
+        assert_eq!(optimizer.recommend_bait(2_000, 100), Some("Worms"));
+        assert_eq!(optimizer.recommend_bait(1_000, 100), None);
+    }
+
+    #[test]
+    fn test_strategy_goals() {
+        let profile = Profile::default();
+        let boat = &BOAT_DATA[&BoatType::Rowboat];
+        let rod_gain = |goal: StrategyGoal, rod: RodType| {
+            let optimizer = Optimizer::new().with_goal(goal);
+            optimizer.calculate_metrics(&ROD_DATA[&rod], boat, Biome::River, &profile)
+                - optimizer.calculate_metrics(&ROD_DATA[&RodType::Plastic], boat, Biome::River, &profile)
+        };
+        // The Golden Rod catches fewer fish than the Lava Rod but finds far more treasure
+        assert!(rod_gain(StrategyGoal::MaxGold, RodType::Lava) > rod_gain(StrategyGoal::MaxGold, RodType::Golden));
+        assert!(rod_gain(StrategyGoal::FarmCharms, RodType::Golden) > rod_gain(StrategyGoal::FarmCharms, RodType::Lava));
+
+        assert_eq!(Optimizer::new().with_goal(StrategyGoal::FarmCharms).recommend_bait(100_000, 100), Some("Artifact Magnet"));
+        assert_eq!(Optimizer::new().with_goal(StrategyGoal::MaxXp).recommend_bait(100_000, 100), Some("Magic Bait"));
+        // Only gold pays for a coinflip
+        let lucky = Bankroll::default().with_stats(GamblingStats { wins: 70, losses: 30, ..Default::default() });
+        let coinflips = |goal: StrategyGoal| Optimizer::new().with_goal(goal).with_bankroll(lucky.clone())
+            .solve_next_move(&ROD_DATA[&RodType::Plastic], boat, Biome::River, &profile, 6_000_000)
+            .into_iter()
+            .filter(|r| matches!(r.action, ActionType::Coinflip { .. }))
+            .count();
+        assert!(coinflips(StrategyGoal::MaxGold) > 0);
+        assert_eq!(coinflips(StrategyGoal::MaxXp), 0);
+        assert_eq!(StrategyGoal::FarmCharms.next(), StrategyGoal::MaxGold);
+    }
+
+    #[test]
+    fn test_chest_value() {
+        let profile = Profile::default();
+        let (rod, boat) = (&ROD_DATA[&RodType::Golden], &BOAT_DATA[&BoatType::Rowboat]);
+        let mut optimizer = Optimizer::new();
+        assert_eq!(optimizer.chest_value(), None);
+        let without = optimizer.calculate_metrics(rod, boat, Biome::River, &profile);
+
+        optimizer.treasure.insert(TreasureQuality::Rare, TreasureObservations { opens: 3, ..Default::default() });
+        optimizer.treasure.insert(TreasureQuality::Epic, TreasureObservations { opens: 1, ..Default::default() });
+        let rare = mechanics::get_treasure_ev(TreasureQuality::Rare, optimizer.treasure.get(&TreasureQuality::Rare));
+        let epic = mechanics::get_treasure_ev(TreasureQuality::Epic, optimizer.treasure.get(&TreasureQuality::Epic));
+        let value = optimizer.chest_value().unwrap();
+        assert!((value - (3.0 * rare + epic) / 4.0).abs() < 1e-6);
+        // Chests now count toward gold per second
+        assert!(optimizer.calculate_metrics(rod, boat, Biome::River, &profile) > without);
+    }
+
+    #[test]
+    fn test_evaluate_prestige() {
+        let optimizer = Optimizer::new();
+        let mut profile = Profile::default();
+        let boat = &BOAT_DATA[&BoatType::Rowboat];
+        // Nothing to lose on a Plastic Rod, +40% from Business Education
+        let plan = optimizer.evaluate_prestige(&ROD_DATA[&RodType::Plastic], boat, Biome::River, &profile);
+        assert_eq!(plan.boost, 0.40);
+        assert!(plan.worthwhile);
+        // An Infinity Rod takes far longer than the horizon to earn back
+        assert!(!optimizer.evaluate_prestige(&ROD_DATA[&RodType::Infinity], boat, Biome::River, &profile).worthwhile);
+
+        for upgrade in UPGRADE_DATA.values().filter(|u| u.currency == UpgradeCurrency::AzureFish) {
+            profile.upgrades.insert(upgrade.name.to_string(), crate::engine::profile::UpgradeStatus { level: upgrade.max_level, max_level: upgrade.max_level, next_cost: None });
+        }
+        let plan = optimizer.evaluate_prestige(&ROD_DATA[&RodType::Plastic], boat, Biome::River, &profile);
+        assert_eq!(plan.boost, 0.0);
+        assert!(!plan.worthwhile);
+    }
+
+    #[test]
+    fn test_league_upgrades_need_hooks() {
+        let optimizer = Optimizer::new();
+        let mut profile = Profile::default();
+        profile.update_from_message("League: <:gold:1> **Gold III**\n1,234 <:hook:2> Hooks", Some("League"));
+        assert_eq!(profile.league, "Gold III");
+        assert_eq!(profile.hooks, 1234);
+        profile.update_from_message("**Duplicator 2.0** (Lvl 3/10) - 1,500 Hooks", Some("League Upgrades"));
+        assert_eq!(profile.upgrades["Duplicator 2.0"].next_cost, Some(1500));
+
+        let league_targets = |profile: &Profile| -> Vec<String> {
+            optimizer.solve_next_move(&ROD_DATA[&RodType::Plastic], &BOAT_DATA[&BoatType::Rowboat], Biome::River, profile, 0)
+                .into_iter()
+                .filter(|r| r.action == ActionType::BuyLeagueUpgrade)
+                .map(|r| r.target_name)
+                .collect()
+        };
+        // 1,234 Hooks: the five 210-Hook upgrades, but not Duplicator 2.0 at 1,500
+        let targets = league_targets(&profile);
+        assert_eq!(targets.len(), 5);
+        assert!(!targets.contains(&"Duplicator 2.0".to_string()));
+
+        profile.hooks = 0;
+        assert!(league_targets(&profile).is_empty());
+    }
+
+    /// Marketing scales gold per fish, Quantity and the pet add up on fish per cast, Haste
+    /// shortens the cooldown.
+    #[test]
+    fn test_charm_and_pet_multipliers() {
+        let optimizer = Optimizer::new();
+        let (rod, boat) = (&ROD_DATA[&RodType::Plastic], &BOAT_DATA[&BoatType::Rowboat]);
+        let gps = |profile: &Profile| optimizer.calculate_metrics(rod, boat, Biome::River, profile);
+        let base = gps(&Profile::default());
+
+        let mut profile = Profile::default();
+        profile.charms.marketing = parse_charm("Marketing X");
+        assert!((gps(&profile) / base - 1.10).abs() < 1e-9);
+        profile.pet = "Shark".to_string();
+        assert!((gps(&profile) / base - 1.10 * 1.15).abs() < 1e-9);
+        profile.charms.quantity = Charm { count: 5, level: 5 };
+        assert!((gps(&profile) / base - 1.10 * 1.20).abs() < 1e-9);
+
+        let mut hasty = Profile::default();
+        hasty.charms.haste = Charm { count: 1, level: 10 };
+        assert!(gps(&hasty) > base);
+    }
+
+    #[test]
+    fn test_pick_charm() {
+        let mut profile = Profile::default();
+        profile.charms.haste = Charm { count: 3, level: 3 };
+        profile.charms.marketing = Charm { count: 1, level: 1 };
+        let optimizer = Optimizer::new();
+        let long = CharmMoment::Grind { seconds: 2.0 * 3600.0 };
+        assert_eq!(optimizer.pick_charm(&profile, long, |_| true), Some(CharmType::Haste));
+        assert_eq!(optimizer.pick_charm(&profile, CharmMoment::Grind { seconds: 600.0 }, |_| true), None);
+        // On cooldown, and no Quantity charm to fall back on
+        assert_eq!(optimizer.pick_charm(&profile, long, |c| c != CharmType::Haste), None);
+        profile.charms.quantity = Charm { count: 1, level: 1 };
+        assert_eq!(optimizer.pick_charm(&profile, long, |c| c != CharmType::Haste), Some(CharmType::Quantity));
+        assert_eq!(optimizer.pick_charm(&profile, long, |_| true), Some(CharmType::Haste));
+
+        let sell = |value| optimizer.pick_charm(&profile, CharmMoment::Sell { value }, |_| true);
+        assert_eq!(sell(250_000), Some(CharmType::Marketing));
+        assert_eq!(sell(5_000), None);
+
+        // The goal's own charm comes first, Haste when there is none of it
+        let xp = Optimizer::new().with_goal(StrategyGoal::MaxXp);
+        assert_eq!(xp.pick_charm(&profile, long, |_| true), Some(CharmType::Haste));
+        profile.charms.experience = Charm { count: 2, level: 2 };
+        assert_eq!(xp.pick_charm(&profile, long, |_| true), Some(CharmType::Experience));
+    }
+
+    #[test]
+    fn test_compare_biomes() {
+        let mut optimizer = Optimizer::new();
+        optimizer.biome_knowledge.entry(Biome::Ocean).or_default().update(4_000, 100, 10);
+        let catches = vec![
+            BiomeCatches { biome: "River".to_string(), fish: 40, gold: 600 },
+            BiomeCatches { biome: "Ocean".to_string(), fish: 10, gold: 900 },
+            BiomeCatches { biome: String::new(), fish: 5, gold: 50 },
+        ];
+        let rows = optimizer.compare_biomes(&catches, &ROD_DATA[&RodType::Plastic], &BOAT_DATA[&BoatType::Rowboat], &Profile::default());
+
+        assert_eq!(rows.len(), Biome::ALL.len());
+        let river = &rows[0];
+        assert_eq!((river.biome, river.fish, river.avg_value, river.learned_avg), (Biome::River, 40, 15.0, 0.0));
+        let ocean = rows.iter().find(|r| r.biome == Biome::Ocean).unwrap();
+        assert_eq!((ocean.fish, ocean.avg_value, ocean.learned_avg), (10, 90.0, 400.0));
+        // The learned $400 fish make Ocean the pick
+        assert_eq!(rows.iter().filter(|r| r.preferred).map(|r| r.biome).collect::<Vec<_>>(), vec![Biome::Ocean]);
+    }
+
+    #[test]
+    fn test_golden_metrics() {
+        let optimizer = Optimizer::new();
+        let profile = Profile::default();
+
+        // Plastic Rod in River with a Rowboat: 7 fish * $15 / 2.75s
+        let gps = optimizer.calculate_metrics(&ROD_DATA[&RodType::Plastic], &BOAT_DATA[&BoatType::Rowboat], Biome::River, &profile);
+        assert!((gps - 38.1818).abs() < 0.001, "GPS drifted: {}", gps);
+    }
 }
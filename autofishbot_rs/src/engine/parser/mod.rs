@@ -0,0 +1,1285 @@
+use regex::Regex;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use crate::engine::game_data::{Biome, TreasureQuality};
+use crate::util::money;
+use tokens::Token;
+
+pub mod tokens;
+
+// Catch, XP, profile and cooldown patterns run on `tokens::plain` text: no emoji, no markdown.
+lazy_static! {
+    // The count before a fish's emoji: "3", "**1,204**", "2x"
+    static ref CATCH_COUNT_PATTERN: Regex = Regex::new(r"(?:^|[^\w.])([\d,]+)\s*x?$").unwrap();
+    // Example: "+173 XP" or "+37,129 XP"
+    static ref XP_PATTERN: Regex = Regex::new(r"\+([\d,]+)\s*XP").unwrap();
+    // Example: "Balance: $3,548"
+    static ref BALANCE_PATTERN: Regex = Regex::new(r"(?i)balance:?\s*\$?\s*([\d,]+)").unwrap();
+    // Example: "Level 21"
+    static ref LEVEL_PATTERN: Regex = Regex::new(r"Level:?\s*(\d+)").unwrap();
+    // Example: "You are now level 31", "reached Level: 31", "30 → 31"
+    static ref LEVEL_UP_PATTERN: Regex = Regex::new(r"(?i)(?:now|reached|to)\s+level:?\s*([\d,]+)|[\d,]+\s*(?:→|->|➜|=>)\s*([\d,]+)").unwrap();
+    // Example: "You must wait 2.5s"
+    static ref COOLDOWN_WAIT_PATTERN: Regex = Regex::new(r"(?i)you must wait\s+([\d.]+)\s*s").unwrap();
+    // Example: "Current cooldown: 3.5 seconds"
+    static ref COOLDOWN_TOTAL_PATTERN: Regex = Regex::new(r"(?i)current cooldown:?\s*([\d.]+)\s*s").unwrap();
+
+    // Shop Item Pattern: "**Item Name** - $500", "**Item Name**: $500", "**Item Name** - **$500**"
+    // Refined to handle colon separators and bold prices. Runs on `tokens::strip_emoji` text.
+    static ref SHOP_ITEM_PATTERN: Regex = Regex::new(r"\*\*([^\*]+)\*\*\s*(?:-|:|–)\s*(?:\*\*)?\$([\d,]+)(?:\*\*)?").unwrap();
+
+    // Example: "HP: **1,234,567** / **5,000,000**"
+    static ref BOSS_HP_PATTERN: Regex = Regex::new(r"HP:\s*\**([\d,]+)\**\s*/\s*\**([\d,]+)\**").unwrap();
+    // Example: "Ends in **3h 20m**" or "Ends in: 45m"
+    static ref ENDS_IN_PATTERN: Regex = Regex::new(r"(?i)ends in:?\s*\**((?:\d+\s*[dhms]\s*)+)").unwrap();
+    // Example: "You dealt **12,345** damage"
+    static ref BOSS_DAMAGE_PATTERN: Regex = Regex::new(r"You dealt \**([\d,]+)\** damage").unwrap();
+    // Example: "Reward: **$5,000** and 2 <:clan:1> Clan Tokens"
+    static ref REWARD_PATTERN: Regex = Regex::new(r"(?i)rewards?:\s*(.+)").unwrap();
+    // Example: "You can vote again in **11h 59m**"
+    static ref VOTE_AGAIN_PATTERN: Regex = Regex::new(r"(?i)vote again in:?\s*\**((?:\d+\s*[dhms]\s*)+)").unwrap();
+    // Example: "Streak: **5** days", "a 5 day streak"
+    static ref DAILY_STREAK_PATTERN: Regex = Regex::new(r"(?i)streak:?\s*\**([\d,]+)|([\d,]+)\**\s*-?\s*days?\s+streak").unwrap();
+    // Example: "**3** <:gold:1> Gold Fish", "+ 2 Bait", "$12,500"
+    static ref DROP_ITEM_PATTERN: Regex = Regex::new(r"^[+\-•\s]*\**([\d,]+)\**\s*x?\s*(?:<a?:[^>]+>\s*)?([A-Za-z][\w\s'.-]*)").unwrap();
+    static ref DROP_MONEY_PATTERN: Regex = Regex::new(r"\$\s*\**([\d,]+)").unwrap();
+    // Example: "Come back in **5h 12m**", "You can claim again in 3h", "Try again in: 20m"
+    static ref CLAIM_AGAIN_PATTERN: Regex = Regex::new(r"(?i)(?:come back|again|available) in:?\s*\**((?:\d+\s*[dhms]\s*)+)").unwrap();
+    // Example: "The Christmas Event has started!", "Winter Festival event is now live"
+    static ref EVENT_START_PATTERN: Regex = Regex::new(r"(?i)((?:[a-z']+\s+)?[a-z']+)\s+event\s+(?:has\s+(?:started|begun)|is\s+(?:now\s+)?(?:live|here|active|on))").unwrap();
+    // Example: "</event:1234>", "`/event shop`"
+    static ref COMMAND_MENTION_PATTERN: Regex = Regex::new(r"(?i)</([a-z0-9_\- ]+):\d+>|`/([a-z0-9_\- ]+)`").unwrap();
+    // Example: "Collect **Presents**", "earn <:snow:1> Snowflakes"
+    static ref EVENT_CURRENCY_PATTERN: Regex = Regex::new(r"(?i)\b(?:collect|earn|gather|find)\s+(?:<a?:[^>]+>\s*)?\**([a-z]{3,})").unwrap();
+    // Example: "1. angler - $1,234,567", "#12 <@42> — Level 52", "`3` Bob: 12,000 Hooks"
+    static ref LEADERBOARD_LINE_PATTERN: Regex = Regex::new(r"^#?(\d+)[.):]?\s+(.+?)\s*(?:\s-|—|–|:|\|)\s*(.*\d.*)$").unwrap();
+    // Example: "Your rank: #57", "You are ranked #57"
+    static ref OWN_RANK_PATTERN: Regex = Regex::new(r"(?i)\byou(?:r rank| are ranked)[^#\d]*#?\s*([\d,]+)").unwrap();
+    static ref MENTION_PATTERN: Regex = Regex::new(r"<@!?(\d+)>").unwrap();
+    // Example: "12/40", "Page 1 / 5"
+    static ref PROGRESS_PATTERN: Regex = Regex::new(r"\d\s*/\s*\d").unwrap();
+    // Example: "3h", "20m", "1d"
+    static ref DURATION_PART_PATTERN: Regex = Regex::new(r"(\d+)\s*([dhms])").unwrap();
+}
+
+#[derive(Debug, Serialize)]
+pub struct CatchEvent {
+    pub fish: Vec<(String, i32)>, // Name, Count
+    pub xp: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlayerStats {
+    pub balance: Option<f32>,
+    pub level: Option<i32>,
+    pub biome: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CooldownEvent {
+    pub wait_time: f32,
+    pub total_cooldown: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShopItem {
+    pub name: String,
+    pub price: f32,
+    pub currency: String,
+    pub description: String,
+    pub stock: Option<i32>,
+    pub stats: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct SelectMenuOption {
+    pub label: String,
+    pub value: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GameEntity {
+    pub entity_type: String,
+    pub name: String,
+    pub details: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ClanEventKind {
+    Boss,
+    Raid,
+}
+
+/// A clan boss or raid announcement; all fields but `kind` and `name` are optional in the embed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClanBossEvent {
+    pub kind: ClanEventKind,
+    pub name: String,
+    pub hp_remaining: Option<u64>,
+    pub hp_total: Option<u64>,
+    pub ends_in: Option<std::time::Duration>,
+    pub defeated: bool,
+}
+
+/// The reply to contributing to a clan boss/raid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClanAttackResult {
+    pub damage: u64,
+    pub reward: Option<String>,
+}
+
+/// Parses compact durations like "3h 20m" or "1d 2h".
+pub fn parse_duration_text(text: &str) -> Option<std::time::Duration> {
+    let mut seconds = 0;
+    let mut found = false;
+    for caps in DURATION_PART_PATTERN.captures_iter(text) {
+        let value: u64 = caps[1].parse().ok()?;
+        let unit = match &caps[2] {
+            "d" => 86_400,
+            "h" => 3_600,
+            "m" => 60,
+            _ => 1,
+        };
+        seconds = value.saturating_mul(unit).saturating_add(seconds);
+        found = true;
+    }
+    found.then(|| std::time::Duration::from_secs(seconds))
+}
+
+/// The "Ends in 3h 20m" countdown that boss and seasonal event embeds carry.
+pub fn parse_ends_in(description: &str) -> Option<std::time::Duration> {
+    ENDS_IN_PATTERN.captures(description).and_then(|c| parse_duration_text(&c[1]))
+}
+
+/// Matches "Clan Boss: Kraken", "Clan Raid", "🐙 Raid - Leviathan"...
+pub fn parse_clan_boss_embed(title: &str, description: &str) -> Option<ClanBossEvent> {
+    let lower = title.to_lowercase();
+    let kind = if lower.contains("raid") {
+        ClanEventKind::Raid
+    } else if lower.contains("clan boss") {
+        ClanEventKind::Boss
+    } else {
+        return None;
+    };
+
+    let name = title.split([':', '-']).nth(1).map(|n| n.trim().to_string()).unwrap_or_else(|| title.trim().to_string());
+    let hp = BOSS_HP_PATTERN.captures(description);
+    Some(ClanBossEvent {
+        kind,
+        name,
+        hp_remaining: hp.as_ref().and_then(|c| money::parse_amount(&c[1])),
+        hp_total: hp.as_ref().and_then(|c| money::parse_amount(&c[2])),
+        ends_in: parse_ends_in(description),
+        defeated: description.to_lowercase().contains("defeated"),
+    })
+}
+
+pub fn parse_clan_attack_result(description: &str) -> Option<ClanAttackResult> {
+    let damage = money::parse_amount(&BOSS_DAMAGE_PATTERN.captures(description)?[1])?;
+    let reward = REWARD_PATTERN.captures(description).map(|c| c[1].replace("**", "").trim().to_string());
+    Some(ClanAttackResult { damage, reward })
+}
+
+#[tracing::instrument(skip_all)]
+pub fn parse_cooldown_embed(description: &str) -> Option<CooldownEvent> {
+    let mut wait = 0.0;
+    let mut total = 0.0;
+    let description = tokens::plain(description);
+
+    if let Some(caps) = COOLDOWN_WAIT_PATTERN.captures(&description) {
+        if let Some(val) = caps.get(1) {
+            if let Ok(v) = val.as_str().parse::<f32>() {
+                wait = v;
+            }
+        }
+    }
+
+    if let Some(caps) = COOLDOWN_TOTAL_PATTERN.captures(&description) {
+        if let Some(val) = caps.get(1) {
+            if let Ok(v) = val.as_str().parse::<f32>() {
+                total = v;
+            }
+        }
+    }
+
+    if wait > 0.0 || total > 0.0 {
+        Some(CooldownEvent { wait_time: wait, total_cooldown: total })
+    } else {
+        None
+    }
+}
+
+#[tracing::instrument(skip_all)]
+pub fn parse_catch_embed(description: &str) -> Option<CatchEvent> {
+    let mut fish_list = Vec::new();
+    let mut xp = 0.0;
+
+    // Parse lines
+    for line in description.lines() {
+        fish_list.extend(catch_line(line));
+        if let Some(caps) = XP_PATTERN.captures(&tokens::plain(line)) {
+            if let Some(xp_str) = caps.get(1) {
+                let clean_xp = xp_str.as_str().replace(",", "");
+                if let Ok(val) = clean_xp.parse::<f32>() {
+                    xp = val;
+                }
+            }
+        }
+    }
+
+    if !fish_list.is_empty() || xp > 0.0 {
+        Some(CatchEvent { fish: fish_list, xp })
+    } else {
+        None
+    }
+}
+
+/// Every "<count> <emoji> <name>" on a line: "3 <:cod:1> Cod", "**2** 🐟 Raw Fish, 1x <:gold:2> Gold Fish!".
+/// A fish is the text after an emoji whose text before it ends with a count.
+fn catch_line(line: &str) -> Vec<(String, i32)> {
+    // Text between emoji; a run of emoji counts as one
+    let mut segments = vec![String::new()];
+    let mut after_emoji = false;
+    for token in tokens::tokenize(line) {
+        match token {
+            Token::Text(text) => {
+                segments.last_mut().expect("never empty").push_str(text);
+                after_emoji = false;
+            },
+            Token::Emoji(_) if !after_emoji => {
+                segments.push(String::new());
+                after_emoji = true;
+            },
+            Token::Emoji(_) => {},
+        }
+    }
+
+    let mut fish = Vec::new();
+    for pair in segments.windows(2) {
+        let before = tokens::plain(&pair[0]);
+        let Some(count) = CATCH_COUNT_PATTERN.captures(&before).and_then(|c| c[1].replace(',', "").parse::<i32>().ok()) else { continue };
+        // The name runs up to the next count, punctuation or the end
+        let after = tokens::plain(&pair[1]);
+        let after = after.split(" - ").next().unwrap_or_default();
+        let name = after.split(|c: char| c.is_ascii_digit() || matches!(c, ',' | '(' | '!' | '|' | '+')).next().unwrap_or_default();
+        let name = name.trim().trim_end_matches(" and").trim_end_matches(" &").trim();
+        if name.starts_with(|c: char| c.is_alphabetic()) && name != "XP" {
+            fish.push((name.to_string(), count));
+        }
+    }
+    fish
+}
+
+#[tracing::instrument(skip_all)]
+pub fn parse_profile_embed(description: &str) -> PlayerStats {
+    let mut balance = None;
+    let mut level = None;
+    let mut biome = None;
+    let description = tokens::plain(description);
+
+    if let Some(caps) = BALANCE_PATTERN.captures(&description) {
+        if let Some(val) = caps.get(1) {
+             let clean = val.as_str().replace(",", "");
+             if let Ok(v) = clean.parse::<f32>() {
+                 balance = Some(v);
+             }
+        }
+    }
+
+    if let Some(caps) = LEVEL_PATTERN.captures(&description) {
+        if let Some(val) = caps.get(1) {
+             if let Ok(v) = val.as_str().parse::<i32>() {
+                 level = Some(v);
+             }
+        }
+    }
+
+    let biome_line = description.lines().find_map(|l| l.split_once(':').filter(|(label, _)| label.trim().eq_ignore_ascii_case("current biome")));
+    if let Some((_, name)) = biome_line {
+        let name = name.trim();
+        if !name.is_empty() {
+            biome = Some(Biome::from_display_name(name).map(|b| b.display_name()).unwrap_or(name).to_string());
+        }
+    }
+
+    PlayerStats { balance, level, biome }
+}
+
+pub fn parse_shop_embed(title: &str, description: &str, fields: Option<&Vec<crate::discord::types::EmbedField>>) -> Vec<ShopItem> {
+    let mut items = Vec::new();
+    let shop_currency = if title.contains("Magma") { "Magma" } else { "Money" };
+
+    // Method 1: Check fields (Common for many bots)
+    if let Some(fields_vec) = fields {
+        for field in fields_vec {
+            // Assume format: Name -> "Price: $X\nDesc: ..."
+            let name = tokens::plain(&field.name);
+            let mut price = 0.0;
+            let mut desc = String::new();
+
+            for line in field.value.lines() {
+                if line.to_lowercase().contains("price") || line.contains("$") {
+                     if let Some(p) = money::parse_amount(&tokens::strip_emoji(line)) {
+                         price = p as f32;
+                     }
+                } else {
+                    desc.push_str(line);
+                    desc.push('\n');
+                }
+            }
+
+            if price > 0.0 {
+                let desc_str = desc.trim().to_string();
+                // Extract stats from description (simple heuristic: lines starting with +)
+                let stats: Vec<String> = desc_str.lines()
+                    .filter(|l| l.trim().starts_with('+'))
+                    .map(|l| l.trim().to_string())
+                    .collect();
+                let stats_str = if stats.is_empty() { None } else { Some(stats.join(", ")) };
+
+                items.push(ShopItem {
+                    name,
+                    price,
+                    currency: shop_currency.to_string(),
+                    description: desc_str,
+                    stock: None,
+                    stats: stats_str,
+                });
+            }
+        }
+    }
+
+    // Method 2: Check Description (List format)
+    if items.is_empty() {
+        for line in description.lines() {
+            // Very basic heuristic parser
+             if let Some(caps) = SHOP_ITEM_PATTERN.captures(&tokens::strip_emoji(line)) {
+                 if let (Some(name_cap), Some(price_cap)) = (caps.get(1), caps.get(2)) {
+                      let clean_price = price_cap.as_str().replace(",", "");
+                      if let Ok(price) = clean_price.parse::<f32>() {
+                           items.push(ShopItem {
+                               name: name_cap.as_str().trim().to_string(),
+                               price,
+                               currency: shop_currency.to_string(),
+                               description: line.to_string(), // Store full line as desc for now
+                               stock: None,
+                               stats: None,
+                           });
+                      }
+                 }
+             }
+        }
+    }
+
+    items
+}
+
+pub fn parse_select_menu_options(msg: &crate::discord::types::Message) -> Option<(String, Vec<SelectMenuOption>)> {
+    if let Some(rows) = &msg.components {
+        for row in rows {
+             if let Some(comps) = &row.components {
+                 for comp in comps {
+                     if comp.r#type == 3 { // Select Menu
+                          if let Some(opts) = &comp.options {
+                               let parsed_opts = opts.iter().map(|o| SelectMenuOption {
+                                   label: o.label.clone(),
+                                   value: o.value.clone(),
+                                   description: o.description.clone()
+                               }).collect();
+                               return Some((comp.custom_id.clone().unwrap_or_default(), parsed_opts));
+                          }
+                     }
+                 }
+             }
+        }
+    }
+    None
+}
+
+/// `custom_id` of the first button whose label contains `label` (case-insensitive).
+pub fn find_button(msg: &crate::discord::types::Message, label: &str) -> Option<String> {
+    let label = label.to_lowercase();
+    msg.components.iter().flatten()
+        .flat_map(|row| row.components.iter().flatten())
+        .find(|c| c.r#type == 2 && c.label.as_ref().is_some_and(|l| l.to_lowercase().contains(&label)))
+        .and_then(|c| c.custom_id.clone())
+}
+
+/// `custom_id` and component type of the first select menu (string, user, role, mentionable
+/// or channel select) whose placeholder contains `placeholder` (case-insensitive).
+pub fn find_select(msg: &crate::discord::types::Message, placeholder: &str) -> Option<(String, u8)> {
+    let placeholder = placeholder.to_lowercase();
+    msg.components.iter().flatten()
+        .flat_map(|row| row.components.iter().flatten())
+        .filter(|c| matches!(c.r#type, 3 | 5..=8))
+        .find(|c| c.placeholder.as_deref().unwrap_or_default().to_lowercase().contains(&placeholder))
+        .and_then(|c| Some((c.custom_id.clone()?, c.r#type)))
+}
+
+/// Virtual Fisher saying the equipped bait ran out ("You have no bait left!").
+pub fn is_out_of_bait(msg: &crate::discord::types::Message) -> bool {
+    let text = message_text(msg).to_lowercase();
+    text.contains("bait") && ["no bait", "out of bait", "ran out of", "don't have any bait", "do not have any bait"].iter().any(|p| text.contains(p))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeKind {
+    Trade,
+    Gift,
+}
+
+/// A trade or gift waiting for this account to accept it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradePrompt {
+    pub kind: TradeKind,
+    pub message_id: String,
+    pub channel_id: String,
+    /// Who sent the message; usually the game bot, with the sender mentioned in the text
+    pub author: String,
+    pub summary: String,
+    pub decline_button: Option<String>,
+}
+
+/// Content plus every embed's title, description and fields, one per line.
+pub fn message_text(msg: &crate::discord::types::Message) -> String {
+    let mut text = msg.content.clone();
+    for embed in &msg.embeds {
+        text.push('\n');
+        text.push_str(embed.title.as_deref().unwrap_or_default());
+        text.push('\n');
+        text.push_str(embed.description.as_deref().unwrap_or_default());
+        for field in embed.fields.iter().flatten() {
+            text.push_str(&format!("\n{}\n{}", field.name, field.value));
+        }
+    }
+    text
+}
+
+/// Matches messages with buttons that mention `user_id` and talk about a trade or gift.
+/// Our own messages (e.g. a `/trade` we started) are never prompts.
+pub fn parse_trade_prompt(msg: &crate::discord::types::Message, user_id: &str) -> Option<TradePrompt> {
+    if msg.author.id == user_id || msg.components.as_ref().is_none_or(|c| c.is_empty()) {
+        return None;
+    }
+    let text = message_text(msg);
+    if !text.contains(&format!("<@{}>", user_id)) && !text.contains(&format!("<@!{}>", user_id)) {
+        return None;
+    }
+
+    let lower = text.to_lowercase();
+    let kind = if lower.contains("gift") {
+        TradeKind::Gift
+    } else if lower.contains("trade") {
+        TradeKind::Trade
+    } else {
+        return None;
+    };
+    let summary = text.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or_default().replace("**", "");
+    Some(TradePrompt {
+        kind,
+        message_id: msg.id.clone(),
+        channel_id: msg.channel_id.clone(),
+        author: msg.author.username.clone(),
+        summary,
+        decline_button: ["decline", "reject", "cancel", "deny"].iter().find_map(|label| find_button(msg, label)),
+    })
+}
+
+/// Virtual Fisher's anti-bot check: casting is blocked until `/verify answer:<code>` is sent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptchaChallenge {
+    /// The code image from the embed or an attachment; without one it has to be solved by hand
+    pub image_url: Option<String>,
+}
+
+/// Matches the captcha prompt, and the "please verify" reminders sent for commands used
+/// while one is pending.
+pub fn parse_captcha(msg: &crate::discord::types::Message) -> Option<CaptchaChallenge> {
+    let lower = message_text(msg).to_lowercase();
+    if !(lower.contains("captcha") || lower.contains("/verify")) || parse_captcha_result(msg).is_some() {
+        return None;
+    }
+    let image_url = msg.embeds.iter()
+        .find_map(|e| e.image.as_ref().map(|i| i.url.clone()))
+        .or_else(|| msg.attachments.iter()
+            .find(|a| [".png", ".jpg", ".jpeg", ".gif", ".webp"].iter().any(|ext| a.filename.to_lowercase().ends_with(ext)))
+            .map(|a| a.url.clone()));
+    Some(CaptchaChallenge { image_url })
+}
+
+/// The reply to a `/verify`: `Some(true)` once the code was accepted, `Some(false)` for a wrong one.
+pub fn parse_captcha_result(msg: &crate::discord::types::Message) -> Option<bool> {
+    let lower = message_text(msg).to_lowercase();
+    if ["incorrect", "wrong code", "invalid code", "not the right code"].iter().any(|p| lower.contains(p)) {
+        Some(false)
+    } else if ["successfully verified", "you have been verified", "you are now verified", "can now continue"].iter().any(|p| lower.contains(p)) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// What came out of an opened crate or present.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CrateDrops {
+    pub items: Vec<(String, u64)>,
+    pub money: Option<u64>,
+}
+
+/// Whether an inventory item is something `/open` works on.
+pub fn is_openable(item: &str) -> bool {
+    let lower = item.to_lowercase();
+    lower.contains("crate") || lower.contains("present")
+}
+
+/// Which openable item some text is about, most specific first.
+pub fn openable_kind(text: &str) -> Option<&'static str> {
+    let lower = text.to_lowercase();
+    ["super crate", "worker crate", "present", "crate"].iter()
+        .zip(["Super Crate", "Worker Crate", "Present", "Crate"])
+        .find(|(needle, _)| lower.contains(*needle))
+        .map(|(_, kind)| kind)
+}
+
+/// Parses "You opened a Super Crate" embeds: one "<count> <item>" per line, plus any money.
+pub fn parse_crate_drops(title: &str, description: &str) -> Option<CrateDrops> {
+    let text = format!("{}\n{}", title, description).to_lowercase();
+    if !text.contains("opened") || !is_openable(&text) {
+        return None;
+    }
+    Some(parse_drop_lines(description))
+}
+
+/// What an item dropped from a chest or crate counts as, for `mechanics::get_treasure_ev`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum DropKind {
+    Money,
+    GoldFish,
+    EmeraldFish,
+    LavaFish,
+    DiamondFish,
+    Charm,
+    Crate,
+    Other,
+}
+
+impl DropKind {
+    /// "Money" is how `CrateDrops::money` is logged to `treasure_history`.
+    pub fn of(item: &str) -> DropKind {
+        let lower = item.to_lowercase();
+        match lower.as_str() {
+            "money" => DropKind::Money,
+            "gold fish" => DropKind::GoldFish,
+            "emerald fish" => DropKind::EmeraldFish,
+            "lava fish" => DropKind::LavaFish,
+            "diamond fish" => DropKind::DiamondFish,
+            _ if lower.contains("charm") => DropKind::Charm,
+            _ if is_openable(&lower) => DropKind::Crate,
+            _ => DropKind::Other,
+        }
+    }
+}
+
+/// A treasure chest's contents, from the embed that follows the catch bringing it up.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TreasureOpen {
+    /// `None` when the embed doesn't say
+    pub quality: Option<TreasureQuality>,
+    pub drops: CrateDrops,
+}
+
+/// Parses "You opened a **Rare** treasure chest and found: ..." embeds. Money, exotic fish,
+/// charms and crates are one "<count> <item>" line each, like crate drops.
+pub fn parse_treasure_open(title: &str, description: &str) -> Option<TreasureOpen> {
+    let text = tokens::plain(&format!("{}\n{}", title, description));
+    let lower = text.to_lowercase();
+    let about_treasure = lower.contains("treasure") || lower.contains("chest");
+    if !about_treasure || !["opened", "found", "contain", "you got", "inside"].iter().any(|p| lower.contains(p)) {
+        return None;
+    }
+    let drops = parse_drop_lines(description);
+    if drops.items.is_empty() && drops.money.is_none() {
+        return None;
+    }
+    Some(TreasureOpen { quality: TreasureQuality::from_text(&text), drops })
+}
+
+/// Every "<count> <item>" line and the money lines of a drop list.
+fn parse_drop_lines(description: &str) -> CrateDrops {
+    let mut drops = CrateDrops::default();
+    for line in description.lines() {
+        let clean = tokens::plain(line);
+        if let Some(caps) = DROP_MONEY_PATTERN.captures(&clean) {
+            drops.money = Some(drops.money.unwrap_or(0) + money::parse_amount(&caps[1]).unwrap_or(0));
+        } else if let Some(caps) = DROP_ITEM_PATTERN.captures(&clean) {
+            if let Some(count) = money::parse_amount(&caps[1]) {
+                drops.items.push((caps[2].trim().to_string(), count));
+            }
+        }
+    }
+    drops
+}
+
+/// Time until the next vote from a vote embed: zero for "You can vote now", `None` if the
+/// embed isn't about voting.
+pub fn parse_vote_wait(title: &str, description: &str) -> Option<std::time::Duration> {
+    if let Some(caps) = VOTE_AGAIN_PATTERN.captures(description) {
+        return parse_duration_text(&caps[1]);
+    }
+    let lower = description.to_lowercase();
+    (title.to_lowercase().contains("vote") && (lower.contains("can vote now") || lower.contains("vote is available")))
+        .then_some(std::time::Duration::ZERO)
+}
+
+/// What a `/daily` claim gave, from its reply embed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DailyReward {
+    pub money: Option<u64>,
+    pub streak: Option<u32>,
+}
+
+/// Parses the `/daily` reply. "Already claimed, come back in 5h" replies aren't rewards.
+pub fn parse_daily_reward(title: &str, description: &str) -> Option<DailyReward> {
+    let lower = format!("{}\n{}", title, description).to_lowercase();
+    if !lower.contains("daily") || lower.contains("already") || lower.contains("come back") {
+        return None;
+    }
+    let clean = description.replace("**", "");
+    let reward = DailyReward {
+        money: DROP_MONEY_PATTERN.captures(&clean).and_then(|c| money::parse_amount(&c[1])),
+        streak: DAILY_STREAK_PATTERN.captures(&clean)
+            .and_then(|c| c.get(1).or(c.get(2)))
+            .and_then(|m| money::parse_amount(m.as_str()))
+            .map(|s| s as u32),
+    };
+    (reward.money.is_some() || reward.streak.is_some()).then_some(reward)
+}
+
+/// How Virtual Fisher answered a `/daily`, clan `/claim` or vote claim.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClaimReply {
+    /// The reward was handed out; `next_in` is when the reply says to come back
+    Granted { reward: String, next_in: Option<std::time::Duration> },
+    /// Not claimable yet; ready again after this long
+    Cooldown(std::time::Duration),
+}
+
+/// Reads a claim reply. `None` when it's neither a reward nor a "come back in 5h".
+pub fn parse_claim_reply(msg: &crate::discord::types::Message) -> Option<ClaimReply> {
+    let text = message_text(msg).replace("**", "");
+    let lower = text.to_lowercase();
+    let wait = CLAIM_AGAIN_PATTERN.captures(&text).and_then(|c| parse_duration_text(&c[1]));
+    let refused = ["already", "not ready", "cooldown", "you need to wait", "you must wait"].iter().any(|p| lower.contains(p));
+    if refused {
+        return wait.map(ClaimReply::Cooldown);
+    }
+    if !["claimed", "collected", "received", "you got", "reward"].iter().any(|p| lower.contains(p)) {
+        return wait.map(ClaimReply::Cooldown);
+    }
+    let reward = REWARD_PATTERN.captures(&text).map(|c| c[1].to_string())
+        .or_else(|| DROP_MONEY_PATTERN.captures(&text).map(|c| format!("${}", &c[1])))
+        .or_else(|| text.lines().map(str::trim).find(|l| !l.is_empty()).map(str::to_string))
+        .unwrap_or_default();
+    Some(ClaimReply::Granted { reward: reward.trim().to_string(), next_in: wait })
+}
+
+/// How a `/coinflip` went.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoinflipResult {
+    pub won: bool,
+    /// Gold won or lost
+    pub amount: u64,
+}
+
+/// Reads "The coin landed on heads! You won **$5,000**" / "... You lost **$5,000**".
+pub fn parse_coinflip_result(title: &str, description: &str) -> Option<CoinflipResult> {
+    let lower = format!("{}\n{}", title, description).to_lowercase();
+    if !lower.contains("coin") {
+        return None;
+    }
+    let won = if lower.contains("you won") || lower.contains("you win") {
+        true
+    } else if lower.contains("you lost") || lower.contains("you lose") {
+        false
+    } else {
+        return None;
+    };
+    let amount = DROP_MONEY_PATTERN.captures(description).and_then(|c| money::parse_amount(&c[1]))?;
+    Some(CoinflipResult { won, amount })
+}
+
+/// A game event starting, as Virtual Fisher announces it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventAnnouncement {
+    /// "Winter Festival", as announced
+    pub name: String,
+    /// The command the announcement points at ("event"), if it names one
+    pub command: Option<String>,
+    /// What the event hands out, singular and lowercase ("present")
+    pub currency: Option<String>,
+    pub ends_in: Option<std::time::Duration>,
+}
+
+/// Reads "The Christmas Event has started! Collect **Presents** with </event:1>".
+pub fn parse_event_announcement(title: &str, description: &str) -> Option<EventAnnouncement> {
+    let text = format!("{}\n{}", title, description).replace("**", "");
+    let caps = EVENT_START_PATTERN.captures(&text)?;
+    let name = caps[1].trim();
+    let name = name.strip_prefix("The ").or_else(|| name.strip_prefix("the ")).unwrap_or(name).to_string();
+    let command = COMMAND_MENTION_PATTERN.captures(description)
+        .and_then(|c| c.get(1).or_else(|| c.get(2)))
+        .map(|m| m.as_str().trim().to_string());
+    let currency = EVENT_CURRENCY_PATTERN.captures(description).map(|c| {
+        let word = c[1].to_lowercase();
+        word.strip_suffix('s').map(str::to_string).unwrap_or(word)
+    });
+    Some(EventAnnouncement { name, command, currency, ends_in: parse_ends_in(description) })
+}
+
+/// The reward text of a quest-claim confirmation ("Quest claimed! Reward: $5,000").
+pub fn parse_quest_reward(title: &str, description: &str) -> Option<String> {
+    if !title.to_lowercase().contains("quest") || !description.to_lowercase().contains("claimed") {
+        return None;
+    }
+    let reward = REWARD_PATTERN.captures(description)
+        .map(|c| c[1].to_string())
+        .unwrap_or_else(|| description.lines().next().unwrap_or_default().to_string());
+    Some(reward.replace("**", "").trim().to_string())
+}
+
+/// A level reached, from its own embed or the line a catch embed gains.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LevelUp {
+    pub level: u32,
+    /// What the level handed out ("$5,000"), when the embed says
+    pub reward: Option<String>,
+}
+
+/// Reads "You leveled up! You are now level **31**", "Level up! 30 → 31" and "You reached
+/// level 31". "Reach level 50" quests and "XP to next level" lines aren't level-ups.
+pub fn parse_level_up(title: &str, description: &str) -> Option<LevelUp> {
+    let text = tokens::plain(&format!("{}\n{}", title, description));
+    let lower = text.to_lowercase();
+    if !["leveled up", "levelled up", "level up", "reached level"].iter().any(|p| lower.contains(p)) {
+        return None;
+    }
+    let level = LEVEL_UP_PATTERN.captures(&text)
+        .and_then(|c| c.get(1).or_else(|| c.get(2)))
+        .or_else(|| LEVEL_PATTERN.captures(&text).and_then(|c| c.get(1)))
+        .and_then(|m| money::parse_amount(m.as_str()))
+        .and_then(|level| u32::try_from(level).ok())?;
+    let reward = REWARD_PATTERN.captures(&text).map(|c| c[1].trim().to_string());
+    Some(LevelUp { level, reward })
+}
+
+/// One species on a `/fishdex` page.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FishdexEntry {
+    pub name: String,
+    /// The page's biome, or the heading line above the entry
+    pub biome: Option<String>,
+    pub caught: bool,
+}
+
+/// Reads a `/fishdex` page: biome headings ("River", "Biome: Ocean", or the title's
+/// "Fishdex - River (1/5)") and one species per line. Species marked ❌/❓, "not caught" or
+/// "Caught: 0" are uncaught; any other named species has been caught. Hidden names ("???")
+/// are skipped.
+pub fn parse_fishdex_embed(title: &str, description: &str) -> Option<Vec<FishdexEntry>> {
+    if !title.to_lowercase().contains("fishdex") {
+        return None;
+    }
+    let mut biome = Biome::from_display_name(&tokens::plain(title)).map(|b| b.display_name().to_string());
+    let mut entries = Vec::new();
+    for line in description.lines() {
+        let plain = tokens::plain(line);
+        let heading = plain.strip_prefix("Biome:").unwrap_or(&plain).trim();
+        if let Some(found) = Biome::ALL.iter().find(|b| b.display_name().eq_ignore_ascii_case(heading)) {
+            biome = Some(found.display_name().to_string());
+            continue;
+        }
+
+        // "12/40 caught", "Page 1/5"
+        if PROGRESS_PATTERN.is_match(&plain) {
+            continue;
+        }
+        // "#3 Cod - Caught: 12", "Raw Salmon (x4)"
+        let name = plain.split([':', '(', '|']).next().unwrap_or_default();
+        let name = name.split(" - ").next().unwrap_or_default()
+            .trim_start_matches(|c: char| c.is_ascii_digit() || matches!(c, '#' | '.' | ' '))
+            .trim();
+        if !name.starts_with(|c: char| c.is_alphabetic()) {
+            continue;
+        }
+        let lower = plain.to_lowercase();
+        let uncaught = ['❌', '❓', '✖'].iter().any(|mark| line.contains(*mark))
+            || ["not caught", "uncaught", "undiscovered", "locked"].iter().any(|p| lower.contains(p));
+        let count = lower.split_once("caught").and_then(|(_, rest)| money::parse_amount(rest));
+        entries.push(FishdexEntry { name: name.to_string(), biome: biome.clone(), caught: !uncaught && count != Some(0) });
+    }
+    Some(entries)
+}
+
+/// One row of a `/leaderboards` embed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LeaderboardEntry {
+    pub rank: u32,
+    /// The name as shown; a mention stays `<@id>`
+    pub name: String,
+    pub user_id: Option<String>,
+    /// Balance, level, Hooks... whatever the board ranks by
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Leaderboard {
+    /// "Balance" for "Balance Leaderboard", "Overall" when the title names nothing
+    pub category: String,
+    /// Best first
+    pub entries: Vec<LeaderboardEntry>,
+    /// "Your rank: #57", for accounts below the listed rows
+    pub own_rank: Option<u32>,
+}
+
+/// Reads a `/leaderboards` page: one "<rank> <name> - <value>" row per line.
+pub fn parse_leaderboard_embed(title: &str, description: &str) -> Option<Leaderboard> {
+    let title = tokens::plain(title);
+    if !title.to_lowercase().contains("leaderboard") {
+        return None;
+    }
+    let category = title.split_whitespace()
+        .filter(|w| !w.to_lowercase().starts_with("leaderboard") && !matches!(*w, "-" | "—" | "–" | "|" | ":"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let category = if category.is_empty() { "Overall".to_string() } else { category };
+
+    let mut entries = Vec::new();
+    let mut own_rank = None;
+    for line in tokens::plain(description).lines() {
+        if let Some(caps) = OWN_RANK_PATTERN.captures(line) {
+            own_rank = money::parse_amount(&caps[1]).and_then(|r| u32::try_from(r).ok());
+            continue;
+        }
+        let Some(caps) = LEADERBOARD_LINE_PATTERN.captures(line) else { continue };
+        let (Ok(rank), Some(value)) = (caps[1].parse(), money::parse_amount(&caps[3])) else { continue };
+        let name = caps[2].trim().to_string();
+        let user_id = MENTION_PATTERN.captures(&name).map(|c| c[1].to_string());
+        entries.push(LeaderboardEntry { rank, name, user_id, value });
+    }
+    (!entries.is_empty() || own_rank.is_some()).then_some(Leaderboard { category, entries, own_rank })
+}
+
+pub fn parse_generic_list(title: &str, description: &str) -> Vec<GameEntity> {
+    let mut entities = Vec::new();
+    let type_name = title.split_whitespace().last().unwrap_or("Unknown").to_string();
+
+    for line in description.lines() {
+        if line.trim().is_empty() { continue; }
+        // Store every non-empty line as an entity for now
+        entities.push(GameEntity {
+            entity_type: type_name.clone(),
+            name: line.chars().take(50).collect(), // First 50 chars as name?
+            details: line.to_string()
+        });
+    }
+    entities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Formats an integer with thousands separators, as Virtual Fisher does ("1,234,567").
+    fn emoji() -> impl Strategy<Value = String> {
+        ("[a-z_0-9]{1,12}", any::<u64>(), any::<bool>())
+            .prop_map(|(name, id, animated)| format!("<{}:{}:{}>", if animated { "a" } else { "" }, name, id))
+    }
+
+    fn fish_name() -> impl Strategy<Value = String> {
+        prop::sample::select(vec!["Raw Fish", "Raw Salmon", "Cod", "Tropical Fish", "Hot Cod", "Emerald Squid", "Space Fish"])
+            .prop_map(|s| s.to_string())
+    }
+
+    fn item_name() -> impl Strategy<Value = String> {
+        "[A-Z][a-z]{2,10}( [A-Z][a-z]{2,10})?"
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parsers_never_panic(input in "\\PC{0,200}") {
+            let _ = parse_catch_embed(&input);
+            let _ = parse_cooldown_embed(&input);
+            let _ = parse_profile_embed(&input);
+            let _ = parse_shop_embed(&input, &input, None);
+            let _ = parse_generic_list(&input, &input);
+            let _ = parse_clan_boss_embed(&input, &input);
+            let _ = parse_clan_attack_result(&input);
+            let _ = parse_duration_text(&input);
+            let _ = parse_quest_reward(&input, &input);
+
+            let mut profile = crate::engine::profile::Profile::default();
+            for title in ["Profile", "Charms", "Active Buffs", "Quests", "Upgrades"] {
+                profile.update_from_message(&input, Some(title));
+            }
+        }
+
+        #[test]
+        fn prop_catch_round_trip(
+            catches in prop::collection::vec((1u64..100_000, fish_name(), emoji()), 1..6),
+            xp in 1u64..9_999_999,
+            xp_position in any::<prop::sample::Index>(),
+        ) {
+            let mut lines: Vec<String> = catches.iter()
+                .map(|(count, name, emoji)| format!("{} {} {}", money::format_thousands(*count), emoji, name))
+                .collect();
+            lines.insert(xp_position.index(lines.len() + 1), format!("+{} XP", money::format_thousands(xp)));
+
+            let event = parse_catch_embed(&lines.join("\n")).expect("catch should parse");
+            let expected: Vec<(String, i32)> = catches.iter().map(|(c, n, _)| (n.clone(), *c as i32)).collect();
+            prop_assert_eq!(event.fish, expected);
+            prop_assert_eq!(event.xp, xp as f32);
+        }
+
+        #[test]
+        fn prop_cooldown_round_trip(wait_centis in 1u32..10_000, total_centis in 1u32..10_000, wait_first in any::<bool>()) {
+            let wait = wait_centis as f32 / 100.0;
+            let total = total_centis as f32 / 100.0;
+            let wait_line = format!("You must wait **{}**s before fishing again.", wait);
+            let total_line = format!("Current cooldown: **{}** seconds", total);
+            let desc = if wait_first { format!("{}\n{}", wait_line, total_line) } else { format!("{}\n{}", total_line, wait_line) };
+
+            let event = parse_cooldown_embed(&desc).expect("cooldown should parse");
+            prop_assert_eq!(event.wait_time, wait);
+            prop_assert_eq!(event.total_cooldown, total);
+        }
+
+        #[test]
+        fn prop_shop_round_trip(
+            items in prop::collection::vec((item_name(), 1u64..9_999_999, 0usize..3, any::<bool>(), prop::option::of(emoji())), 1..8),
+        ) {
+            let separators = ["-", ":", "–"];
+            let desc: Vec<String> = items.iter().enumerate().map(|(i, (name, price, sep, bold_price, emoji))| {
+                let price_str = money::format_money(*price);
+                let price_str = if *bold_price { format!("**{}**", price_str) } else { price_str };
+                let prefix = emoji.clone().map(|e| format!("{} ", e)).unwrap_or_else(|| format!("{}. ", i + 1));
+                let sep = separators[*sep];
+                if sep == ":" {
+                    format!("{}**{}**: {}", prefix, name, price_str)
+                } else {
+                    format!("{}**{}** {} {}", prefix, name, sep, price_str)
+                }
+            }).collect();
+
+            let parsed = parse_shop_embed("Fish Shop", &desc.join("\n"), None);
+            let actual: Vec<(String, f32)> = parsed.into_iter().map(|i| (i.name, i.price)).collect();
+            let expected: Vec<(String, f32)> = items.iter().map(|(n, p, ..)| (n.clone(), *p as f32)).collect();
+            prop_assert_eq!(actual, expected);
+        }
+    }
+
+    /// What `fixtures/embeds/<kind>_<name>.json` parses to, by kind. Boost end times are
+    /// relative to now, so only the boosts' names are kept.
+    fn embed_snapshot(kind: &str, embed: &serde_json::Value) -> serde_json::Value {
+        let title = embed["title"].as_str().unwrap_or_default();
+        let description = embed["description"].as_str().unwrap_or_default();
+        let mut profile = crate::engine::profile::Profile::default();
+        profile.update_from_message(description, Some(title));
+        match kind {
+            "catch" => serde_json::json!(parse_catch_embed(description)),
+            "cooldown" => serde_json::json!(parse_cooldown_embed(description)),
+            "profile" => serde_json::json!({
+                "stats": parse_profile_embed(description),
+                "balance": profile.balance,
+                "level": profile.level,
+                "rod": profile.rod,
+                "biome": profile.biome,
+                "pet": profile.pet,
+                "bait": profile.bait,
+                "league": profile.league,
+                "exotic_fish": profile.exotic_fish,
+                "inventory": profile.inventory,
+                "inventory_value": profile.inventory_value,
+            }),
+            "shop" => {
+                let fields: Option<Vec<crate::discord::types::EmbedField>> = serde_json::from_value(embed["fields"].clone()).unwrap();
+                serde_json::json!(parse_shop_embed(title, description, fields.as_ref()))
+            },
+            "quests" => serde_json::json!(profile.quests),
+            "fishdex" => serde_json::json!(parse_fishdex_embed(title, description)),
+            "levelup" => serde_json::json!(parse_level_up(title, description)),
+            "treasure" => parse_treasure_open(title, description).map_or(serde_json::Value::Null, |t| serde_json::json!({
+                "quality": t.quality.map(|q| q.display_name()),
+                "money": t.drops.money,
+                "items": t.drops.items.iter().map(|(item, amount)| serde_json::json!([item, amount, DropKind::of(item)])).collect::<Vec<_>>(),
+            })),
+            "buffs" => {
+                let mut buffs = serde_json::json!(profile.buffs);
+                buffs["boosts"] = serde_json::json!(profile.buffs.boosts.keys().collect::<Vec<_>>());
+                buffs
+            },
+            _ => panic!("unknown fixture kind {}", kind),
+        }
+    }
+
+    /// Every `fixtures/embeds/*.json` embed must still parse to its `.expected.json`.
+    /// `UPDATE_FIXTURES=1` rewrites the expectations instead.
+    #[test]
+    fn test_embed_fixtures() {
+        let dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/embeds");
+        let mut embeds: Vec<std::path::PathBuf> = std::fs::read_dir(&dir).unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.extension().is_some_and(|e| e == "json") && !p.to_string_lossy().ends_with(".expected.json"))
+            .collect();
+        embeds.sort();
+        assert!(!embeds.is_empty(), "no embeds in {}", dir.display());
+
+        for path in embeds {
+            let stem = path.file_stem().unwrap().to_string_lossy().into_owned();
+            let kind = stem.split('_').next().unwrap();
+            let embed: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+            let actual = embed_snapshot(kind, &embed);
+            let expected_path = path.with_extension("expected.json");
+            if std::env::var_os("UPDATE_FIXTURES").is_some() {
+                std::fs::write(&expected_path, serde_json::to_string_pretty(&actual).unwrap() + "\n").unwrap();
+                continue;
+            }
+            let expected: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&expected_path).unwrap()).unwrap();
+            assert_eq!(actual, expected, "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn test_parse_clan_boss() {
+        let boss = parse_clan_boss_embed("Clan Boss: Kraken", "HP: **1,234,567** / **5,000,000**\nEnds in **3h 20m**").unwrap();
+        assert_eq!(boss.kind, ClanEventKind::Boss);
+        assert_eq!(boss.name, "Kraken");
+        assert_eq!(boss.hp_remaining, Some(1_234_567));
+        assert_eq!(boss.hp_total, Some(5_000_000));
+        assert_eq!(boss.ends_in, Some(std::time::Duration::from_secs(3 * 3600 + 20 * 60)));
+        assert!(!boss.defeated);
+
+        let raid = parse_clan_boss_embed("Clan Raid", "The raid boss has been defeated!").unwrap();
+        assert_eq!(raid.kind, ClanEventKind::Raid);
+        assert!(raid.defeated);
+        assert_eq!(raid.hp_remaining, None);
+
+        assert!(parse_clan_boss_embed("Clan Shop", "").is_none());
+
+        let attack = parse_clan_attack_result("You dealt **12,345** damage to the Kraken!\nReward: **$5,000**").unwrap();
+        assert_eq!(attack.damage, 12_345);
+        assert_eq!(attack.reward.as_deref(), Some("$5,000"));
+    }
+
+    #[test]
+    fn test_parse_quest_reward() {
+        assert_eq!(parse_quest_reward("Quests", "You claimed your quest reward!\nReward: **$12,500**").as_deref(), Some("$12,500"));
+        assert_eq!(parse_quest_reward("Quest Complete", "Claimed 3 <:bait:1> Worms").as_deref(), Some("Claimed 3 <:bait:1> Worms"));
+        assert_eq!(parse_quest_reward("Quests", "Daily - Catch 100 fish - 20/100"), None);
+        assert_eq!(parse_quest_reward("You caught", "claimed"), None);
+    }
+
+    #[test]
+    fn test_parse_crate_drops() {
+        let drops = parse_crate_drops("You opened a Super Crate!", "You got:\n**3** <:gold:1> Gold Fish\n+ 1 <:charm:2> Haste Charm\n$12,500").unwrap();
+        assert_eq!(drops.items, vec![("Gold Fish".to_string(), 3), ("Haste Charm".to_string(), 1)]);
+        assert_eq!(drops.money, Some(12_500));
+        assert!(parse_crate_drops("Inventory", "2 <:crate:1> Crate").is_none());
+        assert!(is_openable("Super Crate") && is_openable("Present") && !is_openable("Gold Fish"));
+        assert_eq!(openable_kind("You found a **Super Crate**!"), Some("Super Crate"));
+    }
+
+    #[test]
+    fn test_parse_vote_wait() {
+        assert_eq!(parse_vote_wait("Vote", "Thanks for voting!\nYou can vote again in **11h 59m**"), Some(std::time::Duration::from_secs(11 * 3600 + 59 * 60)));
+        assert_eq!(parse_vote_wait("Vote for Virtual Fisher", "You can vote now! Vote rewards: 1 crate"), Some(std::time::Duration::ZERO));
+        assert_eq!(parse_vote_wait("You caught", "3 <:cod:1> Cod"), None);
+    }
+
+    #[test]
+    fn test_parse_daily_reward() {
+        assert_eq!(parse_daily_reward("Daily Reward", "You received **$1,500**!\nStreak: **5** days"),
+            Some(DailyReward { money: Some(1500), streak: Some(5) }));
+        assert_eq!(parse_daily_reward("Daily", "You claimed $200 and kept your 12 day streak"),
+            Some(DailyReward { money: Some(200), streak: Some(12) }));
+        assert_eq!(parse_daily_reward("Daily", "You already claimed your daily reward! Come back in **5h 2m**"), None);
+        assert_eq!(parse_daily_reward("You caught", "3 <:cod:1> Cod"), None);
+    }
+
+    #[test]
+    fn test_parse_claim_reply() {
+        let msg = |title: &str, description: &str| -> crate::discord::types::Message {
+            serde_json::from_value(serde_json::json!({
+                "id": "900", "channel_id": "1", "content": "", "timestamp": "",
+                "author": { "id": "574652751745777665", "username": "Virtual Fisher", "discriminator": "0" },
+                "embeds": [{ "title": title, "description": description }]
+            })).unwrap()
+        };
+        let hours = |h: u64| std::time::Duration::from_secs(h * 3600);
+        assert_eq!(parse_claim_reply(&msg("Daily Reward", "You received **$1,500**!\nCome back in **24h**")),
+            Some(ClaimReply::Granted { reward: "$1,500".to_string(), next_in: Some(hours(24)) }));
+        assert_eq!(parse_claim_reply(&msg("Clan", "Claimed! Reward: $5,000 and 2 Clan Tokens")),
+            Some(ClaimReply::Granted { reward: "$5,000 and 2 Clan Tokens".to_string(), next_in: None }));
+        assert_eq!(parse_claim_reply(&msg("Daily", "You already claimed your daily reward! Come back in **5h 2m**")),
+            Some(ClaimReply::Cooldown(hours(5) + std::time::Duration::from_secs(120))));
+        assert_eq!(parse_claim_reply(&msg("Clan", "You can claim again in 3h")), Some(ClaimReply::Cooldown(hours(3))));
+        assert_eq!(parse_claim_reply(&msg("You caught", "3 <:cod:1> Cod")), None);
+    }
+
+    #[test]
+    fn test_parse_coinflip_result() {
+        assert_eq!(parse_coinflip_result("Coinflip", "The coin landed on **heads**! You won **$5,000**!"),
+            Some(CoinflipResult { won: true, amount: 5_000 }));
+        assert_eq!(parse_coinflip_result("Coinflip", "It was tails... You lost $1,250."),
+            Some(CoinflipResult { won: false, amount: 1_250 }));
+        assert_eq!(parse_coinflip_result("Daily Reward", "You won $1,500"), None);
+        assert_eq!(parse_coinflip_result("Coinflip", "Usage: /coinflip <amount> <side>"), None);
+    }
+
+    #[test]
+    fn test_parse_event_announcement() {
+        let announcement = parse_event_announcement("🎄 The Christmas Event has started!",
+            "Collect **Presents** while fishing and spend them in </event:1234>.\nEnds in **6d 12h**").unwrap();
+        assert_eq!(announcement, EventAnnouncement {
+            name: "Christmas".to_string(),
+            command: Some("event".to_string()),
+            currency: Some("present".to_string()),
+            ends_in: Some(std::time::Duration::from_secs((6 * 24 + 12) * 3600)),
+        });
+
+        let festival = parse_event_announcement("Announcement", "A new Winter Festival event is now live! Use `/festival shop`").unwrap();
+        assert_eq!(festival.name, "Winter Festival");
+        assert_eq!(festival.command.as_deref(), Some("festival shop"));
+        assert_eq!(festival.currency, None);
+        assert!(parse_event_announcement("You caught", "You found **2** <:present:9> Presents!").is_none());
+    }
+
+    #[test]
+    fn test_parse_trade_prompt() {
+        let msg = |author: &str, content: &str| -> crate::discord::types::Message {
+            serde_json::from_value(serde_json::json!({
+                "id": "900", "channel_id": "1", "content": content, "timestamp": "",
+                "author": { "id": author, "username": "Virtual Fisher", "discriminator": "0" },
+                "embeds": [{ "title": "Trade Request", "description": "<@42> wants to trade with <@7>" }],
+                "components": [{ "type": 1, "components": [
+                    { "type": 2, "custom_id": "trade_accept", "label": "Accept", "style": 3 },
+                    { "type": 2, "custom_id": "trade_decline", "label": "Decline", "style": 4 }
+                ]}]
+            })).unwrap()
+        };
+
+        let prompt = parse_trade_prompt(&msg("574652751745777665", ""), "7").unwrap();
+        assert_eq!(prompt.kind, TradeKind::Trade);
+        assert_eq!(prompt.decline_button.as_deref(), Some("trade_decline"));
+        assert_eq!(prompt.summary, "Trade Request");
+        // Not addressed to us, or sent by us
+        assert!(parse_trade_prompt(&msg("574652751745777665", ""), "8").is_none());
+        assert!(parse_trade_prompt(&msg("7", ""), "7").is_none());
+        assert_eq!(parse_trade_prompt(&msg("1", "<@7> you got a gift!"), "7").unwrap().kind, TradeKind::Gift);
+    }
+
+    #[test]
+    fn test_find_components() {
+        let msg: crate::discord::types::Message = serde_json::from_value(serde_json::json!({
+            "id": "902", "channel_id": "1", "content": "", "timestamp": "",
+            "author": { "id": "574652751745777665", "username": "Virtual Fisher", "discriminator": "0" },
+            "embeds": [],
+            "components": [
+                { "type": 1, "components": [{ "type": 3, "custom_id": "shop_item", "placeholder": "Choose an item", "options": [] }] },
+                { "type": 1, "components": [{ "type": 5, "custom_id": "gift_user", "placeholder": "Pick a user" }] },
+                { "type": 1, "components": [
+                    { "type": 2, "custom_id": "buy_confirm", "label": "Confirm purchase", "style": 3 },
+                    { "type": 2, "custom_id": "buy_cancel", "label": "Cancel", "style": 4 }
+                ]}
+            ]
+        })).unwrap();
+
+        assert_eq!(find_button(&msg, "confirm").as_deref(), Some("buy_confirm"));
+        assert_eq!(find_select(&msg, "user"), Some(("gift_user".to_string(), 5)));
+        assert_eq!(find_select(&msg, ""), Some(("shop_item".to_string(), 3)));
+        assert!(find_select(&msg, "channel").is_none());
+        assert!(!is_out_of_bait(&msg));
+    }
+
+    #[test]
+    fn test_parse_captcha() {
+        let msg = |embed: serde_json::Value, attachments: serde_json::Value| -> crate::discord::types::Message {
+            serde_json::from_value(serde_json::json!({
+                "id": "901", "channel_id": "1", "content": "", "timestamp": "",
+                "author": { "id": "574652751745777665", "username": "Virtual Fisher", "discriminator": "0" },
+                "embeds": [embed], "components": null, "attachments": attachments
+            })).unwrap()
+        };
+
+        let prompt = msg(serde_json::json!({ "title": "Anti-bot", "description": "Please solve the captcha with `/verify`",
+            "image": { "url": "https://cdn.example/captcha.png" } }), serde_json::json!([]));
+        assert_eq!(parse_captcha(&prompt).unwrap().image_url.as_deref(), Some("https://cdn.example/captcha.png"));
+
+        let attached = msg(serde_json::json!({ "description": "You must complete the captcha before fishing" }),
+            serde_json::json!([{ "url": "https://cdn.example/code.PNG", "filename": "code.PNG" }]));
+        assert_eq!(parse_captcha(&attached).unwrap().image_url.as_deref(), Some("https://cdn.example/code.PNG"));
+
+        let reminder = msg(serde_json::json!({ "description": "Use /verify to continue" }), serde_json::json!([]));
+        assert_eq!(parse_captcha(&reminder), Some(CaptchaChallenge { image_url: None }));
+
+        let solved = msg(serde_json::json!({ "description": "You have been verified! You can now continue fishing." }), serde_json::json!([]));
+        assert!(parse_captcha(&solved).is_none());
+        assert_eq!(parse_captcha_result(&solved), Some(true));
+        let wrong = msg(serde_json::json!({ "description": "Incorrect captcha code, try /verify again" }), serde_json::json!([]));
+        assert_eq!(parse_captcha_result(&wrong), Some(false));
+        assert!(parse_captcha(&wrong).is_none());
+        assert!(parse_captcha(&msg(serde_json::json!({ "description": "You caught a Cod!" }), serde_json::json!([]))).is_none());
+    }
+
+    #[test]
+    fn test_parse_shop_embed_variations() {
+        let title = "Fish Shop";
+
+        // Variation 1: Existing assumption
+        let desc1 = "**Plastic Rod** - $500\n**Steel Rod** - $1,500";
+        let items1 = parse_shop_embed(title, desc1, None);
+        assert_eq!(items1.len(), 2, "Failed Variation 1");
+        assert_eq!(items1[0].name, "Plastic Rod");
+        assert_eq!(items1[0].price, 500.0);
+        assert_eq!(items1[1].name, "Steel Rod");
+        assert_eq!(items1[1].price, 1500.0);
+
+        // Variation 2: With Emojis and no bold on price
+        let desc2 = "<:rod:123> **Plastic Rod** - $500\n<:rod:123> **Steel Rod** - $1,500";
+        let items2 = parse_shop_embed(title, desc2, None);
+        // This fails with current regex if it strictly expects start of line or specific format
+        // Current regex: r"\*\*([^\*]+)\*\*\s+-\s+\$([\d,]+)"
+        // It doesn't anchor to start, so it might pass if format is " - $".
+
+        // Variation 3: Price in bold
+        let desc3 = "**Plastic Rod** - **$500**";
+        let items3 = parse_shop_embed(title, desc3, None);
+
+        // Variation 4: Colon separator
+        let desc4 = "**Plastic Rod**: $500";
+        let items4 = parse_shop_embed(title, desc4, None);
+
+        // Variation 6: ID prefix
+        let desc6 = "1. **Plastic Rod** - $500";
+        let items6 = parse_shop_embed(title, desc6, None);
+
+        assert_eq!(items2.len(), 2, "Failed Variation 2");
+        assert_eq!(items2[0].name, "Plastic Rod");
+        assert_eq!(items2[0].price, 500.0);
+        assert_eq!(items2[1].name, "Steel Rod");
+        assert_eq!(items2[1].price, 1500.0);
+
+        assert_eq!(items3.len(), 1, "Failed Variation 3");
+        assert_eq!(items3[0].name, "Plastic Rod");
+        assert_eq!(items3[0].price, 500.0);
+
+        assert_eq!(items4.len(), 1, "Failed Variation 4");
+        assert_eq!(items4[0].name, "Plastic Rod");
+        assert_eq!(items4[0].price, 500.0);
+
+        assert_eq!(items6.len(), 1, "Failed Variation 6");
+        assert_eq!(items6[0].name, "Plastic Rod");
+        assert_eq!(items6[0].price, 500.0);
+    }
+}
@@ -0,0 +1,134 @@
+/// One emoji in embed text: a custom one (`<:cod:123>`, animated `<a:gold:456>`) or a run of
+/// unicode emoji ("🎣", "👍🏽").
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Emoji<'a> {
+    /// "cod" for `<:cod:123>`; the emoji itself for unicode ones
+    pub name: &'a str,
+    /// `None` for unicode emoji
+    pub id: Option<&'a str>,
+    pub animated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token<'a> {
+    Text(&'a str),
+    Emoji(Emoji<'a>),
+}
+
+/// Splits embed text into text and emoji. Other `<...>` markup (mentions, `</cmd:1>`,
+/// timestamps) stays text.
+pub fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        let found = if rest.starts_with('<') {
+            custom_emoji(rest)
+        } else {
+            unicode_emoji(rest)
+        };
+        match found {
+            Some((emoji, len)) => {
+                if text_start < i {
+                    tokens.push(Token::Text(&text[text_start..i]));
+                }
+                tokens.push(Token::Emoji(emoji));
+                i += len;
+                text_start = i;
+            },
+            None => i += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    if text_start < text.len() {
+        tokens.push(Token::Text(&text[text_start..]));
+    }
+    tokens
+}
+
+/// Every emoji in `text`, in order.
+pub fn emojis(text: &str) -> Vec<Emoji<'_>> {
+    tokenize(text).into_iter().filter_map(|t| match t {
+        Token::Emoji(emoji) => Some(emoji),
+        Token::Text(_) => None,
+    }).collect()
+}
+
+/// `text` without emoji, each line trimmed with its spaces collapsed. Markdown stays, for
+/// parsers that find names by their bold.
+pub fn strip_emoji(text: &str) -> String {
+    let joined: String = tokenize(text).into_iter().map(|t| match t {
+        Token::Text(text) => text,
+        Token::Emoji(_) => " ",
+    }).collect();
+    collapse_spaces(&joined)
+}
+
+/// `text` as read: no emoji, no markdown (`**`, `__`, `*`, `~~`, `||`, backticks) and
+/// single spaces, line by line.
+pub fn plain(text: &str) -> String {
+    let stripped = strip_emoji(text);
+    let unmarked = ["**", "__", "~~", "||", "`", "*"].iter().fold(stripped, |s, mark| s.replace(mark, ""));
+    collapse_spaces(&unmarked)
+}
+
+fn collapse_spaces(text: &str) -> String {
+    text.lines().map(|line| line.split_whitespace().collect::<Vec<_>>().join(" ")).collect::<Vec<_>>().join("\n")
+}
+
+/// `<:name:id>` or `<a:name:id>` at the start of `s`, and its length.
+fn custom_emoji(s: &str) -> Option<(Emoji<'_>, usize)> {
+    let inner = s.strip_prefix('<')?;
+    let (animated, inner) = match inner.strip_prefix("a:") {
+        Some(inner) => (true, inner),
+        None => (false, inner.strip_prefix(':')?),
+    };
+    let (name, inner) = inner.split_once(':')?;
+    let (id, _) = inner.split_once('>')?;
+    let valid_name = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '~');
+    if !valid_name || id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let len = s.len() - inner.len() + id.len() + 1;
+    Some((Emoji { name, id: Some(id), animated }, len))
+}
+
+/// A run of unicode emoji at the start of `s`, with their joiners and modifiers, and its length.
+fn unicode_emoji(s: &str) -> Option<(Emoji<'_>, usize)> {
+    let first = s.chars().next()?;
+    if !is_emoji(first) {
+        return None;
+    }
+    let len: usize = s.chars().take_while(|&c| is_emoji(c) || is_emoji_modifier(c)).map(char::len_utf8).sum();
+    Some((Emoji { name: &s[..len], id: None, animated: false }, len))
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c, '\u{1F000}'..='\u{1FAFF}' | '\u{2600}'..='\u{27BF}' | '\u{2B00}'..='\u{2BFF}' | '\u{2300}'..='\u{23FF}')
+}
+
+/// Zero-width joiners, variation selectors and keycaps that belong to the emoji before them.
+fn is_emoji_modifier(c: char) -> bool {
+    matches!(c, '\u{200D}' | '\u{FE0F}' | '\u{20E3}' | '\u{E0020}'..='\u{E007F}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize("**3** <a:gold_fish:123> Gold Fish 🎣👍🏽 <@42> </fish:9>");
+        assert_eq!(tokens, vec![
+            Token::Text("**3** "),
+            Token::Emoji(Emoji { name: "gold_fish", id: Some("123"), animated: true }),
+            Token::Text(" Gold Fish "),
+            Token::Emoji(Emoji { name: "🎣👍🏽", id: None, animated: false }),
+            Token::Text(" <@42> </fish:9>"),
+        ]);
+        assert_eq!(emojis("<:cod:1>Cod <:bad:x>").len(), 1);
+        assert_eq!(strip_emoji("**<:rod:1> Plastic Rod**  - $500\n ✅ Done "), "** Plastic Rod** - $500\nDone");
+        assert_eq!(plain("Balance: <:coin:5> **$3,548**\n__Level__ `12`"), "Balance: $3,548\nLevel 12");
+        assert_eq!(plain("<:broken"), "<:broken");
+    }
+}
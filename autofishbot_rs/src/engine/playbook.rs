@@ -0,0 +1,134 @@
+use crate::engine::game_data::{Biome, ROD_DATA};
+use crate::engine::profile::Profile;
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+use std::path::Path;
+
+/// A user's phased plan (`[strategy] playbook`), followed instead of the ROI optimizer where
+/// a phase says so. The current phase is the first one whose `until_*` goals aren't met, so
+/// after a prestige resets the profile the playbook starts over on its own.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct Playbook {
+    #[serde(rename = "phase")]
+    pub phases: Vec<Phase>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Phase {
+    pub name: String,
+    /// Fish here; the optimizer's travel suggestions are ignored
+    pub biome: Option<String>,
+    /// Buy this rod once it's affordable, instead of the optimizer's rod pick
+    pub rod: Option<String>,
+    pub until_level: Option<u64>,
+    pub until_rod: Option<String>,
+    pub until_balance: Option<u64>,
+    /// Send `/prestige` once the level gets here
+    pub prestige_at_level: Option<u64>,
+}
+
+impl Phase {
+    /// All of the set `until_*` goals hold. A phase without any never completes.
+    pub fn is_complete(&self, profile: &Profile) -> bool {
+        let goals = [
+            self.until_level.map(|level| profile.level_number().unwrap_or(0) >= level),
+            self.until_rod.as_ref().map(|rod| profile.rod.eq_ignore_ascii_case(rod)),
+            self.until_balance.map(|balance| profile.balance.unwrap_or(0) >= balance),
+        ];
+        goals.iter().any(Option::is_some) && goals.iter().all(|goal| goal.unwrap_or(true))
+    }
+
+    pub fn wants_prestige(&self, profile: &Profile) -> bool {
+        self.prestige_at_level.is_some_and(|level| profile.level_number().unwrap_or(0) >= level)
+    }
+
+    pub fn target_biome(&self) -> Option<Biome> {
+        self.biome.as_deref().and_then(Biome::from_display_name)
+    }
+}
+
+impl Playbook {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| anyhow!("Can't read playbook {}: {}", path.display(), e))?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let playbook: Playbook = toml::from_str(text)?;
+        if playbook.phases.is_empty() {
+            return Err(anyhow!("the playbook has no [[phase]] tables"));
+        }
+        for (i, phase) in playbook.phases.iter().enumerate() {
+            if phase.biome.is_some() && phase.target_biome().is_none() {
+                return Err(anyhow!("phase {}: unknown biome {:?}", i + 1, phase.biome.as_deref().unwrap_or_default()));
+            }
+            if let Some(rod) = phase.rod.as_deref().filter(|rod| !ROD_DATA.values().any(|r| r.name.eq_ignore_ascii_case(rod))) {
+                return Err(anyhow!("phase {}: unknown rod {:?}", i + 1, rod));
+            }
+        }
+        Ok(playbook)
+    }
+
+    /// The first phase not completed yet, with its index; `None` once every phase is done.
+    pub fn current(&self, profile: &Profile) -> Option<(usize, &Phase)> {
+        self.phases.iter().enumerate().find(|(_, phase)| !phase.is_complete(profile))
+    }
+
+    /// "2/3 Volcanic to 30" for the Strategy panel.
+    pub fn describe(&self, profile: &Profile) -> String {
+        match self.current(profile) {
+            Some((i, phase)) => {
+                let name = if phase.name.is_empty() { format!("Phase {}", i + 1) } else { phase.name.clone() };
+                format!("{}/{} {}", i + 1, self.phases.len(), name)
+            },
+            None => "Playbook complete".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_playbook_phases() {
+        let playbook = Playbook::parse(r#"
+            [[phase]]
+            name = "River until Fiberglass"
+            biome = "River"
+            rod = "Fiberglass Rod"
+            until_rod = "Fiberglass Rod"
+
+            [[phase]]
+            name = "Volcanic to 30"
+            biome = "Volcanic"
+            until_level = 30
+
+            [[phase]]
+            name = "Prestige"
+            prestige_at_level = 40
+        "#).unwrap();
+
+        let mut profile = Profile { rod: "Plastic Rod".to_string(), level: Some(5), ..Default::default() };
+        assert_eq!(playbook.current(&profile).map(|(i, _)| i), Some(0));
+
+        profile.rod = "Fiberglass Rod".to_string();
+        let (i, phase) = playbook.current(&profile).unwrap();
+        assert_eq!((i, phase.target_biome()), (1, Some(Biome::Volcanic)));
+        assert_eq!(playbook.describe(&profile), "2/3 Volcanic to 30");
+
+        profile.level = Some(41);
+        let (i, phase) = playbook.current(&profile).unwrap();
+        assert_eq!(i, 2);
+        assert!(phase.wants_prestige(&profile));
+
+        // A prestige resets the profile, and the playbook with it
+        profile.level = Some(1);
+        profile.rod = "Plastic Rod".to_string();
+        assert_eq!(playbook.current(&profile).map(|(i, _)| i), Some(0));
+
+        assert!(Playbook::parse("[[phase]]\nbiome = \"Swamp\"").is_err());
+        assert!(Playbook::parse("").is_err());
+    }
+}
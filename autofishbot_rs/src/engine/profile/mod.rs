@@ -0,0 +1,438 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use crate::config::CosmeticConfig;
+use crate::engine::game_data::{Biome, Boat, Pet, BOAT_DATA, PET_DATA};
+use crate::util::money;
+
+pub mod parse;
+
+/// What each charm level adds to its stat: 0.01 is +1%.
+pub const CHARM_BONUS_PER_LEVEL: f64 = 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CharmType {
+    Marketing,
+    Endurance,
+    Haste,
+    Quantity,
+    Worker,
+    Treasure,
+    Quality,
+    Experience,
+}
+
+impl CharmType {
+    /// As `/charms use` takes it.
+    pub fn name(self) -> &'static str {
+        match self {
+            CharmType::Marketing => "marketing",
+            CharmType::Endurance => "endurance",
+            CharmType::Haste => "haste",
+            CharmType::Quantity => "quantity",
+            CharmType::Worker => "worker",
+            CharmType::Treasure => "treasure",
+            CharmType::Quality => "quality",
+            CharmType::Experience => "experience",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpgradeStatus {
+    pub level: u32,
+    pub max_level: u32,
+    pub next_cost: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    /// `None` until a profile embed (or a saved snapshot) gives it
+    pub balance: Option<u64>,
+    pub level: Option<u32>,
+    pub rod: String,
+    pub biome: String,
+    pub current_biome: Option<String>, // Added for persistent tracking
+    pub pet: String,
+    pub bait: String,
+    /// "Fish Value" from the profile embed
+    pub inventory_value: Option<u64>,
+    pub exotic_fish: ExoticFish,
+    pub inventory: Vec<(String, String)>,
+    pub charms: Charms,
+    pub buffs: Buffs,
+    pub quests: Vec<Quest>,
+    pub upgrades: HashMap<String, UpgradeStatus>,
+    /// League rank as shown, e.g. "Gold III"
+    pub league: String,
+    /// League currency, spent on the League upgrades
+    pub hooks: u64,
+    /// From the last achievements/badges embed
+    pub achievements: Vec<Achievement>,
+    /// Boats seen as owned, from profile and shop embeds (and the `owned_boats` table)
+    #[serde(default)]
+    pub boats: Vec<String>,
+    #[serde(default)]
+    pub workers: Workers,
+}
+
+/// From the workers embed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Workers {
+    pub level: Option<u32>,
+    /// Fish the workers catch per hour, boost included
+    pub fish_per_hour: Option<u64>,
+    /// Fish waiting to be collected
+    pub uncollected: u64,
+    /// Unix time the worker boost runs out; `None` without one
+    pub boost_ends: Option<i64>,
+}
+
+impl Workers {
+    pub fn boosted(&self, now: i64) -> bool {
+        self.boost_ends.is_some_and(|ends| ends > now)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExoticFish {
+    pub gold: i32,
+    pub emerald: i32,
+    pub lava: i32,
+    pub diamond: i32,
+}
+
+impl ExoticFish {
+    pub fn count(&self) -> i32 {
+        self.gold + self.emerald + self.lava + self.diamond
+    }
+}
+
+/// One line of the charms embed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Charm {
+    /// How many are held; one for a charm listed by level ("Haste II")
+    pub count: u64,
+    /// What the bonus scales with: the level, or the count for a charm listed by count
+    pub level: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Charms {
+    pub marketing: Charm,
+    pub endurance: Charm,
+    pub haste: Charm,
+    pub quantity: Charm,
+    pub worker: Charm,
+    pub treasure: Charm,
+    pub quality: Charm,
+    pub experience: Charm,
+    /// "Total charms found"
+    pub found: u64,
+}
+
+impl fmt::Display for Charm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if u64::from(self.level) == self.count {
+            write!(f, "{}", self.count)
+        } else {
+            write!(f, "{} (level {})", self.count, self.level)
+        }
+    }
+}
+
+impl Charms {
+    pub fn get(&self, charm_type: CharmType) -> Charm {
+        match charm_type {
+            CharmType::Marketing => self.marketing,
+            CharmType::Endurance => self.endurance,
+            CharmType::Haste => self.haste,
+            CharmType::Quantity => self.quantity,
+            CharmType::Worker => self.worker,
+            CharmType::Treasure => self.treasure,
+            CharmType::Quality => self.quality,
+            CharmType::Experience => self.experience,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Buffs {
+    pub sell_price: String,
+    pub fish_catch: String,
+    pub fish_quality: String,
+    pub treasure_chance: String,
+    pub treasure_quality: String,
+    pub xp_multiplier: String,
+    pub fishing_cooldown: String,
+    /// Active boosts by `Boost::display_name`, with the unix time each one runs out
+    pub boosts: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Quest {
+    pub category: String,
+    pub objective: String,
+    pub progress: String,
+    pub is_completed: bool,
+}
+
+/// One achievement or badge and how far along it is.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Achievement {
+    pub name: String,
+    pub is_badge: bool,
+    pub progress: u64,
+    pub target: u64,
+    pub is_completed: bool,
+}
+
+impl Achievement {
+    pub fn fraction(&self) -> f64 {
+        if self.is_completed {
+            1.0
+        } else if self.target > 0 {
+            (self.progress as f64 / self.target as f64).min(1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// A crate or present to open, with the message's Open button when there is one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrateOpen {
+    pub name: String,
+    /// The message with the Open button and its channel, when there is one
+    pub message: Option<(String, String)>,
+    pub button_custom_id: Option<String>,
+}
+
+/// A quest that just turned COMPLETED and still needs its reward claimed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuestClaim {
+    pub objective: String,
+    /// The quests message and its channel, for clicking its Claim button
+    pub message_id: String,
+    pub channel_id: String,
+    pub button_custom_id: Option<String>,
+}
+
+impl Profile {
+    /// The balance as Virtual Fisher shows it ("$1,234"), empty until a profile embed was seen.
+    pub fn balance_text(&self) -> String {
+        self.balance.map(money::format_money).unwrap_or_default()
+    }
+
+    /// "Level 52", empty until a profile embed was seen.
+    pub fn level_text(&self) -> String {
+        self.level.map(|level| format!("Level {}", level)).unwrap_or_default()
+    }
+
+    /// `level`, for comparing against config thresholds.
+    pub fn level_number(&self) -> Option<u64> {
+        self.level.map(u64::from)
+    }
+
+    /// A prestige sends the level back down, so a level below `last_level` means one happened.
+    pub fn prestiged_since(&self, last_level: Option<u64>) -> bool {
+        self.level_number().zip(last_level).is_some_and(|(level, last)| level < last)
+    }
+
+    /// The commands that would bring the pet, bait and biome in line with `[cosmetic]`.
+    pub fn cosmetic_commands(&self, cosmetic: &CosmeticConfig) -> Vec<String> {
+        let mut commands = Vec::new();
+        if let Some(pet) = cosmetic.pet.as_deref().filter(|p| !self.pet.to_lowercase().contains(&p.to_lowercase())) {
+            commands.push(format!("{} {}", cosmetic.pet_command, pet));
+        }
+        if let Some(bait) = cosmetic.bait.as_deref().filter(|b| !self.bait.to_lowercase().contains(&b.to_lowercase())) {
+            commands.push(format!("{} {}", cosmetic.bait_command, bait));
+        }
+        if let Some(biome) = cosmetic.biome.as_deref().and_then(Biome::from_display_name) {
+            if Biome::from_display_name(&self.biome) != Some(biome) {
+                commands.push(format!("biome {}", biome.display_name()));
+            }
+        }
+        commands
+    }
+
+    /// Forgets what a prestige resets, as `Optimizer::evaluate_prestige` models it: level, balance,
+    /// rod (back to Plastic) and boats (back to the Rowboat). Upgrades, charms and pets carry over.
+    pub fn reset_for_prestige(&mut self) {
+        self.level = None;
+        self.balance = None;
+        self.rod.clear();
+        self.boats.clear();
+    }
+
+    /// Returns whether `name` is new.
+    pub fn add_boat(&mut self, name: &str) -> bool {
+        if self.boats.iter().any(|b| b.eq_ignore_ascii_case(name)) {
+            return false;
+        }
+        self.boats.push(name.to_string());
+        true
+    }
+
+    /// The best boat owned. Boats are bought in order, so it implies every cheaper one.
+    pub fn current_boat(&self) -> Option<&'static Boat> {
+        self.boats.iter()
+            .filter_map(|name| BOAT_DATA.values().find(|b| b.name.eq_ignore_ascii_case(name)))
+            .max_by_key(|b| b.price)
+    }
+
+    /// Share of achievements completed, counting partial progress, as 0.0..=100.0.
+    pub fn achievement_completion(&self) -> Option<f64> {
+        if self.achievements.is_empty() {
+            return None;
+        }
+        let total: f64 = self.achievements.iter().map(Achievement::fraction).sum();
+        Some(total / self.achievements.len() as f64 * 100.0)
+    }
+
+    /// Unfinished achievements at least `min_fraction` of the way there, closest first,
+    /// so they can be worked on like quests.
+    pub fn near_complete_achievements(&self, min_fraction: f64) -> Vec<&Achievement> {
+        let mut near: Vec<&Achievement> = self.achievements.iter()
+            .filter(|a| !a.is_completed && a.fraction() >= min_fraction)
+            .collect();
+        near.sort_by(|a, b| b.fraction().partial_cmp(&a.fraction()).unwrap_or(std::cmp::Ordering::Equal));
+        near
+    }
+
+    /// How many of a charm the charms embed listed; one for a charm given by level ("Haste II").
+    pub fn charm_count(&self, charm_type: CharmType) -> u64 {
+        self.charms.get(charm_type).count
+    }
+
+    /// What the charm adds to its stat, as a fraction (0.05 is +5%): `CHARM_BONUS_PER_LEVEL`
+    /// for each level the charms embed showed.
+    pub fn get_charm_bonus(&self, charm_type: CharmType) -> f64 {
+        self.charms.get(charm_type).level as f64 * CHARM_BONUS_PER_LEVEL
+    }
+
+    /// The equipped pet, from a profile's "Pet:" line ("Shark (Lvl 3)"). The longest name wins.
+    pub fn pet(&self) -> Option<&'static Pet> {
+        let lower = self.pet.to_lowercase();
+        PET_DATA.values()
+            .filter(|p| lower.contains(&p.name.to_lowercase()))
+            .max_by_key(|p| p.name.len())
+    }
+
+    /// The pet's catch and XP bonuses as fractions, zero without a known pet.
+    pub fn get_pet_mults(&self) -> (f64, f64) {
+        self.pet().map_or((0.0, 0.0), |pet| (pet.catch_bonus, pet.xp_bonus))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_achievements() {
+        let mut profile = Profile::default();
+        profile.update_from_message(
+            "**Your Achievements**\n\
+             <:rod:1> **Fisherman** - Catch 1,000 fish - 900/1,000\n\
+             <:coin:2> **Big Spender** - COMPLETED\n\
+             **Explorer** - Visit every biome - 1/4",
+            Some("Achievements"));
+
+        assert_eq!(profile.achievements.len(), 3);
+        assert_eq!(profile.achievements[0], Achievement { name: "Fisherman".to_string(), is_badge: false, progress: 900, target: 1000, is_completed: false });
+        assert!(profile.achievements[1].is_completed);
+        // (0.9 + 1.0 + 0.25) / 3
+        assert!((profile.achievement_completion().unwrap() - 71.666).abs() < 0.01);
+
+        let near: Vec<&str> = profile.near_complete_achievements(0.8).iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(near, vec!["Fisherman"]);
+    }
+
+    #[test]
+    fn test_charm_and_pet_bonuses() {
+        let mut profile = Profile::default();
+        assert_eq!(profile.get_charm_bonus(CharmType::Marketing), 0.0);
+        assert_eq!(profile.get_pet_mults(), (0.0, 0.0));
+
+        profile.update_from_message("Marketing V/X\nHaste: 3/10", Some("Charms"));
+        assert!((profile.get_charm_bonus(CharmType::Marketing) - 0.05).abs() < 1e-9);
+        // A count says nothing about the level
+        assert_eq!(profile.get_charm_bonus(CharmType::Haste), 0.0);
+        assert_eq!(profile.get_charm_bonus(CharmType::Treasure), 0.0);
+        assert_eq!((profile.charm_count(CharmType::Marketing), profile.charm_count(CharmType::Haste)), (1, 3));
+
+        profile.pet = "shark (Lvl 3)".to_string();
+        assert_eq!(profile.get_pet_mults(), (0.15, 0.05));
+        profile.pet = "Goldfish".to_string();
+        assert!(profile.pet().is_none());
+    }
+
+    #[test]
+    fn test_owned_boats() {
+        let mut profile = Profile::default();
+        assert!(profile.current_boat().is_none());
+        profile.update_from_message("Balance: $1,000\nBoats: <:boat:1> Rowboat, Fishing Boat", Some("Profile"));
+        assert_eq!(profile.boats, vec!["Rowboat", "Fishing Boat"]);
+        profile.update_from_message("**Speedboat** - $100,000 - Owned\n**Pontoon** - $250,000 - Not owned\n✅ **Luxury Yacht**", Some("Boat Shop"));
+        assert_eq!(profile.boats, vec!["Rowboat", "Fishing Boat", "Speedboat", "Luxury Yacht"]);
+        assert_eq!(profile.current_boat().unwrap().name, "Luxury Yacht");
+        assert!(!profile.add_boat("speedboat"));
+    }
+
+    #[test]
+    fn test_reset_for_prestige() {
+        let mut profile = Profile::default();
+        profile.update_from_message("Balance: $1,000\nLevel: 120\nRod: Carbon Rod\nBoats: Rowboat, Speedboat", Some("Profile"));
+        profile.upgrades.insert("Business Education".to_string(), UpgradeStatus { level: 2, max_level: 5, next_cost: None });
+        assert_eq!(profile.current_boat().unwrap().name, "Speedboat");
+
+        profile.reset_for_prestige();
+        // The optimizer's fallbacks for an unknown rod and fleet are the Plastic Rod and the Rowboat
+        assert_eq!((profile.level, profile.balance, profile.rod.as_str()), (None, None, ""));
+        assert!(profile.current_boat().is_none());
+        assert_eq!(profile.upgrades["Business Education"].level, 2);
+    }
+
+    #[test]
+    fn test_prestige_detection() {
+        let mut profile = Profile::default();
+        assert_eq!(profile.level_number(), None);
+        assert!(!profile.prestiged_since(Some(120)));
+
+        profile.level = Some(120);
+        assert_eq!(profile.level_number(), Some(120));
+        assert!(!profile.prestiged_since(None));
+        assert!(!profile.prestiged_since(Some(119)) && !profile.prestiged_since(Some(120)));
+        profile.level = Some(1);
+        assert!(profile.prestiged_since(Some(120)));
+    }
+
+    #[test]
+    fn test_cosmetic_commands() {
+        let cosmetic = CosmeticConfig { pet: Some("dolphin".to_string()), bait: Some("fish".to_string()), biome: Some("ocean".to_string()), ..Default::default() };
+        let mut profile = Profile::default();
+        assert_eq!(profile.cosmetic_commands(&cosmetic), ["pet dolphin", "bait fish", "biome Ocean"]);
+
+        profile.pet = "Dolphin (Lvl 2)".to_string();
+        profile.bait = "Fish Bait".to_string();
+        profile.biome = "ocean".to_string();
+        assert!(profile.cosmetic_commands(&cosmetic).is_empty());
+        // Nothing configured, nothing to send
+        assert!(profile.cosmetic_commands(&CosmeticConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_parse_workers() {
+        let mut profile = Profile::default();
+        profile.update_from_message("**Level:** 7\nFish per hour: **1,200**\nReady to collect: **3,450** fish\nBoost: **1h 30m** left", Some("Your Workers"));
+        let now = chrono::Utc::now().timestamp();
+        assert_eq!(profile.workers.level, Some(7));
+        assert_eq!(profile.workers.fish_per_hour, Some(1_200));
+        assert_eq!(profile.workers.uncollected, 3_450);
+        assert!(profile.workers.boosted(now) && !profile.workers.boosted(now + 2 * 3600));
+
+        let idle = Profile::parse_workers("Level: 7\nFish per hour: 800\nReady to collect: 0\nBoost: none");
+        assert_eq!((idle.uncollected, idle.boost_ends), (0, None));
+    }
+}
@@ -0,0 +1,433 @@
+use super::{Achievement, Charm, Profile, Quest, UpgradeStatus, Workers};
+use crate::engine::game_data::{Biome, Boat, Boost, BOAT_DATA};
+use crate::engine::parser;
+use crate::engine::parser::tokens::{self, Token};
+use crate::util::money;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    // "523/1,000" or "523 / 1000"
+    static ref PROGRESS_PATTERN: Regex = Regex::new(r"([\d,]+)\s*/\s*([\d,]+)").unwrap();
+}
+
+/// "$1,234,567", "$1.5M".
+pub fn parse_balance(text: &str) -> Option<u64> {
+    money::parse_amount(text)
+}
+
+/// "Level 52", or the whole "Level 52, 1,234 XP to next level" line.
+pub fn parse_level(text: &str) -> Option<u32> {
+    let level = text.split(',').next()?;
+    money::parse_amount(level).and_then(|l| u32::try_from(l).ok())
+}
+
+/// A charms embed entry: a count ("Marketing: 3") or a charm given by level ("Haste II",
+/// "Haste Lvl 2"). A bare number is always the count; only a numeral or "Lvl" gives a level.
+pub fn parse_charm(text: &str) -> Charm {
+    // ASCII lowercasing keeps byte offsets valid for slicing `text`
+    let (rest, level) = match text.to_ascii_lowercase().rfind("lvl") {
+        Some(idx) => {
+            let level = text[idx + 3..].trim_start_matches(['.', ' ']).split_whitespace().next()
+                .and_then(|n| n.parse().ok()).unwrap_or(0);
+            (&text[..idx], level)
+        }
+        None => {
+            let (rest, last) = text.trim_end().rsplit_once(' ').unwrap_or(("", text.trim()));
+            match parse_roman(last) {
+                0 => (text, 0),
+                level => (rest, level),
+            }
+        }
+    };
+    let count = money::parse_amount(rest).unwrap_or(u64::from(level > 0));
+    Charm { count, level }
+}
+
+/// "IV"; 0 for anything that isn't a numeral from I to X.
+fn parse_roman(s: &str) -> u32 {
+    match s {
+        "I" => 1, "II" => 2, "III" => 3, "IV" => 4, "V" => 5,
+        "VI" => 6, "VII" => 7, "VIII" => 8, "IX" => 9, "X" => 10,
+        _ => 0
+    }
+}
+
+fn remove_markdown(data: &str) -> String {
+    data.replace(['*', '+', '_'], "")
+}
+
+/// What a "Label: value" line says, without emoji: "Pet: <:cat:1> Cat" is "Cat". Lines
+/// without a label are all value.
+fn labelled_value(line: &str) -> String {
+    let plain = tokens::plain(line);
+    plain.split_once(':').map_or(plain.as_str(), |(_, value)| value).trim().to_string()
+}
+
+/// An inventory line, "12 <:bait:1> Worms": the text before the first emoji and after the last.
+fn inventory_item(line: &str) -> Option<(String, String)> {
+    let parts = tokens::tokenize(line);
+    let first = parts.iter().position(|t| matches!(t, Token::Emoji(_)))?;
+    let last = parts.iter().rposition(|t| matches!(t, Token::Emoji(_)))?;
+    let text = |tokens: &[Token]| tokens::plain(&tokens.iter().filter_map(|t| match t {
+        Token::Text(text) => Some(*text),
+        Token::Emoji(_) => None,
+    }).collect::<String>());
+    Some((text(&parts[..first]), text(&parts[last + 1..])))
+}
+
+impl Profile {
+    pub fn update_from_message(&mut self, content: &str, title: Option<&str>) {
+        if let Some(t) = title {
+            if t.contains("Profile") {
+                self.parse_profile(content);
+            } else if t.contains("Charms") {
+                self.parse_charms(content);
+            } else if t.contains("Active Buffs") {
+                self.parse_buffs(content);
+            } else if t.contains("Quests") {
+                self.parse_quests(content);
+            } else if t.contains("Upgrades") || t.contains("Shop") {
+                // "Shop" might contain upgrades too
+                self.parse_upgrades(content);
+                self.parse_owned_boats(content);
+            } else if t.contains("League") {
+                self.parse_league(content);
+            } else if t.contains("Achievements") || t.contains("Badges") {
+                self.parse_achievements(content, t.contains("Badges"));
+            } else if t.contains("Workers") {
+                self.workers = Self::parse_workers(content);
+            }
+        }
+    }
+
+    /// Whether `update_from_message` has a parser for an embed with this title.
+    pub fn is_known_title(title: &str) -> bool {
+        ["Profile", "Charms", "Active Buffs", "Quests", "League", "Upgrades", "Shop", "Achievements", "Badges", "Workers"].iter().any(|t| title.contains(t))
+    }
+
+    fn parse_profile(&mut self, content: &str) {
+        self.inventory.clear();
+        let clean_content = remove_markdown(content);
+
+        for line in clean_content.lines() {
+            let line = line.trim();
+            if line.is_empty() { continue; }
+
+            if self.parse_league_line(line) {
+                continue;
+            }
+            if let Some(balance) = line.strip_prefix("Balance:") {
+                self.balance = parse_balance(balance);
+            } else if line.contains("XP to next level") {
+                self.level = parse_level(line);
+            } else if line.contains("Rod") {
+                 self.rod = labelled_value(line);
+            } else if line.starts_with("Current biome:") {
+                 let rest = labelled_value(line);
+                 self.biome = Biome::from_display_name(&rest).map(|b| b.display_name().to_string()).unwrap_or(rest);
+            } else if line.starts_with("Pet:") {
+                 self.pet = labelled_value(line);
+            } else if let Some(boats) = line.strip_prefix("Boats:").or_else(|| line.strip_prefix("Boat:")) {
+                 for boat in boats.split(',').filter_map(Self::boat_named) {
+                     self.add_boat(boat.name);
+                 }
+            } else if line.starts_with("Bait:") {
+                 self.bait = labelled_value(line);
+            } else if line.contains("Gold Fish") {
+                 if let Some(val) = money::parse_amount(&tokens::plain(line)) {
+                      self.exotic_fish.gold = val as i32;
+                 }
+            } else if line.contains("Emerald Fish") {
+                 if let Some(val) = money::parse_amount(&tokens::plain(line)) {
+                      self.exotic_fish.emerald = val as i32;
+                 }
+            } else if line.contains("Lava Fish") {
+                 if let Some(val) = money::parse_amount(&tokens::plain(line)) {
+                      self.exotic_fish.lava = val as i32;
+                 }
+            } else if line.contains("Diamond Fish") {
+                 if let Some(val) = money::parse_amount(&tokens::plain(line)) {
+                      self.exotic_fish.diamond = val as i32;
+                 }
+            } else if let Some(value) = line.strip_prefix("Fish Value:") {
+                self.inventory_value = money::parse_amount(value);
+            } else {
+                // Inventory items start with a number, then the item's emoji
+                if line.chars().next().is_some_and(|c| c.is_numeric()) {
+                    if let Some(item) = inventory_item(line) {
+                         self.inventory.push(item);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Shop lines for a boat marked as bought: "**Speedboat** - Owned", "✅ Yacht".
+    fn parse_owned_boats(&mut self, content: &str) {
+        for line in remove_markdown(content).lines() {
+            let lower = line.to_lowercase();
+            let owned = (lower.contains("owned") || lower.contains("purchased") || line.contains('✅'))
+                && !lower.contains("not owned") && !lower.contains("unowned");
+            if let Some(boat) = Self::boat_named(line).filter(|_| owned) {
+                self.add_boat(boat.name);
+            }
+        }
+    }
+
+    /// The boat whose name `text` mentions; the longest name wins, so "Luxury Yacht" isn't a "Yacht".
+    fn boat_named(text: &str) -> Option<&'static Boat> {
+        let lower = text.to_lowercase();
+        BOAT_DATA.values()
+            .filter(|b| lower.contains(&b.name.to_lowercase()))
+            .max_by_key(|b| b.name.len())
+    }
+
+    fn parse_league(&mut self, content: &str) {
+        let clean_content = remove_markdown(content);
+        for line in clean_content.lines() {
+            self.parse_league_line(line.trim());
+        }
+    }
+
+    /// Handles "League: <:gold:1> Gold III", "Rank: Gold III" and "Hooks: 1,234" /
+    /// "1,234 <:hook:1> Hooks" lines, which show up in both the profile and league embeds.
+    fn parse_league_line(&mut self, line: &str) -> bool {
+        if let Some(rank) = line.strip_prefix("League:").or_else(|| line.strip_prefix("Rank:")) {
+            self.league = tokens::plain(rank);
+            true
+        } else if line.contains("Hooks") {
+            if let Some(hooks) = money::parse_amount(line.trim_start_matches("Hooks:")) {
+                self.hooks = hooks;
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// "Level: 5", "Fish per hour: 1,200", "Ready to collect: 3,400 fish", "Boost: 1h 20m left".
+    pub fn parse_workers(content: &str) -> Workers {
+        let mut workers = Workers::default();
+        let now = chrono::Utc::now().timestamp();
+        for line in remove_markdown(content).lines() {
+            let line = line.trim();
+            let lower = line.to_lowercase();
+            let amount = line.split_once(':').map_or(line, |(_, rest)| rest);
+            if lower.contains("per hour") || lower.contains("/h") {
+                workers.fish_per_hour = money::parse_amount(amount);
+            } else if lower.contains("collect") || lower.contains("stored") {
+                workers.uncollected = money::parse_amount(amount).unwrap_or(0);
+            } else if lower.contains("boost") {
+                workers.boost_ends = parser::parse_duration_text(line).map(|left| now + left.as_secs() as i64);
+            } else if lower.starts_with("level") || lower.contains("worker level") {
+                workers.level = money::parse_amount(amount).map(|l| l as u32);
+            }
+        }
+        workers
+    }
+
+    fn parse_charms(&mut self, content: &str) {
+        let clean_content = remove_markdown(content);
+         for line in clean_content.lines() {
+            if line.is_empty() { continue; }
+            let parts: Vec<&str> = line.split('/').collect();
+            if parts.len() < 2 { continue; }
+            let value = parts[0].trim();
+
+            if line.contains("Marketing") { self.charms.marketing = parse_charm(value); }
+            else if line.contains("Endurance") { self.charms.endurance = parse_charm(value); }
+            else if line.contains("Haste") { self.charms.haste = parse_charm(value); }
+            else if line.contains("Quantity") { self.charms.quantity = parse_charm(value); }
+            else if line.contains("Worker") { self.charms.worker = parse_charm(value); }
+            else if line.contains("Treasure") { self.charms.treasure = parse_charm(value); }
+            else if line.contains("Quality") { self.charms.quality = parse_charm(value); }
+            else if line.contains("Experience") { self.charms.experience = parse_charm(value); }
+            else if line.contains("Total charms found") { self.charms.found = money::parse_amount(value).unwrap_or(0); }
+         }
+    }
+
+    fn parse_buffs(&mut self, content: &str) {
+         let clean_content = remove_markdown(content);
+         let now = chrono::Utc::now().timestamp();
+         self.buffs.boosts.clear();
+         for line in clean_content.lines() {
+            let line = tokens::plain(line);
+            if line.is_empty() { continue; }
+            if let (Some(boost), Some(left)) = (Boost::from_text(&line), parser::parse_duration_text(&line)) {
+                 self.buffs.boosts.insert(boost.display_name().to_string(), now + left.as_secs() as i64);
+            }
+            if let Some(idx) = line.find(':') {
+                 let value = line[idx+1..].trim().to_string();
+                 if line.starts_with("Sell") { self.buffs.sell_price = value; }
+                 else if line.contains("catch") { self.buffs.fish_catch = value; }
+                 else if line.contains("Fish quality") { self.buffs.fish_quality = value; }
+                 else if line.contains("chance") { self.buffs.treasure_chance = value; }
+                 else if line.contains("Treasure quality") { self.buffs.treasure_quality = value; }
+                 else if line.contains("XP") { self.buffs.xp_multiplier = value; }
+                 else if line.contains("cooldown") { self.buffs.fishing_cooldown = value; }
+            }
+         }
+    }
+
+    fn parse_quests(&mut self, content: &str) {
+        self.quests.clear();
+        let clean_content = remove_markdown(content);
+        for line in clean_content.lines() {
+            let line = tokens::plain(line);
+            if line.is_empty() || line.contains("Quests have multiple tiers") || line.contains("Quests reset") { continue; }
+
+            if line.starts_with("Daily") {
+                let parts: Vec<&str> = line.split(" - ").collect();
+                if parts.len() >= 3 {
+                    let category = parts[0].to_string();
+                    let objective = parts[1].to_string();
+                    let progress = parts[2].to_string();
+                    self.quests.push(Quest {
+                        category,
+                        objective,
+                        progress,
+                        is_completed: false,
+                    });
+                } else if parts.len() == 2 {
+                     // Completed
+                    let category = parts[0].to_string();
+                    let objective = parts[1].replace(" COMPLETED", "");
+                    self.quests.push(Quest {
+                        category,
+                        objective,
+                        progress: "Completed".to_string(),
+                        is_completed: true,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Lines like "Fisherman - Catch 1,000 fish - 523/1,000" or "Big Spender - COMPLETED".
+    /// Lines with neither a progress count nor a completion mark are headers and skipped.
+    fn parse_achievements(&mut self, content: &str, is_badge: bool) {
+        self.achievements.clear();
+        let clean_content = remove_markdown(content);
+        for line in clean_content.lines() {
+            let line = line.trim();
+            let is_completed = line.contains("COMPLETED") || line.contains('✅');
+            let progress = PROGRESS_PATTERN.captures(line).and_then(|c| {
+                Some((money::parse_amount(&c[1])?, money::parse_amount(&c[2])?))
+            });
+            if progress.is_none() && !is_completed {
+                continue;
+            }
+
+            let name_part = line.split(" - ").next().unwrap_or(line);
+            let name = tokens::plain(name_part).replace(['✅', ':'], "").replace("COMPLETED", "").trim().to_string();
+            if name.is_empty() { continue; }
+
+            let (progress, target) = progress.unwrap_or((0, 0));
+            self.achievements.push(Achievement {
+                name,
+                is_badge,
+                progress,
+                target,
+                is_completed: is_completed || (target > 0 && progress >= target),
+            });
+        }
+    }
+
+    fn parse_upgrades(&mut self, content: &str) {
+        // Example format heuristics:
+        // "**Better Fish** (Lvl 5/21) - $50,000"
+        // "**Salesman** - Lvl 10 - $2,000,000"
+        let clean_content = remove_markdown(content);
+        for line in clean_content.lines() {
+            if line.is_empty() { continue; }
+
+            // Split by dashes or parentheses to find parts
+            // Assume the name is at the start
+            // Looking for "Lvl X" or "Level X"
+            if let Some(lvl_idx) = line.find("Lvl") {
+                let name_part = line[..lvl_idx].trim();
+                let rest = &line[lvl_idx..];
+
+                // Remove trailing punctuation from name
+                let name = name_part.trim_matches(&['-', '(', ')', ' '][..]).to_string();
+
+                // Extract Level
+                let parts: Vec<&str> = rest.split_whitespace().collect();
+                let mut level = 0;
+                let mut max_level = 0;
+
+                // Find the number after "Lvl"
+                for (i, part) in parts.iter().enumerate() {
+                    if (part.contains("Lvl") || part.contains("Level")) && i + 1 < parts.len() {
+                         let lvl_str = parts[i+1].replace([')', ','], "");
+                         if lvl_str.contains('/') {
+                             let lvl_parts: Vec<&str> = lvl_str.split('/').collect();
+                             if let Ok(l) = lvl_parts[0].parse::<u32>() { level = l; }
+                             if lvl_parts.len() > 1 {
+                                 if let Ok(l) = lvl_parts[1].parse::<u32>() { max_level = l; }
+                             }
+                         } else if let Ok(l) = lvl_str.parse::<u32>() {
+                             level = l;
+                         }
+                    }
+                }
+
+                // Extract Price
+                let mut price = None;
+                if let Some(dollar_idx) = line.find('$') {
+                    let price_str: String = line[dollar_idx+1..].chars()
+                        .take_while(|c| c.is_ascii_digit() || *c == ',')
+                        .filter(|c| c.is_ascii_digit())
+                        .collect();
+                    if let Ok(p) = price_str.parse::<u64>() {
+                        price = Some(p);
+                    }
+                } else if let Some(hooks_idx) = rest.find("Hook") {
+                    // League upgrades: "Pet Helper (Lvl 2/5) - 210 Hooks"
+                    price = rest[..hooks_idx].rsplit('-').next().and_then(money::parse_amount);
+                }
+
+                if level > 0 {
+                    self.upgrades.insert(name, UpgradeStatus {
+                        level,
+                        max_level,
+                        next_cost: price
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::profile::CharmType;
+
+    #[test]
+    fn test_parse_fields() {
+        assert_eq!(parse_balance("$1,234,567"), Some(1_234_567));
+        assert_eq!(parse_balance(" $1.5M"), Some(1_500_000));
+        assert_eq!(parse_level("Level 52, 1,234 XP to next level"), Some(52));
+        assert_eq!(parse_level("Level ?"), None);
+        assert_eq!(parse_charm("Marketing: 3"), Charm { count: 3, level: 0 });
+        assert_eq!(parse_charm("Haste II"), Charm { count: 1, level: 2 });
+        assert_eq!(parse_charm("II"), Charm { count: 1, level: 2 });
+        assert_eq!(parse_charm("Haste Lvl 4"), Charm { count: 1, level: 4 });
+        assert_eq!(parse_charm("2 Haste Lvl. 4"), Charm { count: 2, level: 4 });
+        assert_eq!(parse_charm("Quantity: 12"), Charm { count: 12, level: 0 });
+        assert_eq!(parse_charm(""), Charm::default());
+
+        let mut profile = Profile::default();
+        profile.update_from_message(
+            "**Balance:** $12,500\nLevel 41, 900 XP to next level\nFish Value: $3,200",
+            Some("Your Profile"));
+        profile.update_from_message("Marketing: 4/10\nHaste II/III\nTotal charms found: 37/∞", Some("Your Charms"));
+        assert_eq!((profile.balance, profile.level, profile.inventory_value), (Some(12_500), Some(41), Some(3_200)));
+        assert_eq!((profile.balance_text(), profile.level_text()), ("$12,500".to_string(), "Level 41".to_string()));
+        assert_eq!(profile.charm_count(CharmType::Marketing), 4);
+        assert_eq!(profile.charm_count(CharmType::Haste), 1);
+        assert_eq!(profile.charms.found, 37);
+    }
+}
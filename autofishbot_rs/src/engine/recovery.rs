@@ -0,0 +1,93 @@
+use crate::discord::types::Message;
+use crate::engine::parser::{self, CaptchaChallenge, CooldownEvent};
+
+/// What the fishing channel's recent history says about this account, so a restarted bot
+/// doesn't start blind. See `recover`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Recovery {
+    /// Title and description of the newest Profile embed
+    pub profile: Option<(String, String)>,
+    /// The newest cooldown warning
+    pub cooldown: Option<CooldownEvent>,
+    /// A captcha with no "verified" reply after it
+    pub captcha: Option<CaptchaChallenge>,
+}
+
+/// Reads `messages` newest first (as `DiscordClient::get_messages` returns them). Only Virtual
+/// Fisher's messages count, minus its replies to other users' commands.
+pub fn recover(messages: &[Message], vf_id: &str, user_id: Option<&str>) -> Recovery {
+    let mut recovery = Recovery::default();
+    let mut captcha_settled = false;
+    for msg in messages {
+        if msg.author.id != vf_id {
+            continue;
+        }
+        let invoker = msg.interaction_metadata.as_ref().and_then(|m| m.user.as_ref()).map(|u| u.id.as_str());
+        if invoker.is_some_and(|id| user_id.is_some_and(|own| own != id)) {
+            continue;
+        }
+
+        if !captcha_settled {
+            match parser::parse_captcha_result(msg) {
+                Some(true) => captcha_settled = true,
+                // Still unsolved; the prompt further back has the image
+                Some(false) => { recovery.captcha.get_or_insert(CaptchaChallenge { image_url: None }); },
+                None => if let Some(challenge) = parser::parse_captcha(msg) {
+                    let captcha = recovery.captcha.get_or_insert(CaptchaChallenge { image_url: None });
+                    if challenge.image_url.is_some() {
+                        captcha.image_url = challenge.image_url;
+                        captcha_settled = true;
+                    }
+                },
+            }
+        }
+
+        let Some(embed) = msg.embeds.first() else { continue };
+        let description = embed.description.as_deref().unwrap_or_default();
+        match embed.title.as_deref() {
+            Some(title) if title.contains("Profile") && recovery.profile.is_none() => {
+                recovery.profile = Some((title.to_string(), description.to_string()));
+            },
+            None if recovery.cooldown.is_none() => recovery.cooldown = parser::parse_cooldown_embed(description),
+            _ => {},
+        }
+    }
+    recovery
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(author: &str, invoker: Option<&str>, embed: serde_json::Value) -> Message {
+        serde_json::from_value(serde_json::json!({
+            "id": "1", "channel_id": "1", "content": "", "timestamp": "",
+            "author": { "id": author, "username": "Virtual Fisher", "discriminator": "0" },
+            "embeds": [embed], "components": null,
+            "interaction_metadata": invoker.map(|id| serde_json::json!({ "id": "9", "user": { "id": id, "username": "u", "discriminator": "0" } })),
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_recover() {
+        let vf = "574652751745777665";
+        let history = vec![
+            msg(vf, Some("7"), serde_json::json!({ "description": "Incorrect captcha code, try /verify again" })),
+            msg(vf, Some("8"), serde_json::json!({ "title": "bob's Profile", "description": "Balance: $1" })),
+            msg(vf, Some("7"), serde_json::json!({ "title": "alice's Profile", "description": "Balance: $5,000" })),
+            msg(vf, Some("7"), serde_json::json!({ "title": "Anti-bot", "description": "Please solve the captcha with `/verify`",
+                "image": { "url": "https://cdn.example/captcha.png" } })),
+            msg("7", None, serde_json::json!({ "description": "You must wait **9.0**s" })),
+            msg(vf, Some("7"), serde_json::json!({ "description": "You must wait **1.2**s. Current cooldown: **3.0** seconds" })),
+            msg(vf, Some("7"), serde_json::json!({ "description": "You have been verified!" })),
+        ];
+        let recovery = recover(&history, vf, Some("7"));
+        assert_eq!(recovery.profile, Some(("alice's Profile".to_string(), "Balance: $5,000".to_string())));
+        assert_eq!(recovery.captcha.unwrap().image_url.as_deref(), Some("https://cdn.example/captcha.png"));
+        assert_eq!(recovery.cooldown, Some(CooldownEvent { wait_time: 1.2, total_cooldown: 3.0 }));
+
+        // Verified after the prompt
+        let solved = recover(&history[3..].iter().rev().cloned().collect::<Vec<_>>(), vf, Some("7"));
+        assert!(solved.captcha.is_none());
+    }
+}
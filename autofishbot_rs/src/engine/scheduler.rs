@@ -1,12 +1,21 @@
 use crate::config::Config;
 use crate::discord::client::DiscordClient;
+use crate::discord::command::CommandBuilder;
+use crate::discord::types::Message;
+use crate::engine::correlation::NoReply;
 use std::sync::Arc;
-use log::{info, error, warn};
-use serde_json::{json, Value};
-
+use std::time::Duration;
+use anyhow::{Result, anyhow};
+use log::{debug, info, error};
+
+/// Sends commands for the bot, and runs the commands of game events on timers. Nothing else
+/// is on a fixed timer: `/daily` is claimed once per reset window (`Bot::protect_daily_streak`),
+/// clan `/claim` follows the cooldown in its reply (`Bot::claim_clan`), selling is up to
+/// `sell::SellPolicy`, and boosts are bought when their buff runs out (`Bot::buy_boosts`).
 pub struct Scheduler {
     config: Config,
-    tasks: Vec<Task>,
+    /// Run only while a game event is going on (e.g. a clan boss); kept across config reloads
+    event_tasks: Vec<Task>,
 }
 
 struct Task {
@@ -17,121 +26,88 @@ struct Task {
 
 impl Scheduler {
     pub fn new(config: Config) -> Self {
-        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
-        let mut tasks = Vec::new();
-
-        if config.automation.auto_daily {
-             tasks.push(Task {
-                name: "daily".to_string(),
-                last_run: now,
-                interval: 24 * 60 * 60, // 24 hours
-            });
-        }
-
-        if config.automation.auto_sell {
-             tasks.push(Task {
-                name: "sell".to_string(),
-                last_run: now,
-                interval: 10 * 60, // 10 minutes
-            });
+        Self {
+            config,
+            event_tasks: Vec::new(),
         }
+    }
 
-        // Clan Claim
-        tasks.push(Task {
-            name: "claim".to_string(),
-            last_run: now,
-            interval: 4 * 60 * 60, // 4 hours
-        });
-
-        // Boosts (Buy buffs)
-        if config.automation.boosts_length > 0 {
-             tasks.push(Task {
-                name: "shop buy".to_string(),
-                last_run: now,
-                interval: config.automation.boosts_length * 60,
-            });
+    /// Runs `command` every `interval` seconds while `active`, starting right away.
+    pub fn set_event_task(&mut self, command: &str, interval: u64, active: bool) {
+        let existing = self.event_tasks.iter().position(|t| t.name == command);
+        match (existing, active) {
+            (None, true) => {
+                info!("Scheduling {} every {}s while the event lasts", command, interval);
+                self.event_tasks.push(Task { name: command.to_string(), last_run: 0, interval });
+            },
+            (Some(i), true) => self.event_tasks[i].interval = interval,
+            (Some(i), false) => {
+                info!("Event over, unscheduling {}", command);
+                self.event_tasks.remove(i);
+            },
+            (None, false) => {},
         }
+    }
 
-        Self {
-            config,
-            tasks,
-        }
+    pub fn update_config(&mut self, config: Config) {
+        self.config = config;
     }
 
-    pub async fn process(&mut self, client: &Arc<DiscordClient>) {
+    /// Runs the event tasks that are due, in `channel_id`.
+    pub async fn process(&mut self, client: &Arc<DiscordClient>, channel_id: &str) {
         let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+        let config = &self.config;
 
-        for task in &mut self.tasks {
+        for task in self.event_tasks.iter_mut() {
              if task.last_run > now { task.last_run = now; } // Sanity check
 
             if now - task.last_run > task.interval {
                 info!("Running scheduled task: {}", task.name);
-
-                let guild_id = self.config.system.guild_id.to_string();
-                let channel_id = self.config.system.channel_id.to_string();
-
-                let parts: Vec<&str> = task.name.split_whitespace().collect();
-                if parts.is_empty() {
-                    continue;
-                }
-                let cmd_name = parts[0];
-                let sub_parts = &parts[1..];
-
-                // Try fetching command first
-                match client.get_command(&guild_id, cmd_name).await {
-                    Ok(Some(cmd)) => {
-                        let options = Self::build_command_options(&cmd, sub_parts);
-
-                        if let Err(e) = client.send_command(&guild_id, &channel_id, &cmd, options).await {
-                            error!("Task {} failed: {}", task.name, e);
-                        } else {
-                            task.last_run = now;
-                        }
-                    },
-                    Ok(None) => {
-                         // Fallback logic
-                         warn!("Command {} not found via discovery, skipping task.", cmd_name);
-                    },
-                    Err(e) => {
-                         error!("Scheduler error fetching command {}: {}", cmd_name, e);
-                    }
+                match Self::send(client, config, channel_id, &task.name).await {
+                    Ok(()) => task.last_run = now,
+                    Err(e) => error!("Task {} failed: {}", task.name, e),
                 }
             }
         }
     }
 
-    fn build_command_options(cmd_def: &Value, parts: &[&str]) -> Option<Vec<Value>> {
-        if parts.is_empty() {
-            return None;
-        }
-
-        let current_part = parts[0];
-        let remaining_parts = &parts[1..];
-
-        // Find the option definition in the command
-        if let Some(options_array) = cmd_def.get("options").and_then(|v| v.as_array()) {
-            if let Some(option_def) = options_array.iter().find(|o| o["name"] == current_part) {
-                // Recursively build options for the next part
-                let child_options = Self::build_command_options(option_def, remaining_parts);
-
-                let mut option_payload = json!({
-                    "name": current_part,
-                    "type": option_def["type"]
-                });
-
-                if let Some(opts) = child_options {
-                     option_payload["options"] = json!(opts);
-                } else {
-                     option_payload["options"] = json!([]);
-                }
+    /// Sends `command` ("name [subcommand...]") to `channel_id` once, outside the timers.
+    pub async fn run_command(&self, client: &DiscordClient, channel_id: &str, command: &str) -> Result<()> {
+        Self::send(client, &self.config, channel_id, command).await
+    }
 
-                return Some(vec![option_payload]);
-            } else {
-                warn!("Subcommand/Option '{}' not found in definition.", current_part);
-                return None;
-            }
+    /// Like `run_command`, and returns Virtual Fisher's reply. `None` when it didn't come within
+    /// `timeout`, or replies can't be matched to commands at all.
+    pub async fn run_command_and_wait(&self, client: &DiscordClient, channel_id: &str, command: &str, timeout: Duration) -> Result<Option<Message>> {
+        if !client.matches_replies() {
+            return self.run_command(client, channel_id, command).await.map(|()| None);
+        }
+        let guild_id = self.config.system.guild_id.to_string();
+        let (cmd, options) = Self::build(client, &self.config, command).await?;
+        match client.send_command_and_wait(&guild_id, channel_id, &cmd, options, timeout).await {
+            Ok(reply) => Ok(Some(reply)),
+            Err(e) if e.downcast_ref::<NoReply>().is_some() => {
+                debug!("/{}: {}", command, e);
+                Ok(None)
+            },
+            Err(e) => Err(e),
         }
+    }
+
+    /// The command definition and options for `command` ("name [subcommand...] [value]").
+    pub async fn build(client: &DiscordClient, config: &Config, command: &str) -> Result<(serde_json::Value, Option<Vec<serde_json::Value>>)> {
+        let guild_id = config.system.guild_id.to_string();
+        let parts: Vec<&str> = command.split_whitespace().collect();
+        let (cmd_name, sub_parts) = parts.split_first().ok_or_else(|| anyhow!("Empty command"))?;
+        let cmd = client.get_command(&guild_id, cmd_name).await?
+            .ok_or_else(|| anyhow!("Command {} not found via discovery", cmd_name))?;
+        let options = CommandBuilder::new(&cmd).text(sub_parts).build()?;
+        Ok((cmd, options))
+    }
 
-        None
+    async fn send(client: &DiscordClient, config: &Config, channel_id: &str, command: &str) -> Result<()> {
+        let guild_id = config.system.guild_id.to_string();
+        let (cmd, options) = Self::build(client, config, command).await?;
+        client.send_command(&guild_id, channel_id, &cmd, options).await
     }
 }
@@ -0,0 +1,137 @@
+use crate::config::SeasonalEventConfig;
+use crate::engine::parser::{self, EventAnnouncement};
+use crate::util::money;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Events without an "Ends in" line are considered over a day after the last sighting.
+const DEFAULT_EVENT_LIFETIME: Duration = Duration::from_secs(24 * 3600);
+
+/// One embed that mentions a configured seasonal event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeasonalSighting {
+    pub event: String,
+    /// Items the embed says were found ("You found 3 Presents")
+    pub found: Option<u64>,
+    pub ends_in: Option<Duration>,
+}
+
+/// What the TUI shows for an event, accumulated over sightings.
+#[derive(Debug, Clone)]
+pub struct SeasonalStatus {
+    pub collected: u64,
+    pub ends_at: Instant,
+    pub last_seen: Instant,
+}
+
+impl SeasonalStatus {
+    pub fn is_active(&self) -> bool {
+        Instant::now() < self.ends_at
+    }
+}
+
+/// Matches embeds against `[seasonal_events.<name>] keywords`, so a new event only needs config.
+pub fn detect(events: &BTreeMap<String, SeasonalEventConfig>, title: &str, description: &str) -> Option<SeasonalSighting> {
+    let text = format!("{}\n{}", title, description).to_lowercase();
+    let (name, event) = events.iter()
+        .find(|(_, e)| e.keywords.iter().any(|k| !k.is_empty() && text.contains(&k.to_lowercase())))?;
+
+    Some(SeasonalSighting {
+        event: name.clone(),
+        found: event.keywords.iter().find_map(|k| found_count(description, k)),
+        ends_in: parser::parse_ends_in(description),
+    })
+}
+
+/// "You found **3** <:present:1> Presents" -> 3 for keyword "present".
+fn found_count(description: &str, keyword: &str) -> Option<u64> {
+    let pattern = format!(r"(?i)found\s+\**([\d,]+)\**\s*(?:<a?:[^>]+>\s*)?{}", regex::escape(keyword));
+    let caps = Regex::new(&pattern).ok()?.captures(description)?;
+    money::parse_amount(&caps[1])
+}
+
+/// Adds an announced event that isn't configured yet, keyed by its lowercase name
+/// ("winter_festival"), or gives a configured one the announced command when it has none.
+/// Announced events run their command when `auto_run` is set. Returns the key when
+/// something changed.
+pub fn register(events: &mut BTreeMap<String, SeasonalEventConfig>, announcement: &EventAnnouncement, auto_run: bool) -> Option<String> {
+    let name = announcement.name.to_lowercase();
+    let key = name.replace(' ', "_");
+    let existing = events.iter_mut()
+        .find(|(k, e)| **k == key || e.keywords.iter().any(|w| !w.is_empty() && name.contains(&w.to_lowercase())));
+    if let Some((key, event)) = existing {
+        let command = announcement.command.as_ref().filter(|_| event.command.trim().is_empty())?;
+        event.command = command.clone();
+        return Some(key.clone());
+    }
+
+    let keywords = std::iter::once(name).chain(announcement.currency.clone()).collect();
+    let command = announcement.command.clone().unwrap_or_default();
+    events.insert(key.clone(), SeasonalEventConfig {
+        keywords,
+        auto_run: auto_run && !command.is_empty(),
+        command,
+        interval_minutes: crate::config::default_event_interval(),
+    });
+    Some(key)
+}
+
+/// Folds a sighting into the per-event statuses kept on the App.
+pub fn record(statuses: &mut BTreeMap<String, SeasonalStatus>, sighting: &SeasonalSighting) {
+    let now = Instant::now();
+    let ends_at = now + sighting.ends_in.unwrap_or(DEFAULT_EVENT_LIFETIME);
+    let status = statuses.entry(sighting.event.clone()).or_insert(SeasonalStatus { collected: 0, ends_at, last_seen: now });
+    status.collected += sighting.found.unwrap_or(0);
+    status.ends_at = ends_at;
+    status.last_seen = now;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_and_record() {
+        let events = crate::config::default_seasonal_events();
+
+        let sighting = detect(&events, "You caught", "3 <:cod:1> Cod\nYou found **2** <:present:9> Presents!\nEvent ends in **2d 4h**").unwrap();
+        assert_eq!(sighting.event, "christmas");
+        assert_eq!(sighting.found, Some(2));
+        assert_eq!(sighting.ends_in, Some(Duration::from_secs(52 * 3600)));
+
+        let halloween = detect(&events, "Halloween Event", "Trade your pumpkins for prizes").unwrap();
+        assert_eq!(halloween.event, "halloween");
+        assert_eq!(halloween.found, None);
+        assert!(detect(&events, "You caught", "3 <:cod:1> Cod").is_none());
+
+        let mut statuses = BTreeMap::new();
+        record(&mut statuses, &sighting);
+        record(&mut statuses, &sighting);
+        assert_eq!(statuses["christmas"].collected, 4);
+        assert!(statuses["christmas"].is_active());
+    }
+
+    #[test]
+    fn test_register_announced_event() {
+        let mut events = crate::config::default_seasonal_events();
+        let announced = |text: &str| parser::parse_event_announcement("Event", text).unwrap();
+
+        let festival = announced("The Winter Festival event has started! Collect Snowflakes and use </festival:5>.");
+        assert_eq!(register(&mut events, &festival, true).as_deref(), Some("winter_festival"));
+        let event = &events["winter_festival"];
+        assert_eq!(event.keywords, vec!["winter festival", "snowflake"]);
+        assert_eq!(event.command, "festival");
+        assert!(event.auto_run);
+        // Later embeds are recognized by the announced currency
+        assert_eq!(detect(&events, "You caught", "You found **4** Snowflakes").unwrap().found, Some(4));
+        assert_eq!(register(&mut events, &festival, true), None);
+
+        // A configured event keeps its own command
+        let christmas = announced("The Christmas event is live! Use </gifts:6>");
+        assert_eq!(register(&mut events, &christmas, true), None);
+        events.get_mut("christmas").unwrap().command.clear();
+        assert_eq!(register(&mut events, &christmas, true).as_deref(), Some("christmas"));
+        assert_eq!(events["christmas"].command, "gifts");
+    }
+}
@@ -0,0 +1,128 @@
+use crate::config::Config;
+use crate::engine::optimizer::{ActionType, Recommendation};
+use crate::engine::profile::{Buffs, Profile};
+use crate::util::money;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Why the bot is selling.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SellReason {
+    /// Virtual Fisher said the inventory is full
+    Full,
+    Threshold { value: u64, threshold: u64 },
+    /// The inventory covers what the balance is missing for the optimizer's next purchase
+    Purchase { target: String, cost: u64 },
+    /// `[sell] interval_minutes` went by
+    Timer,
+}
+
+impl fmt::Display for SellReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SellReason::Full => write!(f, "inventory full"),
+            SellReason::Threshold { value, threshold } => write!(f, "inventory worth {} (threshold {})",
+                money::format_money(*value), money::format_money(*threshold)),
+            SellReason::Purchase { target, cost } => write!(f, "to afford {} ({})", target, money::format_money(*cost)),
+            SellReason::Timer => write!(f, "scheduled"),
+        }
+    }
+}
+
+/// When and how to sell, from `[automation] auto_sell`/`sell_threshold` and `[sell]`.
+pub struct SellPolicy<'a> {
+    config: &'a Config,
+}
+
+impl<'a> SellPolicy<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        Self { config }
+    }
+
+    /// Checked every cast: the threshold, then the timer. With `wait_for_sell_buff` both
+    /// hold off until a sell-price buff is active.
+    pub fn routine(&self, value: u64, buffs: &Buffs, since_last_sell: Duration) -> Option<SellReason> {
+        let automation = &self.config.automation;
+        let sell = &self.config.sell;
+        if !automation.auto_sell || (sell.wait_for_sell_buff && !sell_buff_active(buffs)) {
+            return None;
+        }
+        if automation.sell_threshold > 0 && value >= automation.sell_threshold {
+            return Some(SellReason::Threshold { value, threshold: automation.sell_threshold });
+        }
+        let interval = Duration::from_secs(sell.interval_minutes * 60);
+        (sell.interval_minutes > 0 && since_last_sell >= interval).then_some(SellReason::Timer)
+    }
+
+    /// With `before_purchase`, a rod or boat the balance alone can't pay for but the balance
+    /// plus the inventory can.
+    pub fn before_purchase(&self, rec: &Recommendation, balance: u64, value: u64) -> Option<SellReason> {
+        let gold_purchase = matches!(rec.action, ActionType::BuyRod | ActionType::BuyBoat);
+        let covered = balance < rec.cost && balance.saturating_add(value) >= rec.cost;
+        (self.config.automation.auto_sell && self.config.sell.before_purchase && gold_purchase && covered)
+            .then(|| SellReason::Purchase { target: rec.target_name.clone(), cost: rec.cost })
+    }
+
+    /// The command to sell with. `None` when exotic fish are held with `keep_exotic` and
+    /// there is no `exotic_safe_command` that leaves them alone.
+    pub fn command(&self, profile: &Profile) -> Option<&'a str> {
+        let sell = &self.config.sell;
+        if !sell.keep_exotic || profile.exotic_fish.count() == 0 {
+            return Some(&sell.command);
+        }
+        (!sell.exotic_safe_command.is_empty()).then_some(sell.exotic_safe_command.as_str())
+    }
+}
+
+/// Whether the `/buffs` sell price line shows a bonus: a positive percentage ("+25%") or a
+/// multiplier over 1 ("1.5x").
+pub fn sell_buff_active(buffs: &Buffs) -> bool {
+    let text = buffs.sell_price.trim();
+    let Some(start) = text.find(|c: char| c.is_ascii_digit()) else { return false };
+    let number: String = text[start..].chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+    let Ok(number) = number.parse::<f64>() else { return false };
+    if text[..start].contains('-') {
+        return false;
+    }
+    if text.contains('%') { number > 0.0 } else { number > 1.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sell_policy() {
+        let mut config = Config::default();
+        config.automation.auto_sell = true;
+        config.automation.sell_threshold = 50_000;
+        let buffs = Buffs::default();
+        let policy = SellPolicy::new(&config);
+        assert_eq!(policy.routine(60_000, &buffs, Duration::ZERO), Some(SellReason::Threshold { value: 60_000, threshold: 50_000 }));
+        assert_eq!(policy.routine(10_000, &buffs, Duration::from_secs(60)), None);
+        assert_eq!(policy.routine(10_000, &buffs, Duration::from_secs(config.sell.interval_minutes * 60)), Some(SellReason::Timer));
+
+        let rod = Recommendation { action: ActionType::BuyRod, target_name: "Fiberglass Rod".to_string(), cost: 10_000, roi_seconds: 60.0 };
+        assert_eq!(policy.before_purchase(&rod, 4_000, 6_000), Some(SellReason::Purchase { target: "Fiberglass Rod".to_string(), cost: 10_000 }));
+        // Already affordable, or still out of reach after selling
+        assert_eq!(policy.before_purchase(&rod, 12_000, 6_000), None);
+        assert_eq!(policy.before_purchase(&rod, 1_000, 6_000), None);
+
+        let mut profile = Profile::default();
+        assert_eq!(policy.command(&profile), Some("sell"));
+        profile.exotic_fish.gold = 2;
+        config.sell.keep_exotic = true;
+        assert_eq!(SellPolicy::new(&config).command(&profile), None);
+        config.sell.exotic_safe_command = "sell fish".to_string();
+        assert_eq!(SellPolicy::new(&config).command(&profile), Some("sell fish"));
+
+        config.sell.wait_for_sell_buff = true;
+        assert_eq!(SellPolicy::new(&config).routine(60_000, &buffs, Duration::ZERO), None);
+        let boosted = Buffs { sell_price: "+25%".to_string(), ..Default::default() };
+        assert!(SellPolicy::new(&config).routine(60_000, &boosted, Duration::ZERO).is_some());
+        assert!(sell_buff_active(&Buffs { sell_price: "1.5x".to_string(), ..Default::default() }));
+        assert!(!sell_buff_active(&Buffs { sell_price: "1x".to_string(), ..Default::default() }));
+        assert!(!sell_buff_active(&Buffs { sell_price: "-10%".to_string(), ..Default::default() }));
+    }
+}
@@ -0,0 +1,106 @@
+use crate::config::Config;
+use crate::discord::replay;
+use crate::engine::captcha::Captcha;
+use crate::engine::cooldown::CooldownManager;
+use crate::engine::database::Database;
+use crate::engine::events::EventProcessor;
+use crate::engine::health::HealthMonitor;
+use crate::tui::app::App;
+use anyhow::Result;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// What a replayed capture left behind. The regression cases in `fixtures/gateway/` pin it
+/// in `<capture>.expected.json`; `headless --replay <file> --summary` prints it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub payloads: usize,
+    pub level: Option<u32>,
+    pub balance: Option<u64>,
+    pub biome: Option<String>,
+    pub species_caught: usize,
+    /// Rows written to `catch_history`; an edit counted as a second catch shows up here
+    pub catch_rows: i64,
+    pub coinflips_won: u64,
+    pub coinflips_lost: u64,
+    pub quest_claims: usize,
+    pub crate_opens: usize,
+    pub trade_declines: usize,
+    pub captcha: bool,
+}
+
+impl SessionSummary {
+    pub fn of(app: &App, payloads: usize, catch_rows: i64, captcha: bool) -> Self {
+        Self {
+            payloads,
+            level: app.profile.level,
+            balance: app.profile.balance,
+            biome: app.profile.current_biome.clone(),
+            species_caught: app.fishdex.completion().0,
+            catch_rows,
+            coinflips_won: app.gambling.wins,
+            coinflips_lost: app.gambling.losses,
+            quest_claims: app.pending_quest_claims.len(),
+            crate_opens: app.pending_crate_opens.len(),
+            trade_declines: app.pending_trade_declines.len(),
+            captcha,
+        }
+    }
+}
+
+/// Feeds every payload of a capture to a fresh `EventProcessor`, in order and without the
+/// recorded delays. No gateway, bot or notifications run, so nothing is sent. Catches and
+/// snapshots go to `db`, which should be fresh so the catch count is this capture's alone.
+pub async fn replay_session(config: &Config, capture: &Path, db: Arc<Database>) -> Result<SessionSummary> {
+    let app = Arc::new(Mutex::new(App::new(config.clone())));
+    let cooldown_manager = Arc::new(Mutex::new(CooldownManager::new(config.system.user_cooldown)));
+    let captcha = Arc::new(Mutex::new(Captcha::new(config.clone())));
+    let mut processor = EventProcessor::new(config, app.clone(), db.clone(), cooldown_manager, Arc::new(HealthMonitor::new()))
+        .with_captcha(captcha.clone());
+
+    let mut payloads = 0;
+    for (line_no, line) in std::fs::read_to_string(capture)?.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some((_, payload)) = replay::parse_line(line) else {
+            warn!("Skipping malformed replay line {}", line_no + 1);
+            continue;
+        };
+        processor.handle(payload).await;
+        payloads += 1;
+    }
+
+    let catch_rows: i64 = sqlx::query_scalar("SELECT count(*) FROM catch_history").fetch_one(&db.pool).await?;
+    let captcha = captcha.lock().await.detected;
+    let app = app.lock().await;
+    Ok(SessionSummary::of(&app, payloads, catch_rows, captcha))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Every `fixtures/gateway/*.jsonl` capture must still produce its `.expected.json`.
+    #[tokio::test]
+    async fn test_replay_fixtures() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/gateway");
+        let mut captures: Vec<PathBuf> = std::fs::read_dir(&dir).unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.extension().is_some_and(|e| e == "jsonl"))
+            .collect();
+        captures.sort();
+        assert!(!captures.is_empty(), "no captures in {}", dir.display());
+
+        for capture in captures {
+            let db = Arc::new(Database::in_memory().await.unwrap());
+            let summary = replay_session(&Config::default(), &capture, db).await.unwrap();
+            let expected: SessionSummary = serde_json::from_str(&std::fs::read_to_string(capture.with_extension("expected.json")).unwrap()).unwrap();
+            assert_eq!(summary, expected, "{}", capture.display());
+        }
+    }
+}
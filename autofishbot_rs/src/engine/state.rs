@@ -1,2 +1,84 @@
-// Placeholder for shared state
-pub struct State;
+use crate::engine::bot::BotState;
+use crate::engine::breaks::BreakKind;
+use crate::engine::cooldown::CooldownSnapshot;
+use crate::engine::optimizer::Recommendation;
+use crate::engine::profile::Profile;
+use crate::engine::sell::SellReason;
+use serde::{Deserialize, Serialize};
+
+/// Older snapshots aren't restored: the profile and any errand in them are likely out of date.
+const MAX_AGE_SECS: i64 = 24 * 3600;
+
+/// What the bot knew at its last save. `Bot::save_snapshot` writes one to `bot_snapshot` every
+/// minute, and `Bot::restore_snapshot` picks it up at startup.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Unix time
+    pub saved_at: i64,
+    /// Balance, rod, boats, biome and the rest, until fresh embeds replace them
+    pub profile: Profile,
+    pub cooldown: CooldownSnapshot,
+    pub state: Option<BotState>,
+    pub pending_recommendation: Option<Recommendation>,
+    pub sell_reason: Option<SellReason>,
+    /// Unix time the break ends, and which one it is
+    pub break_until: Option<(i64, BreakKind)>,
+}
+
+impl Snapshot {
+    pub fn is_fresh(&self, now: i64) -> bool {
+        now - self.saved_at <= MAX_AGE_SECS
+    }
+
+    /// The errand to pick back up: a purchase, a sell or a break that hasn't ended. Anything
+    /// else starts over from fishing; logged out, captcha and degraded are found out live.
+    pub fn resume_state(&self, now: i64) -> Option<BotState> {
+        match self.state? {
+            BotState::Shopping if self.pending_recommendation.is_some() => Some(BotState::Shopping),
+            BotState::Selling if self.sell_reason.is_some() => Some(BotState::Selling),
+            BotState::Break if self.break_until.is_some_and(|(until, _)| until > now) => Some(BotState::Break),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::database::Database;
+    use crate::engine::optimizer::ActionType;
+
+    #[tokio::test]
+    async fn test_snapshot() {
+        let db = Database::in_memory().await.unwrap();
+        assert!(db.load_snapshot().await.unwrap().is_none());
+
+        let now = chrono::Utc::now().timestamp();
+        let mut snapshot = Snapshot {
+            saved_at: now,
+            cooldown: CooldownSnapshot { estimate: 3.5, consecutive_hits: 2 },
+            state: Some(BotState::Shopping),
+            pending_recommendation: Some(Recommendation { action: ActionType::BuyBoat, target_name: "Fishing Boat".to_string(), cost: 50_000, roi_seconds: 120.0 }),
+            ..Default::default()
+        };
+        snapshot.profile.biome = "Volcanic".to_string();
+        snapshot.profile.add_boat("Fishing Boat");
+        db.save_snapshot(&snapshot).await.unwrap();
+        // Only the latest is kept
+        snapshot.sell_reason = Some(SellReason::Timer);
+        db.save_snapshot(&snapshot).await.unwrap();
+
+        let restored = db.load_snapshot().await.unwrap().unwrap();
+        assert_eq!(restored.profile.biome, "Volcanic");
+        assert_eq!(restored.profile.boats, ["Fishing Boat"]);
+        assert_eq!(restored.cooldown, snapshot.cooldown);
+        assert_eq!(restored.sell_reason, Some(SellReason::Timer));
+        assert!(restored.is_fresh(now) && !restored.is_fresh(now + 2 * MAX_AGE_SECS));
+        assert_eq!(restored.resume_state(now), Some(BotState::Shopping));
+
+        let on_break = Snapshot { state: Some(BotState::Break), break_until: Some((now + 60, BreakKind::Sleep)), ..Default::default() };
+        assert_eq!(on_break.resume_state(now), Some(BotState::Break));
+        assert_eq!(on_break.resume_state(now + 120), None);
+        assert_eq!(Snapshot { state: Some(BotState::LoggedOut), ..Default::default() }.resume_state(now), None);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::engine::database::DailyClaim;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::Serialize;
+
+/// Streak and income from the `daily_claims` history.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DailyStats {
+    /// 0 once a window goes by without a claim
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    pub total_income: u64,
+    pub claims: usize,
+}
+
+/// Start of the `/daily` reset window containing `now`, for a reset at `reset_hour` UTC.
+pub fn daily_window_start(now: DateTime<Utc>, reset_hour: u32) -> DateTime<Utc> {
+    let reset_hour = reset_hour.min(23);
+    let today = now.date_naive().and_hms_opt(reset_hour, 0, 0).expect("reset hour is clamped to 0..=23");
+    let today = Utc.from_utc_datetime(&today);
+    if now >= today { today } else { today - Duration::days(1) }
+}
+
+/// Counts consecutive reset windows with a claim. The game's own streak number wins when it's
+/// higher, since claims from before the bot ran aren't in the table.
+pub fn daily_stats(claims: &[DailyClaim], reset_hour: u32, now: DateTime<Utc>) -> DailyStats {
+    let mut stats = DailyStats { claims: claims.len(), ..Default::default() };
+    let mut last_window: Option<DateTime<Utc>> = None;
+    // Length of the run of consecutive windows ending at `last_window`, and the game's count
+    // as of the last claim that reported one in this run
+    let mut run = 0;
+    let mut reported: Option<u32> = None;
+    for claim in claims {
+        stats.total_income += claim.reward.unwrap_or(0);
+        let Some(at) = Utc.timestamp_opt(claim.timestamp, 0).single() else { continue };
+        let window = daily_window_start(at, reset_hour);
+        match last_window {
+            Some(last) if last == window => {},
+            Some(last) if last + Duration::days(1) == window => {
+                run += 1;
+                reported = reported.map(|r| r + 1);
+            },
+            _ => {
+                run = 1;
+                reported = None;
+            },
+        }
+        last_window = Some(window);
+        if let Some(streak) = claim.streak {
+            reported = Some(streak);
+        }
+        stats.longest_streak = stats.longest_streak.max(run).max(reported.unwrap_or(0));
+    }
+
+    // Still alive if the last claim is in this window or the one before
+    let current_window = daily_window_start(now, reset_hour);
+    if last_window.is_some_and(|w| w == current_window || w + Duration::days(1) == current_window) {
+        stats.current_streak = run.max(reported.unwrap_or(0));
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_daily_window_start() {
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+
+        assert_eq!(daily_window_start(at("2026-03-10T15:30:00Z"), 0), at("2026-03-10T00:00:00Z"));
+        assert_eq!(daily_window_start(at("2026-03-10T15:30:00Z"), 16), at("2026-03-09T16:00:00Z"));
+        assert_eq!(daily_window_start(at("2026-03-10T16:00:00Z"), 16), at("2026-03-10T16:00:00Z"));
+        assert_eq!(daily_window_start(at("2026-03-01T01:00:00Z"), 4), at("2026-02-28T04:00:00Z"));
+    }
+
+    #[test]
+    fn test_daily_stats() {
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().timestamp();
+        let claim = |s: &str, reward: Option<u64>, streak: Option<u32>| DailyClaim { timestamp: at(s), reward, streak };
+        let claims = vec![
+            claim("2026-03-01T10:00:00Z", Some(100), None),
+            claim("2026-03-02T10:00:00Z", Some(100), None),
+            claim("2026-03-03T09:00:00Z", Some(100), None),
+            // Missed the 4th
+            claim("2026-03-05T10:00:00Z", Some(200), None),
+            claim("2026-03-06T23:00:00Z", None, None),
+        ];
+        let now = DateTime::parse_from_rfc3339("2026-03-07T12:00:00Z").unwrap().with_timezone(&Utc);
+        let stats = daily_stats(&claims, 0, now);
+        assert_eq!(stats, DailyStats { current_streak: 2, longest_streak: 3, total_income: 500, claims: 5 });
+
+        // Broken once a whole window passes without a claim
+        let later = DateTime::parse_from_rfc3339("2026-03-08T12:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(daily_stats(&claims, 0, later).current_streak, 0);
+
+        // The game's count covers claims from before the bot
+        let mut reported = claims.clone();
+        reported[3].streak = Some(40);
+        let stats = daily_stats(&reported, 0, now);
+        assert_eq!((stats.current_streak, stats.longest_streak), (41, 41));
+    }
+}
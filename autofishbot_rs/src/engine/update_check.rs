@@ -0,0 +1,120 @@
+use crate::config::UpdatesConfig;
+use crate::tui::app::App;
+use anyhow::{Result, anyhow};
+use log::{debug, info};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Release notes mentioning these usually mean the running build misreads the game.
+const GAME_FIX_KEYWORDS: &[&str] = &["parser", "game data", "game-data", "embed"];
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    html_url: String,
+    #[serde(default)]
+    body: String,
+}
+
+/// A release newer than the running build.
+#[derive(Debug, Clone)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub url: String,
+    /// The changelog mentions parser or game-data fixes
+    pub game_fixes: bool,
+}
+
+impl UpdateInfo {
+    pub fn summary(&self) -> String {
+        format!(
+            "Update available: {} (running {}){} - {}",
+            self.version,
+            CURRENT_VERSION,
+            if self.game_fixes { ", includes parser/game-data fixes" } else { "" },
+            self.url,
+        )
+    }
+}
+
+/// Checks once in the background and records the result on `App::update_available`.
+/// Failures are only logged at debug level; being offline is not worth a warning.
+pub fn spawn(config: UpdatesConfig, app_state: Arc<Mutex<App>>) {
+    if !config.enabled {
+        return;
+    }
+    tokio::spawn(async move {
+        match check(&config).await {
+            Ok(Some(update)) => {
+                info!("{}", update.summary());
+                let mut app = app_state.lock().await;
+                app.add_log(update.summary());
+                app.update_available = Some(update);
+            },
+            Ok(None) => debug!("Running the latest release ({})", CURRENT_VERSION),
+            Err(e) => debug!("Update check failed: {}", e),
+        }
+    });
+}
+
+pub async fn check(config: &UpdatesConfig) -> Result<Option<UpdateInfo>> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .user_agent(concat!("autofishbot_rs/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let res = client.get(&config.releases_url).send().await?;
+    if !res.status().is_success() {
+        return Err(anyhow!("releases endpoint returned {}", res.status()));
+    }
+    let release: Release = res.json().await?;
+
+    if !is_newer(&release.tag_name, CURRENT_VERSION) {
+        return Ok(None);
+    }
+    let notes = release.body.to_lowercase();
+    Ok(Some(UpdateInfo {
+        version: release.tag_name,
+        url: release.html_url,
+        game_fixes: GAME_FIX_KEYWORDS.iter().any(|k| notes.contains(k)),
+    }))
+}
+
+/// Compares dotted versions numerically, ignoring a leading `v` and any `-suffix`.
+fn is_newer(latest: &str, current: &str) -> bool {
+    fn parts(version: &str) -> Vec<u64> {
+        version
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect()
+    }
+    let (mut latest, mut current) = (parts(latest), parts(current));
+    let len = latest.len().max(current.len());
+    latest.resize(len, 0);
+    current.resize(len, 0);
+    latest > current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("v0.2.0", "0.1.0"));
+        assert!(is_newer("0.1.10", "0.1.9"));
+        assert!(is_newer("1.0", "0.9.9"));
+        assert!(!is_newer("v0.1.0", "0.1.0"));
+        assert!(!is_newer("0.1", "0.1.0"));
+        assert!(!is_newer("v0.1.0-rc1", "0.1.0"));
+        assert!(!is_newer("0.0.9", "0.1.0"));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::engine::game_data::FISH_DATA;
+use crate::util::money;
+use std::collections::HashMap;
+
+/// What the fish in the inventory would sell for, kept current between inventory embeds.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InventoryValuation {
+    pub total: u64,
+    /// Inventory entries with no known price (bait, crates, unknown fish)
+    pub unpriced: Vec<String>,
+}
+
+impl InventoryValuation {
+    /// Values `inventory` ("amount", "name") pairs from the profile embed. `scraped` prices
+    /// from the `fish` table take precedence over the static `FISH_DATA` catalog.
+    pub fn from_inventory(inventory: &[(String, String)], scraped: &HashMap<String, u64>) -> Self {
+        let mut valuation = Self::default();
+        for (amount, name) in inventory {
+            let amount = money::parse_amount(amount).unwrap_or(0);
+            match price_of(name, scraped) {
+                Some(price) => valuation.total += price * amount,
+                None => valuation.unpriced.push(name.clone()),
+            }
+        }
+        valuation
+    }
+
+    /// Adds a catch, so the value keeps up until the next inventory embed.
+    pub fn add_catch(&mut self, fish: &[(String, i32)], scraped: &HashMap<String, u64>) {
+        for (name, count) in fish {
+            self.total += price_of(name, scraped).unwrap_or(0) * (*count).max(0) as u64;
+        }
+    }
+}
+
+pub fn price_of(name: &str, scraped: &HashMap<String, u64>) -> Option<u64> {
+    scraped.get(name).copied()
+        .or_else(|| FISH_DATA.get(name).map(|f| f.price))
+        .or_else(|| FISH_DATA.values().find(|f| f.name.eq_ignore_ascii_case(name)).map(|f| f.price))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inventory_valuation() {
+        let inventory = vec![
+            ("1,000".to_string(), "Cod".to_string()),
+            ("2".to_string(), "turtle".to_string()),
+            ("3".to_string(), "Worms".to_string()),
+        ];
+        let mut scraped = HashMap::new();
+        let valuation = InventoryValuation::from_inventory(&inventory, &scraped);
+        assert_eq!(valuation.total, 1000 * 10 + 2 * 4000);
+        assert_eq!(valuation.unpriced, vec!["Worms"]);
+
+        scraped.insert("Cod".to_string(), 12);
+        let mut valuation = InventoryValuation::from_inventory(&inventory, &scraped);
+        assert_eq!(valuation.total, 1000 * 12 + 2 * 4000);
+        valuation.add_catch(&[("Squid".to_string(), 2)], &scraped);
+        assert_eq!(valuation.total, 1000 * 12 + 2 * 4000 + 2 * 1200);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::engine::database::Database;
+use crate::engine::health::HealthMonitor;
+use crate::tui::app::App;
+use log::{info, warn};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Sends a state string (e.g. `READY=1`) to the systemd notify socket.
+/// Returns false when not running under systemd with `Type=notify` or the send fails.
+pub fn sd_notify(state: &str) -> bool {
+    let Some(socket) = std::env::var_os("NOTIFY_SOCKET") else { return false };
+    send_datagram(&socket.to_string_lossy(), state)
+}
+
+#[cfg(target_os = "linux")]
+fn send_datagram(socket: &str, state: &str) -> bool {
+    use std::os::linux::net::SocketAddrExt;
+    use std::os::unix::net::{SocketAddr, UnixDatagram};
+
+    let Ok(sock) = UnixDatagram::unbound() else { return false };
+    // A leading '@' denotes an abstract-namespace socket
+    let addr = match socket.strip_prefix('@') {
+        Some(name) => SocketAddr::from_abstract_name(name.as_bytes()),
+        None => SocketAddr::from_pathname(socket),
+    };
+    match addr {
+        Ok(addr) => sock.send_to_addr(state.as_bytes(), &addr).is_ok(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn send_datagram(socket: &str, state: &str) -> bool {
+    let Ok(sock) = std::os::unix::net::UnixDatagram::unbound() else { return false };
+    sock.send_to(state.as_bytes(), socket).is_ok()
+}
+
+#[cfg(not(unix))]
+fn send_datagram(_socket: &str, _state: &str) -> bool {
+    false
+}
+
+/// `WatchdogSec=` as exported by systemd, if it applies to this process.
+pub fn watchdog_interval() -> Option<Duration> {
+    let usec: u64 = std::env::var("WATCHDOG_USEC").ok()?.parse().ok()?;
+    if let Ok(pid) = std::env::var("WATCHDOG_PID") {
+        if pid.parse::<u32>().ok()? != std::process::id() {
+            return None;
+        }
+    }
+    Some(Duration::from_micros(usec))
+}
+
+/// Sends `READY=1` once the bot is first healthy, then `WATCHDOG=1` every half interval while
+/// the health monitor stays healthy. A hung bot stops pinging and systemd restarts it.
+pub fn spawn(health: Arc<HealthMonitor>, app_state: Arc<Mutex<App>>, db: Arc<Database>) {
+    if std::env::var_os("NOTIFY_SOCKET").is_none() {
+        return;
+    }
+    let interval = watchdog_interval();
+    let tick = interval.map(|i| i / 2).unwrap_or(Duration::from_secs(5));
+
+    tokio::spawn(async move {
+        let mut ready = false;
+        let mut ticker = tokio::time::interval(tick);
+        loop {
+            ticker.tick().await;
+
+            let db_writable = db.check_writable().await.is_ok();
+            let bot_running = app_state.lock().await.is_running;
+            let report = health.report(db_writable, bot_running);
+
+            if !report.healthy {
+                warn!("Unhealthy (gateway: {}, db: {}, last catch: {:?}s); withholding watchdog ping",
+                    report.gateway_connected, report.db_writable, report.last_successful_cast_secs);
+                sd_notify("STATUS=Unhealthy");
+                continue;
+            }
+
+            if !ready {
+                ready = true;
+                info!("Notifying systemd: READY");
+                sd_notify("READY=1\nSTATUS=Fishing");
+                if interval.is_none() {
+                    // Nothing left to do without a watchdog
+                    return;
+                }
+            }
+            sd_notify("WATCHDOG=1");
+        }
+    });
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send_datagram() {
+        let path = std::env::temp_dir().join(format!("autofishbot_notify_{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+
+        assert!(send_datagram(&path.to_string_lossy(), "READY=1"));
+        let mut buf = [0u8; 32];
+        let n = listener.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"READY=1");
+
+        std::fs::remove_file(&path).ok();
+    }
+}
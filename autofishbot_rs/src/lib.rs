@@ -2,3 +2,9 @@ pub mod config;
 pub mod discord;
 pub mod engine;
 pub mod tui;
+pub mod web;
+pub mod notifications;
+pub mod report;
+pub mod telemetry;
+pub mod util;
+pub mod scripting;
@@ -7,22 +7,44 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use autofishbot_rs::config::Config;
+use autofishbot_rs::config::{AccountConfig, AccountMode, Config};
+use autofishbot_rs::scripting;
 use autofishbot_rs::tui::app::App;
 use autofishbot_rs::tui::ui;
 use autofishbot_rs::tui::events;
 use autofishbot_rs::discord::gateway::Gateway;
 use autofishbot_rs::discord::types::GatewayPayload;
 use autofishbot_rs::discord::client::DiscordClient;
+use autofishbot_rs::discord::session::SessionState;
+use autofishbot_rs::engine::accounts::supervisor::AccountSupervisor;
 use autofishbot_rs::engine::bot::Bot;
+use autofishbot_rs::engine::correlation::CorrelationStore;
 use autofishbot_rs::engine::database::Database;
+use autofishbot_rs::engine::events::EventProcessor;
+use autofishbot_rs::engine::health::HealthMonitor;
+use autofishbot_rs::engine::config_watcher::ConfigWatcher;
+use autofishbot_rs::engine::update_check;
+use autofishbot_rs::notifications::hub;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(|a| a.as_str()) == Some("validate") {
+        let path = args.get(2).map(|s| s.as_str()).unwrap_or("config.toml");
+        let ok = run_validate(path).await;
+        std::process::exit(if ok { 0 } else { 1 });
+    }
+    if args.get(1).map(|a| a.as_str()) == Some("migrate-config") {
+        return run_migrate_config(&args[2..]);
+    }
+    if args.get(1).map(|a| a.as_str()) == Some("report") {
+        return run_report(&args[2..]).await;
+    }
+
     // Load config
     let config_path = "config.toml";
     let config = if std::path::Path::new(config_path).exists() {
@@ -30,86 +52,100 @@ async fn main() -> Result<()> {
     } else {
         let cfg = Config::default();
         cfg.save(config_path)?;
+        println!("Created {} with default settings; fill in the [system] section.", config_path);
         cfg
     };
 
-    // Initialize Database
-    let db = Arc::new(Database::new("bot_data.db").await?);
+    // --account <name> selects one [[accounts]] entry, --account all runs them all with an Accounts tab
+    let account = args.iter().position(|a| a == "--account").and_then(|i| args.get(i + 1)).cloned();
+    if account.as_deref() == Some("all") {
+        return run_all_accounts(config, config_path).await;
+    }
+    let config = match &account {
+        Some(name) => config.for_account(name)?,
+        None => config,
+    };
+    config.validate()?;
+    let _telemetry = autofishbot_rs::telemetry::init(&config.telemetry)?;
 
-    // Setup TUI
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    // Initialize Database
+    let db_path = match &account {
+        Some(name) => AccountConfig::db_path(name),
+        None => "bot_data.db".to_string(),
+    };
+    let db = Arc::new(Database::new(&db_path).await?);
 
     let app = Arc::new(Mutex::new(App::new(config.clone())));
 
     // Discord Client
-    let client = Arc::new(DiscordClient::new(config.clone())?);
+    let correlation = Arc::new(CorrelationStore::new(db.clone()));
+    let session = Arc::new(SessionState::new());
+    let client = Arc::new(DiscordClient::new(config.clone())?.with_correlation(correlation.clone()).with_session(session.clone()));
 
     // Gateway event channel
-    let (gateway_tx, mut gateway_rx) = tokio::sync::mpsc::channel::<GatewayPayload>(100);
+    let (gateway_tx, gateway_rx) = tokio::sync::mpsc::channel::<GatewayPayload>(100);
 
     // Gateway
-    let mut gateway = Gateway::new(config.clone(), gateway_tx);
-    let _gateway_handle = tokio::spawn(async move {
-        loop {
-            if let Err(e) = gateway.run().await {
-                eprintln!("Gateway error: {}", e);
-                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-            } else {
-                // If run returns Ok, it means we disconnected intentionally (e.g. reconnect opcode)
-                // We just loop back to reconnect immediately.
-                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-            }
+    let health = Arc::new(HealthMonitor::new());
+    let gateway = Gateway::new(config.clone(), gateway_tx).with_health(health.clone()).with_session(session);
+    let notifier = {
+        let mut app = app.lock().await;
+        app.gateway = Some(gateway.stats());
+        app.notifier.clone()
+    };
+    let _gateway_handle = tokio::spawn(gateway.run_forever(notifier));
+
+    // Apply edits to config.toml at runtime without dropping the gateway session
+    let _config_watcher = match ConfigWatcher::spawn(config_path, account.clone(), app.clone()) {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            app.lock().await.add_log(format!("Config hot reload disabled: {}", e));
+            None
         }
-    });
+    };
+
+    hub::start(&config, app.clone()).await;
+    let _scripts = scripting::host::start(&config.scripting, app.clone());
+    update_check::spawn(config.updates.clone(), app.clone());
 
     // Bot Engine
     let mut bot = Bot::new(config.clone(), client.clone(), app.clone(), db.clone()).await;
-    let _bot_handle = tokio::spawn(async move {
-        bot.run().await;
-    });
-
-    // Event Processor
-    let app_clone = app.clone();
-    let _event_processor = tokio::spawn(async move {
-        while let Some(payload) = gateway_rx.recv().await {
-             let mut app = app_clone.lock().await;
-
-             // Handle specific events like MESSAGE_CREATE
-             if let Some(t) = payload.t {
-                 app.add_log(format!("Event: {}", t));
-                 if t == "MESSAGE_CREATE" || t == "MESSAGE_UPDATE" {
-                      if let Some(d) = payload.d {
-                           // Try to parse full message object
-                           if let Ok(msg) = serde_json::from_value::<autofishbot_rs::discord::types::Message>(d.clone()) {
-                               app.last_message_object = Some(msg);
-                           }
-
-                           if let Some(content) = d.get("content").and_then(|v| v.as_str()) {
-                               app.last_message = content.to_string();
-
-                               // Update Profile Parsing
-                               let embeds = d.get("embeds").and_then(|v| v.as_array());
-                               if let Some(embeds_arr) = embeds {
-                                   if let Some(first_embed) = embeds_arr.first() {
-                                       if let Some(title) = first_embed.get("title").and_then(|v| v.as_str()) {
-                                            if let Some(desc) = first_embed.get("description").and_then(|v| v.as_str()) {
-                                                 app.profile.update_from_message(desc, Some(title));
-                                            }
-                                       }
-                                   }
-                               }
-                           }
-                      }
-                 }
-             }
-        }
-    });
+    let cooldown_manager = bot.cooldown_manager.clone();
+    let captcha = bot.captcha.clone();
+    // Observers only watch the channel, so there is no bot to run
+    if config.system.mode != AccountMode::Observer {
+        tokio::spawn(async move {
+            bot.run().await;
+        });
+    }
+
+    // Event Processor: the same one headless mode runs, so clan bosses, catches and cooldowns
+    // reach the TUI too
+    let processor = EventProcessor::new(&config, app.clone(), db.clone(), cooldown_manager, health)
+        .with_correlation(correlation)
+        .with_captcha(captcha);
+    let _event_processor = tokio::spawn(processor.run(gateway_rx));
+
+    run_tui(app).await
+}
+
+/// Runs every `[[accounts]]` entry in this process; the TUI drives the first one.
+async fn run_all_accounts(config: Config, config_path: &str) -> Result<()> {
+    let _telemetry = autofishbot_rs::telemetry::init(&config.telemetry)?;
+    let mut supervisor = AccountSupervisor::start(config, config_path).await?;
+    let app = supervisor.accounts()[0].app.clone();
+    app.lock().await.show_accounts(supervisor.handle());
+    tokio::spawn(async move { supervisor.supervise().await });
+    run_tui(app).await
+}
+
+async fn run_tui(app: Arc<Mutex<App>>) -> Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
 
-    // Main Loop (TUI)
     let res = run_app(&mut terminal, app).await;
 
     // Cleanup
@@ -127,7 +163,7 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-async fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: Arc<Mutex<App>>) -> Result<()> {
+async fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, mut app: Arc<Mutex<App>>) -> Result<()> {
     loop {
         {
             let app_guard = app.lock().await;
@@ -138,9 +174,136 @@ async fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app:
         }
 
         // Handle input
-        {
+        let switch = {
              let mut app_guard = app.lock().await;
              events::handle_events(&mut app_guard)?;
+             app_guard.take_account_switch().zip(app_guard.accounts.clone()).map(|(next, accounts)| (next, accounts, app_guard.selected_account))
+        };
+
+        // Enter on the Accounts tab: every tab now shows that account
+        if let Some((next, accounts, selected)) = switch {
+            {
+                let mut next_guard = next.lock().await;
+                next_guard.adopt_switcher(accounts, selected);
+                let name = next_guard.account.clone().unwrap_or_default();
+                next_guard.add_log(format!("Viewing account '{}'", name));
+            }
+            app = next;
         }
     }
 }
+
+fn report(ok: bool, label: &str, detail: &str) -> bool {
+    println!("[{}] {}{}", if ok { "PASS" } else { "FAIL" }, label, if detail.is_empty() { String::new() } else { format!(" - {}", detail) });
+    ok
+}
+
+/// `autofishbot_rs report [--db bot_data.db] [--out report.html] [--hours 168]`: writes the stats charts and
+/// tables to one HTML file.
+async fn run_report(args: &[String]) -> Result<()> {
+    let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).cloned();
+    let db_path = flag("--db").unwrap_or_else(|| "bot_data.db".to_string());
+    let out = flag("--out").unwrap_or_else(|| "report.html".to_string());
+    let hours = match flag("--hours") {
+        Some(hours) => hours.parse::<i64>().ok().filter(|h| *h > 0).ok_or_else(|| anyhow!("--hours must be a positive number of hours"))?,
+        None => 168,
+    };
+    if !std::path::Path::new(&db_path).exists() {
+        return Err(anyhow!("Database {} not found. Run the bot first or pass --db.", db_path));
+    }
+    // Only for the daily reset hour; the report works without a config
+    let reset_hour = Config::load("config.toml").map(|c| c.automation.daily_reset_hour_utc).unwrap_or(0);
+
+    let db = Database::new(&db_path).await?;
+    let data = autofishbot_rs::report::gather(&db, hours, reset_hour).await?;
+    std::fs::write(&out, autofishbot_rs::report::render_html(&data))?;
+    println!("Wrote {} ({} snapshots, {} biomes, {} cooldowns over {}h)", out, data.snapshots.len(), data.biomes.len(), data.cooldowns.len(), hours);
+    Ok(())
+}
+
+/// `autofishbot_rs migrate-config [legacy.ini | .config] [--out config.toml] [--force]`: converts the
+/// Python bot's .ini config to config.toml.
+fn run_migrate_config(args: &[String]) -> Result<()> {
+    let out = args.iter().position(|a| a == "--out").and_then(|i| args.get(i + 1)).cloned()
+        .unwrap_or_else(|| "config.toml".to_string());
+    let force = args.iter().any(|a| a == "--force");
+    let source = args.first().filter(|a| !a.starts_with("--")).cloned().unwrap_or_else(|| ".config".to_string());
+
+    // A directory is searched for the legacy .ini; only unambiguous picks are accepted
+    let source_path = std::path::PathBuf::from(&source);
+    let ini_path = if source_path.is_dir() {
+        let mut inis: Vec<_> = std::fs::read_dir(&source_path)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "ini"))
+            .collect();
+        inis.sort();
+        match inis.len() {
+            0 => return Err(anyhow!("No .ini files found in {}", source)),
+            1 => inis.remove(0),
+            _ => {
+                let names: Vec<String> = inis.iter().map(|p| p.display().to_string()).collect();
+                return Err(anyhow!("Several legacy configs found, pass one explicitly: {}", names.join(", ")));
+            }
+        }
+    } else {
+        source_path
+    };
+
+    if std::path::Path::new(&out).exists() && !force {
+        return Err(anyhow!("{} already exists; pass --force to overwrite it", out));
+    }
+
+    let content = std::fs::read_to_string(&ini_path)?;
+    let config = Config::from_legacy_ini(&content)?;
+    config.save(&out)?;
+
+    println!("Migrated {} -> {}", ini_path.display(), out);
+    if config.system.guild_id == 0 {
+        println!("Note: the legacy config has no guild_id. Set system.guild_id to the server of channel {} before starting.", config.system.channel_id);
+    }
+    println!("Run `autofishbot_rs validate {}` to check it.", out);
+    Ok(())
+}
+
+/// `autofishbot_rs validate [config.toml]`: sanity-check the config and Discord connectivity before casting.
+async fn run_validate(path: &str) -> bool {
+    println!("Validating {}...", path);
+
+    let config = match Config::load(path) {
+        Ok(c) => { report(true, "Config loaded", ""); c },
+        Err(e) => return report(false, "Config loaded", &e.to_string()),
+    };
+
+    let mut all_ok = true;
+    all_ok &= report(Config::token_format_ok(&config.system.user_token), "system.user_token format",
+        if config.system.user_token.is_empty() { "token is empty" } else { "" });
+    all_ok &= report(config.system.channel_id != 0, "system.channel_id set", "");
+    all_ok &= report(config.system.guild_id != 0, "system.guild_id set", "");
+    all_ok &= report(config.system.user_cooldown > 0.0, "system.user_cooldown positive", &config.system.user_cooldown.to_string());
+
+    let client = match DiscordClient::new(config.clone()) {
+        Ok(c) => c,
+        Err(e) => return report(false, "HTTP client", &e.to_string()),
+    };
+
+    match client.get_current_user().await {
+        Ok(user) => { report(true, "Token accepted by /users/@me", &format!("{} ({})", user.username, user.id)); },
+        Err(e) => all_ok &= report(false, "Token accepted by /users/@me", &e.to_string()),
+    }
+
+    if config.system.guild_id != 0 {
+        match client.get_commands(&config.system.guild_id.to_string()).await {
+            Ok(cmds) => {
+                let has_fish = cmds.iter().any(|c| c["name"] == "fish");
+                all_ok &= report(has_fish, "Guild command index", &format!("{} Virtual Fisher commands, /fish {}", cmds.len(), if has_fish { "found" } else { "missing" }));
+            },
+            Err(e) => all_ok &= report(false, "Guild command index", &e.to_string()),
+        }
+    } else {
+        all_ok &= report(false, "Guild command index", "skipped, guild_id is 0");
+    }
+
+    println!();
+    println!("{}", if all_ok { "All checks passed." } else { "Some checks failed. Fix the items above before starting the bot." });
+    all_ok
+}
@@ -0,0 +1,132 @@
+use crate::config::EmailConfig;
+use crate::notifications::event::{EventKind, Notification, Severity};
+use anyhow::Result;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use log::{info, warn};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+
+/// Emails the owner about critical conditions only: a captcha left unsolved, a rejected token,
+/// repeated failures. Rate limited so a flapping problem can't fill the inbox.
+pub struct Email {
+    config: EmailConfig,
+    limiter: RateLimiter,
+}
+
+impl Email {
+    pub fn spawn(config: EmailConfig, mut receiver: broadcast::Receiver<Notification>) {
+        let limiter = RateLimiter::new(Duration::from_secs(config.min_interval_minutes * 60));
+        let captcha_grace = Duration::from_secs(config.captcha_unsolved_minutes * 60);
+        let mut email = Self { config, limiter };
+
+        tokio::spawn(async move {
+            // A captcha alone isn't worth an email; one that nobody solved in time is
+            let mut captcha_deadline: Option<Instant> = None;
+            loop {
+                let captcha_timeout = async {
+                    match captcha_deadline {
+                        Some(deadline) => tokio::time::sleep_until(deadline).await,
+                        None => std::future::pending().await,
+                    }
+                };
+                tokio::select! {
+                    received = receiver.recv() => match received {
+                        Ok(notification) => match notification.kind {
+                            EventKind::CaptchaDetected => {
+                                captcha_deadline.get_or_insert_with(|| Instant::now() + captcha_grace);
+                            },
+                            EventKind::CaptchaSolved => captcha_deadline = None,
+                            _ if notification.severity == Severity::Critical => email.alert(&notification).await,
+                            _ => {},
+                        },
+                        Err(broadcast::error::RecvError::Lagged(n)) => warn!("Email dropped {} notifications", n),
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                    _ = captcha_timeout => {
+                        captcha_deadline = None;
+                        let minutes = email.config.captcha_unsolved_minutes;
+                        email.alert(&Notification::new(EventKind::CaptchaUnsolved, Severity::Critical,
+                            "Captcha still unsolved",
+                            format!("A captcha has been waiting for {} minutes. Fishing stays paused until it is solved.", minutes))).await;
+                    },
+                }
+            }
+        });
+    }
+
+    async fn alert(&mut self, notification: &Notification) {
+        let Some(suppressed) = self.limiter.try_acquire(Instant::now()) else {
+            info!("Email alert rate limited: {}", notification.title);
+            return;
+        };
+        let mut body = notification.message.clone();
+        if suppressed > 0 {
+            body.push_str(&format!("\n\n{} more critical alert(s) were suppressed by the rate limit since the last email.", suppressed));
+        }
+        if let Err(e) = self.send(&notification.title, body).await {
+            warn!("Email alert failed: {}", e);
+        }
+    }
+
+    async fn send(&self, subject: &str, body: String) -> Result<()> {
+        let message = Message::builder()
+            .from(self.config.from.parse::<Mailbox>()?)
+            .to(self.config.to.parse::<Mailbox>()?)
+            .subject(format!("[autofishbot] {}", subject))
+            .body(body)?;
+
+        let builder = if self.config.smtp_port == 465 {
+            AsyncSmtpTransport::<Tokio1Executor>::relay(&self.config.smtp_host)?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.config.smtp_host)?
+        };
+        let mut builder = builder.port(self.config.smtp_port).timeout(Some(Duration::from_secs(30)));
+        if !self.config.username.is_empty() {
+            builder = builder.credentials(Credentials::new(self.config.username.clone(), self.config.password.clone()));
+        }
+        builder.build().send(message).await?;
+        Ok(())
+    }
+}
+
+/// Allows one alert per `interval` and counts the ones it turns away.
+struct RateLimiter {
+    interval: Duration,
+    last_sent: Option<Instant>,
+    suppressed: u32,
+}
+
+impl RateLimiter {
+    fn new(interval: Duration) -> Self {
+        Self { interval, last_sent: None, suppressed: 0 }
+    }
+
+    /// Returns how many alerts were suppressed since the last one, or `None` if this one must be dropped.
+    fn try_acquire(&mut self, now: Instant) -> Option<u32> {
+        if self.last_sent.is_some_and(|last| now.duration_since(last) < self.interval) {
+            self.suppressed += 1;
+            return None;
+        }
+        self.last_sent = Some(now);
+        Some(std::mem::take(&mut self.suppressed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = RateLimiter::new(Duration::from_secs(60));
+        let start = Instant::now();
+        assert_eq!(limiter.try_acquire(start), Some(0));
+        assert_eq!(limiter.try_acquire(start + Duration::from_secs(10)), None);
+        assert_eq!(limiter.try_acquire(start + Duration::from_secs(59)), None);
+        assert_eq!(limiter.try_acquire(start + Duration::from_secs(60)), Some(2));
+        assert_eq!(limiter.try_acquire(start + Duration::from_secs(61)), None);
+    }
+}
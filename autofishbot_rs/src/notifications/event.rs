@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    CaptchaDetected,
+    CaptchaSolved,
+    /// A captcha stayed unsolved past `[notifications.email] captcha_unsolved_minutes`
+    CaptchaUnsolved,
+    /// Every automatic attempt (`[captcha] max_attempts`) at a captcha was wrong or failed
+    CaptchaFailed,
+    /// A command failed or got no reply, and so did its retry
+    CommandFailed,
+    DailySummary,
+    Error,
+    LevelUp,
+    Purchase,
+    Prestige,
+    /// The same action kept failing (e.g. consecutive /fish send errors)
+    RepeatedFailures,
+    /// Discord rejected the account token
+    TokenInvalid,
+    /// Sent by a user script with `notify(..)`
+    Script,
+    /// Server staff posted in the fishing channel and casting paused
+    HumanActivity,
+    /// Someone sent this account a trade or gift prompt
+    TradeRequest,
+    /// The vote crate can be claimed again
+    VoteAvailable,
+}
+
+/// Something the owner should hear about, fanned out to every enabled channel.
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub kind: EventKind,
+    pub severity: Severity,
+    pub title: String,
+    pub message: String,
+}
+
+impl Notification {
+    pub fn new<T: Into<String>, M: Into<String>>(kind: EventKind, severity: Severity, title: T, message: M) -> Self {
+        Self {
+            kind,
+            severity,
+            title: title.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Plain-text rendering shared by chat-style channels.
+    pub fn to_text(&self) -> String {
+        let icon = match self.severity {
+            Severity::Info => "ℹ️",
+            Severity::Warning => "⚠️",
+            Severity::Critical => "🚨",
+        };
+        if self.message.is_empty() {
+            format!("{} {}", icon, self.title)
+        } else {
+            format!("{} {}\n{}", icon, self.title, self.message)
+        }
+    }
+}
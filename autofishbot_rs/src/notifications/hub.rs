@@ -0,0 +1,112 @@
+use crate::config::Config;
+use crate::notifications::email::Email;
+use crate::notifications::event::{EventKind, Notification, Severity};
+use crate::notifications::telegram::Telegram;
+use crate::notifications::webhook::Webhook;
+use crate::tui::app::App;
+use log::info;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, Mutex};
+
+/// Slow channels drop the oldest notifications beyond this backlog.
+const CHANNEL_CAPACITY: usize = 64;
+const SUMMARY_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Cheap, cloneable handle for publishing notifications; each channel subscribes to it.
+#[derive(Clone)]
+pub struct Notifier {
+    sender: broadcast::Sender<Notification>,
+}
+
+impl Default for Notifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Notifier {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    pub fn send(&self, notification: Notification) {
+        info!("Notification: {}", notification.title);
+        // No subscribers just means no channel is enabled
+        let _ = self.sender.send(notification);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Notification> {
+        self.sender.subscribe()
+    }
+}
+
+/// Starts every channel enabled in `[notifications]`.
+pub async fn start(config: &Config, app_state: Arc<Mutex<App>>) {
+    let notifier = app_state.lock().await.notifier.clone();
+    let telegram = &config.notifications.telegram;
+
+    if telegram.enabled {
+        Telegram::spawn(telegram.clone(), app_state.clone(), notifier.subscribe());
+    }
+    if config.notifications.webhook.enabled {
+        Webhook::spawn(config.notifications.webhook.clone(), notifier.subscribe());
+    }
+    if config.notifications.email.enabled {
+        Email::spawn(config.notifications.email.clone(), notifier.subscribe());
+    }
+    if telegram.enabled && telegram.daily_summary {
+        spawn_daily_summary(app_state, notifier);
+    }
+}
+
+fn spawn_daily_summary(app_state: Arc<Mutex<App>>, notifier: Notifier) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(SUMMARY_INTERVAL);
+        ticker.tick().await; // First tick fires immediately
+        loop {
+            ticker.tick().await;
+            let summary = status_summary(&*app_state.lock().await);
+            notifier.send(Notification::new(EventKind::DailySummary, Severity::Info, "Daily summary", summary));
+        }
+    });
+}
+
+/// Short multi-line status used by summaries and chat `/status` commands.
+pub fn status_summary(app: &App) -> String {
+    format!(
+        "Status: {}\nFish caught: {}\nMoney earned: {}\nBalance: {} | Level: {} | Biome: {}\nStrategy: {} ({})",
+        app.status,
+        app.stats.fish_caught,
+        app.stats.money_earned,
+        app.profile.balance_text(),
+        app.profile.level_text(),
+        app.profile.biome,
+        app.strategy.current_goal,
+        app.strategy.progress,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notifier_fan_out() {
+        let notifier = Notifier::new();
+        // Sending without subscribers must not fail
+        notifier.send(Notification::new(EventKind::Error, Severity::Warning, "ignored", ""));
+
+        let mut a = notifier.subscribe();
+        let mut b = notifier.clone().subscribe();
+        notifier.send(Notification::new(EventKind::CaptchaDetected, Severity::Critical, "Captcha detected", "paused"));
+
+        for rx in [&mut a, &mut b] {
+            let n = rx.try_recv().unwrap();
+            assert_eq!(n.kind, EventKind::CaptchaDetected);
+            assert_eq!(n.to_text(), "🚨 Captcha detected\npaused");
+        }
+        assert!(Severity::Critical > Severity::Warning);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod email;
+pub mod event;
+pub mod hub;
+pub mod telegram;
+pub mod webhook;
@@ -0,0 +1,129 @@
+use crate::config::TelegramConfig;
+use crate::notifications::event::Notification;
+use crate::notifications::hub::status_summary;
+use crate::tui::app::App;
+use anyhow::{Result, anyhow};
+use log::{info, warn};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, Mutex};
+
+const API_BASE: &str = "https://api.telegram.org";
+/// Long-poll timeout for getUpdates, in seconds.
+const POLL_TIMEOUT: u64 = 30;
+
+/// Pushes notifications to one Telegram chat and accepts `/status`, `/start`, `/stop` from it.
+pub struct Telegram {
+    client: Client,
+    config: TelegramConfig,
+    app_state: Arc<Mutex<App>>,
+}
+
+impl Telegram {
+    pub fn spawn(config: TelegramConfig, app_state: Arc<Mutex<App>>, mut receiver: broadcast::Receiver<Notification>) {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(POLL_TIMEOUT + 10))
+            .build()
+            .unwrap_or_default();
+        let telegram = Arc::new(Self { client, config, app_state });
+
+        let sender = telegram.clone();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(notification) => {
+                        if notification.severity < sender.config.min_severity {
+                            continue;
+                        }
+                        if let Err(e) = sender.send_message(&notification.to_text()).await {
+                            warn!("Telegram send failed: {}", e);
+                        }
+                    },
+                    Err(broadcast::error::RecvError::Lagged(n)) => warn!("Telegram dropped {} notifications", n),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        tokio::spawn(async move {
+            telegram.poll_commands().await;
+        });
+    }
+
+    fn url(&self, method: &str) -> String {
+        format!("{}/bot{}/{}", API_BASE, self.config.bot_token, method)
+    }
+
+    pub async fn send_message(&self, text: &str) -> Result<()> {
+        let res = self.client.post(self.url("sendMessage"))
+            .json(&json!({ "chat_id": self.config.chat_id, "text": text }))
+            .send()
+            .await?;
+        if !res.status().is_success() {
+            return Err(anyhow!("sendMessage returned {}", res.status()));
+        }
+        Ok(())
+    }
+
+    async fn poll_commands(&self) {
+        info!("Telegram remote control listening for chat {}", self.config.chat_id);
+        let mut offset: i64 = 0;
+        loop {
+            let updates = match self.get_updates(offset).await {
+                Ok(updates) => updates,
+                Err(e) => {
+                    warn!("Telegram getUpdates failed: {}", e);
+                    tokio::time::sleep(Duration::from_secs(10)).await;
+                    continue;
+                }
+            };
+
+            for update in updates {
+                if let Some(id) = update.get("update_id").and_then(|v| v.as_i64()) {
+                    offset = offset.max(id + 1);
+                }
+                let Some(message) = update.get("message") else { continue };
+                // Only the configured chat may control the bot
+                if message.pointer("/chat/id").and_then(|v| v.as_i64()) != Some(self.config.chat_id) {
+                    continue;
+                }
+                if let Some(text) = message.get("text").and_then(|v| v.as_str()) {
+                    let reply = self.handle_command(text).await;
+                    if let Err(e) = self.send_message(&reply).await {
+                        warn!("Telegram reply failed: {}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn get_updates(&self, offset: i64) -> Result<Vec<Value>> {
+        let res = self.client.get(self.url("getUpdates"))
+            .query(&[("offset", offset.to_string()), ("timeout", POLL_TIMEOUT.to_string())])
+            .send()
+            .await?;
+        let body: Value = res.json().await?;
+        body.get("result")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .ok_or_else(|| anyhow!("unexpected response: {}", body))
+    }
+
+    async fn handle_command(&self, text: &str) -> String {
+        // Commands may arrive as `/status@MyBot` in groups
+        let command = text.split_whitespace().next().unwrap_or("").split('@').next().unwrap_or("");
+        let mut app = self.app_state.lock().await;
+        match command {
+            "/status" => status_summary(&app),
+            "/start" | "/stop" => {
+                if app.is_running != (command == "/start") {
+                    app.toggle_bot();
+                }
+                format!("Bot {}", app.status)
+            },
+            _ => "Commands: /status, /start, /stop".to_string(),
+        }
+    }
+}
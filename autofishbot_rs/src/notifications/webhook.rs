@@ -0,0 +1,82 @@
+use crate::config::WebhookConfig;
+use crate::notifications::event::{Notification, Severity};
+use anyhow::{Result, anyhow};
+use log::warn;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Posts notifications as embeds to a Discord webhook, so they show up on the owner's main account.
+pub struct Webhook {
+    client: Client,
+    config: WebhookConfig,
+}
+
+impl Webhook {
+    pub fn spawn(config: WebhookConfig, mut receiver: broadcast::Receiver<Notification>) {
+        let client = Client::builder().timeout(Duration::from_secs(15)).build().unwrap_or_default();
+        let webhook = Self { client, config };
+
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(notification) => {
+                        if notification.severity < webhook.config.min_severity {
+                            continue;
+                        }
+                        if let Err(e) = webhook.post(&notification).await {
+                            warn!("Webhook post failed: {}", e);
+                        }
+                    },
+                    Err(broadcast::error::RecvError::Lagged(n)) => warn!("Webhook dropped {} notifications", n),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    fn payload(&self, notification: &Notification) -> Value {
+        let color = match notification.severity {
+            Severity::Info => 0x3498db,
+            Severity::Warning => 0xf1c40f,
+            Severity::Critical => 0xe74c3c,
+        };
+        // Ping the owner only when it matters
+        let content = if notification.severity == Severity::Critical && self.config.mention_user_id != 0 {
+            format!("<@{}>", self.config.mention_user_id)
+        } else {
+            String::new()
+        };
+        json!({
+            "username": "Autofishbot",
+            "content": content,
+            "embeds": [{
+                "title": notification.title,
+                "description": notification.message,
+                "color": color,
+                "footer": { "text": format!("{:?}", notification.kind) },
+                "timestamp": chrono::Utc::now().to_rfc3339(),
+            }],
+        })
+    }
+
+    async fn post(&self, notification: &Notification) -> Result<()> {
+        let payload = self.payload(notification);
+        // One retry when Discord rate limits the webhook
+        for _ in 0..2 {
+            let res = self.client.post(&self.config.url).json(&payload).send().await?;
+            if res.status().as_u16() == 429 {
+                let body: Value = res.json().await.unwrap_or_default();
+                let retry_after = body.get("retry_after").and_then(|v| v.as_f64()).unwrap_or(1.0);
+                tokio::time::sleep(Duration::from_secs_f64(retry_after.min(30.0))).await;
+                continue;
+            }
+            if !res.status().is_success() {
+                return Err(anyhow!("webhook returned {}", res.status()));
+            }
+            return Ok(());
+        }
+        Err(anyhow!("webhook still rate limited"))
+    }
+}
@@ -0,0 +1,209 @@
+use crate::engine::database::{BiomeCatches, Database, PlayerSnapshot, Throughput};
+use crate::engine::streak::{self, DailyStats};
+use crate::util::money;
+use anyhow::Result;
+use chrono::{TimeZone, Utc};
+use std::fmt::Write;
+
+const CHART_WIDTH: f64 = 720.0;
+const CHART_HEIGHT: f64 = 220.0;
+/// Room left of and below the plot for the axis labels.
+const MARGIN: f64 = 48.0;
+
+/// Everything `render_html` draws, read from the database by `gather`.
+#[derive(Debug, Clone, Default)]
+pub struct ReportData {
+    pub generated_at: i64,
+    pub hours: i64,
+    pub snapshots: Vec<PlayerSnapshot>,
+    pub biomes: Vec<BiomeCatches>,
+    /// `(unix seconds, wait_time)` per cooldown hit
+    pub cooldowns: Vec<(i64, f64)>,
+    pub throughput: Throughput,
+    pub daily: DailyStats,
+}
+
+pub async fn gather(db: &Database, hours: i64, daily_reset_hour: u32) -> Result<ReportData> {
+    let claims = db.daily_claim_history().await?;
+    Ok(ReportData {
+        generated_at: Utc::now().timestamp(),
+        hours,
+        snapshots: db.snapshots_since(hours).await?,
+        biomes: db.catches_by_biome(hours).await?,
+        cooldowns: db.cooldowns_since(hours).await?,
+        throughput: db.throughput(hours).await?,
+        daily: streak::daily_stats(&claims, daily_reset_hour, Utc::now()),
+    })
+}
+
+/// A standalone page (inline CSS and SVG, no scripts) that can be opened or shared as-is.
+pub fn render_html(data: &ReportData) -> String {
+    let mut html = String::new();
+    let _ = write!(html, r#"<!DOCTYPE html>
+<html><head><meta charset="utf-8"><title>Autofishbot report</title>
+<style>
+body {{ font-family: sans-serif; background: #10151c; color: #dde; margin: 2em auto; max-width: 780px; }}
+h1, h2 {{ color: #7fc8f8; }}
+table {{ border-collapse: collapse; width: 100%; }}
+td, th {{ border-bottom: 1px solid #334; padding: 4px 8px; text-align: right; }}
+td:first-child, th:first-child {{ text-align: left; }}
+svg {{ background: #182029; }}
+.axis {{ fill: #889; font-size: 11px; }}
+.empty {{ color: #889; }}
+</style></head><body>
+<h1>Autofishbot report</h1>
+<p>Last {} hours, generated {}</p>
+"#, data.hours, format_time(data.generated_at));
+
+    let throughput = &data.throughput;
+    let _ = write!(html, "<h2>Summary</h2>\n<table>\n{}{}{}{}{}{}</table>\n",
+        row(&["Fish caught", &throughput.fish.to_string()]),
+        row(&["Catch value", &money::format_money(throughput.gold.max(0) as u64)]),
+        row(&["Fish per hour", &format!("{:.0}", throughput.fish_per_hour())]),
+        row(&["Gold per hour", &money::format_money(throughput.gold_per_hour().max(0.0) as u64)]),
+        row(&["Captchas", &throughput.captchas.to_string()]),
+        row(&["Daily streak", &format!("{} (longest {}, {} from dailies)", data.daily.current_streak,
+            data.daily.longest_streak, money::format_money(data.daily.total_income))]),
+    );
+
+    html.push_str("<h2>Balance</h2>\n");
+    let balance: Vec<(f64, f64)> = data.snapshots.iter().map(|s| (s.timestamp as f64, s.balance)).collect();
+    html.push_str(&line_chart(&balance, |v| money::format_compact(v.max(0.0) as u64)));
+
+    html.push_str("<h2>Catches per biome</h2>\n");
+    let bars: Vec<(String, f64)> = data.biomes.iter().map(|b| (display_biome(&b.biome), b.fish as f64)).collect();
+    html.push_str(&bar_chart(&bars));
+    if !data.biomes.is_empty() {
+        let total: i64 = data.biomes.iter().map(|b| b.fish).sum();
+        html.push_str("<table>\n<tr><th>Biome</th><th>Fish</th><th>Share</th><th>Value</th></tr>\n");
+        for biome in &data.biomes {
+            html.push_str(&row(&[
+                &display_biome(&biome.biome),
+                &biome.fish.to_string(),
+                &format!("{:.1}%", biome.fish as f64 / total.max(1) as f64 * 100.0),
+                &money::format_money(biome.gold.max(0) as u64),
+            ]));
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("<h2>Cooldowns</h2>\n");
+    let cooldowns: Vec<(f64, f64)> = data.cooldowns.iter().map(|(ts, wait)| (*ts as f64, *wait)).collect();
+    html.push_str(&line_chart(&cooldowns, |v| format!("{:.1}s", v)));
+    if !data.cooldowns.is_empty() {
+        let average = data.cooldowns.iter().map(|(_, wait)| wait).sum::<f64>() / data.cooldowns.len() as f64;
+        let _ = writeln!(html, "<p>{} cooldown hits, {:.1}s wait on average</p>", data.cooldowns.len(), average);
+    }
+
+    html.push_str("</body></html>\n");
+    html
+}
+
+fn display_biome(biome: &str) -> String {
+    if biome.is_empty() { "Unknown".to_string() } else { biome.to_string() }
+}
+
+fn row(cells: &[&str]) -> String {
+    let cells: String = cells.iter().map(|c| format!("<td>{}</td>", escape(c))).collect();
+    format!("<tr>{}</tr>\n", cells)
+}
+
+fn format_time(ts: i64) -> String {
+    Utc.timestamp_opt(ts, 0).single().map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string()).unwrap_or_default()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn empty_chart() -> String {
+    "<p class=\"empty\">No data in this window.</p>\n".to_string()
+}
+
+/// `points` are `(unix seconds, value)`; the y axis is labelled with `label`.
+fn line_chart(points: &[(f64, f64)], label: impl Fn(f64) -> String) -> String {
+    if points.is_empty() {
+        return empty_chart();
+    }
+    let (min_x, max_x) = bounds(points.iter().map(|p| p.0));
+    let (min_y, max_y) = bounds(points.iter().map(|p| p.1));
+    let plot_w = CHART_WIDTH - MARGIN;
+    let plot_h = CHART_HEIGHT - MARGIN;
+    let coords: Vec<String> = points.iter().map(|(x, y)| {
+        let px = MARGIN + (x - min_x) / (max_x - min_x) * plot_w;
+        let py = plot_h - (y - min_y) / (max_y - min_y) * plot_h + 8.0;
+        format!("{:.1},{:.1}", px, py)
+    }).collect();
+
+    format!(
+        "<svg width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n\
+         <polyline fill=\"none\" stroke=\"#7fc8f8\" stroke-width=\"2\" points=\"{points}\"/>\n\
+         <text class=\"axis\" x=\"2\" y=\"14\">{top}</text>\n\
+         <text class=\"axis\" x=\"2\" y=\"{bottom_y}\">{bottom}</text>\n\
+         <text class=\"axis\" x=\"{m}\" y=\"{h_label}\">{start}</text>\n\
+         <text class=\"axis\" x=\"{end_x}\" y=\"{h_label}\" text-anchor=\"end\">{end}</text>\n\
+         </svg>\n",
+        w = CHART_WIDTH, h = CHART_HEIGHT, m = MARGIN,
+        points = coords.join(" "),
+        top = escape(&label(max_y)), bottom = escape(&label(min_y)), bottom_y = plot_h + 8.0,
+        h_label = CHART_HEIGHT - 12.0, end_x = CHART_WIDTH - 4.0,
+        start = format_time(min_x as i64), end = format_time(max_x as i64),
+    )
+}
+
+fn bar_chart(bars: &[(String, f64)]) -> String {
+    if bars.is_empty() {
+        return empty_chart();
+    }
+    let max = bars.iter().map(|b| b.1).fold(0.0, f64::max).max(1.0);
+    let bar_h = 22.0;
+    let height = bars.len() as f64 * (bar_h + 6.0) + 6.0;
+    let label_w = 110.0;
+    let mut svg = format!("<svg width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n", w = CHART_WIDTH, h = height);
+    for (i, (name, value)) in bars.iter().enumerate() {
+        let y = 6.0 + i as f64 * (bar_h + 6.0);
+        let width = value / max * (CHART_WIDTH - label_w - 60.0);
+        let _ = writeln!(svg, "<text class=\"axis\" x=\"4\" y=\"{:.1}\">{}</text>\
+            <rect x=\"{}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{}\" fill=\"#f8b26a\"/>\
+            <text class=\"axis\" x=\"{:.1}\" y=\"{:.1}\">{}</text>",
+            y + 15.0, escape(name), label_w, y, width, bar_h, label_w + width + 6.0, y + 15.0, value);
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Min and max, widened so a flat series still has a non-zero range.
+fn bounds(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values.fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    if (max - min).abs() < f64::EPSILON { (min - 1.0, max + 1.0) } else { (min, max) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_html() {
+        let data = ReportData {
+            generated_at: 1_773_000_000,
+            hours: 24,
+            snapshots: vec![
+                PlayerSnapshot { timestamp: 1_772_990_000, level: 10, balance: 1_000.0, biome: "River".to_string() },
+                PlayerSnapshot { timestamp: 1_772_999_000, level: 11, balance: 25_000.0, biome: "River".to_string() },
+            ],
+            biomes: vec![
+                BiomeCatches { biome: "River".to_string(), fish: 30, gold: 300 },
+                BiomeCatches { biome: "<Ocean>".to_string(), fish: 10, gold: 900 },
+            ],
+            cooldowns: Vec::new(),
+            ..Default::default()
+        };
+        let html = render_html(&data);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert_eq!(html.matches("<svg").count(), 2);
+        assert!(html.contains("<td>River</td><td>30</td><td>75.0%</td><td>$300</td>"));
+        assert!(html.contains("&lt;Ocean&gt;") && !html.contains("<Ocean>"));
+        // The empty cooldown chart says so instead of drawing nothing
+        assert!(html.contains("No data in this window."));
+    }
+}
@@ -0,0 +1,236 @@
+use crate::config::ScriptingConfig;
+use crate::notifications::event::{EventKind, Notification, Severity};
+use crate::tui::app::App;
+use anyhow::{Result, anyhow};
+use log::{info, warn};
+use parking_lot::Mutex as SyncMutex;
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Mutex};
+use tokio::task::JoinHandle;
+
+/// How often `on_tick(state)` runs.
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+/// Nesting limit for script function calls.
+const MAX_CALL_LEVELS: usize = 32;
+
+/// What scripts asked for during a call, applied after it returns.
+#[derive(Debug, Default, PartialEq)]
+pub struct ScriptOutput {
+    pub commands: Vec<String>,
+    pub notifications: Vec<(String, String)>,
+}
+
+/// Runs the `[scripting]` Rhai scripts. Each may define `on_event(event)`, called for every
+/// notification, and `on_tick(state)`, called every 30s with a snapshot of the profile.
+/// Scripts can only `send_command(..)` (queued for the bot, allowlisted and rate limited),
+/// `notify(title, message)` and `print(..)`; there is no file or network access.
+pub struct ScriptHost {
+    engine: Engine,
+    scripts: Vec<(String, AST)>,
+    output: Arc<SyncMutex<ScriptOutput>>,
+    config: ScriptingConfig,
+    /// When recent script commands were accepted, for `max_commands_per_minute`
+    sent: VecDeque<Instant>,
+}
+
+impl ScriptHost {
+    /// Compiles every file in `config.scripts`.
+    pub fn load(config: &ScriptingConfig) -> Result<Self> {
+        let sources = config.scripts.iter()
+            .map(|path| std::fs::read_to_string(path).map(|source| (path.clone(), source))
+                .map_err(|e| anyhow!("Can't read script {}: {}", path, e)))
+            .collect::<Result<Vec<_>>>()?;
+        Self::from_sources(config, sources)
+    }
+
+    /// Compiles `(name, source)` pairs; the name is only used in log lines.
+    pub fn from_sources(config: &ScriptingConfig, sources: Vec<(String, String)>) -> Result<Self> {
+        let output = Arc::new(SyncMutex::new(ScriptOutput::default()));
+        let mut engine = Engine::new();
+        engine.set_max_operations(config.max_operations);
+        engine.set_max_call_levels(MAX_CALL_LEVELS);
+        engine.set_max_string_size(10_000);
+        engine.set_max_array_size(1_000);
+        engine.set_max_map_size(1_000);
+        engine.disable_symbol("eval");
+        engine.on_print(|text| info!("[script] {}", text));
+        engine.on_debug(|text, _, _| info!("[script] {}", text));
+
+        let sink = output.clone();
+        engine.register_fn("send_command", move |command: &str| sink.lock().commands.push(command.trim().to_string()));
+        let sink = output.clone();
+        engine.register_fn("notify", move |title: &str, message: &str| {
+            sink.lock().notifications.push((title.to_string(), message.to_string()));
+        });
+
+        let scripts = sources.into_iter()
+            .map(|(name, source)| engine.compile(&source).map(|ast| (name.clone(), ast))
+                .map_err(|e| anyhow!("Script {} doesn't compile: {}", name, e)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { engine, scripts, output, config: config.clone(), sent: VecDeque::new() })
+    }
+
+    pub fn on_event(&self, notification: &Notification) {
+        let mut event = Map::new();
+        event.insert("kind".into(), format!("{:?}", notification.kind).into());
+        event.insert("severity".into(), format!("{:?}", notification.severity).to_lowercase().into());
+        event.insert("title".into(), notification.title.clone().into());
+        event.insert("message".into(), notification.message.clone().into());
+        self.call("on_event", event);
+    }
+
+    pub fn on_tick(&self, state: Map) {
+        self.call("on_tick", state);
+    }
+
+    fn call(&self, name: &str, arg: Map) {
+        for (file, ast) in &self.scripts {
+            if !ast.iter_functions().any(|f| f.name == name && f.params.len() == 1) {
+                continue;
+            }
+            let mut scope = Scope::new();
+            if let Err(e) = self.engine.call_fn::<Dynamic>(&mut scope, ast, name, (Dynamic::from_map(arg.clone()),)) {
+                warn!("Script {} failed in {}: {}", file, name, e);
+            }
+        }
+    }
+
+    /// Takes what the scripts asked for, dropping commands outside `allowed_commands` or
+    /// over the `max_commands_per_minute` budget.
+    pub fn take_output(&mut self, now: Instant) -> ScriptOutput {
+        let mut output = std::mem::take(&mut *self.output.lock());
+        while self.sent.front().is_some_and(|at| now.duration_since(*at) >= Duration::from_secs(60)) {
+            self.sent.pop_front();
+        }
+        output.commands.retain(|command| {
+            let name = command.split_whitespace().next().unwrap_or_default();
+            if !self.config.allowed_commands.iter().any(|allowed| allowed.eq_ignore_ascii_case(name)) {
+                warn!("Script command /{} refused: /{} isn't in [scripting] allowed_commands", command, name);
+                return false;
+            }
+            if self.sent.len() >= self.config.max_commands_per_minute as usize {
+                warn!("Script command /{} dropped: over max_commands_per_minute", command);
+                return false;
+            }
+            self.sent.push_back(now);
+            true
+        });
+        output
+    }
+
+    /// Queues accepted commands for the bot and sends script notifications.
+    fn flush(&mut self, app: &mut App) {
+        let output = self.take_output(Instant::now());
+        for command in output.commands {
+            app.add_log(format!("Script queued /{}", command));
+            app.pending_script_commands.push(command);
+        }
+        for (title, message) in output.notifications {
+            app.notifier.send(Notification::new(EventKind::Script, Severity::Info, title, message));
+        }
+    }
+}
+
+/// The `state` map handed to `on_tick`.
+pub fn state_map(app: &App) -> Map {
+    let profile = &app.profile;
+    let mut state = Map::new();
+    state.insert("running".into(), app.is_running.into());
+    state.insert("balance".into(), (profile.balance.unwrap_or(0) as i64).into());
+    state.insert("level".into(), (profile.level_number().unwrap_or(0) as i64).into());
+    state.insert("biome".into(), profile.biome.clone().into());
+    state.insert("rod".into(), profile.rod.clone().into());
+    state.insert("pet".into(), profile.pet.clone().into());
+    state.insert("bait".into(), profile.bait.clone().into());
+    state.insert("league".into(), profile.league.clone().into());
+    state.insert("hooks".into(), (profile.hooks as i64).into());
+    state.insert("fish_caught".into(), (app.stats.fish_caught as i64).into());
+    state.insert("inventory_value".into(), (app.inventory_valuation.as_ref().map(|v| v.total).unwrap_or(0) as i64).into());
+    state
+}
+
+/// Starts the script host when `[scripting] enabled`; a script that fails to load disables scripting.
+pub fn start(config: &ScriptingConfig, app_state: Arc<Mutex<App>>) -> Option<JoinHandle<()>> {
+    if !config.enabled {
+        return None;
+    }
+    let mut host = match ScriptHost::load(config) {
+        Ok(host) => host,
+        Err(e) => {
+            warn!("Scripting disabled: {}", e);
+            return None;
+        }
+    };
+    info!("Loaded {} script(s)", host.scripts.len());
+
+    Some(tokio::spawn(async move {
+        let mut events = app_state.lock().await.notifier.subscribe();
+        let mut ticker = tokio::time::interval(TICK_INTERVAL);
+        loop {
+            tokio::select! {
+                event = events.recv() => match event {
+                    // A script's own notifications would feed back into it
+                    Ok(notification) if notification.kind != EventKind::Script => host.on_event(&notification),
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Lagged(n)) => warn!("Scripts missed {} notifications", n),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = ticker.tick() => {
+                    let state = state_map(&*app_state.lock().await);
+                    host.on_tick(state);
+                },
+            }
+            host.flush(&mut *app_state.lock().await);
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_script_host() {
+        let config = ScriptingConfig { enabled: true, max_commands_per_minute: 2, ..Default::default() };
+        let script = r#"
+            fn on_tick(state) {
+                if state.balance > 1000000 && state.rod != "Magma Rod" {
+                    send_command("shop buy rod Magma Rod");
+                }
+                send_command("coinflip 500 heads");
+            }
+            fn on_event(event) {
+                if event.kind == "LevelUp" { notify("Level up", event.message); }
+            }
+        "#;
+        let mut host = ScriptHost::from_sources(&config, vec![("test.rhai".to_string(), script.to_string())]).unwrap();
+
+        let mut app = App::new(Config::default());
+        app.profile.balance = Some(1_500_000);
+        app.profile.rod = "Fiberglass Rod".to_string();
+        host.on_tick(state_map(&app));
+        host.on_event(&Notification::new(EventKind::LevelUp, Severity::Info, "Level up", "Level 31"));
+
+        let now = Instant::now();
+        let output = host.take_output(now);
+        // coinflip isn't allowlisted
+        assert_eq!(output.commands, vec!["shop buy rod Magma Rod"]);
+        assert_eq!(output.notifications, vec![("Level up".to_string(), "Level 31".to_string())]);
+
+        // Over the per-minute budget
+        for _ in 0..2 {
+            host.on_tick(state_map(&app));
+        }
+        assert_eq!(host.take_output(now).commands.len(), 1);
+        host.on_tick(state_map(&app));
+        assert_eq!(host.take_output(now + Duration::from_secs(61)).commands.len(), 1);
+
+        let runaway = ScriptHost::from_sources(&config, vec![("loop.rhai".to_string(), "fn on_tick(s) { loop {} }".to_string())]).unwrap();
+        runaway.on_tick(Map::new());
+        assert!(ScriptHost::from_sources(&config, vec![("bad.rhai".to_string(), "fn (".to_string())]).is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use crate::config::TelemetryConfig;
+use anyhow::Result;
+use log::{info, warn};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::Context;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use parking_lot::Mutex;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Trace context of the last command sent; game replies arrive on the gateway task and join its trace.
+static LAST_COMMAND: Mutex<Option<Context>> = Mutex::new(None);
+
+/// Flushes pending spans when dropped; keep it alive for the whole run.
+pub struct TelemetryGuard {
+    provider: SdkTracerProvider,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            warn!("Failed to flush traces: {}", e);
+        }
+    }
+}
+
+/// Installs the OTLP exporter as the global `tracing` subscriber when `[telemetry]` is enabled.
+/// Log output is untouched; only spans are exported.
+pub fn init(config: &TelemetryConfig) -> Result<Option<TelemetryGuard>> {
+    if !config.enabled {
+        return Ok(None);
+    }
+
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(&config.otlp_endpoint)
+        .build()?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(config.service_name.clone()).build())
+        .build();
+
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer("autofishbot"));
+    tracing::subscriber::set_global_default(tracing_subscriber::registry().with(layer))?;
+    info!("Exporting traces to {}", config.otlp_endpoint);
+
+    Ok(Some(TelemetryGuard { provider }))
+}
+
+/// Marks `span` as the command the next game reply answers.
+pub fn record_command(span: &Span) {
+    *LAST_COMMAND.lock() = Some(span.context());
+}
+
+/// Span for handling one game reply (parse + DB writes), parented to the last sent command.
+pub fn reply_span(kind: &str) -> Span {
+    let span = tracing::info_span!("game_reply", kind);
+    if let Some(cx) = LAST_COMMAND.lock().clone() {
+        let _ = span.set_parent(cx);
+    }
+    span
+}
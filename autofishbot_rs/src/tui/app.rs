@@ -1,6 +1,27 @@
-use crate::config::Config;
-use crate::engine::profile::Profile;
-use crate::discord::types::Message;
+use crate::config::{AccountMode, Config};
+use crate::engine::bankroll::GamblingStats;
+use crate::engine::breaks::BreakKind;
+use crate::engine::captcha::CaptchaStatus;
+use crate::engine::database::{CooldownCell, ExplorerCounts};
+use crate::engine::explorer::{ExplorerRequest, ExplorerStatus};
+use crate::engine::fishdex::Fishdex;
+use crate::engine::leaderboard::Standing;
+use crate::engine::levels::LevelPace;
+use crate::engine::net_worth::NetWorth;
+use crate::engine::optimizer::BiomeComparison;
+use crate::engine::parser::{ClanBossEvent, TradePrompt};
+use crate::engine::profile::{CrateOpen, Profile, QuestClaim};
+use crate::engine::seasonal::SeasonalStatus;
+use crate::engine::streak::DailyStats;
+use crate::engine::valuation::InventoryValuation;
+use crate::discord::gateway_health::GatewayHealth;
+use crate::discord::types::{Message, Modal};
+use crate::engine::accounts::control::{self, AccountsHandle};
+use crate::engine::update_check::UpdateInfo;
+use crate::notifications::hub::Notifier;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Default)]
 pub struct StrategyInfo {
@@ -8,6 +29,8 @@ pub struct StrategyInfo {
     pub progress: String,
     pub est_time: String,
     pub current_gps: String,
+    /// Current `[strategy] playbook` phase, e.g. "2/3 Volcanic to 30"; empty without a playbook
+    pub phase: String,
 }
 
 pub struct App {
@@ -21,8 +44,96 @@ pub struct App {
     pub profile: Profile,
     pub last_message: String,
     pub last_message_object: Option<Message>, // Store full message object for parsers
+    /// The last form opened for this account (`INTERACTION_MODAL_CREATE`), for `DiscordClient::submit_modal`
+    pub last_modal: Option<Modal>,
     pub should_quit: bool,
     pub strategy: StrategyInfo,
+    /// Bumped whenever a hot reload changes `config`, so the bot knows to re-sync
+    pub config_generation: u64,
+    pub config_reloaded_at: Option<Instant>,
+    pub notifier: Notifier,
+    /// Discord answered 401/403; the bot sends nothing until the token is accepted again
+    pub logged_out: bool,
+    /// Last send error while sends keep failing; the bot backs off and probes with occasional casts
+    pub degraded: Option<String>,
+    /// Set by the startup update check when a newer release exists
+    pub update_available: Option<UpdateInfo>,
+    /// Last clan boss/raid embed seen, and when
+    pub clan_boss: Option<(ClanBossEvent, Instant)>,
+    /// Seasonal events seen in embeds, by `[seasonal_events]` name
+    pub seasonal_events: BTreeMap<String, SeasonalStatus>,
+    /// Coinflip results, loaded from `coinflips` and updated from each reply
+    pub gambling: GamblingStats,
+    /// Event currency collected per event over all runs, from `event_earnings`
+    pub event_earnings: BTreeMap<String, u64>,
+    /// Completed quests waiting to be claimed by the bot
+    pub pending_quest_claims: Vec<QuestClaim>,
+    /// Quest the bot just claimed, until its reward embed arrives
+    pub awaiting_quest_reward: Option<(String, Instant)>,
+    /// Crates/presents with an Open button, waiting for the bot to click it
+    pub pending_crate_opens: Vec<CrateOpen>,
+    /// Crate the bot just opened, until its drops embed arrives
+    pub opening_crate: Option<(String, Instant)>,
+    /// Sell value of the inventory: set from profile embeds, raised by catches, cleared by selling
+    pub inventory_valuation: Option<InventoryValuation>,
+    /// Streak and income from `daily_claims`, refreshed by the bot's daily check
+    pub daily_stats: Option<DailyStats>,
+    /// When the next vote crate opens (from vote embeds and claims, persisted in `vote_windows`)
+    pub vote_available_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// This account's Discord user id, from the gateway READY event
+    pub user_id: Option<String>,
+    /// And its username, for finding it on leaderboards
+    pub username: Option<String>,
+    /// Trade/gift prompts the bot should click Decline on
+    pub pending_trade_declines: Vec<TradePrompt>,
+    /// Virtual Fisher said the bait ran out; `[automation] auto_buy_baits` buys more
+    pub out_of_bait: bool,
+    /// Cooldown hits per biome and UTC hour over the last week, for the Stats tab
+    pub cooldown_heatmap: Vec<CooldownCell>,
+    /// Catches per biome over the last week against the optimizer's expectation, for the Stats tab
+    pub biome_comparison: Vec<BiomeComparison>,
+    /// Species caught so far, loaded from `catch_history` and updated on every catch
+    pub fishdex: Fishdex,
+    /// Casting is paused until then because a person posted in the channel (who posted)
+    pub human_pause: Option<(Instant, String)>,
+    /// The `[breaks]` break in progress (when it ends) and the next one (when it starts)
+    pub on_break: Option<(Instant, BreakKind)>,
+    pub next_break: Option<(Instant, BreakKind)>,
+    /// `(unix seconds, net worth)` over the last week, for the Stats tab
+    pub net_worth_history: Vec<(i64, NetWorth)>,
+    /// `(unix seconds, standing)` on the leaderboards seen over the last week, for the Dashboard
+    pub rank_history: Vec<(i64, Standing)>,
+    /// Time and XP per level over the last week, for the Dashboard
+    pub level_paces: Vec<LevelPace>,
+    /// `(previous level, level)` of level-ups the bot hasn't run `[level_up]` for yet
+    pub pending_level_ups: Vec<(u32, u32)>,
+    /// Explorer progress, published by the bot
+    pub explorer: ExplorerStatus,
+    pub explorer_counts: ExplorerCounts,
+    /// Start/stop/re-run from the Explorer tab, until the bot picks it up
+    pub explorer_request: Option<ExplorerRequest>,
+    /// Target highlighted on the Explorer tab
+    pub selected_target: usize,
+    /// The pending captcha, published by the bot
+    pub captcha: Option<CaptchaStatus>,
+    /// Text typed into the captcha answer prompt; `Some` while the prompt is open
+    pub captcha_input: Option<String>,
+    /// Answer typed on the Captcha tab, until the bot sends it with `/verify`
+    pub captcha_answer: Option<String>,
+    /// Commands user scripts asked for, sent by the bot one per loop
+    pub pending_script_commands: Vec<String>,
+    /// Websocket heartbeat and reconnect stats, for the status bar
+    pub gateway: Option<Arc<GatewayHealth>>,
+    /// Every account's status, and add/remove requests, when running several in one process
+    pub accounts: Option<AccountsHandle>,
+    /// Row highlighted on the Accounts tab
+    pub selected_account: usize,
+    /// Text typed into the add-account prompt; `Some` while the prompt is open
+    pub account_input: Option<String>,
+    /// Which `[[accounts]]` entry this app belongs to when running several in one process
+    pub account: Option<String>,
+    /// Account picked with Enter on the Accounts tab, until the TUI switches to it
+    pub switch_account: Option<String>,
 }
 
 pub struct Stats {
@@ -34,12 +145,13 @@ pub struct Stats {
 
 impl App {
     pub fn new(config: Config) -> Self {
+        let status = if config.system.mode == AccountMode::Observer { "Observing" } else { "Stopped" };
         Self {
             config,
-            tabs: vec!["Dashboard".to_string(), "Profile".to_string(), "Logs".to_string(), "Config".to_string()],
+            tabs: vec!["Dashboard".to_string(), "Profile".to_string(), "Logs".to_string(), "Config".to_string(), "Stats".to_string(), "Explorer".to_string(), "Captcha".to_string()],
             tab_index: 0,
             is_running: false,
-            status: "Stopped".to_string(),
+            status: status.to_string(),
             logs: Vec::new(),
             stats: Stats {
                 fish_caught: 0,
@@ -50,11 +162,210 @@ impl App {
             profile: Profile::default(),
             last_message: String::new(),
             last_message_object: None,
+            last_modal: None,
             should_quit: false,
             strategy: StrategyInfo::default(),
+            config_generation: 0,
+            config_reloaded_at: None,
+            notifier: Notifier::new(),
+            logged_out: false,
+            degraded: None,
+            update_available: None,
+            clan_boss: None,
+            seasonal_events: BTreeMap::new(),
+            event_earnings: BTreeMap::new(),
+            gambling: GamblingStats::default(),
+            pending_quest_claims: Vec::new(),
+            awaiting_quest_reward: None,
+            pending_crate_opens: Vec::new(),
+            opening_crate: None,
+            inventory_valuation: None,
+            daily_stats: None,
+            vote_available_at: None,
+            user_id: None,
+            username: None,
+            pending_trade_declines: Vec::new(),
+            out_of_bait: false,
+            pending_script_commands: Vec::new(),
+            cooldown_heatmap: Vec::new(),
+            biome_comparison: Vec::new(),
+            fishdex: Fishdex::default(),
+            net_worth_history: Vec::new(),
+            rank_history: Vec::new(),
+            level_paces: Vec::new(),
+            pending_level_ups: Vec::new(),
+            human_pause: None,
+            on_break: None,
+            next_break: None,
+            explorer: ExplorerStatus::default(),
+            explorer_counts: ExplorerCounts::default(),
+            explorer_request: None,
+            selected_target: 0,
+            captcha: None,
+            captcha_input: None,
+            captcha_answer: None,
+            gateway: None,
+            accounts: None,
+            selected_account: 0,
+            account_input: None,
+            account: None,
+            switch_account: None,
         }
     }
 
+    /// Adds the Accounts tab, backed by the supervisor.
+    pub fn show_accounts(&mut self, accounts: AccountsHandle) {
+        self.accounts = Some(accounts);
+        self.tabs.push("Accounts".to_string());
+    }
+
+    pub fn on_accounts_tab(&self) -> bool {
+        self.tabs.get(self.tab_index).is_some_and(|t| t == "Accounts")
+    }
+
+    /// Runtime toggle for `[system] capture_gateway`; the config file isn't changed.
+    pub fn toggle_gateway_capture(&mut self) {
+        let capture = &mut self.config.system.capture_gateway;
+        *capture = !*capture;
+        let message = if *capture { "Gateway capture on (captures/gateway_*.jsonl)" } else { "Gateway capture off" };
+        self.add_log(message.to_string());
+    }
+
+    /// Moves `[strategy] goal` on to the next one; the bot picks it up like a reloaded config.
+    pub fn cycle_strategy_goal(&mut self) {
+        let goal = self.config.strategy.goal.next();
+        self.config.strategy.goal = goal;
+        self.config_generation += 1;
+        self.add_log(format!("Strategy goal: {}", goal.label()));
+    }
+
+    pub fn on_explorer_tab(&self) -> bool {
+        self.tabs.get(self.tab_index).is_some_and(|t| t == "Explorer")
+    }
+
+    pub fn select_target(&mut self, delta: isize) {
+        let count = self.explorer.targets.len();
+        if count > 0 {
+            self.selected_target = self.selected_target.saturating_add_signed(delta).min(count - 1);
+        }
+    }
+
+    /// Exploration runs in the bot loop, so starting it also starts the bot.
+    pub fn toggle_explorer(&mut self) {
+        if self.explorer.active || self.explorer.cooling_down {
+            self.explorer_request = Some(ExplorerRequest::Stop);
+            self.add_log("Explorer stopping".to_string());
+            return;
+        }
+        if self.is_observer() {
+            self.add_log("Observer mode: the bot never sends commands".to_string());
+            return;
+        }
+        self.explorer_request = Some(ExplorerRequest::Start);
+        self.add_log("Explorer starting".to_string());
+        if !self.is_running {
+            self.toggle_bot();
+        }
+    }
+
+    pub fn rerun_selected_target(&mut self) {
+        let Some(target) = self.explorer.targets.get(self.selected_target) else { return };
+        if self.is_observer() {
+            self.add_log("Observer mode: the bot never sends commands".to_string());
+            return;
+        }
+        self.add_log(format!("Explorer re-running /{}", target));
+        self.explorer_request = Some(ExplorerRequest::Run(self.selected_target));
+        if !self.is_running {
+            self.toggle_bot();
+        }
+    }
+
+    pub fn on_captcha_tab(&self) -> bool {
+        self.tabs.get(self.tab_index).is_some_and(|t| t == "Captcha")
+    }
+
+    /// Hands the typed answer to the bot and closes the prompt.
+    pub fn submit_captcha_input(&mut self) {
+        let Some(answer) = self.captcha_input.take() else { return };
+        let answer = answer.trim().to_string();
+        if answer.is_empty() || self.captcha.is_none() {
+            return;
+        }
+        if !self.is_running {
+            self.add_log("Start the bot (S) to send the captcha answer".to_string());
+        }
+        self.add_log(format!("Captcha answer queued: {}", answer));
+        self.captcha_answer = Some(answer);
+    }
+
+    pub fn select_account(&mut self, delta: isize) {
+        let Some(accounts) = &self.accounts else { return };
+        let count = accounts.overview().read().len();
+        if count > 0 {
+            self.selected_account = self.selected_account.saturating_add_signed(delta).min(count - 1);
+        }
+    }
+
+    /// Sends the add-account prompt's contents to the supervisor and closes the prompt.
+    pub fn submit_account_input(&mut self) {
+        let (Some(line), Some(accounts)) = (self.account_input.take(), self.accounts.clone()) else { return };
+        match control::parse_account_line(&line).and_then(|account| accounts.request_add(account)) {
+            Ok(()) => self.add_log("Starting account...".to_string()),
+            Err(e) => self.add_log(format!("Add account: {}", e)),
+        }
+    }
+
+    /// Asks the TUI to show the highlighted account's tabs instead of this one's.
+    pub fn view_selected_account(&mut self) {
+        let Some(accounts) = &self.accounts else { return };
+        let Some(name) = accounts.overview().read().get(self.selected_account).map(|a| a.name.clone()) else { return };
+        if self.account.as_deref() != Some(name.as_str()) {
+            self.switch_account = Some(name);
+        }
+    }
+
+    /// The app `view_selected_account` asked for, if that account is still running.
+    pub fn take_account_switch(&mut self) -> Option<Arc<tokio::sync::Mutex<App>>> {
+        let name = self.switch_account.take()?;
+        let app = self.accounts.as_ref()?.app(&name);
+        if app.is_none() {
+            self.add_log(format!("Account '{}' is not running", name));
+        }
+        app
+    }
+
+    /// Run on the app the TUI switched to, so it opens on the Accounts tab with the same row
+    /// highlighted.
+    pub fn adopt_switcher(&mut self, accounts: AccountsHandle, selected_account: usize) {
+        if self.accounts.is_none() {
+            self.show_accounts(accounts);
+        }
+        self.selected_account = selected_account;
+        self.tab_index = self.tabs.iter().position(|t| t == "Accounts").unwrap_or(0);
+    }
+
+    pub fn remove_selected_account(&mut self) {
+        let Some(accounts) = self.accounts.clone() else { return };
+        let Some(name) = accounts.overview().read().get(self.selected_account).map(|a| a.name.clone()) else { return };
+        match accounts.request_remove(&name) {
+            Ok(()) => self.selected_account = self.selected_account.saturating_sub(1),
+            Err(e) => self.add_log(format!("Remove account: {}", e)),
+        }
+    }
+
+    /// The clan boss/raid still worth attacking: not defeated and not past its end time
+    /// (bosses without one are assumed over 6 hours after the last sighting).
+    pub fn active_clan_boss(&self) -> Option<&ClanBossEvent> {
+        let (boss, seen_at) = self.clan_boss.as_ref()?;
+        let lasts = boss.ends_in.unwrap_or(Duration::from_secs(6 * 3600));
+        (!boss.defeated && seen_at.elapsed() < lasts).then_some(boss)
+    }
+
+    pub fn is_seasonal_event_active(&self, name: &str) -> bool {
+        self.seasonal_events.get(name).is_some_and(|s| s.is_active())
+    }
+
     pub fn on_tick(&mut self) {
         // Update runtime, etc.
     }
@@ -78,9 +389,49 @@ impl App {
         }
     }
 
+    pub fn is_observer(&self) -> bool {
+        self.config.system.mode == AccountMode::Observer
+    }
+
     pub fn toggle_bot(&mut self) {
+        if self.is_observer() {
+            self.add_log("Observer mode: the bot never sends commands".to_string());
+            return;
+        }
         self.is_running = !self.is_running;
-        self.status = if self.is_running { "Running".to_string() } else { "Stopped".to_string() };
+        self.status = if self.logged_out {
+            "Logged out".to_string()
+        } else if self.is_running && self.degraded.is_some() {
+            "Degraded".to_string()
+        } else if self.is_running {
+            "Running".to_string()
+        } else {
+            "Stopped".to_string()
+        };
         self.add_log(format!("Bot {}", if self.is_running { "Started" } else { "Stopped" }));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_submit_captcha_input() {
+        let mut app = App::new(Config::default());
+        // No captcha pending: the prompt just closes
+        app.captcha_input = Some("ABC123".to_string());
+        app.submit_captcha_input();
+        assert_eq!((app.captcha_input.as_deref(), app.captcha_answer.as_deref()), (None, None));
+
+        app.captcha = Some(CaptchaStatus::default());
+        app.captcha_input = Some("   ".to_string());
+        app.submit_captcha_input();
+        assert_eq!(app.captcha_answer, None);
+
+        app.captcha_input = Some(" abc123 ".to_string());
+        app.submit_captcha_input();
+        assert_eq!((app.captcha_input.as_deref(), app.captcha_answer.as_deref()), (None, Some("abc123")));
+        assert!(app.logs.iter().any(|l| l.starts_with("Start the bot")));
+    }
+}
@@ -6,6 +6,28 @@ use crate::tui::app::App;
 pub fn handle_events(app: &mut App) -> Result<()> {
     if event::poll(Duration::from_millis(100))? {
         if let Event::Key(key) = event::read()? {
+            // The add-account and captcha prompts take every key until Enter or Esc
+            if let Some(input) = &mut app.account_input {
+                match key.code {
+                    KeyCode::Enter => app.submit_account_input(),
+                    KeyCode::Esc => app.account_input = None,
+                    KeyCode::Backspace => { input.pop(); },
+                    KeyCode::Char(c) => input.push(c),
+                    _ => {}
+                }
+                return Ok(());
+            }
+            if let Some(input) = &mut app.captcha_input {
+                match key.code {
+                    KeyCode::Enter => app.submit_captcha_input(),
+                    KeyCode::Esc => app.captcha_input = None,
+                    KeyCode::Backspace => { input.pop(); },
+                    KeyCode::Char(c) => input.push(c),
+                    _ => {}
+                }
+                return Ok(());
+            }
+
             match key.code {
                 KeyCode::Char('q') => {
                     app.should_quit = true;
@@ -19,6 +41,24 @@ pub fn handle_events(app: &mut App) -> Result<()> {
                 KeyCode::Char('s') => {
                     app.toggle_bot();
                 },
+                KeyCode::Char('g') => app.toggle_gateway_capture(),
+                KeyCode::Char('o') => app.cycle_strategy_goal(),
+                KeyCode::Up if app.on_accounts_tab() => app.select_account(-1),
+                KeyCode::Down if app.on_accounts_tab() => app.select_account(1),
+                KeyCode::Char('a') if app.on_accounts_tab() && app.accounts.is_some() => {
+                    app.account_input = Some(String::new());
+                },
+                KeyCode::Char('x') if app.on_accounts_tab() => {
+                    app.remove_selected_account();
+                },
+                KeyCode::Enter if app.on_accounts_tab() => app.view_selected_account(),
+                KeyCode::Up if app.on_explorer_tab() => app.select_target(-1),
+                KeyCode::Down if app.on_explorer_tab() => app.select_target(1),
+                KeyCode::Char('e') if app.on_explorer_tab() => app.toggle_explorer(),
+                KeyCode::Enter if app.on_explorer_tab() => app.rerun_selected_target(),
+                KeyCode::Enter if app.on_captcha_tab() && app.captcha.is_some() => {
+                    app.captcha_input = Some(String::new());
+                },
                 _ => {}
             }
         }
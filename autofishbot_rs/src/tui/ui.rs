@@ -2,10 +2,15 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Span, Line},
-    widgets::{Block, Borders, BorderType, List, ListItem, Paragraph, Tabs},
+    widgets::{Block, Borders, BorderType, Cell, List, ListItem, Paragraph, Row, Sparkline, Table, Tabs},
     Frame,
 };
+use crate::engine::accounts::control;
+use crate::engine::accounts::overview::{AccountHealth, AccountsTotals};
+use crate::engine::game_data::Biome;
+use crate::engine::leaderboard;
 use crate::tui::app::App;
+use crate::util::money;
 
 pub fn draw(f: &mut Frame, app: &App) {
     let chunks = Layout::default()
@@ -24,11 +29,15 @@ pub fn draw(f: &mut Frame, app: &App) {
     draw_header(f, app, chunks[0]);
 
     // Main Content
-    match app.tab_index {
-        0 => draw_dashboard(f, app, chunks[1]),
-        1 => draw_profile(f, app, chunks[1]),
-        2 => draw_logs(f, app, chunks[1]),
-        3 => draw_config(f, app, chunks[1]),
+    match app.tabs.get(app.tab_index).map(String::as_str) {
+        Some("Dashboard") => draw_dashboard(f, app, chunks[1]),
+        Some("Profile") => draw_profile(f, app, chunks[1]),
+        Some("Logs") => draw_logs(f, app, chunks[1]),
+        Some("Config") => draw_config(f, app, chunks[1]),
+        Some("Stats") => draw_stats(f, app, chunks[1]),
+        Some("Explorer") => draw_explorer(f, app, chunks[1]),
+        Some("Captcha") => draw_captcha(f, app, chunks[1]),
+        Some("Accounts") => draw_accounts(f, app, chunks[1]),
         _ => {},
     }
 
@@ -45,8 +54,12 @@ fn draw_header(f: &mut Frame, app: &App, area: Rect) {
         Line::from(Span::styled(t, style))
     }).collect();
 
+    let mut block = Block::default().borders(Borders::ALL).border_type(BorderType::Rounded).title(" Autofishbot RS ");
+    if let Some(update) = &app.update_available {
+        block = block.title(Line::from(Span::styled(format!(" {} available ", update.version), Style::default().fg(Color::Yellow))).right_aligned());
+    }
     let tabs = Tabs::new(titles)
-        .block(Block::default().borders(Borders::ALL).border_type(BorderType::Rounded).title(" Autofishbot RS "))
+        .block(block)
         .select(app.tab_index)
         .highlight_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
 
@@ -54,13 +67,23 @@ fn draw_header(f: &mut Frame, app: &App, area: Rect) {
 }
 
 fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
-    let status_style = if app.is_running {
+    let status_style = if app.is_running && app.degraded.is_some() && !app.logged_out {
+        Style::default().fg(Color::Black).bg(Color::Yellow)
+    } else if app.is_running && !app.logged_out {
         Style::default().fg(Color::Black).bg(Color::Green)
     } else {
         Style::default().fg(Color::White).bg(Color::Red)
     };
 
-    let status_text = format!(" STATUS: {} | Q: Quit | TAB: Switch Tab | S: Start/Stop ", app.status);
+    let reloaded = app.config_reloaded_at.is_some_and(|t| t.elapsed().as_secs() < 5);
+    let warning = app.degraded.as_ref().map(|e| format!(" | Sends failing: {}", e)).unwrap_or_default();
+    let paused = app.human_pause.as_ref()
+        .map(|(until, who)| format!(" | Paused: {} posted ({}m left)", who, until.saturating_duration_since(std::time::Instant::now()).as_secs().div_ceil(60)))
+        .unwrap_or_default();
+    let capturing = if app.config.system.capture_gateway { " | Capturing gateway" } else { "" };
+    let captcha = if app.captcha.is_some() { " | Captcha pending (Captcha tab)" } else { "" };
+    let gateway = app.gateway.as_ref().map(|g| format!(" | {}", g.snapshot().summary())).unwrap_or_default();
+    let status_text = format!(" STATUS: {}{}{}{}{}{}{} | Q: Quit | TAB: Switch Tab | S: Start/Stop | G: Capture | O: Goal ", app.status, gateway, warning, captcha, paused, capturing, if reloaded { " | Config reloaded" } else { "" });
     let status_bar = Paragraph::new(status_text)
         .style(status_style);
     f.render_widget(status_bar, area);
@@ -84,15 +107,87 @@ fn draw_dashboard(f: &mut Frame, app: &App, area: Rect) {
         .title(" Agent Brain ")
         .style(Style::default().fg(Color::Magenta));
 
-    let stats_text = vec![
+    let mut stats_text = vec![
+        Line::from(vec![Span::styled("Goal:        ", Style::default().fg(Color::Cyan)), Span::raw(app.config.strategy.goal.label())]),
         Line::from(vec![Span::styled("Strategy:    ", Style::default().fg(Color::Cyan)), Span::raw(&app.strategy.current_goal)]),
         Line::from(vec![Span::styled("Progress:    ", Style::default().fg(Color::Yellow)), Span::raw(&app.strategy.progress)]),
         Line::from(vec![Span::styled("Est. Time:   ", Style::default().fg(Color::Red)), Span::raw(&app.strategy.est_time)]),
-        Line::from(vec![Span::styled("GPS:         ", Style::default().fg(Color::Green)), Span::raw(&app.strategy.current_gps)]),
+        Line::from(vec![Span::styled("Rate:        ", Style::default().fg(Color::Green)), Span::raw(&app.strategy.current_gps)]),
+    ];
+    if !app.strategy.phase.is_empty() {
+        stats_text.push(Line::from(vec![Span::styled("Phase:       ", Style::default().fg(Color::Cyan)), Span::raw(&app.strategy.phase)]));
+    }
+    let minutes_until = |at: std::time::Instant| at.saturating_duration_since(std::time::Instant::now()).as_secs().div_ceil(60);
+    let breaks = match (app.on_break, app.next_break) {
+        (Some((until, kind)), _) => Some(format!("on {}, {}m left", kind.label(), minutes_until(until))),
+        (None, Some((at, kind))) => Some(format!("{} in {}m", kind.label(), minutes_until(at))),
+        (None, None) => None,
+    };
+    if let Some(breaks) = breaks {
+        stats_text.push(Line::from(vec![Span::styled("Next Break:  ", Style::default().fg(Color::Cyan)), Span::raw(breaks)]));
+    }
+    stats_text.extend([
         Line::from(""),
         Line::from(vec![Span::styled("Fish Caught: ", Style::default().fg(Color::Blue)), Span::raw(app.stats.fish_caught.to_string())]),
         Line::from(vec![Span::styled("Runtime:     ", Style::default().fg(Color::White)), Span::raw(&app.stats.runtime)]),
-    ];
+    ]);
+    if let Some(valuation) = &app.inventory_valuation {
+        stats_text.push(Line::from(vec![
+            Span::styled("Inventory:   ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!("{} (balance {})", money::format_money(valuation.total), app.profile.balance_text())),
+        ]));
+    }
+    if let Some(daily) = app.daily_stats.as_ref().filter(|d| d.claims > 0) {
+        stats_text.push(Line::from(vec![
+            Span::styled("Daily:       ", Style::default().fg(Color::LightGreen)),
+            Span::raw(format!("{} day streak (best {}), {} total", daily.current_streak, daily.longest_streak, money::format_money(daily.total_income))),
+        ]));
+    }
+    if app.gambling.flips() > 0 {
+        let net = app.gambling.net();
+        stats_text.push(Line::from(vec![
+            Span::styled("Coinflips:   ", Style::default().fg(Color::LightYellow)),
+            Span::raw(format!("{}W / {}L, net {}{}", app.gambling.wins, app.gambling.losses, if net < 0 { "-" } else { "" }, money::format_money(net.unsigned_abs()))),
+        ]));
+    }
+    for trend in leaderboard::trends(&app.rank_history) {
+        let (arrow, color) = match trend.change {
+            0 => ("=", Color::Gray),
+            c if c > 0 => ("▲", Color::Green),
+            _ => ("▼", Color::Red),
+        };
+        let gap = trend.latest.gap_above
+            .map(|gap| format!(", {} behind #{}", money::format_compact(gap), trend.latest.rank.saturating_sub(1)))
+            .unwrap_or_default();
+        stats_text.push(Line::from(vec![
+            Span::styled("Rank:        ", Style::default().fg(Color::LightBlue)),
+            Span::raw(format!("{} #{} ", trend.latest.category, trend.latest.rank)),
+            Span::styled(format!("{}{}", arrow, trend.change.unsigned_abs()), Style::default().fg(color)),
+            Span::raw(format!(" this week{}", gap)),
+        ]));
+    }
+    if let Some(pace) = app.level_paces.last() {
+        let week_xp: f64 = app.level_paces.iter().map(|p| p.xp).sum();
+        let week_hours: f64 = app.level_paces.iter().map(|p| p.hours).sum();
+        let average = if week_hours > 0.0 { format!(", week avg {}/h", money::format_compact((week_xp / week_hours) as u64)) } else { String::new() };
+        stats_text.push(Line::from(vec![
+            Span::styled("Level Pace:  ", Style::default().fg(Color::LightMagenta)),
+            Span::raw(format!("{} in {:.1}h, {} XP/h{}", pace.level, pace.hours, money::format_compact(pace.xp_per_hour() as u64), average)),
+        ]));
+    }
+    if let Some(available_at) = app.vote_available_at {
+        let left = (available_at - chrono::Utc::now()).num_seconds();
+        let text = if left <= 0 { "available".to_string() } else { format!("in {}h {}m", left / 3600, left % 3600 / 60) };
+        stats_text.push(Line::from(vec![Span::styled("Vote:        ", Style::default().fg(Color::LightCyan)), Span::raw(text)]));
+    }
+    for (name, event) in app.seasonal_events.iter().filter(|(_, e)| e.is_active()) {
+        let left = event.ends_at.saturating_duration_since(std::time::Instant::now()).as_secs();
+        stats_text.push(Line::from(vec![
+            Span::styled("Event:       ", Style::default().fg(Color::LightMagenta)),
+            Span::raw(format!("{} ({} found, {} all time, {}h {}m left)", name, event.collected,
+                app.event_earnings.get(name).copied().unwrap_or(event.collected), left / 3600, left % 3600 / 60)),
+        ]));
+    }
     let stats_p = Paragraph::new(stats_text).block(stats_block).style(Style::default().fg(Color::White));
     f.render_widget(stats_p, left_chunks[0]);
 
@@ -130,12 +225,15 @@ fn draw_profile(f: &mut Frame, app: &App, area: Rect) {
 
      let info_block = Block::default().borders(Borders::ALL).border_type(BorderType::Rounded).title(" Player Info ");
      let info_text = vec![
-         Line::from(vec![Span::styled("Balance: ", Style::default().fg(Color::Yellow)), Span::raw(&app.profile.balance)]),
-         Line::from(vec![Span::styled("Level:   ", Style::default().fg(Color::Cyan)), Span::raw(&app.profile.level)]),
+         Line::from(vec![Span::styled("Balance: ", Style::default().fg(Color::Yellow)), Span::raw(app.profile.balance_text())]),
+         Line::from(vec![Span::styled("Level:   ", Style::default().fg(Color::Cyan)), Span::raw(app.profile.level_text())]),
          Line::from(vec![Span::styled("Biome:   ", Style::default().fg(Color::Green)), Span::raw(&app.profile.biome)]),
          Line::from(vec![Span::styled("Rod:     ", Style::default().fg(Color::Magenta)), Span::raw(&app.profile.rod)]),
          Line::from(vec![Span::styled("Pet:     ", Style::default().fg(Color::Blue)), Span::raw(&app.profile.pet)]),
          Line::from(vec![Span::styled("Bait:    ", Style::default().fg(Color::Red)), Span::raw(&app.profile.bait)]),
+         Line::from(vec![Span::styled("League:  ", Style::default().fg(Color::LightYellow)), Span::raw(format!("{} ({} Hooks)", app.profile.league, app.profile.hooks))]),
+         Line::from(vec![Span::styled("Achiev.: ", Style::default().fg(Color::LightGreen)), Span::raw(app.profile.achievement_completion()
+             .map(|pct| format!("{:.1}% complete", pct)).unwrap_or_else(|| "-".to_string()))]),
      ];
      f.render_widget(Paragraph::new(info_text).block(info_block), left_chunks[0]);
 
@@ -152,13 +250,20 @@ fn draw_profile(f: &mut Frame, app: &App, area: Rect) {
         .split(chunks[1]);
 
      let quest_block = Block::default().borders(Borders::ALL).border_type(BorderType::Rounded).title(" Quests ");
-     let quest_items: Vec<ListItem> = app.profile.quests.iter().map(|q| {
+     let mut quest_items: Vec<ListItem> = app.profile.quests.iter().map(|q| {
          let style = if q.is_completed { Style::default().fg(Color::Green) } else { Style::default() };
          ListItem::new(Line::from(vec![
              Span::styled(format!("[{}] ", if q.is_completed { "X" } else { " " }), style),
              Span::raw(format!("{} - {}", q.category, q.progress)),
          ]))
      }).collect();
+     // Nearly finished achievements are worth chasing alongside the quests
+     quest_items.extend(app.profile.near_complete_achievements(0.8).into_iter().map(|a| {
+         ListItem::new(Line::from(vec![
+             Span::styled("[~] ", Style::default().fg(Color::Yellow)),
+             Span::raw(format!("{} - {}/{}", a.name, a.progress, a.target)),
+         ]))
+     }));
      f.render_widget(List::new(quest_items).block(quest_block), right_chunks[0]);
 
      let charms_block = Block::default().borders(Borders::ALL).border_type(BorderType::Rounded).title(" Charms ");
@@ -197,3 +302,319 @@ fn draw_config(f: &mut Frame, app: &App, area: Rect) {
      let p = Paragraph::new(text).block(config_block);
      f.render_widget(p, area);
 }
+
+fn draw_stats(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(7),
+            Constraint::Min(6),
+            Constraint::Length(app.biome_comparison.len().max(Biome::ALL.len()) as u16 + 4),
+        ])
+        .split(area);
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(chunks[2]);
+    draw_net_worth(f, app, chunks[0]);
+    draw_cooldown_heatmap(f, app, chunks[1]);
+    draw_biome_comparison(f, app, bottom[0]);
+    draw_fishdex(f, app, bottom[1]);
+}
+
+fn draw_net_worth(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).border_type(BorderType::Rounded);
+    let (Some((_, first)), Some((_, latest))) = (app.net_worth_history.first(), app.net_worth_history.last()) else {
+        f.render_widget(Paragraph::new("No net worth snapshots yet").block(block.title(" Net Worth ")), area);
+        return;
+    };
+    let change = latest.total() as i64 - first.total() as i64;
+    let title = format!(" Net Worth {} ({}{} over 7 days): balance {}, inventory {}, rod {} ",
+        money::format_money(latest.total()),
+        if change < 0 { "-" } else { "+" }, money::format_money(change.unsigned_abs()),
+        money::format_compact(latest.balance), money::format_compact(latest.inventory), money::format_compact(latest.assets));
+    // The newest points that fit
+    let width = area.width.saturating_sub(2) as usize;
+    let start = app.net_worth_history.len().saturating_sub(width);
+    let data: Vec<u64> = app.net_worth_history[start..].iter().map(|(_, w)| w.total()).collect();
+    let sparkline = Sparkline::default()
+        .block(block.title(title))
+        .data(&data)
+        .style(Style::default().fg(Color::Green));
+    f.render_widget(sparkline, area);
+}
+
+fn draw_cooldown_heatmap(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).border_type(BorderType::Rounded)
+        .title(" Cooldown hits by hour (UTC, last 7 days) ");
+    if app.cooldown_heatmap.is_empty() {
+        f.render_widget(Paragraph::new("No cooldown hits or catches recorded yet").block(block), area);
+        return;
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(3)])
+        .split(area);
+
+    let mut biomes: Vec<&str> = app.cooldown_heatmap.iter().map(|c| c.biome.as_str()).collect();
+    biomes.dedup();
+    let header = Row::new(std::iter::once("Biome".to_string()).chain((0..24).map(|h| format!("{:02}", h))))
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let rows: Vec<Row> = biomes.iter().map(|biome| {
+        let cells = (0..24).map(|hour| {
+            match app.cooldown_heatmap.iter().find(|c| c.biome == *biome && c.hour == hour) {
+                Some(cell) if cell.hits > 0 => {
+                    // Share of casts lost to the cooldown
+                    let color = match cell.hit_rate() {
+                        r if r < 0.05 => Color::Green,
+                        r if r < 0.15 => Color::Yellow,
+                        _ => Color::Red,
+                    };
+                    Cell::from(cell.hits.to_string()).style(Style::default().fg(color).add_modifier(Modifier::BOLD))
+                },
+                Some(_) => Cell::from("·").style(Style::default().fg(Color::DarkGray)),
+                None => Cell::from(""),
+            }
+        });
+        Row::new(std::iter::once(Cell::from(biome.to_string())).chain(cells))
+    }).collect();
+
+    let widths: Vec<Constraint> = std::iter::once(Constraint::Length(12)).chain((0..24).map(|_| Constraint::Length(3))).collect();
+    let table = Table::new(rows, widths).header(header).column_spacing(1).block(block);
+    f.render_widget(table, chunks[0]);
+
+    let hint = Paragraph::new(format!(
+        "Green: under 5% of casts hit the cooldown, yellow: under 15%, red: more. For red hours raise [system] user_cooldown (now {:.1}s).",
+        app.config.system.user_cooldown,
+    ))
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(hint, chunks[1]);
+}
+
+fn draw_biome_comparison(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).border_type(BorderType::Rounded)
+        .title(" Catches per biome (last 7 days) vs the optimizer ");
+    if app.biome_comparison.is_empty() {
+        f.render_widget(Paragraph::new("Waiting for catch history").block(block), area);
+        return;
+    }
+    const BAR_WIDTH: usize = 20;
+    let most = app.biome_comparison.iter().map(|r| r.fish).max().unwrap_or(0).max(1);
+    let header = Row::new(["Biome", "Catches", "", "Avg Value", "Learned Avg", "Expected $/s"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let rows: Vec<Row> = app.biome_comparison.iter().map(|r| {
+        let bar = "█".repeat((r.fish * BAR_WIDTH as i64 / most) as usize);
+        let name = if r.preferred { format!("{} ★", r.biome.display_name()) } else { r.biome.display_name().to_string() };
+        let style = if r.preferred { Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD) } else { Style::default() };
+        let average = |value: f64| if value > 0.0 { format!("${:.0}", value) } else { "-".to_string() };
+        Row::new(vec![
+            Cell::from(name),
+            Cell::from(bar).style(Style::default().fg(Color::Blue)),
+            Cell::from(r.fish.to_string()),
+            Cell::from(average(r.avg_value)),
+            Cell::from(average(r.learned_avg)),
+            Cell::from(format!("${:.2}", r.expected_gps)),
+        ]).style(style)
+    }).collect();
+
+    let table = Table::new(rows, [
+        Constraint::Length(12),
+        Constraint::Length(BAR_WIDTH as u16),
+        Constraint::Length(8),
+        Constraint::Length(10),
+        Constraint::Length(12),
+        Constraint::Min(12),
+    ])
+    .header(header)
+    .block(block.title_bottom(" ★ optimizer pick for the current rod "));
+    f.render_widget(table, area);
+}
+
+fn draw_fishdex(f: &mut Frame, app: &App, area: Rect) {
+    let (caught, total) = app.fishdex.completion();
+    let title = format!(" Fishdex {}/{} ({:.0}%){} ", caught, total, caught as f64 / total.max(1) as f64 * 100.0,
+        if app.config.strategy.dex_hunting { ", hunting" } else { "" });
+    let header = Row::new(["Biome", "Caught", "Missing"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let rows: Vec<Row> = app.fishdex.progress().iter().map(|p| {
+        let color = if p.missing.is_empty() { Color::Green } else { Color::White };
+        Row::new(vec![
+            Cell::from(p.biome.display_name()),
+            Cell::from(format!("{}/{} {:.0}%", p.caught, p.total, p.percent())).style(Style::default().fg(color)),
+            Cell::from(p.missing.join(", ")).style(Style::default().fg(Color::DarkGray)),
+        ])
+    }).collect();
+    let table = Table::new(rows, [Constraint::Length(10), Constraint::Length(11), Constraint::Min(10)])
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).border_type(BorderType::Rounded).title(title));
+    f.render_widget(table, area);
+}
+
+fn draw_explorer(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(6), Constraint::Min(3), Constraint::Length(3)])
+        .split(area);
+    let explorer = &app.explorer;
+    let counts = &app.explorer_counts;
+
+    let (state, color) = if explorer.active {
+        (explorer.state.as_str(), Color::Green)
+    } else if explorer.cooling_down {
+        ("Cooldown, next cycle within the hour", Color::Yellow)
+    } else {
+        ("Stopped", Color::DarkGray)
+    };
+    let mut progress = format!("{}/{}", (explorer.current + 1).min(explorer.targets.len()), explorer.targets.len());
+    if let Some((option, options)) = explorer.submenu {
+        progress.push_str(&format!(", menu option {}/{}", (option + 1).min(options), options));
+    }
+    let text = vec![
+        Line::from(vec![Span::styled("State:    ", Style::default().fg(Color::Yellow)), Span::styled(state, Style::default().fg(color).add_modifier(Modifier::BOLD))]),
+        Line::from(vec![Span::styled("Target:   ", Style::default().fg(Color::Yellow)), Span::raw(progress)]),
+        Line::from(vec![Span::styled("Commands: ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!("{} discovered this run, {} registered, {} explored", explorer.known_commands, counts.commands, counts.executed))]),
+        Line::from(vec![Span::styled("Stored:   ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!("{} shop items, {} game entities", counts.shop_items, counts.entities))]),
+    ];
+    let summary = Paragraph::new(text)
+        .block(Block::default().borders(Borders::ALL).border_type(BorderType::Rounded).title(" Explorer "));
+    f.render_widget(summary, chunks[0]);
+
+    let targets: Vec<ListItem> = explorer.targets.iter().enumerate().map(|(i, target)| {
+        let marker = if explorer.active && i == explorer.current { "▶ " } else { "  " };
+        let style = if i == app.selected_target { Style::default().add_modifier(Modifier::REVERSED) } else { Style::default() };
+        ListItem::new(format!("{}/{}", marker, target)).style(style)
+    }).collect();
+    let list = List::new(targets)
+        .block(Block::default().borders(Borders::ALL).border_type(BorderType::Rounded).title(" Target Commands "));
+    f.render_widget(list, chunks[1]);
+
+    let hint = Paragraph::new(format!("e: {} exploring   Up/Down: select   Enter: re-run selected",
+        if explorer.active || explorer.cooling_down { "stop" } else { "start" }))
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(hint, chunks[2]);
+}
+
+fn draw_captcha(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(3)])
+        .split(area);
+    let block = Block::default().borders(Borders::ALL).border_type(BorderType::Rounded).title(" Captcha ");
+    let Some(captcha) = &app.captcha else {
+        f.render_widget(Paragraph::new("No captcha pending").block(block), chunks[0]);
+        return;
+    };
+
+    let ocr = if captcha.solvers.is_empty() {
+        Span::styled("no [captcha] provider with an API key, answer by hand", Style::default().fg(Color::Red))
+    } else if captcha.solving {
+        Span::styled("reading the image...", Style::default().fg(Color::Yellow))
+    } else {
+        Span::raw(captcha.solvers.join(" → "))
+    };
+    let image = match &captcha.image_url {
+        Some(url) => Span::styled(url.clone(), Style::default().fg(Color::Cyan).add_modifier(Modifier::UNDERLINED)),
+        None => Span::styled("not seen yet; run a command to get the prompt again", Style::default().fg(Color::DarkGray)),
+    };
+    let tried = if captcha.answers.is_empty() {
+        "none".to_string()
+    } else {
+        captcha.answers.iter().map(|a| if captcha.rejected.contains(a) { format!("{} ✗", a) } else { a.clone() }).collect::<Vec<_>>().join(", ")
+    };
+    let text = vec![
+        Line::from(vec![Span::styled("Image:   ", Style::default().fg(Color::Yellow)), image]),
+        Line::from(vec![Span::styled("Solvers: ", Style::default().fg(Color::Yellow)), ocr]),
+        Line::from(vec![Span::styled("Tried:   ", Style::default().fg(Color::Yellow)), Span::raw(tried)]),
+        Line::from(vec![Span::styled("Attempts:", Style::default().fg(Color::Yellow)),
+            Span::raw(format!(" {}/{} automatic", captcha.attempts, app.config.captcha.max_attempts))]),
+        Line::from(""),
+        Line::from(Span::styled("Open the image in a browser and type the code below; the bot sends it with /verify.", Style::default().fg(Color::DarkGray))),
+    ];
+    f.render_widget(Paragraph::new(text).block(block), chunks[0]);
+
+    let prompt = match &app.captcha_input {
+        Some(input) => Paragraph::new(format!("{}_", control::mask_account_line(input)))
+            .block(Block::default().borders(Borders::ALL).title(" Answer (Enter to send, Esc to cancel) ")),
+        None => Paragraph::new("Enter: type the answer")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(Block::default().borders(Borders::ALL)),
+    };
+    f.render_widget(prompt, chunks[1]);
+}
+
+fn draw_accounts(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).border_type(BorderType::Rounded).title(" Accounts ");
+    let Some(accounts) = &app.accounts else {
+        f.render_widget(Paragraph::new("Single-account mode").block(block), area);
+        return;
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(3)])
+        .split(area);
+
+    let header = Row::new(["Account", "Status", "Balance", "Level", "Biome", "Fish/h", "Gold/h", "Captchas", "Problem", "Last Action"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let overview = accounts.overview().read();
+    let mut rows: Vec<Row> = overview.iter().enumerate().map(|(i, a)| {
+        let color = match a.health {
+            AccountHealth::Healthy => Color::Green,
+            AccountHealth::Degraded => Color::Yellow,
+            AccountHealth::Down => Color::Red,
+        };
+        let name = if app.account.as_deref() == Some(a.name.as_str()) { format!("> {}", a.name) } else { a.name.clone() };
+        Row::new(vec![
+            Cell::from(name).style(Style::default().fg(color).add_modifier(Modifier::BOLD)),
+            Cell::from(a.status.clone()),
+            Cell::from(a.balance.map(money::format_money).unwrap_or_default()),
+            Cell::from(a.level.map(|l| l.to_string()).unwrap_or_default()),
+            Cell::from(a.biome.clone()),
+            Cell::from(format!("{:.0}", a.fish_per_hour)),
+            Cell::from(format!("{:.0}", a.gold_per_hour)),
+            Cell::from(a.captchas.to_string()),
+            Cell::from(a.error.clone().unwrap_or_default()).style(Style::default().fg(color)),
+            Cell::from(a.last_action.clone()),
+        ]).style(if i == app.selected_account { Style::default().add_modifier(Modifier::REVERSED) } else { Style::default() })
+    }).collect();
+
+    let totals = AccountsTotals::of(&overview);
+    rows.push(Row::new(vec![
+        Cell::from("Total"),
+        Cell::from(format!("{}/{} on", totals.running, overview.len())),
+        Cell::from(""),
+        Cell::from(""),
+        Cell::from(""),
+        Cell::from(format!("{:.0}", totals.fish_per_hour)),
+        Cell::from(format!("{:.0}", totals.gold_per_hour)),
+        Cell::from(totals.captchas.to_string()),
+    ]).style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)));
+
+    let table = Table::new(rows, [
+        Constraint::Length(14),
+        Constraint::Length(10),
+        Constraint::Length(12),
+        Constraint::Length(6),
+        Constraint::Length(10),
+        Constraint::Length(8),
+        Constraint::Length(10),
+        Constraint::Length(9),
+        Constraint::Length(24),
+        Constraint::Min(10),
+    ])
+    .header(header)
+    .block(block);
+    f.render_widget(table, chunks[0]);
+
+    let prompt = match &app.account_input {
+        Some(input) => Paragraph::new(format!("{}_", control::mask_account_line(input)))
+            .block(Block::default().borders(Borders::ALL).title(" New account: name token channel_id [guild_id] (Enter to start, Esc to cancel) ")),
+        None => Paragraph::new("Enter: view selected   a: add account   x: remove selected   Up/Down: select")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(Block::default().borders(Borders::ALL)),
+    };
+    f.render_widget(prompt, chunks[1]);
+}
@@ -0,0 +1,93 @@
+/// The first amount in `text`: "$1,234,567", "**12,000** <:hook:1> Hooks", "2.5M".
+/// Emoji ids (`<:coin:123>`) are skipped, and a k/M/B suffix multiplies.
+pub fn parse_amount(text: &str) -> Option<u64> {
+    let text = strip_emoji(text);
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let rest = &text[start..];
+    let end = rest.find(|c: char| !(c.is_ascii_digit() || c == ',' || c == '.')).unwrap_or(rest.len());
+    // A sentence-ending "." isn't a decimal point
+    let number = rest[..end].trim_end_matches(['.', ',']).replace(',', "");
+    let multiplier = match rest[end..].chars().next().map(|c| c.to_ascii_lowercase()) {
+        Some('k') => 1_000.0,
+        Some('m') => 1_000_000.0,
+        Some('b') => 1_000_000_000.0,
+        _ => 1.0,
+    };
+    if multiplier == 1.0 {
+        return number.split('.').next()?.parse().ok();
+    }
+    number.parse::<f64>().ok().map(|n| (n * multiplier).round() as u64)
+}
+
+/// Short form for tight spaces: 950, 12.3K, 4.5M, 1.2B.
+pub fn format_compact(amount: u64) -> String {
+    const UNITS: [(u64, &str); 3] = [(1_000_000_000, "B"), (1_000_000, "M"), (1_000, "K")];
+    for (size, unit) in UNITS {
+        if amount >= size {
+            let value = amount as f64 / size as f64;
+            let digits = if value < 100.0 { format!("{:.1}", value) } else { format!("{:.0}", value) };
+            return format!("{}{}", digits.trim_end_matches(".0"), unit);
+        }
+    }
+    amount.to_string()
+}
+
+/// The game's own style: "$1,234,567".
+pub fn format_money(amount: u64) -> String {
+    format!("${}", format_thousands(amount))
+}
+
+/// "1,234,567", as counts appear in embeds.
+pub fn format_thousands(amount: u64) -> String {
+    let digits = amount.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn strip_emoji(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else { break };
+        out.push_str(&rest[..start]);
+        out.push(' ');
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("$1,234,567"), Some(1_234_567));
+        assert_eq!(parse_amount("Balance: **$42**."), Some(42));
+        assert_eq!(parse_amount("<:hook:1234567> **12,000** Hooks"), Some(12_000));
+        assert_eq!(parse_amount("2.5M"), Some(2_500_000));
+        assert_eq!(parse_amount("costs 12k gold"), Some(12_000));
+        assert_eq!(parse_amount("12.9"), Some(12));
+        assert_eq!(parse_amount("no amount"), None);
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format_compact(950), "950");
+        assert_eq!(format_compact(12_345), "12.3K");
+        assert_eq!(format_compact(4_000_000), "4M");
+        assert_eq!(format_compact(250_000_000), "250M");
+        assert_eq!(format_money(1_234_567), "$1,234,567");
+        assert_eq!(format_money(0), "$0");
+        for n in [0, 7, 1_000, 98_765_432] {
+            assert_eq!(parse_amount(&format_money(n)), Some(n));
+        }
+    }
+}
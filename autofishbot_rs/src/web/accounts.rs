@@ -0,0 +1,60 @@
+use crate::config::AccountConfig;
+use crate::engine::accounts::overview::AccountsTotals;
+use crate::web::server::WebState;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get};
+use axum::{Json, Router};
+use serde_json::json;
+use std::collections::HashMap;
+
+pub fn routes() -> Router<WebState> {
+    Router::new()
+        .route("/api/accounts", get(accounts).post(add_account))
+        .route("/api/accounts/:name", delete(remove_account))
+}
+
+/// Per-account status and throughput rows plus totals (same data as the TUI Accounts tab); 404 in single-account mode.
+async fn accounts(State(web): State<WebState>, headers: HeaderMap, Query(query): Query<HashMap<String, String>>) -> Response {
+    if !web.authorized(&headers, &query) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    match &web.accounts {
+        Some(handle) => {
+            let rows = handle.overview().read().clone();
+            let totals = AccountsTotals::of(&rows);
+            Json(json!({ "accounts": rows, "totals": totals })).into_response()
+        },
+        None => (StatusCode::NOT_FOUND, "Not running multiple accounts").into_response(),
+    }
+}
+
+/// Starts an account from an `[[accounts]]`-shaped JSON body; it runs until removed or the process exits.
+/// Like removing one, this is refused unless `[web] auth_token` is set.
+async fn add_account(State(web): State<WebState>, headers: HeaderMap, Query(query): Query<HashMap<String, String>>, Json(account): Json<AccountConfig>) -> Response {
+    if !web.authorized_strict(&headers, &query) {
+        return (StatusCode::UNAUTHORIZED, "Adding and removing accounts needs [web] auth_token").into_response();
+    }
+    let Some(handle) = &web.accounts else {
+        return (StatusCode::NOT_FOUND, "Not running multiple accounts").into_response();
+    };
+    let name = account.name.clone();
+    match handle.add(account).await {
+        Ok(()) => (StatusCode::CREATED, Json(json!({ "added": name }))).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+async fn remove_account(State(web): State<WebState>, headers: HeaderMap, Query(query): Query<HashMap<String, String>>, Path(name): Path<String>) -> Response {
+    if !web.authorized_strict(&headers, &query) {
+        return (StatusCode::UNAUTHORIZED, "Adding and removing accounts needs [web] auth_token").into_response();
+    }
+    let Some(handle) = &web.accounts else {
+        return (StatusCode::NOT_FOUND, "Not running multiple accounts").into_response();
+    };
+    match handle.remove(&name).await {
+        Ok(()) => Json(json!({ "removed": name })).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
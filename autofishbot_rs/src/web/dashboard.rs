@@ -0,0 +1,79 @@
+use crate::tui::app::App;
+use crate::web::server::WebState;
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// How many log lines the dashboard shows (the TUI keeps 100).
+const RECENT_LOGS: usize = 50;
+
+pub fn routes() -> Router<WebState> {
+    Router::new()
+        .route("/", get(index))
+        .route("/api/state", get(state))
+        .route("/api/toggle", post(toggle))
+        .route("/api/interactions", get(interactions))
+}
+
+async fn index() -> Html<&'static str> {
+    Html(include_str!("dashboard.html"))
+}
+
+async fn state(State(web): State<WebState>, headers: HeaderMap, Query(query): Query<HashMap<String, String>>) -> Response {
+    if !web.authorized(&headers, &query) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let app = web.app.lock().await;
+    Json(snapshot(&app)).into_response()
+}
+
+async fn toggle(State(web): State<WebState>, headers: HeaderMap, Query(query): Query<HashMap<String, String>>) -> Response {
+    if !web.authorized(&headers, &query) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let mut app = web.app.lock().await;
+    app.toggle_bot();
+    Json(snapshot(&app)).into_response()
+}
+
+/// Per-command sent/replied/failed counts over the last day, from the correlation store.
+async fn interactions(State(web): State<WebState>, headers: HeaderMap, Query(query): Query<HashMap<String, String>>) -> Response {
+    if !web.authorized(&headers, &query) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    match web.db.interaction_stats(24).await {
+        Ok(stats) => Json(stats).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// Same information the TUI dashboard and profile tabs show.
+fn snapshot(app: &App) -> Value {
+    let logs: Vec<&String> = app.logs.iter().rev().take(RECENT_LOGS).collect();
+    json!({
+        "status": app.status,
+        "is_running": app.is_running,
+        "stats": {
+            "fish_caught": app.stats.fish_caught,
+            "money_earned": app.stats.money_earned,
+            "captchas_solved": app.stats.captchas_solved,
+            "runtime": app.stats.runtime,
+        },
+        "strategy": {
+            "current_goal": app.strategy.current_goal,
+            "progress": app.strategy.progress,
+            "est_time": app.strategy.est_time,
+            "current_gps": app.strategy.current_gps,
+            "phase": app.strategy.phase,
+        },
+        "profile": serde_json::to_value(&app.profile).unwrap_or(Value::Null),
+        "inventory_value": app.inventory_valuation.as_ref().map(|v| v.total),
+        "daily": app.daily_stats,
+        "last_message": app.last_message,
+        "logs": logs,
+    })
+}
@@ -0,0 +1,21 @@
+use crate::web::server::WebState;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+
+pub fn routes() -> Router<WebState> {
+    Router::new().route("/healthz", get(healthz))
+}
+
+/// Liveness probe: 200 when healthy, 503 otherwise, with the report as the body either way.
+/// Unauthenticated so orchestrators can call it without credentials.
+async fn healthz(State(web): State<WebState>) -> Response {
+    let db_writable = web.db.check_writable().await.is_ok();
+    let bot_running = web.app.lock().await.is_running;
+    let report = web.health.report(db_writable, bot_running);
+
+    let status = if report.healthy { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(report)).into_response()
+}
@@ -0,0 +1,4 @@
+pub mod server;
+pub mod dashboard;
+pub mod health;
+pub mod accounts;
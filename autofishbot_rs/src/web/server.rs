@@ -0,0 +1,69 @@
+use crate::config::WebConfig;
+use crate::engine::accounts::control::AccountsHandle;
+use crate::engine::database::Database;
+use crate::engine::health::HealthMonitor;
+use crate::tui::app::App;
+use crate::web::{accounts, dashboard, health};
+use anyhow::{Result, bail};
+use axum::http::HeaderMap;
+use axum::Router;
+use log::info;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Shared state handed to every web handler.
+#[derive(Clone)]
+pub struct WebState {
+    pub app: Arc<Mutex<App>>,
+    pub health: Arc<HealthMonitor>,
+    pub db: Arc<Database>,
+    /// Set when the supervisor runs several accounts
+    pub accounts: Option<AccountsHandle>,
+    auth_token: Arc<String>,
+}
+
+impl WebState {
+    /// Accepts `Authorization: Bearer <token>` or `?token=<token>`; always true when no token is
+    /// configured, which `serve` only allows on a loopback bind.
+    pub fn authorized(&self, headers: &HeaderMap, query: &HashMap<String, String>) -> bool {
+        if self.auth_token.is_empty() {
+            return true;
+        }
+        let bearer = headers.get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        bearer == Some(self.auth_token.as_str()) || query.get("token") == Some(&*self.auth_token)
+    }
+
+    /// `authorized`, but never without a configured token. For routes that handle Discord
+    /// tokens (adding and removing accounts), which a cross-site POST to localhost could reach.
+    pub fn authorized_strict(&self, headers: &HeaderMap, query: &HashMap<String, String>) -> bool {
+        !self.auth_token.is_empty() && self.authorized(headers, query)
+    }
+}
+
+/// Serves the web UI until the process exits. With several accounts, `app`/`health`/`db` are the first one's.
+pub async fn serve(web: &WebConfig, app: Arc<Mutex<App>>, health: Arc<HealthMonitor>, db: Arc<Database>, accounts: Option<AccountsHandle>) -> Result<()> {
+    // `--web` overrides the bind after `Config::validate`, so check again here
+    if web.auth_token.is_empty() && !web.is_loopback() {
+        bail!("Refusing to serve the web UI on {} without [web] auth_token: set a token or bind to 127.0.0.1", web.bind);
+    }
+    let state = WebState {
+        app,
+        health,
+        db,
+        accounts,
+        auth_token: Arc::new(web.auth_token.clone()),
+    };
+    let router = Router::new()
+        .merge(dashboard::routes())
+        .merge(health::routes())
+        .merge(accounts::routes())
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(&web.bind).await?;
+    info!("Web dashboard listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, router).await?;
+    Ok(())
+}